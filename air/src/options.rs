@@ -152,6 +152,7 @@ pub struct ExecutionOptions {
     expected_cycles: u32,
     enable_tracing: bool,
     enable_debugging: bool,
+    enable_coverage: bool,
 }
 
 impl Default for ExecutionOptions {
@@ -161,6 +162,7 @@ impl Default for ExecutionOptions {
             expected_cycles: MIN_TRACE_LEN as u32,
             enable_tracing: false,
             enable_debugging: false,
+            enable_coverage: false,
         }
     }
 }
@@ -194,6 +196,7 @@ impl ExecutionOptions {
             expected_cycles,
             enable_tracing,
             enable_debugging: false,
+            enable_coverage: false,
         })
     }
 
@@ -214,6 +217,15 @@ impl ExecutionOptions {
         self
     }
 
+    /// Enables collection of code coverage.
+    ///
+    /// When coverage is collected, the VM keeps track of how many times every code block and every
+    /// operation of SPAN blocks was executed (see `ExecutionCoverage` in the processor).
+    pub fn with_coverage(mut self) -> Self {
+        self.enable_coverage = true;
+        self
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -240,4 +252,9 @@ impl ExecutionOptions {
     pub fn enable_debugging(&self) -> bool {
         self.enable_debugging
     }
+
+    /// Returns a flag indicating whether the VM should collect code coverage.
+    pub fn enable_coverage(&self) -> bool {
+        self.enable_coverage
+    }
 }
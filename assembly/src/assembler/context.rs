@@ -1,6 +1,6 @@
 use super::{
-    AssemblyError, CallSet, CodeBlock, CodeBlockTable, Kernel, LibraryPath, NamedProcedure,
-    Procedure, ProcedureCache, ProcedureId, ProcedureName, RpoDigest,
+    AssemblyError, CallSet, CodeBlockTable, Kernel, LibraryPath, MastForest, MastNodeId,
+    NamedProcedure, Procedure, ProcedureCache, ProcedureId, ProcedureName, RpoDigest,
 };
 use crate::ast::{ModuleAst, ProgramAst};
 use alloc::collections::BTreeMap;
@@ -186,8 +186,22 @@ impl AssemblyContext {
 
     /// Completes compilation of the current procedure and adds the compiled procedure to the list
     /// of the current module's compiled procedures.
-    pub fn complete_proc(&mut self, code: CodeBlock) {
-        self.module_stack.last_mut().expect("no modules").complete_proc(code);
+    ///
+    /// The procedure MAST is identified by its root (`mast_root`) and the ID of the root node in
+    /// the assembler's MAST forest (`code`).
+    pub fn complete_proc(&mut self, mast_root: RpoDigest, code: MastNodeId) {
+        self.module_stack.last_mut().expect("no modules").complete_proc(mast_root, code);
+    }
+
+    /// Aborts compilation of the current procedure after a compilation error.
+    ///
+    /// Modules put onto the module stack above the specified depth (i.e., the modules which were
+    /// being compiled as dependencies of the procedure) are discarded, and the procedure is
+    /// completed with the provided placeholder code. This keeps the indexes of the procedures
+    /// compiled after the aborted one stable, so that the compilation of the module can continue.
+    pub fn abort_proc(&mut self, module_depth: usize, mast_root: RpoDigest, code: MastNodeId) {
+        self.module_stack.truncate(module_depth);
+        self.complete_proc(mast_root, code);
     }
 
     // CALL PROCESSORS
@@ -283,6 +297,10 @@ impl AssemblyContext {
 
     /// Transforms this context into a [CodeBlockTable] for the compiled program.
     ///
+    /// The MAST of every procedure in the callset of the program is copied from the assembler's
+    /// MAST forest (`source`) into the MAST forest of the program (`target`), and the code block
+    /// table references the copied procedures.
+    ///
     /// This method is invoked at the end of the compilation of an executable program.
     ///
     /// # Panics
@@ -296,6 +314,8 @@ impl AssemblyContext {
     pub fn into_cb_table(
         mut self,
         proc_cache: &ProcedureCache,
+        source: &MastForest,
+        target: &mut MastForest,
     ) -> Result<CodeBlockTable, AssemblyError> {
         // get the last module off the module stack
         assert_eq!(self.module_stack.len(), 1, "module stack must contain exactly one module");
@@ -314,7 +334,8 @@ impl AssemblyContext {
                 .or_else(|| main_module_context.find_local_proc(mast_root))
                 .ok_or(AssemblyError::CallSetProcedureNotFound(*mast_root))?;
 
-            cb_table.insert(proc.code().clone());
+            let node_id = target.add_subtree(source, proc.code());
+            cb_table.insert(proc.mast_root(), node_id);
         }
 
         Ok(cb_table)
@@ -329,6 +350,16 @@ impl AssemblyContext {
         self.module_stack.last().and_then(|m| m.proc_stack.last())
     }
 
+    /// Returns the number of modules on the module stack.
+    pub(crate) fn module_depth(&self) -> usize {
+        self.module_stack.len()
+    }
+
+    /// Returns the path of the module currently being compiled.
+    pub(crate) fn current_module_path(&self) -> &LibraryPath {
+        &self.module_stack.last().expect("no modules").path
+    }
+
     /// Returns the name of the current procedure, or the reserved name for the main block.
     pub(crate) fn current_context_name(&self) -> &str {
         self.current_proc_context()
@@ -443,9 +474,9 @@ impl ModuleContext {
     /// compiled procedure, and adds it to the list of compiled procedures.
     ///
     /// This also updates module callset to include the callset of the newly compiled procedure.
-    pub fn complete_proc(&mut self, code: CodeBlock) {
+    pub fn complete_proc(&mut self, mast_root: RpoDigest, code: MastNodeId) {
         let proc_context = self.proc_stack.pop().expect("no procedures");
-        let proc = proc_context.into_procedure(code);
+        let proc = proc_context.into_procedure(mast_root, code);
         self.callset.append(proc.callset());
         self.compiled_procs.push(proc);
     }
@@ -562,7 +593,7 @@ impl ProcedureContext {
         &self.name
     }
 
-    pub fn into_procedure(self, code_root: CodeBlock) -> NamedProcedure {
+    pub fn into_procedure(self, mast_root: RpoDigest, code_root: MastNodeId) -> NamedProcedure {
         let Self {
             name,
             is_export,
@@ -570,6 +601,6 @@ impl ProcedureContext {
             callset,
         } = self;
 
-        NamedProcedure::new(name, is_export, num_locals as u32, code_root, callset)
+        NamedProcedure::new(name, is_export, num_locals as u32, mast_root, code_root, callset)
     }
 }
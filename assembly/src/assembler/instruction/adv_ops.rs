@@ -1,6 +1,6 @@
 use super::{validate_param, AssemblyError, SpanBuilder};
 use crate::{ast::AdviceInjectorNode, ADVICE_READ_LIMIT};
-use vm_core::{code_blocks::CodeBlock, Operation};
+use vm_core::{MastNodeId, Operation};
 
 // NON-DETERMINISTIC (ADVICE) INPUTS
 // ================================================================================================
@@ -12,7 +12,7 @@ use vm_core::{code_blocks::CodeBlock, Operation};
 /// # Errors
 /// Returns an error if the specified number of values to pushed is smaller than 1 or greater
 /// than 16.
-pub fn adv_push(span: &mut SpanBuilder, n: u8) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn adv_push(span: &mut SpanBuilder, n: u8) -> Result<Option<MastNodeId>, AssemblyError> {
     validate_param(n, 1..=ADVICE_READ_LIMIT)?;
     span.push_op_many(Operation::AdvPop, n as usize);
     Ok(None)
@@ -25,7 +25,7 @@ pub fn adv_push(span: &mut SpanBuilder, n: u8) -> Result<Option<CodeBlock>, Asse
 pub fn adv_inject(
     span: &mut SpanBuilder,
     injector: &AdviceInjectorNode,
-) -> Result<Option<CodeBlock>, AssemblyError> {
+) -> Result<Option<MastNodeId>, AssemblyError> {
     span.push_advice_injector(injector.into());
     Ok(None)
 }
@@ -1,4 +1,4 @@
-use super::{AssemblyError, CodeBlock, Operation::*, SpanBuilder};
+use super::{AssemblyError, MastNodeId, Operation::*, SpanBuilder};
 use vm_core::AdviceInjector;
 
 // HASHING
@@ -23,7 +23,7 @@ use vm_core::AdviceInjector;
 /// 3. Drop D and B to achieve our result [C, ...]
 ///
 /// This operation takes 20 VM cycles.
-pub(super) fn hash(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub(super) fn hash(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     #[rustfmt::skip]
     let ops = [
         // add 4 elements to the stack to be used as the capacity elements for the RPO permutation
@@ -70,7 +70,7 @@ pub(super) fn hash(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, Assembly
 /// 4. Drop F and D to return our result [E, ...].
 ///
 /// This operation takes 16 VM cycles.
-pub(super) fn hmerge(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub(super) fn hmerge(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     #[rustfmt::skip]
     let ops = [
         // Add 4 elements to the stack to prepare the capacity portion for the RPO permutation
@@ -111,7 +111,7 @@ pub(super) fn hmerge(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, Assemb
 /// - root of the tree, 4 elements.
 ///
 /// This operation takes 9 VM cycles.
-pub(super) fn mtree_get(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub(super) fn mtree_get(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     // stack: [d, i, R, ...]
     // pops the value of the node we are looking for from the advice stack
     read_mtree_node(span);
@@ -141,7 +141,7 @@ pub(super) fn mtree_get(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, Ass
 /// - new root of the tree after the update, 4 elements
 ///
 /// This operation takes 29 VM cycles.
-pub(super) fn mtree_set(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub(super) fn mtree_set(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     // stack: [d, i, R_old, V_new, ...]
 
     // stack: [V_old, R_new, ...] (29 cycles)
@@ -161,7 +161,7 @@ pub(super) fn mtree_set(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, Ass
 /// It is not checked whether the provided roots exist as Merkle trees in the advide providers.
 ///
 /// This operation takes 16 VM cycles.
-pub(super) fn mtree_merge(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub(super) fn mtree_merge(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     // stack input:  [R_rhs, R_lhs, ...]
     // stack output: [R_merged, ...]
 
@@ -184,7 +184,7 @@ pub(super) fn mtree_merge(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, A
 /// After the operation is executed, the stack remains unchanged.
 ///
 /// This operation takes 1 VM cycle.
-pub(super) fn mtree_verify(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub(super) fn mtree_verify(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     span.add_op(MpVerify)
 }
 
@@ -226,7 +226,7 @@ fn read_mtree_node(span: &mut SpanBuilder) {
 /// and perform the mutation on the copied tree.
 ///
 /// This operation takes 29 VM cycles.
-fn update_mtree(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+fn update_mtree(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     // stack: [d, i, R_old, V_new, ...]
     // output: [R_new, R_old, V_new, V_old, ...]
 
@@ -1,5 +1,5 @@
 use super::{
-    mem_ops::local_to_absolute_addr, push_felt, AssemblyContext, AssemblyError, CodeBlock, Felt,
+    mem_ops::local_to_absolute_addr, push_felt, AssemblyContext, AssemblyError, Felt, MastNodeId,
     Operation::*, SpanBuilder,
 };
 
@@ -11,7 +11,7 @@ use super::{
 /// In cases when the immediate value is 0, `PUSH` operation is replaced with `PAD`. Also, in cases
 /// when immediate value is 1, `PUSH` operation is replaced with `PAD INCR` because in most cases
 /// this will be more efficient than doing a `PUSH`.
-pub fn push_one<T>(imm: T, span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError>
+pub fn push_one<T>(imm: T, span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError>
 where
     T: Into<Felt>,
 {
@@ -25,7 +25,7 @@ where
 /// In cases when the immediate value is 0, `PUSH` operation is replaced with `PAD`. Also, in cases
 /// when immediate value is 1, `PUSH` operation is replaced with `PAD INCR` because in most cases
 /// this will be more efficient than doing a `PUSH`.
-pub fn push_many<T>(imms: &[T], span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError>
+pub fn push_many<T>(imms: &[T], span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError>
 where
     T: Into<Felt> + Copy,
 {
@@ -45,7 +45,7 @@ pub fn locaddr(
     span: &mut SpanBuilder,
     index: u16,
     context: &AssemblyContext,
-) -> Result<Option<CodeBlock>, AssemblyError> {
+) -> Result<Option<MastNodeId>, AssemblyError> {
     local_to_absolute_addr(span, index, context.num_proc_locals())?;
     Ok(None)
 }
@@ -58,7 +58,7 @@ pub fn locaddr(
 pub fn caller(
     span: &mut SpanBuilder,
     context: &AssemblyContext,
-) -> Result<Option<CodeBlock>, AssemblyError> {
+) -> Result<Option<MastNodeId>, AssemblyError> {
     if !context.is_kernel() {
         return Err(AssemblyError::caller_out_of_kernel());
     }
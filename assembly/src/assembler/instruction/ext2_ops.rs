@@ -1,4 +1,4 @@
-use super::{AssemblyError, CodeBlock, Operation::*, SpanBuilder};
+use super::{AssemblyError, MastNodeId, Operation::*, SpanBuilder};
 use vm_core::AdviceInjector::Ext2Inv;
 
 /// Given a stack in the following initial configuration [b1, b0, a1, a0, ...] where a = (a0, a1)
@@ -6,7 +6,7 @@ use vm_core::AdviceInjector::Ext2Inv;
 /// operations outputs the result c = (c1, c0) where c1 = a1 + b1 and c0 = a0 + b0.
 ///
 /// This operation takes 5 VM cycles.
-pub fn ext2_add(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn ext2_add(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     #[rustfmt::skip]
     let ops = [
         Swap,           // [b0, b1, a1, a0, ...]
@@ -23,7 +23,7 @@ pub fn ext2_add(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyErr
 /// operations outputs the result c = (c1, c0) where c1 = a1 - b1 and c0 = a0 - b0.
 ///
 /// This operation takes 7 VM cycles.
-pub fn ext2_sub(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn ext2_sub(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     #[rustfmt::skip]
     let ops = [
         Neg,        // [-b1, b0, a1, a0, ...]
@@ -42,7 +42,7 @@ pub fn ext2_sub(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyErr
 /// outputs the product c = (c1, c0) where c0 = a0b0 - 2(a1b1) and c1 = (a0 + a1)(b0 + b1) - a0b0
 ///
 /// This operation takes 3 VM cycles.
-pub fn ext2_mul(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn ext2_mul(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     span.add_ops([Ext2Mul, Drop, Drop])
 }
 
@@ -51,7 +51,7 @@ pub fn ext2_mul(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyErr
 /// operations outputs the result c = (c1, c0) where c = a * b^-1.
 ///
 /// This operation takes 11 VM cycles.
-pub fn ext2_div(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn ext2_div(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     span.push_advice_injector(Ext2Inv);
     #[rustfmt::skip]
     let ops = [
@@ -75,7 +75,7 @@ pub fn ext2_div(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyErr
 /// [-a1, -a0, ...]
 ///
 /// This operation takes 4 VM cycles.
-pub fn ext2_neg(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn ext2_neg(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     #[rustfmt::skip]
     let ops = [
         Neg,            // [a1, a0, ...]
@@ -111,7 +111,7 @@ pub fn ext2_neg(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyErr
 /// assert b  = (1, 0) | (1, 0) is the multiplicative identity of extension field.
 ///
 /// This operation takes 8 VM cycles.
-pub fn ext2_inv(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn ext2_inv(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     span.push_advice_injector(Ext2Inv);
     #[rustfmt::skip]
     let ops = [
@@ -1,5 +1,5 @@
 use super::{
-    validate_param, AssemblyError, CodeBlock, Felt, FieldElement, Operation::*, SpanBuilder, ONE,
+    validate_param, AssemblyError, Felt, FieldElement, MastNodeId, Operation::*, SpanBuilder, ONE,
     ZERO,
 };
 use crate::MAX_EXP_BITS;
@@ -14,7 +14,7 @@ const TWO: Felt = Felt::new(2);
 /// Asserts that the top two words in the stack are equal.
 ///
 /// VM cycles: 11 cycles
-pub fn assertw(span: &mut SpanBuilder, err_code: u32) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn assertw(span: &mut SpanBuilder, err_code: u32) -> Result<Option<MastNodeId>, AssemblyError> {
     span.add_ops([
         MovUp4,
         Eq,
@@ -39,7 +39,7 @@ pub fn assertw(span: &mut SpanBuilder, err_code: u32) -> Result<Option<CodeBlock
 /// - else if imm = 1: INCR
 /// - else if imm = 2: INCR INCR
 /// - otherwise: PUSH(imm) ADD
-pub fn add_imm(span: &mut SpanBuilder, imm: Felt) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn add_imm(span: &mut SpanBuilder, imm: Felt) -> Result<Option<MastNodeId>, AssemblyError> {
     if imm == ZERO {
         span.add_op(Noop)
     } else if imm == ONE {
@@ -55,7 +55,7 @@ pub fn add_imm(span: &mut SpanBuilder, imm: Felt) -> Result<Option<CodeBlock>, A
 /// stack. Specifically, the sequences are:
 /// - if imm = 0: NOOP
 /// - otherwise: PUSH(-imm) ADD
-pub fn sub_imm(span: &mut SpanBuilder, imm: Felt) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn sub_imm(span: &mut SpanBuilder, imm: Felt) -> Result<Option<MastNodeId>, AssemblyError> {
     if imm == ZERO {
         span.add_op(Noop)
     } else {
@@ -68,7 +68,7 @@ pub fn sub_imm(span: &mut SpanBuilder, imm: Felt) -> Result<Option<CodeBlock>, A
 /// - if imm = 0: DROP PAD
 /// - else if imm = 1: NOOP
 /// - otherwise: PUSH(imm) MUL
-pub fn mul_imm(span: &mut SpanBuilder, imm: Felt) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn mul_imm(span: &mut SpanBuilder, imm: Felt) -> Result<Option<MastNodeId>, AssemblyError> {
     if imm == ZERO {
         span.add_ops([Drop, Pad])
     } else if imm == ONE {
@@ -86,7 +86,7 @@ pub fn mul_imm(span: &mut SpanBuilder, imm: Felt) -> Result<Option<CodeBlock>, A
 ///
 /// # Errors
 /// Returns an error if the immediate value is ZERO.
-pub fn div_imm(span: &mut SpanBuilder, imm: Felt) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn div_imm(span: &mut SpanBuilder, imm: Felt) -> Result<Option<MastNodeId>, AssemblyError> {
     if imm == ZERO {
         Err(AssemblyError::division_by_zero())
     } else if imm == ONE {
@@ -103,7 +103,7 @@ pub fn div_imm(span: &mut SpanBuilder, imm: Felt) -> Result<Option<CodeBlock>, A
 /// top of the stack.
 ///
 /// VM cycles: 16 cycles
-pub fn pow2(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn pow2(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     append_pow2_op(span);
     Ok(None)
 }
@@ -139,7 +139,7 @@ pub fn append_pow2_op(span: &mut SpanBuilder) {
 ///
 /// # Errors
 /// Returns an error if num_pow_bits is greater than 64.
-pub fn exp(span: &mut SpanBuilder, num_pow_bits: u8) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn exp(span: &mut SpanBuilder, num_pow_bits: u8) -> Result<Option<MastNodeId>, AssemblyError> {
     validate_param(num_pow_bits, 0..=MAX_EXP_BITS)?;
 
     // arranging the stack to prepare it for expacc instruction.
@@ -168,7 +168,7 @@ pub fn exp(span: &mut SpanBuilder, num_pow_bits: u8) -> Result<Option<CodeBlock>
 /// - pow = 6: 10 cycles
 /// - pow = 7: 12 cycles
 /// - pow > 7: 9 + Ceil(log2(pow))
-pub fn exp_imm(span: &mut SpanBuilder, pow: Felt) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn exp_imm(span: &mut SpanBuilder, pow: Felt) -> Result<Option<MastNodeId>, AssemblyError> {
     if pow.as_int() <= 7 {
         perform_exp_for_small_power(span, pow.as_int());
         Ok(None)
@@ -246,7 +246,7 @@ fn perform_exp_for_small_power(span: &mut SpanBuilder, pow: u64) {
 ///
 /// # Errors
 /// Returns an error if the logarithm argument (top stack element) equals ZERO.
-pub fn ilog2(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn ilog2(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     span.push_advice_injector(ILog2);
     span.push_op(AdvPop); // [ilog2, n, ...]
 
@@ -286,7 +286,7 @@ pub fn ilog2(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError>
 /// and the provided immediate value. Specifically, the sequences are:
 /// - if imm = 0: EQZ
 /// - otherwise: PUSH(imm) EQ
-pub fn eq_imm(span: &mut SpanBuilder, imm: Felt) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn eq_imm(span: &mut SpanBuilder, imm: Felt) -> Result<Option<MastNodeId>, AssemblyError> {
     if imm == ZERO {
         span.add_op(Eqz)
     } else {
@@ -298,7 +298,7 @@ pub fn eq_imm(span: &mut SpanBuilder, imm: Felt) -> Result<Option<CodeBlock>, As
 /// and the provided immediate value. Specifically, the sequences are:
 /// - if imm = 0: EQZ NOT
 /// - otherwise: PUSH(imm) EQ NOT
-pub fn neq_imm(span: &mut SpanBuilder, imm: Felt) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn neq_imm(span: &mut SpanBuilder, imm: Felt) -> Result<Option<MastNodeId>, AssemblyError> {
     if imm == ZERO {
         span.add_ops([Eqz, Not])
     } else {
@@ -309,7 +309,7 @@ pub fn neq_imm(span: &mut SpanBuilder, imm: Felt) -> Result<Option<CodeBlock>, A
 /// Appends a sequence of operations to check equality between two words at the top of the stack.
 ///
 /// This operation takes 15 VM cycles.
-pub fn eqw(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn eqw(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     span.add_ops([
         // duplicate first pair of for comparison(4th elements of each word) in reverse order
         // to avoid using dup.8 after stack shifting(dup.X where X > 7, takes more VM cycles )
@@ -324,7 +324,7 @@ pub fn eqw(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
 /// of 1 is pushed onto the stack if a < b. Otherwise, 0 is pushed.
 ///
 /// This operation takes 14 VM cycles.
-pub fn lt(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn lt(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     // Split both elements into high and low bits
     // 3 cycles
     split_elements(span);
@@ -350,7 +350,7 @@ pub fn lt(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
 /// A value of 1 is pushed onto the stack if a <= b. Otherwise, 0 is pushed.
 ///
 /// This operation takes 15 VM cycles.
-pub fn lte(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn lte(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     // Split both elements into high and low bits
     // 3 cycles
     split_elements(span);
@@ -376,7 +376,7 @@ pub fn lte(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
 /// of 1 is pushed onto the stack if a > b. Otherwise, 0 is pushed.
 ///
 /// This operation takes 15 VM cycles.
-pub fn gt(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn gt(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     // Split both elements into high and low bits
     // 3 cycles
     split_elements(span);
@@ -402,7 +402,7 @@ pub fn gt(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
 /// A value of 1 is pushed onto the stack if a >= b. Otherwise, 0 is pushed.
 ///
 /// This operation takes 16 VM cycles.
-pub fn gte(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn gte(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     // Split both elements into high and low bits
     // 3 cycles
     split_elements(span);
@@ -426,7 +426,7 @@ pub fn gte(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
 /// Checks if the top element in the stack is an odd number or not.
 ///
 /// Vm cycles: 5
-pub fn is_odd(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn is_odd(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     span.add_ops([U32split, Drop, Pad, Incr, U32and])
 }
 
@@ -1,7 +1,8 @@
 use super::{
-    push_felt, push_u32_value, validate_param, AssemblyContext, AssemblyError, CodeBlock, Felt,
-    Operation::*, SpanBuilder,
+    field_ops::assertw, push_felt, push_u32_value, validate_param, AssemblyContext, AssemblyError,
+    DataSegment, Felt, MastNodeId, Operation::*, SpanBuilder,
 };
+use vm_core::{AdviceInjector::MapValueToStack, Word};
 
 // INSTRUCTION PARSERS
 // ================================================================================================
@@ -26,7 +27,7 @@ pub fn mem_read(
     addr: Option<u32>,
     is_local: bool,
     is_single: bool,
-) -> Result<Option<CodeBlock>, AssemblyError> {
+) -> Result<Option<MastNodeId>, AssemblyError> {
     // if the address was provided as an immediate value, put it onto the stack
     if let Some(addr) = addr {
         if is_local {
@@ -76,7 +77,7 @@ pub fn mem_write_imm(
     addr: u32,
     is_local: bool,
     is_single: bool,
-) -> Result<Option<CodeBlock>, AssemblyError> {
+) -> Result<Option<MastNodeId>, AssemblyError> {
     if is_local {
         local_to_absolute_addr(span, addr as u16, context.num_proc_locals())?;
     } else {
@@ -93,6 +94,38 @@ pub fn mem_write_imm(
     Ok(None)
 }
 
+/// Appends operations to the span needed to load the specified data segment into memory.
+///
+/// The values of the segment are moved onto the advice stack from the advice map, where they are
+/// stored under the commitment to the segment, and are then piped into memory two words at a
+/// time. The hash of the piped values is checked against the commitment, and thus, the program
+/// fails if the advice provider supplies data which differs from the data of the segment. The
+/// stack is left unchanged.
+///
+/// VM cycles: 39 + num_words cycles, plus up to 5 cycles depending on the pushed values
+pub fn load_data_segment(span: &mut SpanBuilder, segment: &DataSegment) {
+    // push the commitment onto the stack and move the values of the segment onto the advice stack
+    let commitment: Word = segment.commitment().into();
+    commitment.iter().for_each(|&value| push_felt(span, value));
+    span.push_advice_injector(MapValueToStack {
+        include_len: false,
+        key_offset: 0,
+    });
+
+    // set up the hasher state and the write pointer: [C, B, A, write_ptr, COM, ...]
+    push_u32_value(span, segment.address());
+    span.push_ops([Pad; 12]);
+
+    // pipe the values into memory while hashing them
+    for _ in 0..segment.num_words() / 2 {
+        span.push_ops([Pipe, HPerm]);
+    }
+
+    // drop everything but the hash and check it against the commitment
+    span.push_ops([Drop, Drop, Drop, Drop, SwapW, Drop, Drop, Drop, Drop, MovUp4, Drop]);
+    assertw(span, 0).expect("assertw cannot fail");
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
@@ -1,6 +1,6 @@
 use super::{
-    Assembler, AssemblyContext, AssemblyError, CodeBlock, Felt, Instruction, Operation,
-    ProcedureId, RpoDigest, SpanBuilder, ONE, ZERO,
+    Assembler, AssemblyContext, AssemblyError, DataSegment, Felt, Instruction, MastNodeId,
+    Operation, ProcedureId, RpoDigest, SpanBuilder, ONE, ZERO,
 };
 use crate::utils::bound_into_included_u64;
 use core::ops::RangeBounds;
@@ -12,6 +12,7 @@ mod env_ops;
 mod ext2_ops;
 mod field_ops;
 mod mem_ops;
+pub(super) use mem_ops::load_data_segment;
 mod procedures;
 mod u32_ops;
 
@@ -26,7 +27,7 @@ impl Assembler {
         instruction: &Instruction,
         span: &mut SpanBuilder,
         ctx: &mut AssemblyContext,
-    ) -> Result<Option<CodeBlock>, AssemblyError> {
+    ) -> Result<Option<MastNodeId>, AssemblyError> {
         use Operation::*;
 
         // if the assembler is in debug mode, start tracking the instruction about to be executed;
@@ -345,7 +346,7 @@ impl Assembler {
 ///
 /// When the value is 0, PUSH operation is replaced with PAD. When the value is 1, PUSH operation
 /// is replaced with PAD INCR because in most cases this will be more efficient than doing a PUSH.
-fn push_u32_value(span: &mut SpanBuilder, value: u32) {
+pub(super) fn push_u32_value(span: &mut SpanBuilder, value: u32) {
     use Operation::*;
 
     if value == 0 {
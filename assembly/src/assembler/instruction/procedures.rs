@@ -1,5 +1,5 @@
 use super::{
-    Assembler, AssemblyContext, AssemblyError, CodeBlock, Operation, ProcedureId, RpoDigest,
+    Assembler, AssemblyContext, AssemblyError, MastNodeId, Operation, ProcedureId, RpoDigest,
     SpanBuilder,
 };
 use alloc::vec::Vec;
@@ -12,7 +12,7 @@ impl Assembler {
         &self,
         proc_idx: u16,
         context: &mut AssemblyContext,
-    ) -> Result<Option<CodeBlock>, AssemblyError> {
+    ) -> Result<Option<MastNodeId>, AssemblyError> {
         // register an "inlined" call to the procedure at the specified index in the module
         // currently being complied; this updates the callset of the procedure currently being
         // compiled
@@ -21,15 +21,16 @@ impl Assembler {
         // TODO: if the procedure consists of a single SPAN block, we could just append all
         // operations from that SPAN block to the span builder instead of returning a code block
 
-        // return the code block of the procedure
-        Ok(Some(proc.code().clone()))
+        // return the root node of the procedure; the procedure MAST is already in the assembler's
+        // MAST forest, and thus, it is referenced rather than copied
+        Ok(Some(proc.code()))
     }
 
     pub(super) fn exec_imported(
         &self,
         proc_id: &ProcedureId,
         context: &mut AssemblyContext,
-    ) -> Result<Option<CodeBlock>, AssemblyError> {
+    ) -> Result<Option<MastNodeId>, AssemblyError> {
         // make sure the procedure is in procedure cache
         self.ensure_procedure_is_in_cache(proc_id, context)?;
 
@@ -44,29 +45,30 @@ impl Assembler {
         // TODO: if the procedure consists of a single SPAN block, we could just append all
         // operations from that SPAN block to the span builder instead of returning a code block
 
-        // return the code block of the procedure
-        Ok(Some(proc.code().clone()))
+        // return the root node of the procedure; the procedure MAST is already in the assembler's
+        // MAST forest, and thus, it is referenced rather than copied
+        Ok(Some(proc.code()))
     }
 
     pub(super) fn call_local(
         &self,
         index: u16,
         context: &mut AssemblyContext,
-    ) -> Result<Option<CodeBlock>, AssemblyError> {
+    ) -> Result<Option<MastNodeId>, AssemblyError> {
         // register a "non-inlined" call to the procedure at the specified index in the module
         // currently being complied; this updates the callset of the procedure currently being
         // compiled
         let proc = context.register_local_call(index, false)?;
 
         // create a new CALL block for the procedure call and return
-        Ok(Some(CodeBlock::new_call(proc.mast_root())))
+        Ok(Some(self.mast_forest.borrow_mut().add_call(proc.mast_root())))
     }
 
     pub(super) fn call_mast_root(
         &self,
         mast_root: &RpoDigest,
         context: &mut AssemblyContext,
-    ) -> Result<Option<CodeBlock>, AssemblyError> {
+    ) -> Result<Option<MastNodeId>, AssemblyError> {
         // get the procedure from the assembler
         let proc_cache = self.proc_cache.borrow();
 
@@ -79,14 +81,14 @@ impl Assembler {
         }
 
         // create a new CALL block for the procedure call and return
-        Ok(Some(CodeBlock::new_call(*mast_root)))
+        Ok(Some(self.mast_forest.borrow_mut().add_call(*mast_root)))
     }
 
     pub(super) fn call_imported(
         &self,
         proc_id: &ProcedureId,
         context: &mut AssemblyContext,
-    ) -> Result<Option<CodeBlock>, AssemblyError> {
+    ) -> Result<Option<MastNodeId>, AssemblyError> {
         // make sure the procedure is in procedure cache
         self.ensure_procedure_is_in_cache(proc_id, context)?;
 
@@ -99,14 +101,14 @@ impl Assembler {
         context.register_external_call(proc, false)?;
 
         // create a new CALL block for the procedure call and return
-        Ok(Some(CodeBlock::new_call(proc.mast_root())))
+        Ok(Some(self.mast_forest.borrow_mut().add_call(proc.mast_root())))
     }
 
     pub(super) fn syscall(
         &self,
         proc_id: &ProcedureId,
         context: &mut AssemblyContext,
-    ) -> Result<Option<CodeBlock>, AssemblyError> {
+    ) -> Result<Option<MastNodeId>, AssemblyError> {
         // fetch from proc cache and check if its a kernel procedure
         // note: the assembler is expected to have all kernel procedures properly inserted in the
         // proc cache upon initialization, with their correct procedure ids
@@ -125,17 +127,17 @@ impl Assembler {
         context.register_external_call(proc, false)?;
 
         // create a new SYSCALL block for the procedure call and return
-        Ok(Some(CodeBlock::new_syscall(proc.mast_root())))
+        Ok(Some(self.mast_forest.borrow_mut().add_syscall(proc.mast_root())))
     }
 
-    pub(super) fn dynexec(&self) -> Result<Option<CodeBlock>, AssemblyError> {
+    pub(super) fn dynexec(&self) -> Result<Option<MastNodeId>, AssemblyError> {
         // create a new DYN block for the dynamic code execution and return
-        Ok(Some(CodeBlock::new_dyn()))
+        Ok(Some(self.mast_forest.borrow_mut().add_dyn()))
     }
 
-    pub(super) fn dyncall(&self) -> Result<Option<CodeBlock>, AssemblyError> {
+    pub(super) fn dyncall(&self) -> Result<Option<MastNodeId>, AssemblyError> {
         // create a new CALL block whose target is DYN
-        Ok(Some(CodeBlock::new_dyncall()))
+        Ok(Some(self.mast_forest.borrow_mut().add_dyncall()))
     }
 
     pub(super) fn procref_local(
@@ -143,7 +145,7 @@ impl Assembler {
         proc_idx: u16,
         context: &mut AssemblyContext,
         span: &mut SpanBuilder,
-    ) -> Result<Option<CodeBlock>, AssemblyError> {
+    ) -> Result<Option<MastNodeId>, AssemblyError> {
         // get root of the compiled local procedure and add it to the callset to be able to use
         // dynamic instructions with this procedure later
         let proc_root = context.register_local_call(proc_idx, false)?.mast_root();
@@ -158,7 +160,7 @@ impl Assembler {
         proc_id: &ProcedureId,
         context: &mut AssemblyContext,
         span: &mut SpanBuilder,
-    ) -> Result<Option<CodeBlock>, AssemblyError> {
+    ) -> Result<Option<MastNodeId>, AssemblyError> {
         // make sure the procedure is in procedure cache
         self.ensure_procedure_is_in_cache(proc_id, context)?;
 
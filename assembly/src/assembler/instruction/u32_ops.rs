@@ -1,6 +1,6 @@
 use super::{
     field_ops::append_pow2_op,
-    push_u32_value, validate_param, AssemblyError, CodeBlock, Felt,
+    push_u32_value, validate_param, AssemblyError, Felt, MastNodeId,
     Operation::{self, *},
     SpanBuilder, ZERO,
 };
@@ -24,7 +24,7 @@ pub enum U32OpMode {
 ///
 /// Implemented by executing DUP U32SPLIT SWAP DROP EQZ on each element in the word
 /// and combining the results using AND operation (total of 23 VM cycles)
-pub fn u32testw(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn u32testw(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     #[rustfmt::skip]
     let ops = [
          // Test the fourth element
@@ -49,7 +49,7 @@ pub fn u32testw(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyErr
 pub fn u32assertw(
     span: &mut SpanBuilder,
     err_code: Felt,
-) -> Result<Option<CodeBlock>, AssemblyError> {
+) -> Result<Option<MastNodeId>, AssemblyError> {
     #[rustfmt::skip]
     let ops = [
         // Test the first and the second elements
@@ -84,7 +84,7 @@ pub fn u32add(
     span: &mut SpanBuilder,
     op_mode: U32OpMode,
     imm: Option<u32>,
-) -> Result<Option<CodeBlock>, AssemblyError> {
+) -> Result<Option<MastNodeId>, AssemblyError> {
     handle_arithmetic_operation(span, U32add, op_mode, imm)
 }
 
@@ -102,7 +102,7 @@ pub fn u32sub(
     span: &mut SpanBuilder,
     op_mode: U32OpMode,
     imm: Option<u32>,
-) -> Result<Option<CodeBlock>, AssemblyError> {
+) -> Result<Option<MastNodeId>, AssemblyError> {
     handle_arithmetic_operation(span, U32sub, op_mode, imm)
 }
 
@@ -120,7 +120,7 @@ pub fn u32mul(
     span: &mut SpanBuilder,
     op_mode: U32OpMode,
     imm: Option<u32>,
-) -> Result<Option<CodeBlock>, AssemblyError> {
+) -> Result<Option<MastNodeId>, AssemblyError> {
     handle_arithmetic_operation(span, U32mul, op_mode, imm)
 }
 
@@ -134,7 +134,7 @@ pub fn u32mul(
 pub fn u32div(
     span: &mut SpanBuilder,
     imm: Option<u32>,
-) -> Result<Option<CodeBlock>, AssemblyError> {
+) -> Result<Option<MastNodeId>, AssemblyError> {
     handle_division(span, imm)?;
     span.add_op(Drop)
 }
@@ -149,7 +149,7 @@ pub fn u32div(
 pub fn u32mod(
     span: &mut SpanBuilder,
     imm: Option<u32>,
-) -> Result<Option<CodeBlock>, AssemblyError> {
+) -> Result<Option<MastNodeId>, AssemblyError> {
     handle_division(span, imm)?;
     span.add_ops([Swap, Drop])
 }
@@ -164,7 +164,7 @@ pub fn u32mod(
 pub fn u32divmod(
     span: &mut SpanBuilder,
     imm: Option<u32>,
-) -> Result<Option<CodeBlock>, AssemblyError> {
+) -> Result<Option<MastNodeId>, AssemblyError> {
     handle_division(span, imm)
 }
 
@@ -184,7 +184,7 @@ fn handle_arithmetic_operation(
     op: Operation,
     op_mode: U32OpMode,
     imm: Option<u32>,
-) -> Result<Option<CodeBlock>, AssemblyError> {
+) -> Result<Option<MastNodeId>, AssemblyError> {
     if let Some(imm) = imm {
         push_u32_value(span, imm);
     }
@@ -204,7 +204,7 @@ fn handle_arithmetic_operation(
 fn handle_division(
     span: &mut SpanBuilder,
     imm: Option<u32>,
-) -> Result<Option<CodeBlock>, AssemblyError> {
+) -> Result<Option<MastNodeId>, AssemblyError> {
     if let Some(imm) = imm {
         if imm == 0 {
             return Err(AssemblyError::division_by_zero());
@@ -224,7 +224,7 @@ fn handle_division(
 /// subtracting the element, flips the bits of the original value to perform a bitwise NOT.
 ///
 /// This takes 5 VM cycles.
-pub fn u32not(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn u32not(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     #[rustfmt::skip]
     let ops = [
         // Perform the operation
@@ -247,7 +247,10 @@ pub fn u32not(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError
 /// VM cycles per mode:
 /// - u32shl: 18 cycles
 /// - u32shl.b: 3 cycles
-pub fn u32shl(span: &mut SpanBuilder, imm: Option<u8>) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn u32shl(
+    span: &mut SpanBuilder,
+    imm: Option<u8>,
+) -> Result<Option<MastNodeId>, AssemblyError> {
     prepare_bitwise::<MAX_U32_SHIFT_VALUE>(span, imm)?;
     if imm != Some(0) {
         span.add_ops([U32mul, Drop])
@@ -264,7 +267,10 @@ pub fn u32shl(span: &mut SpanBuilder, imm: Option<u8>) -> Result<Option<CodeBloc
 /// VM cycles per mode:
 /// - u32shr: 18 cycles
 /// - u32shr.b: 3 cycles
-pub fn u32shr(span: &mut SpanBuilder, imm: Option<u8>) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn u32shr(
+    span: &mut SpanBuilder,
+    imm: Option<u8>,
+) -> Result<Option<MastNodeId>, AssemblyError> {
     prepare_bitwise::<MAX_U32_SHIFT_VALUE>(span, imm)?;
     if imm != Some(0) {
         span.add_ops([U32div, Drop])
@@ -284,7 +290,7 @@ pub fn u32shr(span: &mut SpanBuilder, imm: Option<u8>) -> Result<Option<CodeBloc
 pub fn u32rotl(
     span: &mut SpanBuilder,
     imm: Option<u8>,
-) -> Result<Option<CodeBlock>, AssemblyError> {
+) -> Result<Option<MastNodeId>, AssemblyError> {
     prepare_bitwise::<MAX_U32_ROTATE_VALUE>(span, imm)?;
     if imm != Some(0) {
         span.add_ops([U32mul, Add])
@@ -304,7 +310,7 @@ pub fn u32rotl(
 pub fn u32rotr(
     span: &mut SpanBuilder,
     imm: Option<u8>,
-) -> Result<Option<CodeBlock>, AssemblyError> {
+) -> Result<Option<MastNodeId>, AssemblyError> {
     match imm {
         Some(0) => {
             // if rotation is performed by 0, do nothing (Noop)
@@ -326,7 +332,7 @@ pub fn u32rotr(
 /// Translates u32popcnt assembly instructions to VM operations.
 ///
 /// This operation takes 33 cycles.
-pub fn u32popcnt(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn u32popcnt(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     #[rustfmt::skip]
     let ops = [
         // i = i - ((i >> 1) & 0x55555555);
@@ -363,7 +369,7 @@ pub fn u32popcnt(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyEr
 /// provider).
 ///
 /// This operation takes 37 VM cycles.
-pub fn u32clz(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn u32clz(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     span.push_advice_injector(U32Clz);
     span.push_op(AdvPop); // [clz, n, ...]
 
@@ -375,7 +381,7 @@ pub fn u32clz(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError
 /// provider).
 ///
 /// This operation takes 34 VM cycles.
-pub fn u32ctz(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn u32ctz(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     span.push_advice_injector(U32Ctz);
     span.push_op(AdvPop); // [ctz, n, ...]
 
@@ -387,7 +393,7 @@ pub fn u32ctz(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError
 /// provider).
 ///
 /// This operation takes 36 VM cycles.
-pub fn u32clo(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn u32clo(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     span.push_advice_injector(U32Clo);
     span.push_op(AdvPop); // [clo, n, ...]
 
@@ -399,7 +405,7 @@ pub fn u32clo(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError
 /// provider).
 ///
 /// This operation takes 33 VM cycles.
-pub fn u32cto(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn u32cto(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     span.push_advice_injector(U32Cto);
     span.push_op(AdvPop); // [cto, n, ...]
 
@@ -467,7 +473,7 @@ fn prepare_bitwise<const MAX_VALUE: u8>(
 /// `[clz, n, ... ] -> [clz, ... ]`
 ///
 /// VM cycles: 36
-fn calculate_clz(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+fn calculate_clz(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     // [clz, n, ...]
     #[rustfmt::skip]
     let ops_group_1 = [
@@ -542,7 +548,7 @@ fn calculate_clz(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyEr
 /// `[clo, n, ... ] -> [clo, ... ]`
 ///
 /// VM cycles: 35
-fn calculate_clo(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+fn calculate_clo(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     // [clo, n, ...]
     #[rustfmt::skip]
     let ops_group_1 = [
@@ -617,7 +623,7 @@ fn calculate_clo(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyEr
 /// `[ctz, n, ... ] -> [ctz, ... ]`
 ///
 /// VM cycles: 33
-fn calculate_ctz(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+fn calculate_ctz(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     // [ctz, n, ...]
     #[rustfmt::skip]
     let ops_group_1 = [
@@ -691,7 +697,7 @@ fn calculate_ctz(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyEr
 /// `[cto, n, ... ] -> [cto, ... ]`
 ///
 /// VM cycles: 32
-fn calculate_cto(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+fn calculate_cto(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     // [cto, n, ...]
     #[rustfmt::skip]
     let ops_group_1 = [
@@ -735,7 +741,7 @@ fn calculate_cto(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyEr
 /// Translates u32lt assembly instructions to VM operations.
 ///
 /// This operation takes 3 cycles.
-pub fn u32lt(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn u32lt(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     compute_lt(span);
 
     Ok(None)
@@ -744,7 +750,7 @@ pub fn u32lt(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError>
 /// Translates u32lte assembly instructions to VM operations.
 ///
 /// This operation takes 5 cycles.
-pub fn u32lte(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn u32lte(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     // Compute the lt with reversed number to get a gt check
     span.push_op(Swap);
     compute_lt(span);
@@ -756,7 +762,7 @@ pub fn u32lte(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError
 /// Translates u32gt assembly instructions to VM operations.
 ///
 /// This operation takes 4 cycles.
-pub fn u32gt(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn u32gt(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     // Reverse the numbers so we can get a gt check.
     span.push_op(Swap);
 
@@ -768,7 +774,7 @@ pub fn u32gt(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError>
 /// Translates u32gte assembly instructions to VM operations.
 ///
 /// This operation takes 4 cycles.
-pub fn u32gte(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn u32gte(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     compute_lt(span);
 
     // Flip the final results to get the gte results.
@@ -782,7 +788,7 @@ pub fn u32gte(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError
 /// Then we finally drop the top element to keep the min.
 ///
 /// This operation takes 8 cycles.
-pub fn u32min(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn u32min(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     compute_max_and_min(span);
 
     // Drop the max and keep the min
@@ -796,7 +802,7 @@ pub fn u32min(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError
 /// Then we finally drop the 2nd element to keep the max.
 ///
 /// This operation takes 9 cycles.
-pub fn u32max(span: &mut SpanBuilder) -> Result<Option<CodeBlock>, AssemblyError> {
+pub fn u32max(span: &mut SpanBuilder) -> Result<Option<MastNodeId>, AssemblyError> {
     compute_max_and_min(span);
 
     // Drop the min and keep the max
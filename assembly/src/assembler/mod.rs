@@ -1,17 +1,26 @@
 use super::{
-    ast::{instrument, Instruction, ModuleAst, Node, ProcedureAst, ProgramAst},
+    ast::{
+        instrument, CodeBody, ImportedConstants, Instruction, ModuleAst, Node, ProcedureAst,
+        ProgramAst, StackEffectAnalyzer,
+    },
     crypto::hash::RpoDigest,
-    AssemblyError, CallSet, CodeBlock, CodeBlockTable, Felt, Kernel, Library, LibraryError,
-    LibraryPath, Module, NamedProcedure, Operation, Procedure, ProcedureId, ProcedureName, Program,
-    ONE, ZERO,
+    AssemblyError, AssemblyWarning, CallSet, CodeBlockTable, DataSegment, Felt, Kernel, Library,
+    LibraryError, LibraryPath, MastForest, MastNodeId, Module, NamedProcedure, Operation,
+    Procedure, ProcedureId, ProcedureName, Program, ONE, ZERO,
 };
-use alloc::collections::BTreeMap;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
 use alloc::vec::Vec;
-use core::{borrow::Borrow, cell::RefCell};
-use vm_core::{utils::group_vector_elements, Decorator, DecoratorList};
+use core::{borrow::Borrow, cell::RefCell, mem};
+use vm_core::{
+    utils::group_vector_elements, CodeLocation, Decorator, DecoratorList, MastNode,
+    ProcedureLocation, SourceMap, SpanLocations,
+};
 
 mod instruction;
 
+mod optimizer;
+
 mod module_provider;
 use module_provider::ModuleProvider;
 
@@ -27,20 +36,57 @@ use procedure_cache::ProcedureCache;
 #[cfg(test)]
 mod tests;
 
+// CONSTANTS
+// ================================================================================================
+
+/// The maximum number of iterations of a `for` loop for which the loop is unrolled; loops with
+/// more iterations are compiled into LOOP blocks.
+const MAX_UNROLLED_FOR_ITERATIONS: u32 = 4;
+
 // ASSEMBLER
 // ================================================================================================
 /// Miden Assembler which can be used to convert Miden assembly source code into program MAST.
 ///
+/// All code compiled by the assembler is added to a single deduplicated [MastForest]. Thus, the
+/// MAST of a procedure is stored only once regardless of how many times the procedure is invoked.
+/// When a program is compiled, only the nodes reachable from the program are copied from this
+/// forest into the forest of the program.
+///
 /// The assembler can be instantiated in several ways using a "builder" pattern. Specifically:
 /// - If `with_kernel()` or `with_kernel_module()` methods are not used, the assembler will be
 ///   instantiated with a default empty kernel. Programs compiled using such assembler
 ///   cannot make calls to kernel procedures via `syscall` instruction.
+/// - If `with_source_map()` method is used, the assembler will attach a [SourceMap] to compiled
+///   programs. The source map maps operations of the program to the locations in the source code
+///   they were compiled from, provided that the source code contains location information.
+/// - If `with_optimizations()` method is used, the assembler will apply peephole optimizations to
+///   the operations of every SPAN block it builds - e.g., `swap swap` sequences are removed, and
+///   `push.1 add` sequences are replaced with a single `INCR` operation. Optimized programs
+///   execute in fewer cycles, but have different MAST roots than unoptimized ones.
+/// - If `with_constant()` or `with_constants()` methods are used, the compiled code can reference
+///   the specified constants as if they were declared in the compiled source; this way parameters
+///   (e.g., the depth of a Merkle tree) can be supplied to the code without generating its source.
+///
+/// Error messages of the assertions in the compiled code (e.g., `assert.err="invalid value"`) are
+/// always attached to the compiled programs, so that failed assertions can be explained.
+///
+/// Data segments declared in a program (e.g., `data.100=[1, 2, 3]`) are loaded into memory by code
+/// which the assembler places before the program body, and are attached to the compiled program,
+/// so that they can be supplied to the program via the advice provider at execution time.
 #[derive(Default)]
 pub struct Assembler {
     kernel: Kernel,
     module_provider: ModuleProvider,
+    imported_constants: ImportedConstants,
     proc_cache: RefCell<ProcedureCache>,
+    mast_forest: RefCell<MastForest>,
+    source_map: RefCell<SourceMap>,
+    module_errors: RefCell<ErrorCodeMap>,
+    program_errors: RefCell<ErrorCodeMap>,
+    data_segments: RefCell<Vec<DataSegment>>,
     in_debug_mode: bool,
+    emit_source_map: bool,
+    optimize: bool,
 }
 
 impl Assembler {
@@ -53,12 +99,87 @@ impl Assembler {
         self
     }
 
+    /// Instructs the assembler to emit source maps for compiled programs.
+    ///
+    /// Source locations are tracked only for the code compiled after this method is invoked.
+    pub fn with_source_map(mut self, emit_source_map: bool) -> Self {
+        self.emit_source_map = emit_source_map;
+        self
+    }
+
+    /// Instructs the assembler to apply peephole optimizations to the compiled code.
+    ///
+    /// Optimizations are applied only to the code compiled after this method is invoked.
+    pub fn with_optimizations(mut self, optimize: bool) -> Self {
+        self.optimize = optimize;
+        self
+    }
+
+    /// Enables the specified features for the evaluation of conditional compilation directives
+    /// (e.g., `#if DEBUG`) in the sources compiled by the assembler.
+    ///
+    /// Since the directives are evaluated when the sources are parsed, the MAST roots of the
+    /// compiled procedures reflect the enabled features; modules of libraries are parsed when the
+    /// libraries are built, and thus, are not affected by this method.
+    ///
+    /// # Errors
+    /// Returns an error if the assembler has already compiled any procedures (e.g., the procedures
+    /// of a kernel), as the procedures cached by the assembler could have been compiled with
+    /// different features.
+    pub fn with_features<I, S>(mut self, features: I) -> Result<Self, AssemblyError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.ensure_no_compiled_procedures()?;
+        self.imported_constants = mem::take(&mut self.imported_constants).with_features(features);
+        Ok(self)
+    }
+
+    /// Defines an external constant with the specified name and value, which can be referenced by
+    /// the sources compiled by the assembler (e.g., `push.TREE_DEPTH` or `const.B=TREE_DEPTH*2`).
+    ///
+    /// External constants cannot be redeclared via `const` statements of the compiled sources;
+    /// attempting to do so results in a compilation error. As with features, modules of libraries
+    /// are not affected by this method (see [Assembler::with_features()]).
+    ///
+    /// # Errors
+    /// Returns an error if the assembler has already compiled any procedures.
+    ///
+    /// # Panics
+    /// Panics if the name is not a valid constant name.
+    pub fn with_constant<S: Into<String>>(
+        mut self,
+        name: S,
+        value: Felt,
+    ) -> Result<Self, AssemblyError> {
+        self.ensure_no_compiled_procedures()?;
+        self.imported_constants =
+            mem::take(&mut self.imported_constants).with_constant(name, value);
+        Ok(self)
+    }
+
+    /// Defines the specified external constants; see [Assembler::with_constant()] for details.
+    pub fn with_constants<I, S>(self, constants: I) -> Result<Self, AssemblyError>
+    where
+        I: IntoIterator<Item = (S, Felt)>,
+        S: Into<String>,
+    {
+        constants
+            .into_iter()
+            .try_fold(self, |assembler, (name, value)| assembler.with_constant(name, value))
+    }
+
     /// Adds the library to provide modules for the compilation.
+    ///
+    /// Constants exported from the modules of the library can be referenced by the compiled code
+    /// after the modules are imported.
     pub fn with_library<L>(mut self, library: &L) -> Result<Self, AssemblyError>
     where
         L: Library,
     {
         self.module_provider.add_library(library)?;
+        self.imported_constants = mem::take(&mut self.imported_constants).with_library(library);
         Ok(self)
     }
 
@@ -76,10 +197,17 @@ impl Assembler {
     /// # Errors
     /// Returns an error if compiling kernel source results in an error.
     ///
+    /// Warnings about the kernel source are discarded; to get them, parse the source via
+    /// [ModuleAst::parse_with_warnings()] and use [Assembler::with_kernel_module()].
+    ///
     /// # Panics
     /// Panics if the assembler has already been used to compile programs.
     pub fn with_kernel(self, kernel_source: &str) -> Result<Self, AssemblyError> {
-        let kernel_ast = ModuleAst::parse(kernel_source)?;
+        let (kernel_ast, _) =
+            ModuleAst::parse_with_imported_constants(kernel_source, &self.imported_constants)
+                .map_err(|errors| {
+                    AssemblyError::parsing_errors(errors, LibraryPath::KERNEL_PATH)
+                })?;
         self.with_kernel_module(kernel_ast)
     }
 
@@ -108,6 +236,21 @@ impl Assembler {
         self.in_debug_mode
     }
 
+    /// Returns true if this assembler emits source maps for compiled programs.
+    pub fn emits_source_map(&self) -> bool {
+        self.emit_source_map
+    }
+
+    /// Returns true if this assembler applies peephole optimizations to the compiled code.
+    pub fn optimizations_enabled(&self) -> bool {
+        self.optimize
+    }
+
+    /// Returns the constants exported from the modules of the libraries added to this assembler.
+    pub fn imported_constants(&self) -> &ImportedConstants {
+        &self.imported_constants
+    }
+
     /// Returns a reference to the kernel for this assembler.
     ///
     /// If the assembler was instantiated without a kernel, the internal kernel will be empty.
@@ -122,17 +265,41 @@ impl Assembler {
     /// on Miden VM.
     ///
     /// # Errors
-    /// Returns an error if parsing or compilation of the specified program fails.
+    /// Returns an error if parsing or compilation of the specified program fails. If several
+    /// errors are found, all of them are reported via [AssemblyError::Multiple].
+    ///
+    /// Warnings about the source code are discarded; to get them, use
+    /// [Assembler::compile_with_warnings()].
     pub fn compile<S>(&self, source: S) -> Result<Program, AssemblyError>
+    where
+        S: AsRef<str>,
+    {
+        self.compile_with_warnings(source).map(|(program, _)| program)
+    }
+
+    /// Compiles the provided source code into a [Program], and returns it together with the
+    /// warnings about the source code which is valid but is likely to contain a mistake (e.g.,
+    /// unused imports, procedures, or constants). See [ProgramAst::parse_with_warnings()] for the
+    /// list of reported warnings.
+    ///
+    /// # Errors
+    /// Returns an error if parsing or compilation of the specified program fails. If several
+    /// errors are found, all of them are reported via [AssemblyError::Multiple].
+    pub fn compile_with_warnings<S>(
+        &self,
+        source: S,
+    ) -> Result<(Program, Vec<AssemblyWarning>), AssemblyError>
     where
         S: AsRef<str>,
     {
         // parse the program into an AST
         let source = source.as_ref();
-        let program = ProgramAst::parse(source)?;
+        let (program, _) =
+            ProgramAst::parse_with_imported_constants(source, &self.imported_constants)
+                .map_err(|errors| AssemblyError::parsing_errors(errors, LibraryPath::EXEC_PATH))?;
 
         // compile the program and return
-        self.compile_ast(&program)
+        self.compile_ast_with_warnings(&program)
     }
 
     /// Compiles the provided abstract syntax tree into a [Program]. The resulting program can be
@@ -140,50 +307,99 @@ impl Assembler {
     ///
     /// # Errors
     /// Returns an error if the compilation of the specified program fails.
+    ///
+    /// Warnings about the source code of the program are discarded; to get them, use
+    /// [Assembler::compile_ast_with_warnings()].
     #[instrument("compile_ast", skip_all)]
     pub fn compile_ast(&self, program: &ProgramAst) -> Result<Program, AssemblyError> {
         // compile the program
         let mut context = AssemblyContext::for_program(Some(program));
         let program_root = self.compile_in_context(program, &mut context)?;
 
-        // convert the context into a call block table for the program
-        let cb_table = context.into_cb_table(&self.proc_cache.borrow())?;
-
         // build and return the program
-        Ok(Program::with_kernel(program_root, self.kernel.clone(), cb_table))
+        self.build_program(program_root, context)
+    }
+
+    /// Compiles the provided abstract syntax tree into a [Program], and returns it together with
+    /// the warnings about the source code of the program (see [ProgramAst::warnings()]).
+    ///
+    /// # Errors
+    /// Returns an error if the compilation of the specified program fails.
+    pub fn compile_ast_with_warnings(
+        &self,
+        program: &ProgramAst,
+    ) -> Result<(Program, Vec<AssemblyWarning>), AssemblyError> {
+        let warnings = program.warnings().to_vec();
+        Ok((self.compile_ast(program)?, warnings))
     }
 
-    /// Compiles the provided [ProgramAst] into a program and returns the program root
-    /// ([CodeBlock]). Mutates the provided context by adding all of the call targets of
-    /// the program to the [CallSet].
+    /// Compiles the provided [ProgramAst] into a program and returns the ID of the program root
+    /// node in the assembler's MAST forest. Mutates the provided context by adding all of the call
+    /// targets of the program to the [CallSet].
     ///
     /// # Errors
     /// - If the provided context is not appropriate for compiling a program.
     /// - If any of the local procedures defined in the program are exported.
+    /// - If any of the local procedures does not match its declared signature.
     /// - If compilation of any of the local procedures fails.
     /// - if compilation of the program body fails.
+    ///
+    /// Errors in all local procedures and in the program body are reported together.
+    ///
+    /// If the program declares data segments, the returned root executes the code loading these
+    /// segments into memory before the program body.
     pub fn compile_in_context(
         &self,
         program: &ProgramAst,
         context: &mut AssemblyContext,
-    ) -> Result<CodeBlock, AssemblyError> {
+    ) -> Result<MastNodeId, AssemblyError> {
         // check to ensure that the context is appropriate for compiling a program
         if context.current_context_name() != ProcedureName::main().as_str() {
             return Err(AssemblyError::InvalidProgramAssemblyContext);
         }
 
-        // compile all local procedures; this will add the procedures to the specified context
+        // error codes of the previously compiled program do not apply to this program
+        let error_codes = program.explicit_error_codes();
+        self.module_errors
+            .borrow()
+            .check_collisions(program.error_messages(), &error_codes)?;
+        *self.program_errors.borrow_mut() = ErrorCodeMap {
+            messages: program.error_messages().clone(),
+            explicit: error_codes,
+        };
+        *self.data_segments.borrow_mut() = program.data_segments().to_vec();
+
+        // compile all local procedures; this will add the procedures to the specified context.
+        // compilation continues after a failed procedure so that all errors are reported
+        let mut errors = check_proc_signatures(program.procedures(), context);
+        errors.extend(check_for_loops(program.procedures(), Some(program.body()), context));
         for proc_ast in program.procedures() {
             if proc_ast.is_export {
-                return Err(AssemblyError::exported_proc_in_program(&proc_ast.name));
+                let location = get_proc_location(proc_ast, context);
+                errors.push(
+                    AssemblyError::exported_proc_in_program(&proc_ast.name)
+                        .with_source_location(location.as_ref()),
+                );
+            }
+            if let Err(err) = self.compile_procedure(proc_ast, context) {
+                errors.push(err);
             }
-            self.compile_procedure(proc_ast, context)?;
         }
 
         // compile the program body
-        let program_root = self.compile_body(program.body().nodes().iter(), context, None)?;
-
-        Ok(program_root)
+        match self.compile_body(program.body(), context, None) {
+            Ok(program_root) if errors.is_empty() => {
+                match self.compile_data_segments(program.data_segments()) {
+                    Some(init) => Ok(self.mast_forest.borrow_mut().add_join(init, program_root)),
+                    None => Ok(program_root),
+                }
+            }
+            Ok(_) => Err(AssemblyError::multiple(errors)),
+            Err(err) => {
+                errors.push(err);
+                Err(AssemblyError::multiple(errors))
+            }
+        }
     }
 
     // MODULE COMPILER
@@ -192,10 +408,16 @@ impl Assembler {
     /// Compiles all procedures in the specified module and adds them to the procedure cache.
     /// Returns a vector of procedure digests for all exported procedures in the module.
     ///
+    /// Warnings about the source code of the module are not reported by this method; they are
+    /// available via [ModuleAst::warnings()].
+    ///
     /// # Errors
     /// - If a module with the same path already exists in the module stack of the
     ///   [AssemblyContext].
     /// - If a lock to the [ProcedureCache] can not be attained.
+    /// - If any of the procedures in the module does not match its declared signature, or if
+    ///   compilation of any of the procedures fails; errors in all procedures of the module are
+    ///   reported together.
     #[instrument(level = "trace",
                  name = "compile_module",
                  fields(module = path.unwrap_or(&LibraryPath::anon_path()).path()), skip_all)]
@@ -208,6 +430,14 @@ impl Assembler {
         // a variable to track MAST roots of all procedures exported from this module
         let mut proc_roots = Vec::new();
         context.begin_module(path.unwrap_or(&LibraryPath::anon_path()), module)?;
+        let error_codes = module.explicit_error_codes();
+        self.module_errors
+            .borrow()
+            .check_collisions(module.error_messages(), &error_codes)?;
+        self.program_errors
+            .borrow()
+            .check_collisions(module.error_messages(), &error_codes)?;
+        self.module_errors.borrow_mut().extend(module.error_messages(), error_codes);
 
         // process all re-exported procedures
         for reexporteed_proc in module.reexported_procs().iter() {
@@ -242,8 +472,16 @@ impl Assembler {
         // compile all local (internal end exported) procedures in the module; once the compilation
         // is complete, we get all compiled procedures (and their combined callset) from the
         // context
-        for proc_ast in module.procs().iter() {
-            self.compile_procedure(proc_ast, context)?;
+        let mut errors = check_proc_signatures(module.procs(), context);
+        errors.extend(check_for_loops(module.procs(), None, context));
+        errors.extend(
+            module
+                .procs()
+                .iter()
+                .filter_map(|proc_ast| self.compile_procedure(proc_ast, context).err()),
+        );
+        if !errors.is_empty() {
+            return Err(AssemblyError::multiple(errors));
         }
         let (module_procs, module_callset) = context.complete_module()?;
 
@@ -276,13 +514,45 @@ impl Assembler {
     // --------------------------------------------------------------------------------------------
 
     /// Compiles procedure AST into MAST and adds the complied procedure to the provided context.
+    ///
+    /// If compilation of the procedure body fails, a placeholder procedure is added to the
+    /// context instead, so that the compilation of the remaining procedures can continue.
     fn compile_procedure(
         &self,
         proc: &ProcedureAst,
         context: &mut AssemblyContext,
     ) -> Result<(), AssemblyError> {
-        context.begin_proc(&proc.name, proc.is_export, proc.num_locals)?;
-        let code = if proc.num_locals > 0 {
+        context
+            .begin_proc(&proc.name, proc.is_export, proc.num_locals)
+            .map_err(|err| err.with_source_location(get_proc_location(proc, context).as_ref()))?;
+
+        let module_depth = context.module_depth();
+        match self.compile_procedure_body(proc, context) {
+            Ok(code) => {
+                let mast_root = self.mast_forest.borrow()[code].hash();
+                if self.emit_source_map {
+                    self.track_proc_location(proc, mast_root, code, context);
+                }
+                context.complete_proc(mast_root, code);
+                Ok(())
+            }
+            Err(err) => {
+                let code = self.mast_forest.borrow_mut().add_span(vec![Operation::Noop], vec![]);
+                let mast_root = self.mast_forest.borrow()[code].hash();
+                context.abort_proc(module_depth, mast_root, code);
+                Err(err)
+            }
+        }
+    }
+
+    /// Compiles the body of the procedure into MAST, adds the MAST to the assembler's MAST forest,
+    /// and returns the ID of its root node.
+    fn compile_procedure_body(
+        &self,
+        proc: &ProcedureAst,
+        context: &mut AssemblyContext,
+    ) -> Result<MastNodeId, AssemblyError> {
+        if proc.num_locals > 0 {
             // for procedures with locals, we need to update fmp register before and after the
             // procedure body is executed. specifically:
             // - to allocate procedure locals we need to increment fmp by the number of locals
@@ -292,38 +562,47 @@ impl Assembler {
                 prologue: vec![Operation::Push(num_locals), Operation::FmpUpdate],
                 epilogue: vec![Operation::Push(-num_locals), Operation::FmpUpdate],
             };
-            self.compile_body(proc.body.nodes().iter(), context, Some(wrapper))?
+            self.compile_body(&proc.body, context, Some(wrapper))
         } else {
-            self.compile_body(proc.body.nodes().iter(), context, None)?
-        };
-
-        context.complete_proc(code);
-
-        Ok(())
+            self.compile_body(&proc.body, context, None)
+        }
     }
 
     // CODE BODY COMPILER
     // --------------------------------------------------------------------------------------------
 
-    /// TODO: add comments
-    fn compile_body<A, N>(
+    /// Compiles the provided sequence of AST nodes into MAST, adds the MAST to the assembler's
+    /// MAST forest, and returns the ID of its root node.
+    ///
+    /// If the wrapper is provided, the prologue and the epilogue of the wrapper are executed
+    /// before and after the compiled body respectively.
+    ///
+    /// If the assembler emits source maps, the operations of every instruction are mapped to the
+    /// location of the instruction, and the operations of the epilogue are mapped to the location
+    /// of the `end` token terminating the body.
+    fn compile_body(
         &self,
-        body: A,
+        body: &CodeBody,
         context: &mut AssemblyContext,
         wrapper: Option<BodyWrapper>,
-    ) -> Result<CodeBlock, AssemblyError>
-    where
-        A: Iterator<Item = N>,
-        N: Borrow<Node>,
-    {
-        let mut blocks: Vec<CodeBlock> = Vec::new();
-        let mut span = SpanBuilder::new(wrapper);
+    ) -> Result<MastNodeId, AssemblyError> {
+        let mut blocks: Vec<MastNodeId> = Vec::new();
+        let mut span = SpanBuilder::new(wrapper).with_optimizations(self.optimize);
 
-        for node in body {
-            match node.borrow() {
+        for (idx, node) in body.nodes().iter().enumerate() {
+            match node {
                 Node::Instruction(inner) => {
-                    if let Some(block) = self.compile_instruction(inner, &mut span, context)? {
-                        span.extract_span_into(&mut blocks);
+                    if self.emit_source_map {
+                        if let Some(location) = get_code_location(body, idx, context) {
+                            span.track_location(location);
+                        }
+                    }
+                    let block =
+                        self.compile_instruction(inner, &mut span, context).map_err(|err| {
+                            err.with_source_location(get_code_location(body, idx, context).as_ref())
+                        })?;
+                    if let Some(block) = block {
+                        self.extract_span_into(&mut span, &mut blocks);
                         blocks.push(block);
                     }
                 }
@@ -332,52 +611,197 @@ impl Assembler {
                     true_case,
                     false_case,
                 } => {
-                    span.extract_span_into(&mut blocks);
+                    self.extract_span_into(&mut span, &mut blocks);
 
-                    let true_case = self.compile_body(true_case.nodes().iter(), context, None)?;
+                    let true_case = self.compile_body(true_case, context, None)?;
 
                     // else is an exception because it is optional; hence, will have to be replaced
                     // by noop span
                     let false_case = if !false_case.nodes().is_empty() {
-                        self.compile_body(false_case.nodes().iter(), context, None)?
+                        self.compile_body(false_case, context, None)?
                     } else {
-                        CodeBlock::new_span(vec![Operation::Noop])
+                        self.mast_forest.borrow_mut().add_span(vec![Operation::Noop], Vec::new())
                     };
 
-                    let block = CodeBlock::new_split(true_case, false_case);
+                    let block = self.mast_forest.borrow_mut().add_split(true_case, false_case);
+                    self.track_branch_location(block, body, idx, context);
 
                     blocks.push(block);
                 }
 
                 Node::Repeat { times, body } => {
-                    span.extract_span_into(&mut blocks);
+                    self.extract_span_into(&mut span, &mut blocks);
 
-                    let block = self.compile_body(body.nodes().iter(), context, None)?;
+                    let block = self.compile_body(body, context, None)?;
 
                     for _ in 0..*times {
-                        blocks.push(block.clone());
+                        blocks.push(block);
+                    }
+                }
+
+                Node::For {
+                    times,
+                    body: loop_body,
+                } => {
+                    if self.emit_source_map {
+                        if let Some(location) = get_code_location(body, idx, context) {
+                            span.track_location(location);
+                        }
                     }
+                    self.compile_for_loop(*times, loop_body, context, &mut span, &mut blocks)?;
                 }
 
-                Node::While { body } => {
-                    span.extract_span_into(&mut blocks);
+                Node::While { body: loop_body } => {
+                    self.extract_span_into(&mut span, &mut blocks);
 
-                    let block = self.compile_body(body.nodes().iter(), context, None)?;
-                    let block = CodeBlock::new_loop(block);
+                    let block = self.compile_body(loop_body, context, None)?;
+                    let block = self.mast_forest.borrow_mut().add_loop(block);
+                    self.track_branch_location(block, body, idx, context);
 
                     blocks.push(block);
                 }
             }
         }
 
-        span.extract_final_span_into(&mut blocks);
+        if self.emit_source_map {
+            if let Some(location) = get_code_location(body, body.nodes().len(), context) {
+                span.track_location(location);
+            }
+        }
+
+        let mut mast_forest = self.mast_forest.borrow_mut();
+        let mut source_map = self.source_map.borrow_mut();
+        span.extract_final_span_into(&mut blocks, &mut mast_forest, &mut source_map);
         Ok(if blocks.is_empty() {
-            CodeBlock::new_span(vec![Operation::Noop])
+            mast_forest.add_span(vec![Operation::Noop], Vec::new())
         } else {
-            combine_blocks(blocks)
+            combine_blocks(blocks, &mut mast_forest, &mut source_map)
         })
     }
 
+    /// Compiles a `for` loop which executes the provided body the specified number of times, and
+    /// appends the resulting blocks to the provided list of blocks.
+    ///
+    /// The index of the current iteration is at the top of the stack when the body is executed,
+    /// and is dropped from the stack after the last iteration. Loops with a small number of
+    /// iterations are unrolled; otherwise, the loop is compiled into a LOOP block which increments
+    /// the index after every iteration, and exits once the index reaches the number of iterations.
+    fn compile_for_loop(
+        &self,
+        times: u32,
+        body: &CodeBody,
+        context: &mut AssemblyContext,
+        span: &mut SpanBuilder,
+        blocks: &mut Vec<MastNodeId>,
+    ) -> Result<(), AssemblyError> {
+        if times == 0 {
+            return Err(AssemblyError::param_out_of_bounds(0, 1, u32::MAX as u64));
+        }
+
+        if times <= MAX_UNROLLED_FOR_ITERATIONS {
+            let body = self.compile_body(body, context, None)?;
+            for idx in 0..times {
+                if idx > 0 {
+                    span.push_op(Operation::Drop);
+                }
+                instruction::push_u32_value(span, idx);
+                self.extract_span_into(span, blocks);
+                blocks.push(body);
+            }
+        } else {
+            // push the initial index and the flag which enters the loop: [1, 0, ...]
+            span.push_ops([Operation::Pad, Operation::Pad, Operation::Incr]);
+            self.extract_span_into(span, blocks);
+
+            // after every iteration, increment the index and continue while it is not equal to
+            // the number of iterations: [idx + 1 != times, idx + 1, ...]
+            let wrapper = BodyWrapper {
+                prologue: Vec::new(),
+                epilogue: vec![
+                    Operation::Incr,
+                    Operation::Dup0,
+                    Operation::Push(Felt::from(times)),
+                    Operation::Eq,
+                    Operation::Not,
+                ],
+            };
+            let body = self.compile_body(body, context, Some(wrapper))?;
+            blocks.push(self.mast_forest.borrow_mut().add_loop(body));
+        }
+        span.push_op(Operation::Drop);
+        Ok(())
+    }
+
+    /// Compiles the code loading the specified data segments into memory, and returns the ID of
+    /// the resulting SPAN block, or None if there are no data segments.
+    fn compile_data_segments(&self, segments: &[DataSegment]) -> Option<MastNodeId> {
+        if segments.is_empty() {
+            return None;
+        }
+        let mut span = SpanBuilder::new(None).with_optimizations(self.optimize);
+        for segment in segments {
+            instruction::load_data_segment(&mut span, segment);
+        }
+        let mut blocks = Vec::new();
+        self.extract_span_into(&mut span, &mut blocks);
+        blocks.pop()
+    }
+
+    /// Extracts a SPAN block from the provided span builder into the assembler's MAST forest and
+    /// appends the ID of the block to the provided list of blocks.
+    fn extract_span_into(&self, span: &mut SpanBuilder, blocks: &mut Vec<MastNodeId>) {
+        span.extract_span_into(
+            blocks,
+            &mut self.mast_forest.borrow_mut(),
+            &mut self.source_map.borrow_mut(),
+        );
+    }
+
+    /// Adds the location of the provided procedure, which was compiled into the MAST with the
+    /// specified root, to the assembler's source map.
+    fn track_proc_location(
+        &self,
+        proc: &ProcedureAst,
+        mast_root: RpoDigest,
+        code: MastNodeId,
+        context: &AssemblyContext,
+    ) {
+        let Some(declaration) = get_proc_location(proc, context) else {
+            return;
+        };
+
+        // procedures compiled into a single SPAN block are identified by their first instruction,
+        // as such blocks are merged with adjacent SPAN blocks when the procedures are inlined
+        let mut source_map = self.source_map.borrow_mut();
+        let entry = match self.mast_forest.borrow()[code] {
+            MastNode::Span(_) => source_map
+                .get_span(mast_root)
+                .and_then(|locations| locations.iter().next())
+                .map(|(_, location)| location.clone()),
+            _ => None,
+        };
+        let location = ProcedureLocation::new(proc.name.as_ref(), declaration, entry);
+        source_map.insert_procedure(mast_root, location);
+    }
+
+    /// Maps the SPLIT or LOOP block with the specified ID to the location of the `if.true` or
+    /// `while.true` node at the specified index of the provided body, if the assembler emits
+    /// source maps.
+    fn track_branch_location(
+        &self,
+        block: MastNodeId,
+        body: &CodeBody,
+        idx: usize,
+        context: &AssemblyContext,
+    ) {
+        if self.emit_source_map {
+            if let Some(location) = get_code_location(body, idx, context) {
+                let block_hash = self.mast_forest.borrow()[block].hash();
+                self.source_map.borrow_mut().insert_branch(block_hash, location);
+            }
+        }
+    }
+
     // PROCEDURE CACHE
     // --------------------------------------------------------------------------------------------
 
@@ -413,17 +837,157 @@ impl Assembler {
         Ok(())
     }
 
-    // CODE BLOCK BUILDER
+    /// Returns an error if the assembler has already compiled any procedures.
+    ///
+    /// Options which are applied when the sources are parsed (e.g., features) can be changed only
+    /// before any procedures are compiled; otherwise, the procedures cached by the assembler and
+    /// the code compiled afterwards could be parsed under different options.
+    fn ensure_no_compiled_procedures(&self) -> Result<(), AssemblyError> {
+        if self
+            .proc_cache
+            .try_borrow()
+            .map_err(|_| AssemblyError::InvalidCacheLock)?
+            .is_empty()
+        {
+            Ok(())
+        } else {
+            Err(AssemblyError::procedures_already_compiled())
+        }
+    }
+
+    // PROGRAM BUILDER
     // --------------------------------------------------------------------------------------------
-    /// Returns the [CodeBlockTable] associated with the [AssemblyContext].
+    /// Builds a [Program] from the program root compiled in the provided [AssemblyContext].
+    ///
+    /// The MAST forest of the program contains only the nodes of the assembler's MAST forest
+    /// reachable from the program root and from the procedures in the program's callset.
     ///
     /// # Errors
     /// Returns an error if a required procedure is not found in the [Assembler] procedure cache.
-    pub fn build_cb_table(
+    pub fn build_program(
         &self,
+        program_root: MastNodeId,
         context: AssemblyContext,
-    ) -> Result<CodeBlockTable, AssemblyError> {
-        context.into_cb_table(&self.proc_cache.borrow())
+    ) -> Result<Program, AssemblyError> {
+        let mast_forest = self.mast_forest.borrow();
+        let mut forest = MastForest::new();
+        let entrypoint = forest.add_subtree(&mast_forest, program_root);
+
+        // convert the context into a code block table for the program
+        let cb_table: CodeBlockTable =
+            context.into_cb_table(&self.proc_cache.borrow(), &mast_forest, &mut forest)?;
+
+        // copy the locations of the SPAN blocks, procedures and branches which are a part of the
+        // program
+        let mut source_map = SourceMap::new();
+        if self.emit_source_map {
+            let assembler_source_map = self.source_map.borrow();
+            let mut node_hashes = BTreeSet::new();
+            let mut span_locations = BTreeSet::new();
+            for (_, node) in forest.nodes() {
+                if let Some(locations) = assembler_source_map.get_span(node.hash()) {
+                    span_locations.extend(locations.iter().map(|(_, location)| location));
+                    source_map.insert_span(node.hash(), locations.clone());
+                }
+                if let Some(location) = assembler_source_map.get_branch(node.hash()) {
+                    source_map.insert_branch(node.hash(), location.clone());
+                }
+                node_hashes.insert(node.hash());
+            }
+
+            // procedures compiled into a single SPAN block may be a part of the program only via
+            // the SPAN blocks they were merged into
+            for (mast_root, location) in assembler_source_map.procedures() {
+                let is_inlined =
+                    location.entry().is_some_and(|entry| span_locations.contains(entry));
+                if node_hashes.contains(&mast_root) || is_inlined {
+                    source_map.insert_procedure(mast_root, location.clone());
+                }
+            }
+        }
+
+        // copy the error messages of the assertions which are a part of the program
+        let module_errors = self.module_errors.borrow();
+        let program_errors = self.program_errors.borrow();
+        let error_messages = forest
+            .nodes()
+            .filter_map(|(_, node)| match node {
+                MastNode::Span(span) => Some(span.op_batches()),
+                _ => None,
+            })
+            .flat_map(|batches| batches.iter().flat_map(|batch| batch.ops()))
+            .filter_map(|op| match op {
+                Operation::Assert(err_code) => Some(*err_code),
+                Operation::U32assert2(err_code) => Some(err_code.as_int() as u32),
+                _ => None,
+            })
+            .filter_map(|err_code| {
+                let msg = module_errors.get(err_code).or_else(|| program_errors.get(err_code))?;
+                Some((err_code, msg.clone()))
+            })
+            .collect();
+
+        Ok(Program::from_forest(forest, entrypoint, self.kernel.clone(), cb_table)
+            .with_source_map(source_map)
+            .with_error_messages(error_messages)
+            .with_data_segments(self.data_segments.take()))
+    }
+}
+
+// ERROR CODE MAP
+// ================================================================================================
+
+/// Error messages of the assertions in compiled sources keyed by their error codes, together with
+/// the error codes of the assertions which are specified explicitly rather than derived from
+/// messages.
+#[derive(Default)]
+struct ErrorCodeMap {
+    messages: BTreeMap<u32, String>,
+    explicit: BTreeSet<u32>,
+}
+
+impl ErrorCodeMap {
+    /// Returns the error message of the specified error code, if any.
+    fn get(&self, code: u32) -> Option<&String> {
+        self.messages.get(&code)
+    }
+
+    /// Adds the specified error messages and explicitly specified error codes to this map.
+    fn extend(&mut self, messages: &BTreeMap<u32, String>, explicit: BTreeSet<u32>) {
+        self.messages.extend(messages.clone());
+        self.explicit.extend(explicit);
+    }
+
+    /// Returns an error if the code of any of the specified messages collides with the code of a
+    /// different message or with an explicitly specified code in this map, or if any of the
+    /// specified explicit codes collides with the code of a message in this map; otherwise, an
+    /// assertion could fail with a wrong message.
+    fn check_collisions(
+        &self,
+        messages: &BTreeMap<u32, String>,
+        explicit: &BTreeSet<u32>,
+    ) -> Result<(), AssemblyError> {
+        let describe = |msg: &str| format!("message \"{msg}\"");
+        let without_message = "an assertion without an error message";
+
+        for code in explicit.iter() {
+            if let Some(other) = self.messages.get(code) {
+                return Err(AssemblyError::error_code_collision(
+                    *code,
+                    without_message,
+                    &describe(other),
+                ));
+            }
+        }
+        for (code, msg) in messages.iter() {
+            let other = match self.messages.get(code) {
+                Some(other) if other != msg => describe(other),
+                _ if self.explicit.contains(code) => without_message.into(),
+                _ => continue,
+            };
+            return Err(AssemblyError::error_code_collision(*code, &describe(msg), &other));
+        }
+        Ok(())
     }
 }
 
@@ -440,26 +1004,30 @@ struct BodyWrapper {
 // HELPER FUNCTIONS
 // ================================================================================================
 
-fn combine_blocks(mut blocks: Vec<CodeBlock>) -> CodeBlock {
+fn combine_blocks(
+    mut blocks: Vec<MastNodeId>,
+    forest: &mut MastForest,
+    source_map: &mut SourceMap,
+) -> MastNodeId {
     debug_assert!(!blocks.is_empty(), "cannot combine empty block list");
     // merge consecutive Span blocks.
-    let mut merged_blocks: Vec<CodeBlock> = Vec::with_capacity(blocks.len());
+    let mut merged_blocks: Vec<MastNodeId> = Vec::with_capacity(blocks.len());
     // Keep track of all the consecutive Span blocks and are merged together when
     // there is a discontinuity.
-    let mut contiguous_spans: Vec<CodeBlock> = Vec::new();
+    let mut contiguous_spans: Vec<MastNodeId> = Vec::new();
 
     blocks.drain(0..).for_each(|block| {
-        if block.is_span() {
+        if forest[block].is_span() {
             contiguous_spans.push(block);
         } else {
             if !contiguous_spans.is_empty() {
-                merged_blocks.push(combine_spans(&mut contiguous_spans));
+                merged_blocks.push(combine_spans(&mut contiguous_spans, forest, source_map));
             }
             merged_blocks.push(block);
         }
     });
     if !contiguous_spans.is_empty() {
-        merged_blocks.push(combine_spans(&mut contiguous_spans));
+        merged_blocks.push(combine_spans(&mut contiguous_spans, forest, source_map));
     }
 
     // build a binary tree of blocks joining them using JOIN blocks
@@ -469,9 +1037,9 @@ fn combine_blocks(mut blocks: Vec<CodeBlock>) -> CodeBlock {
 
         let mut grouped_blocks = Vec::new();
         core::mem::swap(&mut blocks, &mut grouped_blocks);
-        let mut grouped_blocks = group_vector_elements::<CodeBlock, 2>(grouped_blocks);
-        grouped_blocks.drain(0..).for_each(|pair| {
-            blocks.push(CodeBlock::new_join(pair));
+        let mut grouped_blocks = group_vector_elements::<MastNodeId, 2>(grouped_blocks);
+        grouped_blocks.drain(0..).for_each(|[first, second]| {
+            blocks.push(forest.add_join(first, second));
         });
 
         if let Some(block) = last_block {
@@ -483,30 +1051,154 @@ fn combine_blocks(mut blocks: Vec<CodeBlock>) -> CodeBlock {
     blocks.remove(0)
 }
 
-/// Combines a vector of SPAN blocks into a single SPAN block.
+/// Combines a vector of SPAN nodes into a single SPAN node.
+///
+/// Source locations of the combined nodes found in the provided source map are added to the
+/// source map for the resulting node.
 ///
 /// # Panics
-/// Panics if any of the provided blocks is not a SPAN block.
-fn combine_spans(spans: &mut Vec<CodeBlock>) -> CodeBlock {
+/// Panics if any of the provided nodes is not a SPAN node.
+fn combine_spans(
+    spans: &mut Vec<MastNodeId>,
+    forest: &mut MastForest,
+    source_map: &mut SourceMap,
+) -> MastNodeId {
     if spans.len() == 1 {
         return spans.remove(0);
     }
 
     let mut ops = Vec::<Operation>::new();
     let mut decorators = DecoratorList::new();
+    let mut locations = Vec::new();
     spans.drain(0..).for_each(|block| {
-        if let CodeBlock::Span(span) = block {
+        if let MastNode::Span(span) = &forest[block] {
             for decorator in span.decorators() {
                 decorators.push((decorator.0 + ops.len(), decorator.1.clone()));
             }
+            if let Some(span_locations) = source_map.get_span(span.hash()) {
+                for (range, location) in span_locations.iter() {
+                    let range = (range.start + ops.len())..(range.end + ops.len());
+                    locations.push((range, location.clone()));
+                }
+            }
             for batch in span.op_batches() {
                 ops.extend_from_slice(batch.ops());
             }
         } else {
-            panic!("CodeBlock was expected to be a Span Block, got {block:?}.");
+            panic!("MAST node was expected to be a SPAN node, got {:?}.", forest[block]);
         }
     });
-    CodeBlock::new_span_with_decorators(ops, decorators)
+    let node_id = forest.add_span(ops, decorators);
+    source_map.insert_span(forest[node_id].hash(), SpanLocations::new(locations));
+    node_id
+}
+
+/// Returns the location of the node at the specified index in the provided code body, or None
+/// if the body has no location information.
+///
+/// The index equal to the number of nodes in the body refers to the `end` token of the body.
+fn get_code_location(
+    body: &CodeBody,
+    idx: usize,
+    context: &AssemblyContext,
+) -> Option<CodeLocation> {
+    body.source_locations().get(idx).map(|location| {
+        let path = context.current_module_path().path();
+        CodeLocation::new(path, location.line(), location.column())
+    })
+}
+
+/// Returns the location of the declaration of the provided procedure, or None if the procedure
+/// has no location information.
+fn get_proc_location(proc: &ProcedureAst, context: &AssemblyContext) -> Option<CodeLocation> {
+    proc.body.has_locations().then(|| {
+        let path = context.current_module_path().path();
+        CodeLocation::new(path, proc.start.line(), proc.start.column())
+    })
+}
+
+/// Checks the declared signatures of the provided procedures against the stack effects of their
+/// bodies, and returns an error for every procedure which does not match its signature.
+///
+/// Procedures whose stack effect cannot be determined statically (e.g., procedures with `while`
+/// loops, or procedures which invoke imported procedures) are not checked.
+fn check_proc_signatures(procs: &[ProcedureAst], context: &AssemblyContext) -> Vec<AssemblyError> {
+    let analyzer = StackEffectAnalyzer::new(procs);
+    analyzer
+        .procedure_effects()
+        .filter_map(|(proc, effect)| {
+            let signature = proc.signature.as_ref()?;
+            let effect = effect.as_ref().ok()?;
+            if signature.is_satisfied_by(effect) {
+                return None;
+            }
+            let location = get_proc_location(proc, context);
+            let error = AssemblyError::proc_signature_mismatch(
+                &proc.name,
+                signature.stack_effect(),
+                *effect,
+            );
+            Some(error.with_source_location(location.as_ref()))
+        })
+        .collect()
+}
+
+/// Checks the bodies of all `for` loops in the provided procedures and body, and returns an error
+/// for every loop whose body changes the depth of the stack, or whose stack effect cannot be
+/// determined statically.
+///
+/// The index of the current iteration is expected at the top of the stack after every iteration;
+/// if the body could move the index, unrolled loops and loops compiled into LOOP blocks would
+/// behave differently.
+fn check_for_loops(
+    procs: &[ProcedureAst],
+    body: Option<&CodeBody>,
+    context: &AssemblyContext,
+) -> Vec<AssemblyError> {
+    let analyzer = StackEffectAnalyzer::new(procs);
+    let mut errors = Vec::new();
+    for body in procs.iter().map(|proc| &proc.body).chain(body) {
+        collect_for_loop_errors(&analyzer, body, context, &mut errors);
+    }
+    errors
+}
+
+/// Adds an error for every `for` loop in the specified body, and in all of the nested bodies,
+/// whose body does not have a zero net stack effect to the provided list.
+fn collect_for_loop_errors(
+    analyzer: &StackEffectAnalyzer,
+    body: &CodeBody,
+    context: &AssemblyContext,
+    errors: &mut Vec<AssemblyError>,
+) {
+    for (idx, node) in body.nodes().iter().enumerate() {
+        match node {
+            Node::Instruction(_) => (),
+            Node::IfElse {
+                true_case,
+                false_case,
+            } => {
+                collect_for_loop_errors(analyzer, true_case, context, errors);
+                collect_for_loop_errors(analyzer, false_case, context, errors);
+            }
+            Node::For {
+                body: loop_body, ..
+            } => {
+                let effect = analyzer.analyze(loop_body).ok();
+                if effect.map_or(true, |effect| effect.net_effect() != 0) {
+                    let location = get_code_location(body, idx, context);
+                    errors.push(
+                        AssemblyError::invalid_for_loop_body(effect)
+                            .with_source_location(location.as_ref()),
+                    );
+                }
+                collect_for_loop_errors(analyzer, loop_body, context, errors);
+            }
+            Node::Repeat { body, .. } | Node::While { body } => {
+                collect_for_loop_errors(analyzer, body, context, errors)
+            }
+        }
+    }
 }
 
 /// Builds a procedure ID based on the provided parameters.
@@ -0,0 +1,316 @@
+use super::{Decorator, DecoratorList, Operation};
+use crate::ast::{event, Level};
+use alloc::vec::Vec;
+use core::ops::Range;
+use vm_core::CodeLocation;
+
+// PEEPHOLE RULES
+// ================================================================================================
+
+/// A pattern matching a single operation in a peephole rule.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum OpPattern {
+    /// Matches the specified operation exactly.
+    Op(Operation),
+    /// Matches any operation which pushes a constant onto the stack (i.e., `Pad` or `Push`).
+    Push,
+    /// Matches any of the `Dup*` operations.
+    Dup,
+}
+
+impl OpPattern {
+    /// Returns true if the provided operation matches this pattern.
+    fn matches(&self, op: &Operation) -> bool {
+        use Operation::*;
+        match self {
+            Self::Op(expected) => expected == op,
+            Self::Push => matches!(op, Pad | Push(_)),
+            Self::Dup => matches!(
+                op,
+                Dup0 | Dup1
+                    | Dup2
+                    | Dup3
+                    | Dup4
+                    | Dup5
+                    | Dup6
+                    | Dup7
+                    | Dup9
+                    | Dup11
+                    | Dup13
+                    | Dup15
+            ),
+        }
+    }
+}
+
+/// A peephole optimization rule: a sequence of operations matching the pattern of the rule is
+/// replaced with the replacement of the rule.
+///
+/// The replacement must have the same effect on the stack as any sequence matched by the
+/// pattern, and must be strictly shorter than the pattern.
+#[derive(Debug)]
+pub(super) struct PeepholeRule {
+    pub name: &'static str,
+    pub pattern: &'static [OpPattern],
+    pub replacement: &'static [Operation],
+}
+
+impl PeepholeRule {
+    /// Returns true if the provided operations end with a sequence matched by this rule.
+    fn matches_tail(&self, ops: &[Operation]) -> bool {
+        ops.len() >= self.pattern.len()
+            && ops[ops.len() - self.pattern.len()..]
+                .iter()
+                .zip(self.pattern)
+                .all(|(op, pattern)| pattern.matches(op))
+    }
+}
+
+macro_rules! rule {
+    ($name:literal, [$($pattern:expr),*] => [$($replacement:expr),*]) => {
+        PeepholeRule {
+            name: $name,
+            pattern: &[$($pattern),*],
+            replacement: &[$($replacement),*],
+        }
+    };
+}
+
+use OpPattern::{Dup, Op, Push};
+use Operation::*;
+
+/// Peephole optimization rules applied by the assembler, in the order of their priority.
+pub(super) const RULES: &[PeepholeRule] = &[
+    // ----- redundant stack manipulation -----------------------------------------------------
+    rule!("swap swap", [Op(Swap), Op(Swap)] => []),
+    rule!("swapw swapw", [Op(SwapW), Op(SwapW)] => []),
+    rule!("swapw2 swapw2", [Op(SwapW2), Op(SwapW2)] => []),
+    rule!("swapw3 swapw3", [Op(SwapW3), Op(SwapW3)] => []),
+    rule!("swapdw swapdw", [Op(SwapDW), Op(SwapDW)] => []),
+    rule!("movup.2 movdn.2", [Op(MovUp2), Op(MovDn2)] => []),
+    rule!("movup.3 movdn.3", [Op(MovUp3), Op(MovDn3)] => []),
+    rule!("movup.4 movdn.4", [Op(MovUp4), Op(MovDn4)] => []),
+    rule!("movup.5 movdn.5", [Op(MovUp5), Op(MovDn5)] => []),
+    rule!("movup.6 movdn.6", [Op(MovUp6), Op(MovDn6)] => []),
+    rule!("movup.7 movdn.7", [Op(MovUp7), Op(MovDn7)] => []),
+    rule!("movup.8 movdn.8", [Op(MovUp8), Op(MovDn8)] => []),
+    rule!("movdn.2 movup.2", [Op(MovDn2), Op(MovUp2)] => []),
+    rule!("movdn.3 movup.3", [Op(MovDn3), Op(MovUp3)] => []),
+    rule!("movdn.4 movup.4", [Op(MovDn4), Op(MovUp4)] => []),
+    rule!("movdn.5 movup.5", [Op(MovDn5), Op(MovUp5)] => []),
+    rule!("movdn.6 movup.6", [Op(MovDn6), Op(MovUp6)] => []),
+    rule!("movdn.7 movup.7", [Op(MovDn7), Op(MovUp7)] => []),
+    rule!("movdn.8 movup.8", [Op(MovDn8), Op(MovUp8)] => []),
+    rule!("dup drop", [Dup, Op(Drop)] => []),
+    rule!("push drop", [Push, Op(Drop)] => []),
+    // ----- arithmetic with identity elements ------------------------------------------------
+    rule!("push.0 add", [Op(Pad), Op(Add)] => []),
+    rule!("push.1 add", [Op(Pad), Op(Incr), Op(Add)] => [Incr]),
+    rule!("push.1 mul", [Op(Pad), Op(Incr), Op(Mul)] => []),
+    rule!("push.0 eq", [Op(Pad), Op(Eq)] => [Eqz]),
+    rule!("neg neg", [Op(Neg), Op(Neg)] => []),
+    // ----- commutative operations -----------------------------------------------------------
+    rule!("swap add", [Op(Swap), Op(Add)] => [Add]),
+    rule!("swap mul", [Op(Swap), Op(Mul)] => [Mul]),
+    rule!("swap eq", [Op(Swap), Op(Eq)] => [Eq]),
+    rule!("swap and", [Op(Swap), Op(And)] => [And]),
+    rule!("swap or", [Op(Swap), Op(Or)] => [Or]),
+];
+
+// OPTIMIZER
+// ================================================================================================
+
+/// Applies peephole optimization rules to the provided list of operations of a SPAN block.
+///
+/// Operations are rewritten repeatedly until no rule matches, so that sequences which become
+/// redundant after another rewrite (e.g., `swap dup drop swap`) are removed as well. Decorators
+/// and source locations of the operations are updated to refer to the rewritten operations:
+/// - A rewrite never moves operations across a decorator; thus, decorators are executed at the
+///   same point of the computation as before the rewrite.
+/// - Cycle counts of `AsmOp` decorators are updated to reflect the rewritten operations, and
+///   `AsmOp` decorators of instructions whose operations were removed entirely are dropped.
+/// - Operations produced by a rewrite are mapped to the location of the first rewritten
+///   operation.
+pub(super) fn optimize(
+    ops: &mut Vec<Operation>,
+    decorators: &mut DecoratorList,
+    locations: &mut Vec<(Range<usize>, CodeLocation)>,
+) {
+    let mut output = Vec::with_capacity(ops.len());
+
+    // positions[i] is the index in the output of the operation which takes place of the i-th
+    // input operation; positions[ops.len()] is the length of the output
+    let mut positions = Vec::with_capacity(ops.len() + 1);
+
+    // rewrites are applied only to operations after the barrier, i.e., after the last decorator
+    let mut barrier = 0;
+    let mut decorator_positions = decorators.iter().map(|(pos, _)| *pos).peekable();
+
+    for (idx, &op) in ops.iter().enumerate() {
+        positions.push(output.len());
+        while decorator_positions.next_if(|&pos| pos <= idx).is_some() {
+            barrier = output.len();
+        }
+
+        output.push(op);
+        while let Some(rule) = RULES.iter().find(|rule| rule.matches_tail(&output[barrier..])) {
+            event!(Level::TRACE, "applied peephole rule `{}`", rule.name);
+            let start = output.len() - rule.pattern.len();
+            output.truncate(start);
+            output.extend_from_slice(rule.replacement);
+
+            // the rewritten operations are mapped to the end of the replacement
+            let end = output.len();
+            for pos in positions.iter_mut().rev().take_while(|pos| **pos > start) {
+                *pos = end;
+            }
+        }
+    }
+    positions.push(output.len());
+
+    if output.len() == ops.len() {
+        return;
+    }
+
+    // update decorators; cycle counts of AsmOp decorators are recomputed from the positions of
+    // the first and the last operations of the instruction
+    decorators.retain_mut(|(pos, decorator)| {
+        if let Decorator::AsmOp(asm_op) = decorator {
+            let end = (*pos + asm_op.num_cycles() as usize).min(ops.len());
+            let num_cycles = positions[end] - positions[*pos];
+            if num_cycles == 0 {
+                return false;
+            }
+            asm_op.set_num_cycles(num_cycles as u8);
+        }
+        *pos = positions[*pos];
+        true
+    });
+
+    // update source locations, dropping the locations all operations of which were removed
+    locations.retain_mut(|(range, _)| {
+        *range = positions[range.start]..positions[range.end];
+        range.start < range.end
+    });
+
+    *ops = output;
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{optimize, OpPattern, Operation, RULES};
+    use crate::Felt;
+    use alloc::{string::ToString, vec::Vec};
+    use vm_core::{AdviceInjector, AssemblyOp, CodeLocation, Decorator};
+
+    /// Builds a sequence of operations matched by the specified pattern.
+    fn instantiate(pattern: &[OpPattern]) -> Vec<Operation> {
+        pattern
+            .iter()
+            .map(|pattern| match pattern {
+                OpPattern::Op(op) => *op,
+                OpPattern::Push => Operation::Push(Felt::new(7)),
+                OpPattern::Dup => Operation::Dup5,
+            })
+            .collect()
+    }
+
+    fn asm_op(op: &str, num_cycles: u8) -> Decorator {
+        Decorator::AsmOp(AssemblyOp::new("#main".to_string(), num_cycles, op.to_string(), false))
+    }
+
+    #[test]
+    fn every_rule_is_applied() {
+        for rule in RULES {
+            assert!(
+                rule.replacement.len() < rule.pattern.len(),
+                "rule `{}` is too long",
+                rule.name
+            );
+
+            let mut ops = instantiate(rule.pattern);
+            optimize(&mut ops, &mut Vec::new(), &mut Vec::new());
+            assert_eq!(rule.replacement, ops, "rule `{}` was not applied", rule.name);
+
+            // the rule is applied in the middle of a sequence as well
+            let pattern = instantiate(rule.pattern);
+            let mut ops = [&[Operation::U32add], pattern.as_slice(), &[Operation::U32mul]].concat();
+            optimize(&mut ops, &mut Vec::new(), &mut Vec::new());
+            let expected = [&[Operation::U32add], rule.replacement, &[Operation::U32mul]].concat();
+            assert_eq!(expected, ops, "rule `{}` was not applied", rule.name);
+        }
+    }
+
+    #[test]
+    fn rules_are_applied_repeatedly() {
+        use Operation::*;
+
+        // removing `dup drop` makes `swap swap` adjacent, and removing it makes `pad add`
+        // adjacent
+        let mut ops = vec![Pad, Swap, Dup3, Drop, Swap, Add, Mul];
+        optimize(&mut ops, &mut Vec::new(), &mut Vec::new());
+        assert_eq!(vec![Mul], ops);
+
+        // operations which do not match any rule are left intact
+        let mut ops = vec![Swap, Dup1, Add, Pad, Incr, Inv];
+        optimize(&mut ops, &mut Vec::new(), &mut Vec::new());
+        assert_eq!(vec![Swap, Dup1, Add, Pad, Incr, Inv], ops);
+    }
+
+    #[test]
+    fn rewrites_do_not_cross_decorators() {
+        use Operation::*;
+
+        // the advice injector must be executed between the two swaps
+        let injector = Decorator::Advice(AdviceInjector::U64Div);
+        let mut ops = vec![Swap, Swap, Pad, Drop];
+        let mut decorators = vec![(1, injector.clone())];
+        optimize(&mut ops, &mut decorators, &mut Vec::new());
+        assert_eq!(vec![Swap, Swap], ops);
+        assert_eq!(vec![(1, injector.clone())], decorators);
+
+        // a decorator preceding the rewritten sequence does not prevent the rewrite
+        let mut ops = vec![Add, Swap, Swap, Mul];
+        let mut decorators = vec![(1, injector.clone()), (3, injector.clone())];
+        optimize(&mut ops, &mut decorators, &mut Vec::new());
+        assert_eq!(vec![Add, Mul], ops);
+        assert_eq!(vec![(1, injector.clone()), (1, injector)], decorators);
+    }
+
+    #[test]
+    fn decorators_and_locations_are_updated() {
+        use Operation::*;
+
+        // `push.1 add` followed by `mul` and `movup.2 movdn.2`
+        let mut ops = vec![Pad, Incr, Add, Mul, MovUp2, MovDn2, Pad];
+        let mut decorators = vec![(0, asm_op("push.1", 2)), (3, asm_op("mul", 1))];
+        let mut locations = vec![
+            (0..2, CodeLocation::new("#exec", 1, 1)),
+            (2..3, CodeLocation::new("#exec", 1, 8)),
+            (3..4, CodeLocation::new("#exec", 1, 12)),
+            (4..6, CodeLocation::new("#exec", 2, 1)),
+            (6..7, CodeLocation::new("#exec", 3, 1)),
+        ];
+        optimize(&mut ops, &mut decorators, &mut locations);
+
+        assert_eq!(vec![Incr, Mul, Pad], ops);
+        assert_eq!(vec![(0, asm_op("push.1", 1)), (1, asm_op("mul", 1))], decorators);
+        let expected = vec![
+            (0..1, CodeLocation::new("#exec", 1, 1)),
+            (1..2, CodeLocation::new("#exec", 1, 12)),
+            (2..3, CodeLocation::new("#exec", 3, 1)),
+        ];
+        assert_eq!(expected, locations);
+
+        // AsmOp decorators of instructions whose operations were removed entirely are dropped;
+        // here, the first instruction is compiled into `swap swap`
+        let mut ops = vec![Swap, Swap, Add];
+        let mut decorators = vec![(0, asm_op("exec.foo", 2)), (2, asm_op("add", 1))];
+        optimize(&mut ops, &mut decorators, &mut Vec::new());
+        assert_eq!(vec![Add], ops);
+        assert_eq!(vec![(0, asm_op("add", 1))], decorators);
+    }
+}
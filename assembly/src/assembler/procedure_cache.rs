@@ -137,6 +137,11 @@ impl ProcedureCache {
     // TEST HELPERS
     // --------------------------------------------------------------------------------------------
 
+    /// Returns true if the [ProcedureCache] contains no procedures.
+    pub fn is_empty(&self) -> bool {
+        self.procedures.is_empty()
+    }
+
     /// Returns an iterator over the [Procedure]s in the [ProcedureCache].
     #[cfg(test)]
     pub fn values(&self) -> impl Iterator<Item = &Procedure> {
@@ -1,10 +1,11 @@
 use super::{
-    AssemblyContext, AssemblyError, BodyWrapper, Borrow, CodeBlock, Decorator, DecoratorList,
-    Instruction, Operation,
+    optimizer, AssemblyContext, AssemblyError, BodyWrapper, Borrow, Decorator, DecoratorList,
+    Instruction, MastForest, MastNodeId, Operation,
 };
 use alloc::string::ToString;
 use alloc::vec::Vec;
-use vm_core::{AdviceInjector, AssemblyOp};
+use core::ops::Range;
+use vm_core::{AdviceInjector, AssemblyOp, CodeLocation, SourceMap, SpanLocations};
 
 // SPAN BUILDER
 // ================================================================================================
@@ -16,12 +17,21 @@ use vm_core::{AdviceInjector, AssemblyOp};
 ///
 /// The same span builder can be used to construct many blocks. It is expected that when the last
 /// SPAN block in a procedure's body is constructed `extract_final_span_into()` will be used.
+///
+/// If source locations are tracked via `track_location()`, the locations of the operations of
+/// every extracted SPAN block are added to the provided [SourceMap].
+///
+/// If optimizations are enabled via `with_optimizations()`, peephole optimization rules are
+/// applied to the operations of every SPAN block before the block is extracted.
 #[derive(Default)]
 pub struct SpanBuilder {
     ops: Vec<Operation>,
     decorators: DecoratorList,
     epilogue: Vec<Operation>,
     last_asmop_pos: usize,
+    locations: Vec<(Range<usize>, CodeLocation)>,
+    current_location: Option<(usize, CodeLocation)>,
+    optimize: bool,
 }
 
 impl SpanBuilder {
@@ -38,23 +48,29 @@ impl SpanBuilder {
                 ops: wrapper.prologue,
                 decorators: Vec::new(),
                 epilogue: wrapper.epilogue,
-                last_asmop_pos: 0,
+                ..Default::default()
             },
             None => Self::default(),
         }
     }
 
+    /// Enables or disables peephole optimization of the SPAN blocks extracted from this builder.
+    pub(super) fn with_optimizations(mut self, optimize: bool) -> Self {
+        self.optimize = optimize;
+        self
+    }
+
     // OPERATIONS
     // --------------------------------------------------------------------------------------------
 
     /// Adds the specified operation to the list of span operations and returns Ok(None).
-    pub fn add_op(&mut self, op: Operation) -> Result<Option<CodeBlock>, AssemblyError> {
+    pub fn add_op(&mut self, op: Operation) -> Result<Option<MastNodeId>, AssemblyError> {
         self.ops.push(op);
         Ok(None)
     }
 
     /// Adds the specified sequence operations to the list of span operations and returns Ok(None).
-    pub fn add_ops<I, O>(&mut self, ops: I) -> Result<Option<CodeBlock>, AssemblyError>
+    pub fn add_ops<I, O>(&mut self, ops: I) -> Result<Option<MastNodeId>, AssemblyError>
     where
         I: IntoIterator<Item = O>,
         O: Borrow<Operation>,
@@ -133,19 +149,69 @@ impl SpanBuilder {
         }
     }
 
+    // SOURCE LOCATIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Sets the source location of the operations added to this builder from now on.
+    ///
+    /// The operations added to the builder since the previous invocation of this method are
+    /// mapped to the previously tracked location. If no location has been tracked for the current
+    /// SPAN block yet, the operations already in the builder (e.g., the prologue of the wrapper)
+    /// are mapped to the specified location.
+    pub fn track_location(&mut self, location: CodeLocation) {
+        if self.current_location.is_none() && self.locations.is_empty() {
+            self.current_location = Some((0, location));
+        } else {
+            self.close_current_location();
+            self.current_location = Some((self.ops.len(), location));
+        }
+    }
+
+    /// Maps the operations added to this builder since the last invocation of `track_location()`
+    /// to the location which was tracked at that invocation.
+    fn close_current_location(&mut self) {
+        if let Some((start, location)) = self.current_location.take() {
+            if start < self.ops.len() {
+                self.locations.push((start..self.ops.len(), location));
+            }
+        }
+    }
+
     // SPAN CONSTRUCTORS
     // --------------------------------------------------------------------------------------------
 
-    /// Creates a new SPAN block from the operations and decorators currently in this builder and
-    /// appends the block to the provided target.
+    /// Creates a new SPAN node from the operations and decorators currently in this builder, adds
+    /// it to the provided MAST forest, and appends the ID of the node to the provided target.
     ///
     /// This consumes all operations and decorators in the builder, but does not touch the
-    /// operations in the epilogue of the builder.
-    pub fn extract_span_into(&mut self, target: &mut Vec<CodeBlock>) {
+    /// operations in the epilogue of the builder. Source locations of the consumed operations are
+    /// added to the provided source map.
+    ///
+    /// If optimizations are enabled and all operations of the block are optimized away, a SPAN
+    /// block is created only if the builder contains decorators; in such a case, the block
+    /// consists of a single NOOP operation.
+    pub fn extract_span_into(
+        &mut self,
+        target: &mut Vec<MastNodeId>,
+        forest: &mut MastForest,
+        source_map: &mut SourceMap,
+    ) {
+        self.close_current_location();
+
+        if self.optimize && !self.ops.is_empty() {
+            optimizer::optimize(&mut self.ops, &mut self.decorators, &mut self.locations);
+            if self.ops.is_empty() && !self.decorators.is_empty() {
+                self.ops.push(Operation::Noop);
+            }
+        }
+
         if !self.ops.is_empty() {
             let ops = self.ops.drain(..).collect();
             let decorators = self.decorators.drain(..).collect();
-            target.push(CodeBlock::new_span_with_decorators(ops, decorators));
+            let node_id = forest.add_span(ops, decorators);
+            let locations = SpanLocations::new(self.locations.drain(..).collect());
+            source_map.insert_span(forest[node_id].hash(), locations);
+            target.push(node_id);
         } else if !self.decorators.is_empty() {
             // this is a bug in the assembler. we shouldn't have decorators added without their
             // associated operations
@@ -154,15 +220,20 @@ impl SpanBuilder {
         }
     }
 
-    /// Creates a new SPAN block from the operations and decorators currently in this builder and
-    /// appends the block to the provided target.
+    /// Creates a new SPAN node from the operations and decorators currently in this builder, adds
+    /// it to the provided MAST forest, and appends the ID of the node to the provided target.
     ///
-    /// The main differences from the `extract_span_int()` method above are:
+    /// The main differences from the `extract_span_into()` method above are:
     /// - Operations contained in the epilogue of the span builder are appended to the list of
     ///   ops which go into the new SPAN block.
     /// - The span builder is consumed in the process.
-    pub fn extract_final_span_into(mut self, target: &mut Vec<CodeBlock>) {
+    pub fn extract_final_span_into(
+        mut self,
+        target: &mut Vec<MastNodeId>,
+        forest: &mut MastForest,
+        source_map: &mut SourceMap,
+    ) {
         self.ops.append(&mut self.epilogue);
-        self.extract_span_into(target);
+        self.extract_span_into(target, forest, source_map);
     }
 }
@@ -1,8 +1,9 @@
-use super::{combine_blocks, Assembler, CodeBlock, Library, Module, Operation};
+use super::{combine_blocks, Assembler, Library, MastForest, Module, Operation, SourceMap};
 use crate::{ast::ModuleAst, LibraryNamespace, LibraryPath, Version};
 use alloc::string::ToString;
 use alloc::vec::Vec;
 use core::slice::Iter;
+use vm_core::code_blocks::CodeBlock;
 
 // TESTS
 // ================================================================================================
@@ -136,8 +137,108 @@ fn nested_blocks() {
 
     let exec = CodeBlock::new_span(vec![Operation::Push(29u32.into())]);
 
-    let combined = combine_blocks(vec![before, r#if, nested, exec, syscall]);
+    let mut forest = MastForest::new();
+    let blocks = [before, r#if, nested, exec, syscall]
+        .iter()
+        .map(|block| forest.add_code_block(block))
+        .collect();
+    let combined = combine_blocks(blocks, &mut forest, &mut SourceMap::new());
     let program = assembler.compile(program).unwrap();
 
-    assert_eq!(combined.hash(), program.hash());
+    assert_eq!(forest[combined].hash(), program.hash());
+}
+
+#[test]
+fn source_map() {
+    let source = "\
+proc.foo.1
+    loc_store.0
+end
+begin
+    push.1 push.2
+    exec.foo
+    mul
+end";
+    let assembler = Assembler::default().with_source_map(true);
+    let program = assembler.compile(source).unwrap();
+
+    // the body of `foo` is merged with the main body into a single SPAN block
+    let source_map = program.source_map();
+    assert_eq!(1, source_map.num_spans());
+    let (span_hash, locations) = source_map.iter().next().unwrap();
+    assert_eq!(program.hash(), span_hash);
+
+    let locations = locations
+        .iter()
+        .map(|(range, location)| (range.clone(), location.to_string()))
+        .collect::<Vec<_>>();
+    // the prologue of `foo` is mapped to its first instruction, and its epilogue to its `end`
+    let expected = vec![
+        (0..2, "#exec:5:5".to_string()),
+        (2..3, "#exec:5:12".to_string()),
+        (3..9, "#exec:2:5".to_string()),
+        (9..11, "#exec:3:1".to_string()),
+        (11..12, "#exec:7:5".to_string()),
+    ];
+    assert_eq!(expected, locations);
+
+    // source maps are not emitted by default
+    let program = Assembler::default().compile(source).unwrap();
+    assert!(program.source_map().is_empty());
+}
+
+#[test]
+fn peephole_optimizations() {
+    let source = "\
+begin
+    swap swap
+    push.1 add
+    dup.3 drop
+    movup.2 push.5 mul movdn.2
+end";
+
+    // optimizations are disabled by default
+    let assembler = Assembler::default();
+    assert!(!assembler.optimizations_enabled());
+    let program = assembler.compile(source).unwrap();
+    let expected = CodeBlock::new_span(vec![
+        Operation::Swap,
+        Operation::Swap,
+        Operation::Pad,
+        Operation::Incr,
+        Operation::Add,
+        Operation::Dup3,
+        Operation::Drop,
+        Operation::MovUp2,
+        Operation::Push(5u32.into()),
+        Operation::Mul,
+        Operation::MovDn2,
+    ]);
+    assert_eq!(expected.hash(), program.hash());
+
+    let assembler = Assembler::default().with_optimizations(true).with_source_map(true);
+    let program = assembler.compile(source).unwrap();
+    let expected = CodeBlock::new_span(vec![
+        Operation::Incr,
+        Operation::MovUp2,
+        Operation::Push(5u32.into()),
+        Operation::Mul,
+        Operation::MovDn2,
+    ]);
+    assert_eq!(expected.hash(), program.hash());
+
+    // operations remaining after the optimization are mapped to their original instructions
+    let (_, locations) = program.source_map().iter().next().unwrap();
+    let locations = locations
+        .iter()
+        .map(|(range, location)| (range.clone(), location.to_string()))
+        .collect::<Vec<_>>();
+    let expected = vec![
+        (0..1, "#exec:3:5".to_string()),
+        (1..2, "#exec:5:5".to_string()),
+        (2..3, "#exec:5:13".to_string()),
+        (3..4, "#exec:5:20".to_string()),
+        (4..5, "#exec:5:24".to_string()),
+    ];
+    assert_eq!(expected, locations);
 }
@@ -0,0 +1,310 @@
+use super::{
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Felt, LibraryPath, ModuleAst,
+    Serializable, StarkField, CONSTANT_LABEL_PARSER,
+};
+use crate::{crypto::hash::Rpo256, Library};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt, str::from_utf8};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Maximum number of elements in an array constant.
+pub const MAX_CONST_ARRAY_LEN: usize = u16::MAX as usize;
+
+/// Maximum number of elements in a data segment.
+pub const MAX_DATA_SEGMENT_LEN: usize = u16::MAX as usize;
+
+/// Maximum length (in bytes) of an error message.
+pub const MAX_ERROR_MSG_LEN: usize = u16::MAX as usize;
+
+// CONSTANT VALUE
+// ================================================================================================
+
+/// The value of a constant declared in a module or a program.
+///
+/// Values of field elements are stored in their canonical form, i.e., they are always smaller than
+/// the field modulus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstantValue {
+    /// A single field element, e.g. `const.A=5`.
+    Felt(u64),
+    /// A non-empty list of field elements, e.g. `const.IV=[1, 2, 3, 4]`.
+    Array(Vec<u64>),
+    /// An error message, e.g. `const.ERR_BALANCE="insufficient balance"`, which can be used only
+    /// as an error code of an assertion; see [error_code_from_msg()].
+    String(String),
+}
+
+impl fmt::Display for ConstantValue {
+    /// Writes this value in the form in which it can be used in a constant declaration.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Felt(value) => write!(f, "{value}"),
+            Self::Array(elements) => {
+                let elements = elements.iter().map(|value| value.to_string()).collect::<Vec<_>>();
+                write!(f, "[{}]", elements.join(", "))
+            }
+            Self::String(msg) => write!(f, "\"{msg}\""),
+        }
+    }
+}
+
+impl Serializable for ConstantValue {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        match self {
+            Self::Felt(value) => {
+                target.write_u8(0);
+                target.write_u64(*value);
+            }
+            Self::Array(elements) => {
+                assert!(elements.len() <= MAX_CONST_ARRAY_LEN, "constant array too long");
+                target.write_u8(1);
+                target.write_u16(elements.len() as u16);
+                elements.iter().for_each(|value| target.write_u64(*value));
+            }
+            Self::String(msg) => {
+                assert!(msg.len() <= MAX_ERROR_MSG_LEN, "error message too long");
+                target.write_u8(2);
+                target.write_u16(msg.len() as u16);
+                target.write_bytes(msg.as_bytes());
+            }
+        }
+    }
+}
+
+impl Deserializable for ConstantValue {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let read_felt = |source: &mut R| {
+            let value = source.read_u64()?;
+            if value >= Felt::MODULUS {
+                let reason = format!("constant value {value} is not a valid field element");
+                return Err(DeserializationError::InvalidValue(reason));
+            }
+            Ok(value)
+        };
+
+        match source.read_u8()? {
+            0 => Ok(Self::Felt(read_felt(source)?)),
+            1 => {
+                let len = source.read_u16()? as usize;
+                if len == 0 {
+                    let reason = "constant array must not be empty".to_string();
+                    return Err(DeserializationError::InvalidValue(reason));
+                }
+                let elements = (0..len).map(|_| read_felt(source)).collect::<Result<_, _>>()?;
+                Ok(Self::Array(elements))
+            }
+            2 => {
+                let len = source.read_u16()? as usize;
+                let msg = source.read_vec(len)?;
+                let msg = from_utf8(&msg)
+                    .map_err(|e| DeserializationError::InvalidValue(e.to_string()))?;
+                Ok(Self::String(msg.to_string()))
+            }
+            tag => Err(DeserializationError::InvalidValue(format!(
+                "invalid constant value tag: {tag}"
+            ))),
+        }
+    }
+}
+
+// ERROR MESSAGES
+// ================================================================================================
+
+/// Returns the error code of assertions failing with the specified error message.
+///
+/// The code is derived from the hash of the message, so that the same message always maps to the
+/// same code regardless of the module in which it is used; the code is never zero, since the zero
+/// code denotes an assertion without an error code.
+pub fn error_code_from_msg(msg: &str) -> u32 {
+    let hash = Rpo256::hash(msg.as_bytes());
+    let code = hash.as_elements()[0].as_int() as u32;
+    code.max(1)
+}
+
+/// Writes the specified map of error codes to error messages into the target.
+pub(super) fn write_error_messages<W: ByteWriter>(
+    target: &mut W,
+    error_messages: &BTreeMap<u32, String>,
+) {
+    assert!(error_messages.len() <= u16::MAX as usize, "too many error messages");
+    target.write_u16(error_messages.len() as u16);
+    for (code, msg) in error_messages.iter() {
+        assert!(msg.len() <= MAX_ERROR_MSG_LEN, "error message too long");
+        target.write_u32(*code);
+        target.write_u16(msg.len() as u16);
+        target.write_bytes(msg.as_bytes());
+    }
+}
+
+/// Reads a map of error codes to error messages previously written via [write_error_messages()]
+/// from the source.
+pub(super) fn read_error_messages<R: ByteReader>(
+    source: &mut R,
+) -> Result<BTreeMap<u32, String>, DeserializationError> {
+    let num_messages = source.read_u16()? as usize;
+    let mut error_messages = BTreeMap::new();
+    for _ in 0..num_messages {
+        let code = source.read_u32()?;
+        let len = source.read_u16()? as usize;
+        let msg = source.read_vec(len)?;
+        let msg = from_utf8(&msg).map_err(|e| DeserializationError::InvalidValue(e.to_string()))?;
+        error_messages.insert(code, msg.to_string());
+    }
+    Ok(error_messages)
+}
+
+// EXPORTED CONSTANTS
+// ================================================================================================
+
+/// Writes the specified exported constants into the target.
+pub(super) fn write_constants<W: ByteWriter>(
+    target: &mut W,
+    constants: &BTreeMap<String, ConstantValue>,
+) {
+    assert!(constants.len() <= u16::MAX as usize, "too many exported constants");
+    target.write_u16(constants.len() as u16);
+    for (name, value) in constants.iter() {
+        target.write_u8(name.len() as u8);
+        target.write_bytes(name.as_bytes());
+        value.write_into(target);
+    }
+}
+
+/// Reads exported constants previously written via [write_constants()] from the source.
+pub(super) fn read_constants<R: ByteReader>(
+    source: &mut R,
+) -> Result<BTreeMap<String, ConstantValue>, DeserializationError> {
+    let num_constants = source.read_u16()? as usize;
+    let mut constants = BTreeMap::new();
+    for _ in 0..num_constants {
+        let name_len = source.read_u8()? as usize;
+        let name = source.read_vec(name_len)?;
+        let name = from_utf8(&name)
+            .map_err(|e| DeserializationError::InvalidValue(e.to_string()))?
+            .to_string();
+        let value = ConstantValue::read_from(source)?;
+        constants.insert(name, value);
+    }
+    Ok(constants)
+}
+
+// IMPORTED CONSTANTS
+// ================================================================================================
+
+/// Constants exported from modules, which can be referenced from the parsed source after the
+/// modules are imported, e.g. `push.u64::MAX` after `use.std::math::u64`.
+///
+/// References to constants are resolved during parsing, so the exported constants of imported
+/// modules must be provided to the parser up front; the [Assembler](crate::Assembler) does this
+/// for all modules of the libraries it was instantiated with.
+///
+/// The set can also contain external constants, which are defined outside of Miden assembly
+/// sources (e.g., parameters supplied by the host application). External constants are referenced
+/// by their unqualified names (e.g., `push.TREE_DEPTH`), and cannot be redeclared by the parsed
+/// source.
+///
+/// The set also specifies the features enabled for the evaluation of conditional compilation
+/// directives (e.g., `#if DEBUG`), which are evaluated during parsing as well.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ImportedConstants {
+    modules: BTreeMap<LibraryPath, BTreeMap<String, ConstantValue>>,
+    external: BTreeMap<String, ConstantValue>,
+    features: BTreeSet<String>,
+    /// If true, references to constants which cannot be resolved are accepted; this is used to
+    /// validate the structure of sources whose imported modules or external constants are not
+    /// available.
+    lenient: bool,
+}
+
+impl ImportedConstants {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns an empty set of imported constants.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a set of imported constants under which references to unknown constants of
+    /// imported modules, as well as to unknown external constants, are treated as valid references
+    /// to single-value constants.
+    pub(crate) fn lenient() -> Self {
+        Self {
+            modules: BTreeMap::new(),
+            external: BTreeMap::new(),
+            features: BTreeSet::new(),
+            lenient: true,
+        }
+    }
+
+    /// Adds the constants exported from all modules of the provided library to this set.
+    pub fn with_library<L: Library>(mut self, library: &L) -> Self {
+        for module in library.modules() {
+            self.add_module(module.path.clone(), &module.ast);
+        }
+        self
+    }
+
+    /// Adds an external constant with the specified name and value to this set; if a constant with
+    /// the same name has already been added, its value is replaced.
+    ///
+    /// # Panics
+    /// Panics if the name is not a valid constant name (e.g., `TREE_DEPTH`).
+    pub fn with_constant<S: Into<String>>(mut self, name: S, value: Felt) -> Self {
+        let name = name.into();
+        if let Err(err) = CONSTANT_LABEL_PARSER.parse_label(&name) {
+            panic!("invalid constant name: {err}");
+        }
+        self.external.insert(name, ConstantValue::Felt(value.as_int()));
+        self
+    }
+
+    /// Enables the specified features for the evaluation of conditional compilation directives.
+    pub fn with_features<I, S>(mut self, features: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.features.extend(features.into_iter().map(Into::into));
+        self
+    }
+
+    /// Adds the constants exported from the module with the specified path to this set.
+    pub fn add_module(&mut self, path: LibraryPath, module: &ModuleAst) {
+        if !module.exported_constants().is_empty() {
+            self.modules.insert(path, module.exported_constants().clone());
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the constants exported from the module with the specified path, if any.
+    pub fn get_module_constants(
+        &self,
+        path: &LibraryPath,
+    ) -> Option<&BTreeMap<String, ConstantValue>> {
+        self.modules.get(path)
+    }
+
+    /// Returns the external constants of this set, keyed by their names.
+    pub fn external_constants(&self) -> &BTreeMap<String, ConstantValue> {
+        &self.external
+    }
+
+    /// Returns the features enabled for the evaluation of conditional compilation directives.
+    pub fn features(&self) -> &BTreeSet<String> {
+        &self.features
+    }
+
+    /// Returns true if references to unknown constants of imported modules are accepted.
+    pub(crate) fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+}
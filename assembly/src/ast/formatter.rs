@@ -0,0 +1,310 @@
+use super::{
+    super::tokens::{LineInfo, LineTokenizer},
+    ImportedConstants, LibraryPath, ModuleAst, ParsingError, ProgramAst, Token,
+};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// The indentation used for every level of nesting.
+const INDENT: &str = "    ";
+
+// SOURCE FORMATTER
+// ================================================================================================
+
+/// Formats the provided Miden assembly source (of either a program or a module) in the canonical
+/// style, and returns the formatted source.
+///
+/// Unlike the [Display](core::fmt::Display) implementations of [ProgramAst] and [ModuleAst], the
+/// formatter works on the source code directly, and thus preserves all comments and the layout of
+/// instructions within lines. Specifically, the formatter:
+/// - Indents every line by four spaces per level of nesting.
+/// - Separates instructions within a line, and trailing comments, by a single space.
+/// - Collapses consecutive blank lines into one, removes blank lines at the start and at the end
+///   of the source and of code blocks, and separates top-level declarations by a blank line.
+/// - Sorts consecutive `use` statements by module path.
+///
+/// # Errors
+/// Returns an error if the source is not a valid program or module.
+pub fn format_source(source: &str) -> Result<String, ParsingError> {
+    let lines = source.lines().map(SourceLine::parse).collect::<Vec<_>>();
+
+    // make sure the source is valid, so that the nesting of the code is well-formed; the imported
+    // modules are not available, so references to their constants are not resolved
+    let imported_constants = ImportedConstants::lenient();
+    let result = if lines.iter().any(|line| line.first_token() == Some(Token::BEGIN)) {
+        ProgramAst::parse_with_imported_constants(source, &imported_constants).map(|_| ())
+    } else {
+        ModuleAst::parse_with_imported_constants(source, &imported_constants).map(|_| ())
+    };
+    result.map_err(|mut errors| errors.swap_remove(0))?;
+
+    let mut formatter = SourceFormatter::default();
+    for line in lines {
+        formatter.add_line(line);
+    }
+    Ok(formatter.finish())
+}
+
+// SOURCE LINE
+// ================================================================================================
+
+/// A line of Miden assembly source.
+enum SourceLine<'a> {
+    /// A line which contains only whitespace.
+    Blank,
+    /// A line which contains only a comment (including a doc comment).
+    Comment(&'a str),
+    /// A line which contains code tokens, optionally followed by a comment.
+    Code {
+        tokens: Vec<&'a str>,
+        comment: Option<&'a str>,
+    },
+}
+
+impl<'a> SourceLine<'a> {
+    /// Splits the provided line into code tokens and a comment.
+    fn parse(line: &'a str) -> Self {
+        let line = line.trim();
+        let (code, comment) = match comment_pos(line) {
+            Some(pos) => (line[..pos].trim_end(), Some(&line[pos..])),
+            None => (line, None),
+        };
+
+        match (code.is_empty(), comment) {
+            (true, None) => Self::Blank,
+            (true, Some(comment)) => Self::Comment(comment),
+            (false, comment) => {
+                let info = LineInfo::new(0, 0).with_contents(code);
+                let tokens = LineTokenizer::new(&info)
+                    .map(|tokenizer| tokenizer.map(|(token, _)| token).collect())
+                    .unwrap_or_default();
+                Self::Code { tokens, comment }
+            }
+        }
+    }
+
+    /// Returns the first code token of this line, if any.
+    fn first_token(&self) -> Option<&'a str> {
+        match self {
+            Self::Code { tokens, .. } => tokens.first().copied(),
+            _ => None,
+        }
+    }
+
+    /// Returns true if this line consists of a single `use` statement.
+    fn is_import(&self) -> bool {
+        match self {
+            Self::Code { tokens, .. } => tokens.len() == 1 && token_name(tokens[0]) == Token::USE,
+            _ => false,
+        }
+    }
+
+    /// Returns the contents of this line with normalized whitespace.
+    fn contents(&self) -> String {
+        match self {
+            Self::Blank => String::new(),
+            Self::Comment(comment) => comment.to_string(),
+            Self::Code { tokens, comment } => {
+                let tokens = tokens.iter().map(|token| normalize_token(token)).collect::<Vec<_>>();
+                let mut contents = tokens.join(" ");
+                if let Some(comment) = comment {
+                    contents.push(' ');
+                    contents.push_str(comment);
+                }
+                contents
+            }
+        }
+    }
+}
+
+// FORMATTER STATE
+// ================================================================================================
+
+/// Accumulates formatted lines of the source.
+#[derive(Default)]
+struct SourceFormatter {
+    /// Formatted lines.
+    output: Vec<String>,
+    /// `use` statements which are yet to be sorted and added to the output.
+    imports: Vec<(String, String)>,
+    /// The current level of nesting.
+    depth: usize,
+    /// True if a blank line was encountered since the last non-blank line.
+    pending_blank: bool,
+    /// True if the last added line opened a code block.
+    block_opened: bool,
+    /// True if the last added line completed a top-level declaration.
+    declaration_closed: bool,
+}
+
+impl SourceFormatter {
+    /// Adds the specified source line to the formatted output.
+    fn add_line(&mut self, line: SourceLine) {
+        if matches!(line, SourceLine::Blank) {
+            self.flush_imports();
+            self.pending_blank = !self.output.is_empty();
+            return;
+        }
+
+        if line.is_import() {
+            let contents = line.contents();
+            let path = line.first_token().unwrap_or_default().to_string();
+            self.imports.push((path, contents));
+            return;
+        }
+        self.flush_imports();
+
+        // compute the indentation of the line; lines which start by closing a block are indented
+        // at the level of the block opening
+        let starts_with_closer =
+            matches!(line.first_token().map(token_name), Some(Token::END) | Some(Token::ELSE));
+        let indent = if starts_with_closer {
+            self.depth.saturating_sub(1)
+        } else {
+            self.depth
+        };
+
+        // update the level of nesting
+        let mut closed_block = false;
+        if let SourceLine::Code { tokens, .. } = &line {
+            for token in tokens {
+                if opens_block(token) {
+                    self.depth += 1;
+                } else if token_name(token) == Token::END {
+                    self.depth = self.depth.saturating_sub(1);
+                    closed_block = true;
+                }
+            }
+        }
+
+        self.push_line(indent, &line.contents(), starts_with_closer);
+        self.block_opened = self.depth > indent;
+        self.declaration_closed = closed_block && self.depth == 0;
+    }
+
+    /// Returns the formatted source.
+    fn finish(mut self) -> String {
+        self.flush_imports();
+        let mut source = self.output.join("\n");
+        source.push('\n');
+        source
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Adds the pending `use` statements to the output, sorted by module path.
+    fn flush_imports(&mut self) {
+        if self.imports.is_empty() {
+            return;
+        }
+        let mut imports = core::mem::take(&mut self.imports);
+        imports.sort();
+        for (_, contents) in imports {
+            self.push_line(self.depth, &contents, false);
+            self.block_opened = false;
+            self.declaration_closed = false;
+        }
+    }
+
+    /// Adds a line with the specified indentation and contents to the output, preceded by a blank
+    /// line if one is required.
+    fn push_line(&mut self, indent: usize, contents: &str, closes_block: bool) {
+        let blank_allowed = !self.block_opened && !closes_block;
+        if (self.pending_blank || self.declaration_closed) && blank_allowed {
+            self.output.push(String::new());
+        }
+        self.pending_blank = false;
+        self.output.push(format!("{}{contents}", INDENT.repeat(indent)));
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the name of the instruction or the declaration represented by the specified token,
+/// e.g. `push` for `push.1`.
+fn token_name(token: &str) -> &str {
+    token.split('.').next().unwrap_or_default()
+}
+
+/// Returns the position of the comment in the specified line, ignoring comment prefixes within
+/// quoted strings, or None if the line has no comment.
+fn comment_pos(line: &str) -> Option<usize> {
+    let mut in_string = false;
+    for (idx, c) in line.char_indices() {
+        if c == Token::STRING_DELIM {
+            in_string = !in_string;
+        } else if c == Token::COMMENT_PREFIX && !in_string {
+            return Some(idx);
+        }
+    }
+    None
+}
+
+/// Returns the specified token with normalized whitespace.
+///
+/// Only procedure declarations with signatures, declarations of array constants and data
+/// segments, declarations of named locals, and quoted strings may contain whitespace; the
+/// signatures are rendered in the canonical form, e.g. `(a: felt, b: u32) -> (c: word)`, elements
+/// of arrays and data segments are separated by `, `, locals are rendered as e.g.
+/// `local.buf: [word; 4]`, and strings are left intact.
+fn normalize_token(token: &str) -> String {
+    if is_const_declaration(token) || token_name(token) == Token::DATA {
+        let array = token.split_once('=').and_then(|(name, value)| {
+            let elements = value.strip_prefix('[')?;
+            Some((name, elements))
+        });
+        return match array {
+            Some((name, elements)) => {
+                let elements = elements.trim_end_matches(']').split(',').map(str::trim);
+                format!("{name}=[{}]", elements.collect::<Vec<_>>().join(", "))
+            }
+            None => token.to_string(),
+        };
+    }
+
+    if token_name(token) == Token::LOCAL {
+        let Some((name, ty)) = token.split_once(':') else {
+            return token.to_string();
+        };
+        let ty = ty.trim();
+        let array = ty.strip_prefix('[').and_then(|ty| ty.strip_suffix(']'));
+        return match array.and_then(|array| array.split_once(';')) {
+            Some((elem_ty, len)) => format!("{name}: [{}; {}]", elem_ty.trim(), len.trim()),
+            None => format!("{name}: {ty}"),
+        };
+    }
+
+    if !matches!(token_name(token), Token::PROC | Token::EXPORT) {
+        return token.to_string();
+    }
+    let Some(pos) = token.find('(') else {
+        return token.to_string();
+    };
+    match Token::new(token, Default::default()).parse_proc() {
+        Ok((.., Some(signature))) => format!("{}{signature}", &token[..pos]),
+        _ => token.to_string(),
+    }
+}
+
+/// Returns true if the specified token opens a code block which is closed by an `end` token.
+fn opens_block(token: &str) -> bool {
+    match token_name(token) {
+        Token::BEGIN | Token::IF | Token::WHILE | Token::REPEAT | Token::FOR | Token::PROC => true,
+        // re-exported procedures do not have a body
+        Token::EXPORT => !is_const_declaration(token) && !token.contains(LibraryPath::PATH_DELIM),
+        _ => false,
+    }
+}
+
+/// Returns true if the specified token declares a constant, which may be exported.
+fn is_const_declaration(token: &str) -> bool {
+    let token = token.strip_prefix("export.").unwrap_or(token);
+    token_name(token) == Token::CONST
+}
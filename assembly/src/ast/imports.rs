@@ -115,6 +115,11 @@ impl ModuleImports {
         self.invoked_procs.get(id).map(|(_, path)| path)
     }
 
+    /// Returns an iterator over the names (aliases) and paths of all imported modules.
+    pub(super) fn imported_modules(&self) -> impl Iterator<Item = (&str, &LibraryPath)> {
+        self.imports.iter().map(|(name, path)| (name.as_str(), path))
+    }
+
     /// Return the paths of all imported module
     pub fn import_paths(&self) -> Vec<&LibraryPath> {
         self.imports.values().collect()
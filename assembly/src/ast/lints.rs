@@ -0,0 +1,248 @@
+use super::{
+    CodeBody, Felt, Instruction, LibraryPath, LocalConstMap, ModuleImports, Node, ProcedureAst,
+    SourceLocation, Token, TokenStream,
+};
+use crate::AssemblyWarning;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::{vec, vec::Vec};
+
+// LINTS
+// ================================================================================================
+
+/// Returns the locations of the `use` statements in the provided token stream, keyed by the path
+/// of the imported module.
+///
+/// The `use` statements are expected to be located at the start of the stream, before the
+/// current position of the stream.
+pub(super) fn import_locations(tokens: &mut TokenStream) -> BTreeMap<LibraryPath, SourceLocation> {
+    let mut locations = BTreeMap::new();
+    for pos in 0..tokens.pos() {
+        let token = tokens.read_at(pos).expect("no use token");
+        if token.parts()[0] == Token::USE {
+            if let Ok((path, _)) = token.parse_use() {
+                locations.insert(path, *token.location());
+            }
+        }
+    }
+    locations
+}
+
+/// Returns a warning for every imported module from which no procedure is invoked or re-exported,
+/// and no constant is referenced.
+pub(super) fn check_unused_imports(
+    import_info: &ModuleImports,
+    import_locations: &BTreeMap<LibraryPath, SourceLocation>,
+    reexported_modules: &BTreeSet<LibraryPath>,
+    constants: &LocalConstMap,
+) -> Vec<AssemblyWarning> {
+    let used_paths: BTreeSet<&LibraryPath> = import_info
+        .invoked_procs()
+        .values()
+        .map(|(_name, path)| path)
+        .chain(reexported_modules)
+        .chain(constants.used_imports(import_info))
+        .collect();
+
+    import_info
+        .import_paths()
+        .into_iter()
+        .filter(|path| !used_paths.contains(path))
+        .map(|path| {
+            let location = import_locations.get(path).copied().unwrap_or_default();
+            AssemblyWarning::unused_import(path, location)
+        })
+        .collect()
+}
+
+/// Returns a warning for every constant which is never referenced.
+pub(super) fn check_unused_constants(constants: &LocalConstMap) -> Vec<AssemblyWarning> {
+    constants
+        .unused()
+        .map(|(name, location)| AssemblyWarning::unused_constant(name, *location))
+        .collect()
+}
+
+/// Returns a warning for every local procedure which is not reachable from the specified roots.
+///
+/// For programs, the root is the program body; for modules, the roots are the bodies of exported
+/// procedures.
+pub(super) fn check_unused_procedures<'a, R>(
+    procedures: &[ProcedureAst],
+    roots: R,
+) -> Vec<AssemblyWarning>
+where
+    R: IntoIterator<Item = &'a CodeBody>,
+{
+    let mut reachable = BTreeSet::new();
+    let mut stack = Vec::new();
+    for body in roots {
+        collect_invoked_local_procs(body, &mut stack);
+    }
+    while let Some(idx) = stack.pop() {
+        if reachable.insert(idx) {
+            if let Some(proc) = procedures.get(idx as usize) {
+                collect_invoked_local_procs(&proc.body, &mut stack);
+            }
+        }
+    }
+
+    procedures
+        .iter()
+        .enumerate()
+        .filter(|(idx, proc)| !proc.is_export && !reachable.contains(&(*idx as u16)))
+        .map(|(_, proc)| AssemblyWarning::unused_procedure(&proc.name, proc.start))
+        .collect()
+}
+
+/// Returns a warning for every procedure which declares memory locals but never accesses them.
+pub(super) fn check_unused_locals(procedures: &[ProcedureAst]) -> Vec<AssemblyWarning> {
+    procedures
+        .iter()
+        .filter(|proc| proc.num_locals > 0 && !accesses_locals(&proc.body))
+        .map(|proc| AssemblyWarning::unused_locals(&proc.name, proc.num_locals, proc.start))
+        .collect()
+}
+
+/// Returns a warning for every block of code which follows an assertion that always fails.
+///
+/// An assertion is considered to always fail if the values it checks are pushed onto the stack by
+/// the immediately preceding instructions (e.g., `push.0 assert`).
+pub(super) fn check_unreachable_code(body: &CodeBody) -> Vec<AssemblyWarning> {
+    let mut warnings = Vec::new();
+    collect_unreachable_code(body, &mut warnings);
+    warnings
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Adds indexes of the local procedures invoked from the specified body to the provided list.
+fn collect_invoked_local_procs(body: &CodeBody, invoked: &mut Vec<u16>) {
+    for node in body.nodes() {
+        match node {
+            Node::Instruction(
+                Instruction::ExecLocal(idx)
+                | Instruction::CallLocal(idx)
+                | Instruction::ProcRefLocal(idx),
+            ) => invoked.push(*idx),
+            Node::Instruction(_) => (),
+            Node::IfElse {
+                true_case,
+                false_case,
+            } => {
+                collect_invoked_local_procs(true_case, invoked);
+                collect_invoked_local_procs(false_case, invoked);
+            }
+            Node::Repeat { body, .. } | Node::For { body, .. } | Node::While { body } => {
+                collect_invoked_local_procs(body, invoked)
+            }
+        }
+    }
+}
+
+/// Returns true if any of the instructions in the specified body accesses procedure locals.
+fn accesses_locals(body: &CodeBody) -> bool {
+    body.nodes().iter().any(|node| match node {
+        Node::Instruction(instruction) => matches!(
+            instruction,
+            Instruction::Locaddr(_)
+                | Instruction::LocLoad(_)
+                | Instruction::LocLoadW(_)
+                | Instruction::LocStore(_)
+                | Instruction::LocStoreW(_)
+        ),
+        Node::IfElse {
+            true_case,
+            false_case,
+        } => accesses_locals(true_case) || accesses_locals(false_case),
+        Node::Repeat { body, .. } | Node::For { body, .. } | Node::While { body } => {
+            accesses_locals(body)
+        }
+    })
+}
+
+/// Adds a warning for the code following an always failing assertion in the specified body, and
+/// in all of the nested bodies, to the provided list.
+fn collect_unreachable_code(body: &CodeBody, warnings: &mut Vec<AssemblyWarning>) {
+    let nodes = body.nodes();
+    for (idx, node) in nodes.iter().enumerate() {
+        match node {
+            Node::Instruction(instruction) => {
+                if idx + 1 < nodes.len() && always_fails(instruction, &nodes[..idx]) {
+                    let location =
+                        body.source_locations().get(idx + 1).copied().unwrap_or_default();
+                    warnings.push(AssemblyWarning::unreachable_code(location));
+                    return;
+                }
+            }
+            Node::IfElse {
+                true_case,
+                false_case,
+            } => {
+                collect_unreachable_code(true_case, warnings);
+                collect_unreachable_code(false_case, warnings);
+            }
+            Node::Repeat { body, .. } | Node::For { body, .. } | Node::While { body } => {
+                collect_unreachable_code(body, warnings)
+            }
+        }
+    }
+}
+
+/// Returns true if the specified instruction is an assertion which always fails when executed
+/// right after the specified preceding nodes.
+fn always_fails(instruction: &Instruction, preceding: &[Node]) -> bool {
+    let values = pushed_values(preceding);
+    match instruction {
+        Instruction::Assert | Instruction::AssertWithError(_) => {
+            values.first().is_some_and(|value| *value != 1)
+        }
+        Instruction::Assertz | Instruction::AssertzWithError(_) => {
+            values.first().is_some_and(|value| *value != 0)
+        }
+        Instruction::AssertEq | Instruction::AssertEqWithError(_) => match values.get(..2) {
+            Some([a, b]) => a != b,
+            _ => false,
+        },
+        Instruction::U32Assert | Instruction::U32AssertWithError(_) => {
+            values.first().is_some_and(|value| *value > u32::MAX as u64)
+        }
+        Instruction::U32Assert2 | Instruction::U32Assert2WithError(_) => {
+            values.iter().take(2).any(|value| *value > u32::MAX as u64)
+        }
+        _ => false,
+    }
+}
+
+/// Returns the values which are on top of the stack right after the specified nodes are executed,
+/// starting from the topmost one, as far as they are pushed by the trailing push instructions.
+fn pushed_values(preceding: &[Node]) -> Vec<u64> {
+    let mut values = Vec::new();
+    for node in preceding.iter().rev() {
+        let pushed = match node {
+            Node::Instruction(Instruction::PushU8(value)) => vec![*value as u64],
+            Node::Instruction(Instruction::PushU16(value)) => vec![*value as u64],
+            Node::Instruction(Instruction::PushU32(value)) => vec![*value as u64],
+            Node::Instruction(Instruction::PushFelt(value)) => vec![value.as_int()],
+            Node::Instruction(Instruction::PushWord(values)) => {
+                values.iter().map(Felt::as_int).collect()
+            }
+            Node::Instruction(Instruction::PushU8List(values)) => {
+                values.iter().map(|value| *value as u64).collect()
+            }
+            Node::Instruction(Instruction::PushU16List(values)) => {
+                values.iter().map(|value| *value as u64).collect()
+            }
+            Node::Instruction(Instruction::PushU32List(values)) => {
+                values.iter().map(|value| *value as u64).collect()
+            }
+            Node::Instruction(Instruction::PushFeltList(values)) => {
+                values.iter().map(Felt::as_int).collect()
+            }
+            _ => break,
+        };
+        // values are pushed in order, and thus, the last pushed value ends up on top of the stack
+        values.extend(pushed.into_iter().rev());
+    }
+    values
+}
@@ -3,13 +3,14 @@
 //! Structs in this module (specifically [ProgramAst] and [ModuleAst]) can be used to parse source
 //! code into relevant ASTs. This can be done via their `parse()` methods.
 use super::{
-    crypto::hash::RpoDigest, ByteReader, ByteWriter, Deserializable, DeserializationError, Felt,
-    LabelError, LibraryPath, ParsingError, ProcedureId, ProcedureName, Serializable, SliceReader,
-    StarkField, Token, TokenStream, MAX_LABEL_LEN,
+    crypto::hash::RpoDigest, AssemblyWarning, ByteReader, ByteWriter, DataSegment, Deserializable,
+    DeserializationError, Felt, LabelError, LibraryPath, ParsingError, ProcedureId, ProcedureName,
+    Serializable, SliceReader, StarkField, Token, TokenStream, MAX_LABEL_LEN,
 };
-use alloc::collections::BTreeMap;
+use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::string::String;
 use alloc::vec::Vec;
+use core::cell::RefCell;
 use vm_core::utils::bound_into_included_u64;
 
 pub use tracing::{event, info_span, instrument, Level};
@@ -23,12 +24,23 @@ pub use nodes::{AdviceInjectorNode, Instruction, Node};
 mod code_body;
 pub use code_body::CodeBody;
 
+mod constants;
+pub use constants::{
+    error_code_from_msg, ConstantValue, ImportedConstants, MAX_CONST_ARRAY_LEN,
+    MAX_DATA_SEGMENT_LEN, MAX_ERROR_MSG_LEN,
+};
+
 mod format;
 use format::*;
 
+mod formatter;
+pub use formatter::format_source;
+
 mod imports;
 pub use imports::ModuleImports;
 
+mod lints;
+
 mod invocation_target;
 pub use invocation_target::InvocationTarget;
 
@@ -44,12 +56,19 @@ mod program;
 pub use program::ProgramAst;
 
 pub(crate) use parsers::{
-    parse_param_with_constant_lookup, NAMESPACE_LABEL_PARSER, PROCEDURE_LABEL_PARSER,
+    parse_param_with_constant_lookup, CONSTANT_LABEL_PARSER, NAMESPACE_LABEL_PARSER,
+    PROCEDURE_LABEL_PARSER,
 };
 
 mod serde;
 pub use serde::AstSerdeOptions;
 
+mod signature;
+pub use signature::{ParamType, ProcedureSignature, SignatureParam, MAX_SIGNATURE_PARAMS};
+
+mod stack_effect;
+pub use stack_effect::{StackEffect, StackEffectAnalyzer, StackEffectError};
+
 #[cfg(test)]
 pub mod tests;
 
@@ -80,9 +99,181 @@ const MAX_STACK_WORD_OFFSET: u8 = 12;
 // TYPE ALIASES
 // ================================================================================================
 type LocalProcMap = BTreeMap<ProcedureName, (u16, ProcedureAst)>;
-type LocalConstMap = BTreeMap<String, u64>;
 type ReExportedProcMap = BTreeMap<ProcedureName, ProcReExport>;
 type InvokedProcsMap = BTreeMap<ProcedureId, (ProcedureName, LibraryPath)>;
+type LocalVarMap = BTreeMap<String, (u16, u16)>;
+
+// LOCAL CONSTANTS
+// ================================================================================================
+
+/// A map of constants available in a module or a program, which maps a constant name to its value.
+///
+/// The map contains constants declared in the module or the program itself, constants exported
+/// from the imported modules, and external constants; constants of the imported modules are keyed
+/// by their qualified names, e.g. `u64::MAX`.
+///
+/// The map also keeps track of the location at which each constant was declared, of the declared
+/// constants which are exported, and of the constants which were looked up during parsing, so
+/// that unused constants and imports can be reported. Error messages of the assertions parsed
+/// using this map are collected in it as well.
+#[derive(Debug, Default)]
+pub(crate) struct LocalConstMap {
+    constants: BTreeMap<String, (ConstantValue, SourceLocation)>,
+    exported: BTreeSet<String>,
+    imported: BTreeMap<String, ConstantValue>,
+    external: BTreeMap<String, ConstantValue>,
+    lenient_imports: bool,
+    used: RefCell<BTreeSet<String>>,
+    error_messages: RefCell<BTreeMap<u32, String>>,
+    error_codes: RefCell<BTreeSet<u32>>,
+}
+
+impl LocalConstMap {
+    /// Returns a constant map containing the constants exported from the imported modules and the
+    /// external constants.
+    fn with_imports(import_info: &ModuleImports, imported: &ImportedConstants) -> Self {
+        let mut constants = Self {
+            external: imported.external_constants().clone(),
+            lenient_imports: imported.is_lenient(),
+            ..Self::default()
+        };
+        for (alias, path) in import_info.imported_modules() {
+            let Some(module_constants) = imported.get_module_constants(path) else {
+                continue;
+            };
+            for (name, value) in module_constants.iter() {
+                let name = format!("{alias}{}{name}", LibraryPath::PATH_DELIM);
+                constants.imported.insert(name, value.clone());
+            }
+        }
+        constants
+    }
+
+    /// Returns true if a constant with the specified name has been declared.
+    fn contains_key(&self, name: &str) -> bool {
+        self.constants.contains_key(name)
+    }
+
+    /// Returns true if the specified name is the name of an external constant.
+    fn is_external(&self, name: &str) -> bool {
+        self.external.contains_key(name)
+    }
+
+    /// Returns the value of the constant with the specified name, and marks the constant as used.
+    ///
+    /// Constants of imported modules are looked up by their qualified names, e.g. `u64::MAX`.
+    fn get(&self, name: &str) -> Option<&ConstantValue> {
+        let value = match self.constants.get(name) {
+            Some((value, _)) => value,
+            None => self.imported.get(name).or_else(|| self.external.get(name))?,
+        };
+        self.used.borrow_mut().insert(name.into());
+        Some(value)
+    }
+
+    /// Returns true if the specified name is a name of a constant which cannot be resolved (i.e.,
+    /// a constant of an imported module or an external constant), but references to such
+    /// constants are accepted; such constants are marked as used.
+    fn is_unresolved(&self, name: &str) -> bool {
+        let is_unresolved = self.lenient_imports
+            && !self.constants.contains_key(name)
+            && !self.imported.contains_key(name)
+            && !self.external.contains_key(name);
+        if is_unresolved {
+            self.used.borrow_mut().insert(name.into());
+        }
+        is_unresolved
+    }
+
+    /// Declares a constant with the specified name and value at the specified location.
+    fn insert(&mut self, name: String, value: ConstantValue, location: SourceLocation) {
+        self.constants.insert(name, (value, location));
+    }
+
+    /// Marks the declared constant with the specified name as exported.
+    fn export(&mut self, name: String) {
+        self.exported.insert(name);
+    }
+
+    /// Returns the names and values of the declared constants which are exported.
+    fn exported(&self) -> BTreeMap<String, ConstantValue> {
+        self.exported
+            .iter()
+            .map(|name| (name.clone(), self.constants[name].0.clone()))
+            .collect()
+    }
+
+    /// Returns an iterator over names and declaration locations of the declared constants which
+    /// are neither exported nor looked up.
+    fn unused(&self) -> impl Iterator<Item = (&str, &SourceLocation)> + '_ {
+        let used = self.used.borrow().clone();
+        self.constants
+            .iter()
+            .filter(move |(name, _)| !used.contains(name.as_str()))
+            .filter(|(name, _)| !self.exported.contains(name.as_str()))
+            .map(|(name, (_, location))| (name.as_str(), location))
+    }
+
+    /// Records the error message of an assertion failing with the specified error code.
+    ///
+    /// Returns a description of the error the code collides with if the code is already used for a
+    /// different message, or as an explicitly specified error code.
+    fn add_error_message(&self, code: u32, msg: &str) -> Result<(), String> {
+        if self.error_codes.borrow().contains(&code) {
+            return Err("an assertion without an error message".into());
+        }
+        let mut error_messages = self.error_messages.borrow_mut();
+        match error_messages.get(&code) {
+            Some(other) if other != msg => Err(format!("message \"{other}\"")),
+            Some(_) => Ok(()),
+            None => {
+                error_messages.insert(code, msg.into());
+                Ok(())
+            }
+        }
+    }
+
+    /// Records an explicitly specified error code of an assertion (e.g., `assert.err=123`).
+    ///
+    /// Returns a description of the error the code collides with if the code is already used for
+    /// an error message.
+    fn add_error_code(&self, code: u32) -> Result<(), String> {
+        if let Some(msg) = self.error_messages.borrow().get(&code) {
+            return Err(format!("message \"{msg}\""));
+        }
+        self.error_codes.borrow_mut().insert(code);
+        Ok(())
+    }
+
+    /// Returns the error messages recorded via [LocalConstMap::add_error_message()], keyed by
+    /// their error codes.
+    fn error_messages(&self) -> BTreeMap<u32, String> {
+        self.error_messages.borrow().clone()
+    }
+
+    /// Returns the paths of the imported modules whose constants were looked up.
+    fn used_imports<'a>(&'a self, import_info: &'a ModuleImports) -> BTreeSet<&'a LibraryPath> {
+        self.used
+            .borrow()
+            .iter()
+            .filter_map(|name| name.split_once(LibraryPath::PATH_DELIM))
+            .filter_map(|(alias, _)| import_info.get_module_path(alias))
+            .collect()
+    }
+}
+
+impl<const N: usize> From<[(String, u64); N]> for LocalConstMap {
+    fn from(constants: [(String, u64); N]) -> Self {
+        let constants = constants
+            .into_iter()
+            .map(|(name, value)| (name, (ConstantValue::Felt(value), SourceLocation::default())))
+            .collect();
+        Self {
+            constants,
+            ..Self::default()
+        }
+    }
+}
 
 // HELPER FUNCTIONS
 // ================================================================================================
@@ -95,15 +286,45 @@ fn sort_procs_into_vec(proc_map: LocalProcMap) -> Vec<ProcedureAst> {
     procedures.into_iter().map(|(_idx, proc)| proc).collect()
 }
 
-/// Logging a warning message for every imported but unused module.
-fn check_unused_imports(import_info: &ModuleImports) {
-    let import_lib_paths = import_info.import_paths();
-    let invoked_procs_paths: Vec<&LibraryPath> =
-        import_info.invoked_procs().iter().map(|(_id, (_name, path))| path).collect();
+/// Returns the error codes of the assertions in the specified bodies which are specified
+/// explicitly, i.e., which are not derived from any of the specified error messages.
+fn explicit_error_codes<'a, I>(bodies: I, error_messages: &BTreeMap<u32, String>) -> BTreeSet<u32>
+where
+    I: IntoIterator<Item = &'a CodeBody>,
+{
+    let mut codes = BTreeSet::new();
+    bodies.into_iter().for_each(|body| collect_error_codes(body, &mut codes));
+    codes.retain(|code| *code != 0 && !error_messages.contains_key(code));
+    codes
+}
 
-    for lib in import_lib_paths {
-        if !invoked_procs_paths.contains(&lib) {
-            event!(Level::WARN, "unused import: \"{}\"", lib);
+/// Adds the error codes of the assertions in the specified body, and in all of the nested bodies,
+/// to the provided set.
+fn collect_error_codes(body: &CodeBody, codes: &mut BTreeSet<u32>) {
+    for node in body.nodes() {
+        match node {
+            Node::Instruction(
+                Instruction::AssertWithError(code)
+                | Instruction::AssertEqWithError(code)
+                | Instruction::AssertEqwWithError(code)
+                | Instruction::AssertzWithError(code)
+                | Instruction::U32AssertWithError(code)
+                | Instruction::U32Assert2WithError(code)
+                | Instruction::U32AssertWWithError(code),
+            ) => {
+                codes.insert(*code);
+            }
+            Node::Instruction(_) => (),
+            Node::IfElse {
+                true_case,
+                false_case,
+            } => {
+                collect_error_codes(true_case, codes);
+                collect_error_codes(false_case, codes);
+            }
+            Node::Repeat { body, .. } | Node::For { body, .. } | Node::While { body } => {
+                collect_error_codes(body, codes)
+            }
         }
     }
 }
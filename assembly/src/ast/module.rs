@@ -1,17 +1,21 @@
-use super::check_unused_imports;
 use super::{
+    constants::{read_constants, read_error_messages, write_constants, write_error_messages},
+    event, explicit_error_codes,
     format::*,
     imports::ModuleImports,
+    lints,
     parsers::{parse_constants, ParserContext},
     serde::AstSerdeOptions,
-    sort_procs_into_vec, LocalProcMap, ProcReExport, ProcedureAst, ReExportedProcMap, MAX_DOCS_LEN,
-    MAX_LOCAL_PROCS, MAX_REEXPORTED_PROCS,
+    sort_procs_into_vec, ConstantValue, ImportedConstants, LocalConstMap, LocalProcMap,
+    LocalVarMap, ProcReExport, ProcedureAst, ReExportedProcMap, MAX_DOCS_LEN, MAX_LOCAL_PROCS,
+    MAX_REEXPORTED_PROCS,
     {
-        ByteReader, ByteWriter, Deserializable, DeserializationError, ParsingError, SliceReader,
-        Token, TokenStream,
+        AssemblyWarning, ByteReader, ByteWriter, Deserializable, DeserializationError, Level,
+        ParsingError, SliceReader, Token, TokenStream,
     },
 };
 
+use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::{fmt, str::from_utf8};
@@ -23,13 +27,21 @@ use vm_core::utils::Serializable;
 /// An abstract syntax tree of a Miden module.
 ///
 /// A module AST consists of a list of procedure ASTs, a list of re-exported procedures, a list of
-/// imports, and module documentation. Local procedures could be internal or exported.
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// imports, a list of exported constants, error messages of the assertions in the procedures, and
+/// module documentation. Local procedures could be internal or exported.
+///
+/// If the module was parsed from source code, the AST also contains the warnings about the source
+/// code; these are not a part of the module, and thus, are not serialized and are ignored when
+/// comparing module ASTs.
+#[derive(Debug, Clone)]
 pub struct ModuleAst {
     pub(super) local_procs: Vec<ProcedureAst>,
     pub(super) reexported_procs: Vec<ProcReExport>,
     pub(super) import_info: ModuleImports,
+    pub(super) exported_constants: BTreeMap<String, ConstantValue>,
+    pub(super) error_messages: BTreeMap<u32, String>,
     pub(super) docs: Option<String>,
+    pub(super) warnings: Vec<AssemblyWarning>,
 }
 
 impl ModuleAst {
@@ -61,7 +73,10 @@ impl ModuleAst {
             local_procs,
             reexported_procs,
             import_info: Default::default(),
+            exported_constants: BTreeMap::new(),
+            error_messages: BTreeMap::new(),
             docs,
+            warnings: Vec::new(),
         })
     }
 
@@ -75,33 +90,141 @@ impl ModuleAst {
         self
     }
 
+    /// Adds the provided constants to the list of constants exported from this module.
+    pub fn with_exported_constants(
+        mut self,
+        constants: impl IntoIterator<Item = (String, ConstantValue)>,
+    ) -> Self {
+        self.exported_constants.extend(constants);
+        self
+    }
+
+    /// Adds the provided error messages, keyed by their error codes, to the error messages of the
+    /// assertions in this module.
+    pub fn with_error_messages(mut self, error_messages: BTreeMap<u32, String>) -> Self {
+        self.error_messages.extend(error_messages);
+        self
+    }
+
     // PARSER
     // --------------------------------------------------------------------------------------------
     /// Parses the provided source into a [ModuleAst].
     ///
     /// A module consists of internal and exported procedures but does not contain a body.
+    ///
+    /// # Errors
+    /// Returns the first syntax error found in the source. To get all syntax errors found in the
+    /// source, use [ModuleAst::parse_with_recovery()].
     pub fn parse(source: &str) -> Result<Self, ParsingError> {
-        let mut tokens = TokenStream::new(source)?;
-        let mut import_info = ModuleImports::parse(&mut tokens)?;
-        let local_constants = parse_constants(&mut tokens)?;
+        Self::parse_in_discovery_order(source, &ImportedConstants::default())
+            .map(|(ast, _)| ast)
+            .map_err(|mut errors| errors.swap_remove(0))
+    }
+
+    /// Parses the provided source into a [ModuleAst], reporting all syntax errors found in the
+    /// source.
+    ///
+    /// Unlike [ModuleAst::parse()], this does not stop at the first syntax error. Instead, the
+    /// parser recovers at the nearest instruction, block, or procedure boundary and continues, so
+    /// that all errors are reported in a single pass.
+    ///
+    /// # Errors
+    /// Returns a non-empty list of syntax errors, ordered by their position in the source.
+    pub fn parse_with_recovery(source: &str) -> Result<Self, Vec<ParsingError>> {
+        Self::parse_with_warnings(source).map(|(module, _)| module)
+    }
+
+    /// Parses the provided source into a [ModuleAst], reporting all syntax errors found in the
+    /// source, as well as warnings about code which is valid but is likely to contain a mistake.
+    ///
+    /// The following warnings are reported:
+    /// - Imported modules from which no procedure is invoked or re-exported, and no constant is
+    ///   referenced.
+    /// - Constants which are never referenced.
+    /// - Internal procedures which are not reachable from any of the exported procedures.
+    /// - Code following an assertion which always fails.
+    /// - Procedures which declare memory locals but never access them.
+    ///
+    /// # Errors
+    /// Returns a non-empty list of syntax errors, ordered by their position in the source.
+    pub fn parse_with_warnings(
+        source: &str,
+    ) -> Result<(Self, Vec<AssemblyWarning>), Vec<ParsingError>> {
+        Self::parse_with_imported_constants(source, &ImportedConstants::default())
+    }
+
+    /// Parses the provided source into a [ModuleAst] in the same way as
+    /// [ModuleAst::parse_with_warnings()], resolving references to constants exported from the
+    /// imported modules (e.g., `u64::MAX`) against the provided set of imported constants. Code
+    /// excluded by conditional compilation directives (e.g., `#if DEBUG`) is determined by the
+    /// features enabled in this set.
+    ///
+    /// # Errors
+    /// Returns a non-empty list of syntax errors, ordered by their position in the source.
+    pub fn parse_with_imported_constants(
+        source: &str,
+        imported_constants: &ImportedConstants,
+    ) -> Result<(Self, Vec<AssemblyWarning>), Vec<ParsingError>> {
+        Self::parse_in_discovery_order(source, imported_constants).map_err(|mut errors| {
+            errors.sort_by_key(|err| *err.location());
+            errors
+        })
+    }
+
+    /// Parses the provided source in the same way as [ModuleAst::parse_with_imported_constants()],
+    /// returning the syntax errors in the order they were found by the parser.
+    ///
+    /// Since the parser reports an unterminated statement or procedure only after it has parsed
+    /// its body, errors of nested blocks are found before the errors of the enclosing statements.
+    fn parse_in_discovery_order(
+        source: &str,
+        imported_constants: &ImportedConstants,
+    ) -> Result<(Self, Vec<AssemblyWarning>), Vec<ParsingError>> {
+        let mut tokens =
+            TokenStream::new(source, imported_constants.features()).map_err(|err| vec![err])?;
+        let mut import_info = ModuleImports::parse(&mut tokens).map_err(|err| vec![err])?;
+        let import_locations = lints::import_locations(&mut tokens);
+        let local_constants = LocalConstMap::with_imports(&import_info, imported_constants);
+        let local_constants =
+            parse_constants(&mut tokens, local_constants, true).map_err(|err| vec![err])?;
         let mut context = ParserContext {
             import_info: &mut import_info,
             local_procs: LocalProcMap::default(),
             reexported_procs: ReExportedProcMap::default(),
             local_constants,
             num_proc_locals: 0,
+            proc_locals: LocalVarMap::default(),
+            reexported_modules: Default::default(),
+            errors: Vec::new(),
+            eof_error_reported: false,
         };
-        context.parse_procedures(&mut tokens, true)?;
+        context.parse_procedures(&mut tokens, true);
 
         // make sure program body is absent and there are no more instructions.
         if let Some(token) = tokens.read() {
-            if token.parts()[0] == Token::BEGIN {
-                return Err(ParsingError::not_a_library_module(token));
+            let err = if token.parts()[0] == Token::BEGIN {
+                ParsingError::not_a_library_module(token)
             } else {
-                return Err(ParsingError::dangling_ops_after_module(token));
-            }
+                ParsingError::dangling_ops_after_module(token)
+            };
+            context.errors.push(err);
+        }
+
+        if !context.errors.is_empty() {
+            return Err(context.errors);
         }
 
+        let mut warnings = lints::check_unused_imports(
+            context.import_info,
+            &import_locations,
+            &context.reexported_modules,
+            &context.local_constants,
+        );
+        warnings.extend(lints::check_unused_constants(&context.local_constants));
+
+        let exported_constants = context.local_constants.exported();
+        let error_messages = context.local_constants.error_messages();
+
         // build a list of local procs sorted by their declaration order
         let local_procs = sort_procs_into_vec(context.local_procs);
 
@@ -111,9 +234,27 @@ impl ModuleAst {
         // get module docs and make sure the size is within the limit
         let docs = tokens.take_module_comments();
 
-        check_unused_imports(context.import_info);
+        let mut module = Self::new(local_procs, reexported_procs, docs)
+            .map_err(|err| vec![err])?
+            .with_import_info(import_info)
+            .with_exported_constants(exported_constants)
+            .with_error_messages(error_messages);
+
+        let exported_bodies =
+            module.local_procs.iter().filter(|proc| proc.is_export).map(|proc| &proc.body);
+        warnings.extend(lints::check_unused_procedures(&module.local_procs, exported_bodies));
+        warnings.extend(lints::check_unused_locals(&module.local_procs));
+        for proc in module.local_procs.iter() {
+            warnings.extend(lints::check_unreachable_code(&proc.body));
+        }
 
-        Ok(Self::new(local_procs, reexported_procs, docs)?.with_import_info(import_info))
+        warnings.sort_by_key(|warning| *warning.source_location());
+        for warning in warnings.iter() {
+            event!(Level::WARN, "{}", warning);
+        }
+        module.warnings.clone_from(&warnings);
+
+        Ok((module, warnings))
     }
 
     // PUBLIC ACCESSORS
@@ -139,6 +280,30 @@ impl ModuleAst {
         &self.import_info
     }
 
+    /// Returns the constants exported from this module, keyed by their names.
+    pub fn exported_constants(&self) -> &BTreeMap<String, ConstantValue> {
+        &self.exported_constants
+    }
+
+    /// Returns the error messages of the assertions in this module, keyed by their error codes.
+    pub fn error_messages(&self) -> &BTreeMap<u32, String> {
+        &self.error_messages
+    }
+
+    /// Returns the error codes of the assertions in this module which are specified explicitly
+    /// rather than derived from error messages.
+    pub(crate) fn explicit_error_codes(&self) -> BTreeSet<u32> {
+        explicit_error_codes(self.local_procs.iter().map(|proc| &proc.body), &self.error_messages)
+    }
+
+    /// Returns the warnings about the source code of this module found when the module was
+    /// parsed, ordered by their position in the source.
+    ///
+    /// See [ModuleAst::parse_with_warnings()] for the list of reported warnings.
+    pub fn warnings(&self) -> &[AssemblyWarning] {
+        &self.warnings
+    }
+
     // STATE MUTATORS
     // --------------------------------------------------------------------------------------------
 
@@ -175,6 +340,10 @@ impl ModuleAst {
             self.import_info.write_into(target);
         }
 
+        // serialize exported constants and error messages
+        write_constants(target, &self.exported_constants);
+        write_error_messages(target, &self.error_messages);
+
         // serialize procedures
         assert!(self.local_procs.len() <= u16::MAX as usize, "too many local procs");
         assert!(
@@ -212,6 +381,10 @@ impl ModuleAst {
             ModuleImports::default()
         };
 
+        // deserialize exported constants and error messages
+        let exported_constants = read_constants(source)?;
+        let error_messages = read_error_messages(source)?;
+
         // deserialize re-exports
         let num_reexported_procs = source.read_u16()? as usize;
         let reexported_procs = source.read_many::<ProcReExport>(num_reexported_procs)?;
@@ -222,7 +395,10 @@ impl ModuleAst {
 
         match Self::new(local_procs, reexported_procs, docs) {
             Err(err) => Err(DeserializationError::UnknownError(err.message().clone())),
-            Ok(res) => Ok(res.with_import_info(import_info)),
+            Ok(res) => Ok(res
+                .with_import_info(import_info)
+                .with_exported_constants(exported_constants)
+                .with_error_messages(error_messages)),
         }
     }
 
@@ -304,6 +480,14 @@ impl fmt::Display for ModuleAst {
             writeln!(f)?;
         }
 
+        // Exported constants
+        for (name, value) in self.exported_constants.iter() {
+            writeln!(f, "export.const.{name}={value}")?;
+        }
+        if !self.exported_constants.is_empty() {
+            writeln!(f)?;
+        }
+
         // Re-exports
         for proc in self.reexported_procs.iter() {
             writeln!(f, "export.{}", proc.name())?;
@@ -320,3 +504,17 @@ impl fmt::Display for ModuleAst {
         Ok(())
     }
 }
+
+impl PartialEq for ModuleAst {
+    fn eq(&self, other: &Self) -> bool {
+        // warnings are not a part of the module, and thus, are not compared
+        self.local_procs == other.local_procs
+            && self.reexported_procs == other.reexported_procs
+            && self.import_info == other.import_info
+            && self.exported_constants == other.exported_constants
+            && self.error_messages == other.error_messages
+            && self.docs == other.docs
+    }
+}
+
+impl Eq for ModuleAst {}
@@ -58,6 +58,19 @@ impl fmt::Display for FormattableNode<'_> {
                 self.context.indent(f)?;
                 writeln!(f, "end")
             }
+            Node::For { times, body } => {
+                self.context.indent(f)?;
+                writeln!(f, "for.{times}")?;
+
+                write!(
+                    f,
+                    "{}",
+                    FormattableCodeBody::new(body, &self.context.inner_scope_context())
+                )?;
+
+                self.context.indent(f)?;
+                writeln!(f, "end")
+            }
             Node::While { body } => {
                 self.context.indent(f)?;
                 writeln!(f, "while.true")?;
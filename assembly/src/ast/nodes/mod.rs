@@ -32,6 +32,12 @@ pub enum Node {
         times: u32,
         body: CodeBody,
     },
+    /// A bounded loop which executes its body the specified number of times, with the index of
+    /// the current iteration at the top of the stack; the body must leave the index there.
+    For {
+        times: u32,
+        body: CodeBody,
+    },
     While {
         body: CodeBody,
     },
@@ -36,6 +36,21 @@ impl Deserializable for Node {
             let body = CodeBody::new(nodes);
 
             Ok(Node::Repeat { times, body })
+        } else if first_byte == OpCode::For as u8 {
+            source.read_u8()?;
+
+            let times = source.read_u32()?;
+            if times == 0 {
+                return Err(DeserializationError::InvalidValue(
+                    "for loop must have at least one iteration".into(),
+                ));
+            }
+
+            let nodes_len = source.read_u16()? as usize;
+            let nodes = source.read_many::<Node>(nodes_len)?;
+            let body = CodeBody::new(nodes);
+
+            Ok(Node::For { times, body })
         } else if first_byte == OpCode::While as u8 {
             source.read_u8()?;
 
@@ -355,6 +370,7 @@ impl Deserializable for Instruction {
             // ----- control flow -----------------------------------------------------------------
             // control flow instructions should be parsed as a part of Node::read_from() and we
             // should never get here
+            OpCode::For => unreachable!(),
             OpCode::IfElse => unreachable!(),
             OpCode::Repeat => unreachable!(),
             OpCode::While => unreachable!(),
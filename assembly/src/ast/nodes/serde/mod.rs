@@ -269,6 +269,7 @@ pub enum OpCode {
     Trace = 228,
 
     // ----- control flow -------------------------------------------------------------------------
+    For = 252,
     IfElse = 253,
     Repeat = 254,
     While = 255,
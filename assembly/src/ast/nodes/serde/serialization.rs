@@ -35,6 +35,14 @@ impl Serializable for Node {
                 target.write_u16(body.nodes().len() as u16);
                 target.write_many(body.nodes());
             }
+            Self::For { times, body } => {
+                OpCode::For.write_into(target);
+                target.write_u32(*times);
+
+                assert!(body.nodes().len() <= MAX_BODY_LEN, "too many body nodes");
+                target.write_u16(body.nodes().len() as u16);
+                target.write_many(body.nodes());
+            }
             Self::While { body } => {
                 OpCode::While.write_into(target);
 
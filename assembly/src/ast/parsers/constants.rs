@@ -1,4 +1,7 @@
-use super::{Felt, LocalConstMap, ParsingError, Token};
+use super::{
+    get_constant_element, split_const_index, Felt, LocalConstMap, ParsingError, Token,
+    UNRESOLVED_CONST_VALUE,
+};
 use alloc::string::String;
 use alloc::vec::Vec;
 use core::fmt::Display;
@@ -218,7 +221,8 @@ impl<'a> OperationIterator<'a> {
     }
 }
 
-/// Returns the number in `value` or the constant value if the value is the name of the constant.
+/// Returns the number in `value` or the constant value if the value is a reference to a constant
+/// (i.e., the name of a constant, or an indexed element of an array constant).
 fn parse_operand(
     op: &Token,
     expression: &str,
@@ -230,16 +234,21 @@ fn parse_operand(
     if let Ok(parsed_number) = parsed_number {
         Ok(Operation::Value(Felt::new(parsed_number)))
     }
-    // if it is a name of the constant get its value from the `constants` map
+    // if it is a reference to a constant get its value from the `constants` map
     else {
-        let parsed_number = constants.get(&value).ok_or_else(|| {
+        let (name, index) = split_const_index(&value);
+        if constants.is_unresolved(name) {
+            return Ok(Operation::Value(Felt::new(UNRESOLVED_CONST_VALUE)));
+        }
+        let constant = constants.get(name).ok_or_else(|| {
             ParsingError::invalid_const_value(
                 op,
                 expression,
-                &format!("constant with name {} was not initialized", value),
+                &format!("constant with name {} was not initialized", name),
             )
         })?;
-        Ok(Operation::Value(Felt::new(*parsed_number)))
+        let parsed_number = get_constant_element(op, name, constant, index, constants)?;
+        Ok(Operation::Value(Felt::new(parsed_number)))
     }
 }
 
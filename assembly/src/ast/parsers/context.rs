@@ -1,9 +1,10 @@
 use super::{
-    super::ProcReExport, adv_ops, debug, events, field_ops, io_ops, stack_ops, sys_ops, u32_ops,
-    CodeBody, Instruction, InvocationTarget, LibraryPath, LocalConstMap, LocalProcMap,
-    ModuleImports, Node, ParsingError, ProcedureAst, ProcedureId, ProcedureName, ReExportedProcMap,
-    Token, TokenStream, MAX_BODY_LEN, MAX_DOCS_LEN,
+    super::ProcReExport, adv_ops, debug, events, field_ops, io_ops, is_const_export, stack_ops,
+    sys_ops, u32_ops, CodeBody, Instruction, InvocationTarget, LibraryPath, LocalConstMap,
+    LocalProcMap, LocalVarMap, ModuleImports, Node, ParsingError, ProcedureAst, ProcedureId,
+    ProcedureName, ReExportedProcMap, Token, TokenStream, MAX_BODY_LEN, MAX_DOCS_LEN,
 };
+use alloc::collections::BTreeSet;
 use alloc::string::ToString;
 use alloc::vec::Vec;
 
@@ -11,15 +12,41 @@ use alloc::vec::Vec;
 // ================================================================================================
 
 /// AST Parser context that holds internal state to generate correct ASTs.
+///
+/// The parser does not stop at the first syntax error. Instead, errors are accumulated in the
+/// context, and the parser recovers at the nearest instruction, block, or procedure boundary, so
+/// that all errors in the source can be reported in a single pass.
 pub struct ParserContext<'a> {
     pub import_info: &'a mut ModuleImports,
     pub local_procs: LocalProcMap,
     pub reexported_procs: ReExportedProcMap,
     pub local_constants: LocalConstMap,
     pub num_proc_locals: u16,
+    pub proc_locals: LocalVarMap,
+    pub reexported_modules: BTreeSet<LibraryPath>,
+    pub errors: Vec<ParsingError>,
+    pub eof_error_reported: bool,
 }
 
 impl ParserContext<'_> {
+    // ERROR RECOVERY
+    // --------------------------------------------------------------------------------------------
+
+    /// Adds the provided error to the list of errors found in the source.
+    ///
+    /// Errors reported at the end of the token stream are caused by statements or procedures
+    /// without a matching `end` token. In such a case, the enclosing statements are not terminated
+    /// either; to avoid cascading errors, only the first such error is recorded.
+    pub fn report_error(&mut self, tokens: &TokenStream, err: ParsingError) {
+        if tokens.eof() {
+            if self.eof_error_reported {
+                return;
+            }
+            self.eof_error_reported = true;
+        }
+        self.errors.push(err);
+    }
+
     // STATEMENT PARSERS
     // --------------------------------------------------------------------------------------------
 
@@ -33,7 +60,7 @@ impl ParserContext<'_> {
         tokens.advance();
 
         // read the `if` clause
-        let mut true_case = self.parse_body(tokens, true)?;
+        let mut true_case = self.parse_body(tokens, true);
 
         // build the `else` clause; if the else clause is specified, then parse it;
         // otherwise, set the `else` to an empty vector
@@ -46,7 +73,7 @@ impl ParserContext<'_> {
                     tokens.advance();
 
                     // parse the `false` branch
-                    let false_case = self.parse_body(tokens, false)?;
+                    let false_case = self.parse_body(tokens, false);
 
                     // consume the `end` token
                     match tokens.read() {
@@ -107,7 +134,7 @@ impl ParserContext<'_> {
         tokens.advance();
 
         // read the loop body
-        let body = self.parse_body(tokens, false)?;
+        let body = self.parse_body(tokens, false);
 
         // consume the `end` token
         match tokens.read() {
@@ -138,7 +165,7 @@ impl ParserContext<'_> {
         tokens.advance();
 
         // read the loop body
-        let body = self.parse_body(tokens, false)?;
+        let body = self.parse_body(tokens, false);
 
         // consume the `end` token
         match tokens.read() {
@@ -160,6 +187,37 @@ impl ParserContext<'_> {
         Ok(Node::Repeat { times, body })
     }
 
+    /// Parses a for statement from the provided token stream into an AST node.
+    fn parse_for(&mut self, tokens: &mut TokenStream) -> Result<Node, ParsingError> {
+        // record start of the for block and consume the 'for' token
+        let for_start = tokens.pos();
+        let for_token = tokens.read().expect("no for token");
+        let times = for_token.parse_for(&self.local_constants)?;
+        tokens.advance();
+
+        // read the loop body
+        let body = self.parse_body(tokens, false);
+
+        // consume the `end` token
+        match tokens.read() {
+            None => {
+                let token = tokens.read_at(for_start).expect("no for token");
+                Err(ParsingError::unmatched_for(token))
+            }
+            Some(token) => match token.parts()[0] {
+                Token::END => token.validate_end(),
+                Token::ELSE => Err(ParsingError::dangling_else(token)),
+                _ => {
+                    let token = tokens.read_at(for_start).expect("no for token");
+                    Err(ParsingError::unmatched_for(token))
+                }
+            },
+        }?;
+        tokens.advance();
+
+        Ok(Node::For { times, body })
+    }
+
     // CALL PARSERS
     // --------------------------------------------------------------------------------------------
 
@@ -238,19 +296,32 @@ impl ParserContext<'_> {
     // PROCEDURE PARSERS
     // --------------------------------------------------------------------------------------------
 
-    /// Parse procedures in the source and store them in the program
-    pub fn parse_procedures(
-        &mut self,
-        tokens: &mut TokenStream,
-        allow_export: bool,
-    ) -> Result<(), ParsingError> {
+    /// Parse procedures in the source and store them in the program.
+    ///
+    /// Errors encountered while parsing a procedure are added to the list of errors in this
+    /// context, and parsing resumes after the `end` token of the malformed procedure.
+    pub fn parse_procedures(&mut self, tokens: &mut TokenStream, allow_export: bool) {
         // parse procedures until all `proc` or `exec` tokens have been consumed
         while let Some(token) = tokens.read() {
             let is_reexport = match token.parts()[0] {
+                Token::EXPORT if is_const_export(token) => {
+                    // constants must be declared above procedures; skip the declaration
+                    self.errors.push(ParsingError::const_invalid_scope(token));
+                    tokens.advance();
+                    continue;
+                }
+                Token::DATA => {
+                    // data segments must be declared in programs above procedures; skip the
+                    // declaration
+                    self.errors.push(ParsingError::data_invalid_scope(token));
+                    tokens.advance();
+                    continue;
+                }
                 Token::EXPORT => {
                     if !allow_export {
                         let proc_name = token.parts()[1];
-                        return Err(ParsingError::proc_export_not_allowed(token, proc_name));
+                        let err = ParsingError::proc_export_not_allowed(token, proc_name);
+                        self.errors.push(err);
                     }
                     token.parts()[1].contains(LibraryPath::PATH_DELIM)
                 }
@@ -261,19 +332,36 @@ impl ParserContext<'_> {
                 _ => break,
             };
 
+            let proc_start = tokens.pos();
             if is_reexport {
                 // parse procedure re-export and add it to the list of re-exported procedures
-                let proc = self.parse_reexported_procedure(tokens)?;
-                self.reexported_procs.insert(proc.name.clone(), proc);
+                match self.parse_reexported_procedure(tokens) {
+                    Ok(proc) => {
+                        self.reexported_procs.insert(proc.name.clone(), proc);
+                    }
+                    Err(err) => {
+                        // a re-export consists of a single token; skip it
+                        self.errors.push(err);
+                        tokens.seek(proc_start);
+                        tokens.advance();
+                    }
+                }
             } else {
                 // parse the procedure body and add it to the list of local procedures
-                let proc = self.parse_procedure(tokens)?;
-                let proc_idx = self.local_procs.len() as u16;
-                self.local_procs.insert(proc.name.clone(), (proc_idx, proc));
+                match self.parse_procedure(tokens) {
+                    Ok(proc) => {
+                        let proc_idx = self.local_procs.len() as u16;
+                        self.local_procs.insert(proc.name.clone(), (proc_idx, proc));
+                    }
+                    Err(err) => {
+                        self.report_error(tokens, err);
+                        self.num_proc_locals = 0;
+                        self.proc_locals.clear();
+                        skip_block(tokens, proc_start);
+                    }
+                }
             }
         }
-
-        Ok(())
     }
 
     /// Parses a procedure from token stream and add it to the set of local procedures defined
@@ -290,7 +378,7 @@ impl ParserContext<'_> {
         // parse procedure declaration, make sure the procedure with the same name hasn't been
         // declared previously, and consume the `proc` or `export` token.
         let header = tokens.read().expect("missing procedure header");
-        let (name, num_locals, is_export) = header.parse_proc()?;
+        let (name, num_locals, is_export, signature) = header.parse_proc()?;
         if self.contains_proc_name(&name) {
             return Err(ParsingError::duplicate_proc_name(header, name.as_str()));
         }
@@ -312,12 +400,15 @@ impl ParserContext<'_> {
             None
         };
 
+        // parse declarations of named locals, if any
+        let num_locals = self.parse_locals(tokens, num_locals)?;
         self.num_proc_locals = num_locals;
 
         // parse procedure body
-        let body = self.parse_body(tokens, false)?;
+        let body = self.parse_body(tokens, false);
 
         self.num_proc_locals = 0;
+        self.proc_locals.clear();
 
         // consume the 'end' token
         match tokens.read() {
@@ -337,8 +428,47 @@ impl ParserContext<'_> {
 
         // build and return the procedure
         let (nodes, locations) = body.into_parts();
-        Ok(ProcedureAst::new(name, num_locals, nodes, is_export, docs)
-            .with_source_locations(locations, start))
+        let proc = ProcedureAst::new(name, num_locals, nodes, is_export, docs)
+            .with_source_locations(locations, start);
+        Ok(match signature {
+            Some(signature) => proc.with_signature(signature),
+            None => proc,
+        })
+    }
+
+    /// Parses declarations of named locals at the start of a procedure body, and allocates the
+    /// declared locals right after the `num_locals` locals specified in the procedure header.
+    ///
+    /// Returns the total number of locals of the procedure.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - A local declaration is malformed.
+    /// - A local with the same name has already been declared in the procedure.
+    /// - The total number of locals exceeds the maximum number of locals of a procedure.
+    fn parse_locals(
+        &mut self,
+        tokens: &mut TokenStream,
+        num_locals: u16,
+    ) -> Result<u16, ParsingError> {
+        let mut num_locals = num_locals;
+        while let Some(token) = tokens.read() {
+            if token.parts()[0] != Token::LOCAL {
+                break;
+            }
+            let (name, size) = token.parse_local()?;
+            if self.proc_locals.contains_key(&name) {
+                return Err(ParsingError::duplicate_local_name(token, &name));
+            }
+            let total = num_locals as u64 + size as u64;
+            if total > u16::MAX as u64 {
+                return Err(ParsingError::too_many_proc_locals(token, total, u16::MAX as u64));
+            }
+            self.proc_locals.insert(name, (num_locals, size));
+            num_locals = total as u16;
+            tokens.advance();
+        }
+        Ok(num_locals)
     }
 
     /// Parses procedure re-export from the token stream and adds it to the set of procedures
@@ -350,7 +480,7 @@ impl ParserContext<'_> {
     /// - A procedure with the same name as re-exported procedure has already been either
     ///   declared or re-exported from this context.
     fn parse_reexported_procedure(
-        &self,
+        &mut self,
         tokens: &mut TokenStream,
     ) -> Result<ProcReExport, ParsingError> {
         let proc_start = tokens.pos();
@@ -383,6 +513,7 @@ impl ParserContext<'_> {
         }
 
         let proc_id = ProcedureId::from_name(&ref_name, module_path);
+        self.reexported_modules.insert(module_path.clone());
         Ok(ProcReExport::new(proc_id, proc_name, docs))
     }
 
@@ -391,47 +522,56 @@ impl ParserContext<'_> {
     /// Parses AST tokens from the token stream and add them to the nodes vector.
     ///
     /// Nodes are added to the list until `if`, `else`, `while`, `repeat`, `end`, `export`, `proc`,
-    /// or `begin` tokens are encountered, or an error occurs.
-    pub fn parse_body(
-        &mut self,
-        tokens: &mut TokenStream,
-        break_on_else: bool,
-    ) -> Result<CodeBody, ParsingError> {
+    /// or `begin` tokens are encountered.
+    ///
+    /// Errors encountered while parsing the body are added to the list of errors in this context.
+    /// A malformed instruction is skipped, while a malformed `if`, `while`, `repeat`, or `for`
+    /// statement is skipped up to and including its `end` token.
+    pub fn parse_body(&mut self, tokens: &mut TokenStream, break_on_else: bool) -> CodeBody {
         let start_pos = tokens.pos();
         let mut nodes = Vec::new();
         let mut locations = Vec::new();
 
         while let Some(token) = tokens.read() {
             match token.parts()[0] {
-                Token::IF => {
-                    locations.push(*token.location());
-                    let body = self.parse_if(tokens)?;
-                    nodes.push(body);
+                Token::IF | Token::WHILE | Token::REPEAT | Token::FOR => {
+                    let block_start = tokens.pos();
+                    let location = *token.location();
+                    let result = match token.parts()[0] {
+                        Token::IF => self.parse_if(tokens),
+                        Token::WHILE => self.parse_while(tokens),
+                        Token::REPEAT => self.parse_repeat(tokens),
+                        _ => self.parse_for(tokens),
+                    };
+                    match result {
+                        Ok(node) => {
+                            locations.push(location);
+                            nodes.push(node);
+                        }
+                        Err(err) => {
+                            self.report_error(tokens, err);
+                            skip_block(tokens, block_start);
+                        }
+                    }
                 }
                 Token::ELSE => {
-                    token.validate_else()?;
+                    // the `else` token is validated by the parser of the enclosing if-else
+                    // statement
                     if break_on_else {
                         break;
                     }
-                    return Err(ParsingError::dangling_else(token));
-                }
-                Token::WHILE => {
-                    locations.push(*token.location());
-                    let body = self.parse_while(tokens)?;
-                    nodes.push(body);
-                }
-                Token::REPEAT => {
-                    locations.push(*token.location());
-                    let body = self.parse_repeat(tokens)?;
-                    nodes.push(body);
+                    self.errors.push(ParsingError::dangling_else(token));
+                    tokens.advance();
                 }
                 Token::END => {
+                    // the `end` token is validated and consumed by the parser of the enclosing
+                    // statement
                     locations.push(*token.location());
-                    token.validate_end()?;
                     break;
                 }
                 Token::USE => {
-                    return Err(ParsingError::import_inside_body(token));
+                    self.errors.push(ParsingError::import_inside_body(token));
+                    tokens.advance();
                 }
                 Token::EXPORT | Token::PROC | Token::BEGIN => {
                     // break out of the loop; whether this results in an error will be determined
@@ -439,8 +579,13 @@ impl ParserContext<'_> {
                     break;
                 }
                 _ => {
-                    locations.push(*token.location());
-                    nodes.push(self.parse_op_token(token)?);
+                    match self.parse_op_token(token) {
+                        Ok(node) => {
+                            locations.push(*token.location());
+                            nodes.push(node);
+                        }
+                        Err(err) => self.errors.push(err),
+                    }
                     tokens.advance();
                 }
             }
@@ -448,10 +593,10 @@ impl ParserContext<'_> {
 
         if nodes.len() > MAX_BODY_LEN {
             let token = tokens.read_at(start_pos - 1).expect("no body start token");
-            return Err(ParsingError::body_too_long(token, nodes.len(), MAX_BODY_LEN));
+            self.errors.push(ParsingError::body_too_long(token, nodes.len(), MAX_BODY_LEN));
         }
 
-        Ok(CodeBody::new(nodes).with_source_locations(locations))
+        CodeBody::new(nodes).with_source_locations(locations)
     }
 
     // HELPER METHODS
@@ -581,21 +726,21 @@ impl ParserContext<'_> {
             "push" => io_ops::parse_push(op, &self.local_constants),
 
             "sdepth" => simple_instruction(op, Sdepth),
-            "locaddr" => io_ops::parse_locaddr(op, &self.local_constants),
+            "locaddr" => io_ops::parse_locaddr(op, &self.local_constants, &self.proc_locals),
             "caller" => simple_instruction(op, Caller), // TODO: error if not in SYSCALL (issue #551)
             "clk" => simple_instruction(op, Clk),
 
             "mem_load" => io_ops::parse_mem_load(op, &self.local_constants),
-            "loc_load" => io_ops::parse_loc_load(op, &self.local_constants),
+            "loc_load" => io_ops::parse_loc_load(op, &self.local_constants, &self.proc_locals),
 
             "mem_loadw" => io_ops::parse_mem_loadw(op, &self.local_constants),
-            "loc_loadw" => io_ops::parse_loc_loadw(op, &self.local_constants),
+            "loc_loadw" => io_ops::parse_loc_loadw(op, &self.local_constants, &self.proc_locals),
 
             "mem_store" => io_ops::parse_mem_store(op, &self.local_constants),
-            "loc_store" => io_ops::parse_loc_store(op, &self.local_constants),
+            "loc_store" => io_ops::parse_loc_store(op, &self.local_constants, &self.proc_locals),
 
             "mem_storew" => io_ops::parse_mem_storew(op, &self.local_constants),
-            "loc_storew" => io_ops::parse_loc_storew(op, &self.local_constants),
+            "loc_storew" => io_ops::parse_loc_storew(op, &self.local_constants, &self.proc_locals),
 
             "mem_stream" => simple_instruction(op, MemStream),
             "adv_pipe" => simple_instruction(op, AdvPipe),
@@ -630,6 +775,12 @@ impl ParserContext<'_> {
             // ----- constant statements ----------------------------------------------------------
             "const" => Err(ParsingError::const_invalid_scope(op)),
 
+            // ----- data segments ----------------------------------------------------------------
+            "data" => Err(ParsingError::data_invalid_scope(op)),
+
+            // ----- local declarations -----------------------------------------------------------
+            "local" => Err(ParsingError::local_invalid_scope(op)),
+
             // ----- debug decorators -------------------------------------------------------------
             "breakpoint" => simple_instruction(op, Breakpoint),
             "debug" => debug::parse_debug(op, self.num_proc_locals),
@@ -681,3 +832,30 @@ fn simple_instruction(op: &Token, instruction: Instruction) -> Result<Node, Pars
         _ => Err(ParsingError::extra_param(op)),
     }
 }
+
+/// Skips the statement or procedure starting at the specified position of the token stream.
+///
+/// The stream is moved past the `end` token matching the `if`, `while`, `repeat`, `for`, `proc`,
+/// or `export` token at the start position. If the matching `end` token is not found, the stream is
+/// moved to the next `proc`, `export`, or `begin` token, or to the end of the stream.
+fn skip_block(tokens: &mut TokenStream, start: usize) {
+    tokens.seek(start);
+    tokens.advance();
+
+    let mut depth = 1;
+    while let Some(token) = tokens.read() {
+        match token.parts()[0] {
+            Token::IF | Token::WHILE | Token::REPEAT | Token::FOR => depth += 1,
+            Token::END => {
+                depth -= 1;
+                if depth == 0 {
+                    tokens.advance();
+                    return;
+                }
+            }
+            Token::EXPORT | Token::PROC | Token::BEGIN => return,
+            _ => (),
+        }
+        tokens.advance();
+    }
+}
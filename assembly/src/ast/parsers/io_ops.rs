@@ -1,9 +1,10 @@
 use super::{
-    parse_checked_param, parse_hex_value, parse_param_with_constant_lookup, Endianness, Felt,
+    parse_checked_param, parse_hex_value, parse_param_with_constant_lookup, split_const_index,
+    try_get_constant_value, ConstantValue, Endianness, Felt,
     Instruction::*,
-    LocalConstMap,
+    LocalConstMap, LocalVarMap,
     Node::{self, Instruction},
-    ParsingError, Token, CONSTANT_LABEL_PARSER, HEX_CHUNK_SIZE,
+    ParsingError, Token, HEX_CHUNK_SIZE,
 };
 use crate::{StarkField, ADVICE_READ_LIMIT, MAX_PUSH_INPUTS};
 use alloc::vec::Vec;
@@ -39,6 +40,8 @@ pub fn parse_push(op: &Token, constants: &LocalConstMap) -> Result<Node, Parsing
                 }
                 // if we have many hex parameters without delimiter
                 Some(param_str) => parse_long_hex_param(op, param_str),
+                // if we have an array constant, push all of its elements
+                None if is_array_constant(param_str, constants) => parse_param_list(op, constants),
                 // if we have one decimal parameter
                 None => {
                     let value = parse_non_hex_param_with_constants_lookup(
@@ -61,14 +64,18 @@ pub fn parse_push(op: &Token, constants: &LocalConstMap) -> Result<Node, Parsing
 ///
 /// # Errors
 /// Returns an error if the instruction token contains a wrong number of parameters, or if
-/// the provided parameter is not a u16 value.
-pub fn parse_locaddr(op: &Token, constants: &LocalConstMap) -> Result<Node, ParsingError> {
+/// the provided parameter is neither a u16 value nor a reference to a named local.
+pub fn parse_locaddr(
+    op: &Token,
+    constants: &LocalConstMap,
+    locals: &LocalVarMap,
+) -> Result<Node, ParsingError> {
     debug_assert_eq!(op.parts()[0], "locaddr");
     match op.num_parts() {
         0 => unreachable!(),
         1 => Err(ParsingError::missing_param(op, "locaddr.<index>")),
         2 => {
-            let index = parse_param_with_constant_lookup::<u16>(op, 1, constants)?;
+            let index = parse_local_index(op, 1, constants, locals)?;
             Ok(Instruction(Locaddr(index)))
         }
         _ => Err(ParsingError::extra_param(op)),
@@ -116,14 +123,18 @@ pub fn parse_mem_load(op: &Token, constants: &LocalConstMap) -> Result<Node, Par
 ///
 /// # Errors
 /// Returns an error if the instruction token contains a wrong number of parameters, or if
-/// the provided parameter is not a u16 value.
-pub fn parse_loc_load(op: &Token, constants: &LocalConstMap) -> Result<Node, ParsingError> {
+/// the provided parameter is neither a u16 value nor a reference to a named local.
+pub fn parse_loc_load(
+    op: &Token,
+    constants: &LocalConstMap,
+    locals: &LocalVarMap,
+) -> Result<Node, ParsingError> {
     debug_assert_eq!(op.parts()[0], "loc_load");
     match op.num_parts() {
         0 => unreachable!(),
         1 => Err(ParsingError::missing_param(op, "loc_load.<index>")),
         2 => {
-            let index = parse_param_with_constant_lookup::<u16>(op, 1, constants)?;
+            let index = parse_local_index(op, 1, constants, locals)?;
             Ok(Instruction(LocLoad(index)))
         }
         _ => Err(ParsingError::extra_param(op)),
@@ -153,14 +164,18 @@ pub fn parse_mem_loadw(op: &Token, constants: &LocalConstMap) -> Result<Node, Pa
 ///
 /// # Errors
 /// Returns an error if the instruction token contains a wrong number of parameters, or if
-/// the provided parameter is not a u16 value.
-pub fn parse_loc_loadw(op: &Token, constants: &LocalConstMap) -> Result<Node, ParsingError> {
+/// the provided parameter is neither a u16 value nor a reference to a named local.
+pub fn parse_loc_loadw(
+    op: &Token,
+    constants: &LocalConstMap,
+    locals: &LocalVarMap,
+) -> Result<Node, ParsingError> {
     debug_assert_eq!(op.parts()[0], "loc_loadw");
     match op.num_parts() {
         0 => unreachable!(),
         1 => Err(ParsingError::missing_param(op, "loc_loadw.<index>")),
         2 => {
-            let index = parse_param_with_constant_lookup::<u16>(op, 1, constants)?;
+            let index = parse_local_index(op, 1, constants, locals)?;
             Ok(Instruction(LocLoadW(index)))
         }
         _ => Err(ParsingError::extra_param(op)),
@@ -190,14 +205,18 @@ pub fn parse_mem_store(op: &Token, constants: &LocalConstMap) -> Result<Node, Pa
 ///
 /// # Errors
 /// Returns an error if the instruction token contains a wrong number of parameters, or if
-/// the provided parameter is not a u16 value.
-pub fn parse_loc_store(op: &Token, constants: &LocalConstMap) -> Result<Node, ParsingError> {
+/// the provided parameter is neither a u16 value nor a reference to a named local.
+pub fn parse_loc_store(
+    op: &Token,
+    constants: &LocalConstMap,
+    locals: &LocalVarMap,
+) -> Result<Node, ParsingError> {
     debug_assert_eq!(op.parts()[0], "loc_store");
     match op.num_parts() {
         0 => unreachable!(),
         1 => Err(ParsingError::missing_param(op, "loc_store.<index>")),
         2 => {
-            let index = parse_param_with_constant_lookup::<u16>(op, 1, constants)?;
+            let index = parse_local_index(op, 1, constants, locals)?;
             Ok(Instruction(LocStore(index)))
         }
         _ => Err(ParsingError::extra_param(op)),
@@ -227,14 +246,18 @@ pub fn parse_mem_storew(op: &Token, constants: &LocalConstMap) -> Result<Node, P
 ///
 /// # Errors
 /// Returns an error if the instruction token contains a wrong number of parameters, or if
-/// the provided parameter is not a u16 value.
-pub fn parse_loc_storew(op: &Token, constants: &LocalConstMap) -> Result<Node, ParsingError> {
+/// the provided parameter is neither a u16 value nor a reference to a named local.
+pub fn parse_loc_storew(
+    op: &Token,
+    constants: &LocalConstMap,
+    locals: &LocalVarMap,
+) -> Result<Node, ParsingError> {
     debug_assert_eq!(op.parts()[0], "loc_storew");
     match op.num_parts() {
         0 => unreachable!(),
         1 => Err(ParsingError::missing_param(op, "loc_storew.<index>")),
         2 => {
-            let index = parse_param_with_constant_lookup::<u16>(op, 1, constants)?;
+            let index = parse_local_index(op, 1, constants, locals)?;
             Ok(Instruction(LocStoreW(index)))
         }
         _ => Err(ParsingError::extra_param(op)),
@@ -244,23 +267,81 @@ pub fn parse_loc_storew(op: &Token, constants: &LocalConstMap) -> Result<Node, P
 // HELPER FUNCTIONS
 // ================================================================================================
 
+/// Parses the index of a procedure local referenced by the specified parameter of the instruction.
+///
+/// The parameter can be either the name of a local declared in the procedure, optionally followed
+/// by the index of an element of an array local (e.g., `buf[2]`), or a u16 value specified
+/// directly or via a constant.
+///
+/// # Errors
+/// Returns an error if the local has not been declared in the procedure, or if the index of the
+/// element is invalid or out of bounds for the local.
+fn parse_local_index(
+    op: &Token,
+    param_idx: usize,
+    constants: &LocalConstMap,
+    locals: &LocalVarMap,
+) -> Result<u16, ParsingError> {
+    let (name, index) = split_const_index(op.parts()[param_idx]);
+    if !name.starts_with(|c: char| c.is_ascii_lowercase()) {
+        return parse_param_with_constant_lookup::<u16>(op, param_idx, constants);
+    }
+
+    let &(start, size) = locals.get(name).ok_or_else(|| ParsingError::local_not_found(op, name))?;
+    let offset = match index {
+        None => 0,
+        Some(index) => match index.parse::<u64>() {
+            Ok(index) => index,
+            Err(_) => try_get_constant_value(op, index, constants)?
+                .ok_or_else(|| ParsingError::invalid_param(op, param_idx))?,
+        },
+    };
+    if offset >= size as u64 {
+        return Err(ParsingError::local_index_out_of_bounds(op, name, offset, size));
+    }
+    Ok(start + offset as u16)
+}
+
 /// Parses a list of parameters (each of which could be in decimal or hexadecimal form) and returns
 /// an appropriate push instruction node.
+///
+/// Parameters referencing array constants are expanded into all elements of the array.
+///
+/// # Errors
+/// Returns an error if any of the parameters is invalid, or if the parameters expand to more than
+/// [MAX_PUSH_INPUTS] values.
 fn parse_param_list(op: &Token, constants: &LocalConstMap) -> Result<Node, ParsingError> {
-    let values =
-        op.parts().iter().enumerate().skip(1).map(|(param_idx, &param_str)| {
-            match param_str.strip_prefix("0x") {
-                Some(param_str) => parse_hex_value(op, param_str, param_idx, Endianness::Big),
-                None => parse_non_hex_param_with_constants_lookup(
+    let mut values = Vec::new();
+    for (param_idx, &param_str) in op.parts().iter().enumerate().skip(1) {
+        match param_str.strip_prefix("0x") {
+            Some(param_str) => {
+                values.push(parse_hex_value(op, param_str, param_idx, Endianness::Big)?)
+            }
+            None => match constants.get(param_str) {
+                Some(ConstantValue::Array(elements)) => values.extend_from_slice(elements),
+                _ => values.push(parse_non_hex_param_with_constants_lookup(
                     op,
                     constants,
                     param_idx,
                     0..Felt::MODULUS,
-                ),
-            }
-        });
+                )?),
+            },
+        }
+    }
 
-    build_push_many_instruction(values)
+    if values.len() > MAX_PUSH_INPUTS {
+        let reason = format!(
+            "parameters expand to {} values, but at most {MAX_PUSH_INPUTS} values can be pushed",
+            values.len()
+        );
+        return Err(ParsingError::invalid_param_with_reason(op, 1, &reason));
+    }
+    build_push_many_instruction(values.into_iter().map(Ok))
+}
+
+/// Returns true if the specified parameter is the name of an array constant.
+fn is_array_constant(param_str: &str, constants: &LocalConstMap) -> bool {
+    matches!(constants.get(param_str), Some(ConstantValue::Array(_)))
 }
 
 /// Parses a non hexadecimal parameter and returns the value. Takes as argument a constant map
@@ -272,13 +353,10 @@ fn parse_non_hex_param_with_constants_lookup<R: RangeBounds<u64>>(
     range: R,
 ) -> Result<u64, ParsingError> {
     let param_str = op.parts()[param_idx];
-    // if we have a valid constant label then try and fetch it
-    match CONSTANT_LABEL_PARSER.parse_label(param_str) {
-        Ok(_) => constants
-            .get(param_str)
-            .cloned()
-            .ok_or_else(|| ParsingError::const_not_found(op)),
-        Err(_) => parse_checked_param(op, param_idx, range),
+    // if we have a valid constant reference then try and fetch it
+    match try_get_constant_value(op, param_str, constants)? {
+        Some(value) => Ok(value),
+        None => parse_checked_param(op, param_idx, range),
     }
 }
 
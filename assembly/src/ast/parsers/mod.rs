@@ -1,9 +1,10 @@
 use super::{
-    bound_into_included_u64, AdviceInjectorNode, CodeBody, Deserializable, Felt, Instruction,
-    InvocationTarget, LabelError, LibraryPath, LocalConstMap, LocalProcMap, ModuleImports, Node,
-    ParsingError, ProcedureAst, ProcedureId, ProcedureName, ReExportedProcMap, RpoDigest,
-    SliceReader, StarkField, Token, TokenStream, MAX_BODY_LEN, MAX_DOCS_LEN, MAX_LABEL_LEN,
-    MAX_STACK_WORD_OFFSET,
+    bound_into_included_u64, error_code_from_msg, AdviceInjectorNode, CodeBody, ConstantValue,
+    DataSegment, Deserializable, Felt, Instruction, InvocationTarget, LabelError, LibraryPath,
+    LocalConstMap, LocalProcMap, LocalVarMap, ModuleImports, Node, ParsingError, ProcedureAst,
+    ProcedureId, ProcedureName, ReExportedProcMap, RpoDigest, SliceReader, StarkField, Token,
+    TokenStream, MAX_BODY_LEN, MAX_CONST_ARRAY_LEN, MAX_DATA_SEGMENT_LEN, MAX_DOCS_LEN,
+    MAX_ERROR_MSG_LEN, MAX_LABEL_LEN, MAX_STACK_WORD_OFFSET,
 };
 use crate::HEX_CHUNK_SIZE;
 use alloc::string::{String, ToString};
@@ -31,6 +32,12 @@ pub use labels::{
     PROCEDURE_LABEL_PARSER,
 };
 
+/// The value used in place of references to constants which cannot be resolved, when such
+/// references are accepted (see [LocalConstMap::is_unresolved()]).
+///
+/// The value is non-zero, so that using it as a divisor does not result in an error.
+const UNRESOLVED_CONST_VALUE: u64 = 1;
+
 /// Helper enum for endianness determination in the parsing functions.
 #[derive(Debug)]
 pub enum Endianness {
@@ -41,38 +48,95 @@ pub enum Endianness {
 // PARSERS FUNCTIONS
 // ================================================================================================
 
-/// Parses all `const` statements into a map which maps a const name to a value
-pub fn parse_constants(tokens: &mut TokenStream) -> Result<LocalConstMap, ParsingError> {
-    // instantiate new constant map for this module
-    let mut constants = LocalConstMap::new();
-
+/// Parses all `const` statements into the provided constant map, which maps a const name to a
+/// value, and returns the map.
+///
+/// Constants declared via `export.const` are marked as exported. If `allow_exports` is false,
+/// exporting constants results in an error.
+pub fn parse_constants(
+    tokens: &mut TokenStream,
+    mut constants: LocalConstMap,
+    allow_exports: bool,
+) -> Result<LocalConstMap, ParsingError> {
     // iterate over tokens until we find a const declaration
     while let Some(token) = tokens.read() {
-        match token.parts()[0] {
-            Token::CONST => {
-                let (name, value) = parse_constant(token, &constants)?;
+        let is_export = is_const_export(token);
+        if token.parts()[0] != Token::CONST && !is_export {
+            break;
+        }
+        if is_export && !allow_exports {
+            return Err(ParsingError::const_export_not_allowed(token));
+        }
 
-                if constants.contains_key(&name) {
-                    return Err(ParsingError::duplicate_const_name(token, &name));
-                }
+        let (name, value) = parse_constant(token, usize::from(is_export), &constants)?;
 
-                constants.insert(name, value);
-                tokens.advance();
-            }
-            _ => break,
+        if constants.contains_key(&name) {
+            return Err(ParsingError::duplicate_const_name(token, &name));
+        }
+        if constants.is_external(&name) {
+            return Err(ParsingError::external_const_redeclared(token, &name));
+        }
+
+        constants.insert(name.clone(), value, *token.location());
+        if is_export {
+            constants.export(name);
         }
+        tokens.advance();
     }
 
     Ok(constants)
 }
 
-/// Parses a constant token and returns a (constant_name, constant_value) tuple
-fn parse_constant(token: &Token, constants: &LocalConstMap) -> Result<(String, u64), ParsingError> {
-    match token.num_parts() {
+/// Parses all `data` statements into a list of data segments, which are loaded into memory before
+/// the program body is executed.
+///
+/// A data segment is declared as `data.<address>=[<v0>, <v1>, ...]`, where the address and the
+/// values are constant values. A value may also be the name of an array constant, in which case
+/// all elements of the array are placed into the segment.
+pub fn parse_data_segments(
+    tokens: &mut TokenStream,
+    constants: &LocalConstMap,
+) -> Result<Vec<DataSegment>, ParsingError> {
+    let mut segments: Vec<DataSegment> = Vec::new();
+    while let Some(token) = tokens.read() {
+        if token.parts()[0] != Token::DATA {
+            break;
+        }
+
+        let segment = parse_data_segment(token, constants)?;
+        if let Some(other) = segments.iter().find(|other| other.overlaps(&segment)) {
+            return Err(ParsingError::data_segments_overlap(token, other.address()));
+        }
+        segments.push(segment);
+        tokens.advance();
+    }
+
+    Ok(segments)
+}
+
+/// Returns true if the specified token is a declaration of an exported constant, i.e., it starts
+/// with `export.const`.
+pub fn is_const_export(token: &Token) -> bool {
+    token.num_parts() > 1 && token.parts()[0] == Token::EXPORT && token.parts()[1] == Token::CONST
+}
+
+/// Parses a constant token and returns a (constant_name, constant_value) tuple; the `const`
+/// keyword is expected to be located at the specified part of the token.
+fn parse_constant(
+    token: &Token,
+    const_idx: usize,
+    constants: &LocalConstMap,
+) -> Result<(String, ConstantValue), ParsingError> {
+    let parts = &token.parts()[const_idx..];
+    match parts.len() {
         0 => unreachable!(),
         1 => Err(ParsingError::missing_param(token, "const.<name>=<value>")),
         2 => {
-            let const_declaration: Vec<&str> = token.parts()[1].split('=').collect();
+            // error messages may contain `=` characters, so they are split off first
+            let const_declaration: Vec<&str> = match parts[1].split_once('=') {
+                Some((name, msg)) if msg.starts_with(Token::STRING_DELIM) => vec![name, msg],
+                _ => parts[1].split('=').collect(),
+            };
             match const_declaration.len() {
                 0 => unreachable!(),
                 1 => Err(ParsingError::missing_param(token, "const.<name>=<value>")),
@@ -80,7 +144,17 @@ fn parse_constant(token: &Token, constants: &LocalConstMap) -> Result<(String, u
                     let name = CONSTANT_LABEL_PARSER
                         .parse_label(const_declaration[0])
                         .map_err(|err| ParsingError::invalid_const_name(token, err))?;
-                    let value = parse_const_value(token, const_declaration[1], constants)?;
+                    let value = const_declaration[1];
+                    let msg = parse_error_msg(value).map_err(|reason| {
+                        ParsingError::invalid_const_value(token, value, &reason)
+                    })?;
+                    let value = match (msg, value.strip_prefix('[')) {
+                        (Some(msg), _) => ConstantValue::String(msg.to_string()),
+                        (None, Some(elements)) => parse_const_array(token, elements, constants)?,
+                        (None, None) => {
+                            ConstantValue::Felt(parse_const_value(token, value, constants)?)
+                        }
+                    };
                     Ok((name.to_string(), value))
                 }
                 _ => Err(ParsingError::extra_param(token)),
@@ -90,25 +164,166 @@ fn parse_constant(token: &Token, constants: &LocalConstMap) -> Result<(String, u
     }
 }
 
+/// Parses a data segment token of the form `data.<address>=[<v0>, <v1>, ...]`.
+fn parse_data_segment(
+    token: &Token,
+    constants: &LocalConstMap,
+) -> Result<DataSegment, ParsingError> {
+    let (address, values) = match token.parts() {
+        [_, declaration] => declaration
+            .split_once('=')
+            .ok_or_else(|| ParsingError::missing_param(token, "data.<address>=[<values>]"))?,
+        [_] => return Err(ParsingError::missing_param(token, "data.<address>=[<values>]")),
+        _ => return Err(ParsingError::extra_param(token)),
+    };
+
+    let address = parse_const_value(token, address, constants)?;
+    let address = u32::try_from(address).map_err(|_| {
+        ParsingError::invalid_data_segment(token, "address must be a valid memory address")
+    })?;
+
+    let Some(values) = values.strip_prefix('[').and_then(|values| values.strip_suffix(']')) else {
+        let reason = "values must be enclosed in square brackets";
+        return Err(ParsingError::invalid_data_segment(token, reason));
+    };
+    if values.trim().is_empty() {
+        let reason = "data segments must contain at least one value";
+        return Err(ParsingError::invalid_data_segment(token, reason));
+    }
+
+    let mut elements = Vec::new();
+    for value in values.split(',').map(str::trim) {
+        match constants.get(value) {
+            Some(ConstantValue::Array(array)) => elements.extend_from_slice(array),
+            _ => elements.push(parse_const_value(token, value, constants)?),
+        }
+    }
+    if elements.len() > MAX_DATA_SEGMENT_LEN {
+        let reason = format!("data segments can contain at most {MAX_DATA_SEGMENT_LEN} values");
+        return Err(ParsingError::invalid_data_segment(token, &reason));
+    }
+
+    let elements: Vec<Felt> = elements.into_iter().map(Felt::new).collect();
+    let end_address =
+        address as u64 + elements.len().next_multiple_of(DataSegment::PADDING) as u64 / 4;
+    if end_address > u32::MAX as u64 + 1 {
+        let reason = "data segment does not fit into memory";
+        return Err(ParsingError::invalid_data_segment(token, reason));
+    }
+
+    Ok(DataSegment::new(address, elements))
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
-/// If `constant_name` is a valid constant name, returns the value of this constant or an error if
-/// the constant does not exist in set of available constants.
+/// If `const_name` is a valid reference to a constant, returns the value of this constant or an
+/// error if the constant does not exist in set of available constants.
 ///
-/// If `constant_name` is not a valid constant name, returns None.
+/// A reference is either the name of a single-value constant (e.g., `A`), or the name of an array
+/// constant followed by an index of an element of the array (e.g., `TABLE[3]`); the index must be
+/// a decimal number or a reference to a single-value constant. Constants exported from imported
+/// modules are referenced by their qualified names (e.g., `u64::MAX`).
+///
+/// If `const_name` is not a valid constant reference, returns None.
 fn try_get_constant_value(
     op: &Token,
     const_name: &str,
     constants: &LocalConstMap,
 ) -> Result<Option<u64>, ParsingError> {
-    match CONSTANT_LABEL_PARSER.parse_label(const_name) {
-        Ok(_) => constants
-            .get(const_name)
-            .ok_or_else(|| ParsingError::const_not_found(op))
-            .map(|v| Some(*v)),
-        Err(_) => Ok(None),
+    let (name, index) = split_const_index(const_name);
+    if !is_constant_name(name) {
+        return Ok(None);
+    }
+    if constants.is_unresolved(name) {
+        return Ok(Some(UNRESOLVED_CONST_VALUE));
     }
+    let value = constants.get(name).ok_or_else(|| ParsingError::const_not_found(op))?;
+    get_constant_element(op, name, value, index, constants).map(Some)
+}
+
+/// Returns true if the specified string is a valid name of a constant, or a valid qualified name
+/// of a constant exported from an imported module (e.g., `u64::MAX`).
+fn is_constant_name(name: &str) -> bool {
+    let label = match name.split_once(LibraryPath::PATH_DELIM) {
+        Some((alias, label)) if !alias.is_empty() => label,
+        Some(_) => return false,
+        None => name,
+    };
+    CONSTANT_LABEL_PARSER.parse_label(label).is_ok()
+}
+
+/// Splits a constant reference into the name of the constant and the index of the accessed array
+/// element, if any; e.g., `TABLE[3]` is split into `TABLE` and `3`.
+fn split_const_index(const_ref: &str) -> (&str, Option<&str>) {
+    match const_ref.strip_suffix(']').and_then(|const_ref| const_ref.split_once('[')) {
+        Some((name, index)) => (name, Some(index)),
+        None => (const_ref, None),
+    }
+}
+
+/// Returns the specified constant value if no index is provided, or the element of the array
+/// constant at the specified index otherwise.
+///
+/// # Errors
+/// Returns an error if:
+/// - The index is not provided for an array constant, or provided for a single-value constant.
+/// - The index is not a valid decimal number or single-value constant, or it is out of bounds.
+fn get_constant_element(
+    op: &Token,
+    name: &str,
+    value: &ConstantValue,
+    index: Option<&str>,
+    constants: &LocalConstMap,
+) -> Result<u64, ParsingError> {
+    match (value, index) {
+        (ConstantValue::Felt(value), None) => Ok(*value),
+        (ConstantValue::Felt(_), Some(_)) => Err(ParsingError::const_not_array(op, name)),
+        (ConstantValue::Array(_), None) => Err(ParsingError::const_not_scalar(op, name)),
+        (ConstantValue::String(_), _) => Err(ParsingError::const_not_numeric(op, name)),
+        (ConstantValue::Array(elements), Some(index)) => {
+            let index = match index.parse::<u64>() {
+                Ok(index) => index,
+                Err(_) => try_get_constant_value(op, index, constants)?.ok_or_else(|| {
+                    let reason = format!("invalid index `{index}` of constant '{name}'");
+                    ParsingError::invalid_const_value(op, index, &reason)
+                })?,
+            };
+            usize::try_from(index)
+                .ok()
+                .and_then(|idx| elements.get(idx))
+                .copied()
+                .ok_or_else(|| {
+                    ParsingError::const_index_out_of_bounds(op, name, index, elements.len())
+                })
+        }
+    }
+}
+
+/// Parses the elements of an array constant; `elements` is expected to contain comma-separated
+/// constant values followed by the closing bracket, e.g. `1, 2, A+1]`.
+fn parse_const_array(
+    op: &Token,
+    elements: &str,
+    constants: &LocalConstMap,
+) -> Result<ConstantValue, ParsingError> {
+    let Some(elements) = elements.strip_suffix(']') else {
+        let reason = "array constants must be enclosed in square brackets";
+        return Err(ParsingError::invalid_const_value(op, elements, reason));
+    };
+    if elements.trim().is_empty() {
+        let reason = "array constants must contain at least one element";
+        return Err(ParsingError::invalid_const_value(op, elements, reason));
+    }
+    if elements.split(',').count() > MAX_CONST_ARRAY_LEN {
+        let reason = format!("array constants can contain at most {MAX_CONST_ARRAY_LEN} elements");
+        return Err(ParsingError::invalid_const_value(op, elements, &reason));
+    }
+    elements
+        .split(',')
+        .map(|element| parse_const_value(op, element.trim(), constants))
+        .collect::<Result<Vec<_>, _>>()
+        .map(ConstantValue::Array)
 }
 
 /// Parses a constant value and ensures it falls within bounds specified by the caller.
@@ -208,26 +423,68 @@ fn check_div_by_zero(value: u64, op: &Token, param_idx: usize) -> Result<(), Par
 /// code.
 ///
 /// The code is expected to be specified via the first instruction parameter and have the form
-/// `err=<code>`.
+/// `err=<code>`, where the code is either a u32 value, or an error message. An error message is
+/// either a string enclosed in double quotes (e.g., `err="insufficient balance"`) or a reference to
+/// a constant holding such string; the code of the message is derived from the message via
+/// [error_code_from_msg()], and the message is recorded in the provided constant map.
 fn parse_error_code(token: &Token, constants: &LocalConstMap) -> Result<u32, ParsingError> {
     let inst = token.parts()[0];
-    let err_code_parts: Vec<&str> = token.parts()[1].split('=').collect();
-    match err_code_parts.len() {
-        0 => unreachable!(),
-        1 => Err(ParsingError::missing_param(token, format!("{inst}.err=<code>").as_str())),
-        2 => {
-            if err_code_parts[0] != "err" {
-                return Err(ParsingError::invalid_param(token, 1));
-            }
+    let Some((param_name, err_code_str)) = token.parts()[1].split_once('=') else {
+        return Err(ParsingError::missing_param(token, format!("{inst}.err=<code>").as_str()));
+    };
+    if param_name != "err" {
+        return Err(ParsingError::invalid_param(token, 1));
+    }
 
-            let err_code_str = err_code_parts[1];
-            let err_code = match try_get_constant_value(token, err_code_str, constants)? {
-                Some(val) => val.try_into().map_err(|_| ParsingError::invalid_param(token, 1))?,
-                None => err_code_str.parse().map_err(|_| ParsingError::invalid_param(token, 1))?,
-            };
-            Ok(err_code)
+    let msg = parse_error_msg(err_code_str)
+        .map_err(|reason| ParsingError::invalid_param_with_reason(token, 1, &reason))?;
+    let msg = match (msg, constants.get(err_code_str)) {
+        (Some(msg), _) => Some(msg),
+        (None, Some(ConstantValue::String(msg))) => Some(msg.as_str()),
+        _ => None,
+    };
+    if let Some(msg) = msg {
+        let err_code = error_code_from_msg(msg);
+        constants
+            .add_error_message(err_code, msg)
+            .map_err(|other| ParsingError::error_code_collision(token, err_code, &other))?;
+        return Ok(err_code);
+    }
+
+    if err_code_str.contains('=') {
+        return Err(ParsingError::extra_param(token));
+    }
+    let err_code = match try_get_constant_value(token, err_code_str, constants)? {
+        Some(val) => val.try_into().map_err(|_| ParsingError::invalid_param(token, 1))?,
+        None => err_code_str.parse().map_err(|_| ParsingError::invalid_param(token, 1))?,
+    };
+    if err_code != 0 {
+        constants
+            .add_error_code(err_code)
+            .map_err(|other| ParsingError::error_code_collision(token, err_code, &other))?;
+    }
+    Ok(err_code)
+}
+
+/// Parses an error message enclosed in double quotes (e.g., `"insufficient balance"`), and returns
+/// the message without the quotes.
+///
+/// Returns None if the value does not start with a double quote, or the reason why the message is
+/// invalid if the message is not properly enclosed in double quotes, is empty, or is too long.
+fn parse_error_msg(value: &str) -> Result<Option<&str>, String> {
+    let Some(msg) = value.strip_prefix(Token::STRING_DELIM) else {
+        return Ok(None);
+    };
+    match msg.strip_suffix(Token::STRING_DELIM) {
+        None => Err("error messages must be enclosed in double quotes".to_string()),
+        Some(msg) if msg.contains(Token::STRING_DELIM) => {
+            Err("error messages cannot contain double quotes".to_string())
         }
-        _ => Err(ParsingError::extra_param(token)),
+        Some("") => Err("error messages cannot be empty".to_string()),
+        Some(msg) if msg.len() > MAX_ERROR_MSG_LEN => {
+            Err(format!("error messages can be at most {MAX_ERROR_MSG_LEN} bytes long"))
+        }
+        Some(msg) => Ok(Some(msg)),
     }
 }
 
@@ -7,7 +7,8 @@ use crate::ast::{MAX_BODY_LEN, MAX_DOCS_LEN};
 
 use super::{
     super::tokens::SourceLocation, code_body::CodeBody, nodes::Node, ByteReader, ByteWriter,
-    Deserializable, DeserializationError, LibraryPath, ProcedureId, ProcedureName, Serializable,
+    Deserializable, DeserializationError, LibraryPath, ProcedureId, ProcedureName,
+    ProcedureSignature, Serializable,
 };
 use core::{iter, str::from_utf8};
 
@@ -17,13 +18,14 @@ use core::{iter, str::from_utf8};
 /// An abstract syntax tree of a Miden procedure.
 ///
 /// A procedure AST consists of a list of body nodes and additional metadata about the procedure
-/// (e.g., procedure name, number of memory locals used by the procedure, and whether a procedure
-/// is exported or internal).
+/// (e.g., procedure name, number of memory locals used by the procedure, whether a procedure
+/// is exported or internal, and the declared signature of the procedure, if any).
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ProcedureAst {
     pub name: ProcedureName,
     pub docs: Option<String>,
     pub num_locals: u16,
+    pub signature: Option<ProcedureSignature>,
     pub body: CodeBody,
     pub start: SourceLocation,
     pub is_export: bool,
@@ -49,12 +51,19 @@ impl ProcedureAst {
             name,
             docs,
             num_locals,
+            signature: None,
             body,
             is_export,
             start,
         }
     }
 
+    /// Attaches the provided signature to this procedure.
+    pub fn with_signature(mut self, signature: ProcedureSignature) -> Self {
+        self.signature = Some(signature);
+        self
+    }
+
     /// Binds the provided `locations` into the ast nodes.
     ///
     /// The `start` location points to the first node of this block.
@@ -129,6 +138,13 @@ impl Serializable for ProcedureAst {
 
         target.write_bool(self.is_export);
         target.write_u16(self.num_locals);
+        match &self.signature {
+            Some(signature) => {
+                target.write_bool(true);
+                signature.write_into(target);
+            }
+            None => target.write_bool(false),
+        }
         assert!(self.body.nodes().len() <= MAX_BODY_LEN, "too many body instructions");
         target.write_u16(self.body.nodes().len() as u16);
         target.write_many(self.body.nodes());
@@ -150,6 +166,11 @@ impl Deserializable for ProcedureAst {
 
         let is_export = source.read_bool()?;
         let num_locals = source.read_u16()?;
+        let signature = if source.read_bool()? {
+            Some(ProcedureSignature::read_from(source)?)
+        } else {
+            None
+        };
         let body_len = source.read_u16()? as usize;
         let nodes = source.read_many::<Node>(body_len)?;
         let body = CodeBody::new(nodes);
@@ -157,6 +178,7 @@ impl Deserializable for ProcedureAst {
         Ok(Self {
             name,
             num_locals,
+            signature,
             body,
             start,
             is_export,
@@ -1,23 +1,28 @@
-use alloc::vec::Vec;
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::{String, ToString},
+    vec::Vec,
+};
 
 use crate::ast::MAX_BODY_LEN;
 
 use super::{
     super::tokens::SourceLocation,
-    check_unused_imports,
     code_body::CodeBody,
+    constants::{read_error_messages, write_error_messages},
+    event,
     imports::ModuleImports,
-    instrument,
+    instrument, lints,
     nodes::Node,
-    parsers::{parse_constants, ParserContext},
+    parsers::{parse_constants, parse_data_segments, ParserContext},
     serde::AstSerdeOptions,
     {
-        format::*, sort_procs_into_vec, LocalProcMap, ProcedureAst, ReExportedProcMap,
-        MAX_LOCAL_PROCS,
+        explicit_error_codes, format::*, sort_procs_into_vec, ImportedConstants, LocalConstMap,
+        LocalProcMap, LocalVarMap, ProcedureAst, ReExportedProcMap, MAX_LOCAL_PROCS,
     },
     {
-        ByteReader, ByteWriter, Deserializable, DeserializationError, ParsingError, Serializable,
-        SliceReader, Token, TokenStream,
+        AssemblyWarning, ByteReader, ByteWriter, DataSegment, Deserializable, DeserializationError,
+        Level, ParsingError, Serializable, SliceReader, Token, TokenStream,
     },
 };
 
@@ -31,13 +36,21 @@ use std::{fs, io, path::Path};
 ///
 /// A program AST consists of a body of the program, a list of internal procedure ASTs, a list of
 /// imported libraries, a map from procedure ids to procedure names for imported procedures used in
-/// the module, and the source location of the program.
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// the module, error messages of the assertions in the program, data segments loaded into memory
+/// before the program is executed, and the source location of the program.
+///
+/// If the program was parsed from source code, the AST also contains the warnings about the
+/// source code; these are not a part of the program, and thus, are not serialized and are ignored
+/// when comparing program ASTs.
+#[derive(Debug, Clone)]
 pub struct ProgramAst {
     pub(super) body: CodeBody,
     pub(super) local_procs: Vec<ProcedureAst>,
     pub(super) import_info: ModuleImports,
+    pub(super) error_messages: BTreeMap<u32, String>,
+    pub(super) data_segments: Vec<DataSegment>,
     pub(super) start: SourceLocation,
+    pub(super) warnings: Vec<AssemblyWarning>,
 }
 
 impl ProgramAst {
@@ -66,7 +79,10 @@ impl ProgramAst {
             body,
             local_procs,
             import_info: Default::default(),
+            error_messages: BTreeMap::new(),
+            data_segments: Vec::new(),
             start,
+            warnings: Vec::new(),
         })
     }
 
@@ -80,6 +96,19 @@ impl ProgramAst {
         self
     }
 
+    /// Adds the provided error messages, keyed by their error codes, to the error messages of the
+    /// assertions in this program.
+    pub fn with_error_messages(mut self, error_messages: BTreeMap<u32, String>) -> Self {
+        self.error_messages.extend(error_messages);
+        self
+    }
+
+    /// Adds the provided data segments to the data segments of this program.
+    pub fn with_data_segments(mut self, data_segments: Vec<DataSegment>) -> Self {
+        self.data_segments.extend(data_segments);
+        self
+    }
+
     /// Binds the provided `locations` to the nodes of this program's body.
     ///
     /// The `start` location points to the `begin` token which does not have its own node.
@@ -119,16 +148,114 @@ impl ProgramAst {
         &self.import_info
     }
 
+    /// Returns the error messages of the assertions in this program, keyed by their error codes.
+    pub fn error_messages(&self) -> &BTreeMap<u32, String> {
+        &self.error_messages
+    }
+
+    /// Returns the error codes of the assertions in this program which are specified explicitly
+    /// rather than derived from error messages.
+    pub(crate) fn explicit_error_codes(&self) -> BTreeSet<u32> {
+        let bodies = self.local_procs.iter().map(|proc| &proc.body);
+        explicit_error_codes(bodies.chain([&self.body]), &self.error_messages)
+    }
+
+    /// Returns the data segments which are loaded into memory before this program is executed.
+    pub fn data_segments(&self) -> &[DataSegment] {
+        &self.data_segments
+    }
+
+    /// Returns the warnings about the source code of this program found when the program was
+    /// parsed, ordered by their position in the source.
+    ///
+    /// See [ProgramAst::parse_with_warnings()] for the list of reported warnings.
+    pub fn warnings(&self) -> &[AssemblyWarning] {
+        &self.warnings
+    }
+
     // PARSER
     // --------------------------------------------------------------------------------------------
     /// Parses the provided source into a [ProgramAst].
     ///
     /// A program consist of a body and a set of internal (i.e., not exported) procedures.
-    #[instrument(name = "parse_program", skip_all)]
+    ///
+    /// # Errors
+    /// Returns the first syntax error found in the source. To get all syntax errors found in the
+    /// source, use [ProgramAst::parse_with_recovery()].
     pub fn parse(source: &str) -> Result<ProgramAst, ParsingError> {
-        let mut tokens = TokenStream::new(source)?;
-        let mut import_info = ModuleImports::parse(&mut tokens)?;
-        let local_constants = parse_constants(&mut tokens)?;
+        Self::parse_in_discovery_order(source, &ImportedConstants::default())
+            .map(|(ast, _)| ast)
+            .map_err(|mut errors| errors.swap_remove(0))
+    }
+
+    /// Parses the provided source into a [ProgramAst], reporting all syntax errors found in the
+    /// source.
+    ///
+    /// Unlike [ProgramAst::parse()], this does not stop at the first syntax error. Instead, the
+    /// parser recovers at the nearest instruction, block, or procedure boundary and continues, so
+    /// that all errors are reported in a single pass.
+    ///
+    /// # Errors
+    /// Returns a non-empty list of syntax errors, ordered by their position in the source.
+    pub fn parse_with_recovery(source: &str) -> Result<ProgramAst, Vec<ParsingError>> {
+        Self::parse_with_warnings(source).map(|(program, _)| program)
+    }
+
+    /// Parses the provided source into a [ProgramAst], reporting all syntax errors found in the
+    /// source, as well as warnings about code which is valid but is likely to contain a mistake.
+    ///
+    /// The following warnings are reported:
+    /// - Imported modules from which no procedure is invoked and no constant is referenced.
+    /// - Constants which are never referenced.
+    /// - Local procedures which are not reachable from the program body.
+    /// - Code following an assertion which always fails.
+    /// - Procedures which declare memory locals but never access them.
+    ///
+    /// # Errors
+    /// Returns a non-empty list of syntax errors, ordered by their position in the source.
+    pub fn parse_with_warnings(
+        source: &str,
+    ) -> Result<(ProgramAst, Vec<AssemblyWarning>), Vec<ParsingError>> {
+        Self::parse_with_imported_constants(source, &ImportedConstants::default())
+    }
+
+    /// Parses the provided source into a [ProgramAst] in the same way as
+    /// [ProgramAst::parse_with_warnings()], resolving references to constants exported from the
+    /// imported modules (e.g., `u64::MAX`) against the provided set of imported constants. Code
+    /// excluded by conditional compilation directives (e.g., `#if DEBUG`) is determined by the
+    /// features enabled in this set.
+    ///
+    /// # Errors
+    /// Returns a non-empty list of syntax errors, ordered by their position in the source.
+    #[instrument(name = "parse_program", skip_all)]
+    pub fn parse_with_imported_constants(
+        source: &str,
+        imported_constants: &ImportedConstants,
+    ) -> Result<(ProgramAst, Vec<AssemblyWarning>), Vec<ParsingError>> {
+        Self::parse_in_discovery_order(source, imported_constants).map_err(|mut errors| {
+            errors.sort_by_key(|err| *err.location());
+            errors
+        })
+    }
+
+    /// Parses the provided source in the same way as [ProgramAst::parse_with_imported_constants()],
+    /// returning the syntax errors in the order they were found by the parser.
+    ///
+    /// Since the parser reports an unterminated statement or procedure only after it has parsed
+    /// its body, errors of nested blocks are found before the errors of the enclosing statements.
+    fn parse_in_discovery_order(
+        source: &str,
+        imported_constants: &ImportedConstants,
+    ) -> Result<(ProgramAst, Vec<AssemblyWarning>), Vec<ParsingError>> {
+        let mut tokens =
+            TokenStream::new(source, imported_constants.features()).map_err(|err| vec![err])?;
+        let mut import_info = ModuleImports::parse(&mut tokens).map_err(|err| vec![err])?;
+        let import_locations = lints::import_locations(&mut tokens);
+        let local_constants = LocalConstMap::with_imports(&import_info, imported_constants);
+        let local_constants =
+            parse_constants(&mut tokens, local_constants, false).map_err(|err| vec![err])?;
+        let data_segments =
+            parse_data_segments(&mut tokens, &local_constants).map_err(|err| vec![err])?;
 
         let mut context = ParserContext {
             import_info: &mut import_info,
@@ -136,60 +263,54 @@ impl ProgramAst {
             reexported_procs: ReExportedProcMap::default(),
             local_constants,
             num_proc_locals: 0,
+            proc_locals: LocalVarMap::default(),
+            reexported_modules: Default::default(),
+            errors: Vec::new(),
+            eof_error_reported: false,
         };
 
-        context.parse_procedures(&mut tokens, false)?;
+        context.parse_procedures(&mut tokens, false);
 
-        // make sure program body is present
-        let next_token = tokens
-            .read()
-            .ok_or_else(|| ParsingError::unexpected_eof(*tokens.eof_location()))?;
-        if next_token.parts()[0] != Token::BEGIN {
-            return Err(ParsingError::unexpected_token(next_token, Token::BEGIN));
-        }
+        // parse the program body; the errors are accumulated in the parser context
+        let body = parse_program_body(&mut context, &mut tokens);
 
-        let program_start = tokens.pos();
-        // consume the 'begin' token
-        let header = tokens.read().expect("missing program header");
-        let start = *header.location();
-        header.validate_begin()?;
-        tokens.advance();
-
-        // make sure there is something to be read
-        if tokens.eof() {
-            return Err(ParsingError::unexpected_eof(*tokens.eof_location()));
-        }
-
-        // parse the sequence of nodes and add each node to the list
-        let body = context.parse_body(&mut tokens, false)?;
-
-        // consume the 'end' token
-        match tokens.read() {
-            None => Err(ParsingError::unmatched_begin(
-                tokens.read_at(program_start).expect("no begin token"),
-            )),
-            Some(token) => match token.parts()[0] {
-                Token::END => token.validate_end(),
-                Token::ELSE => Err(ParsingError::dangling_else(token)),
-                _ => Err(ParsingError::unmatched_begin(
-                    tokens.read_at(program_start).expect("no begin token"),
-                )),
-            },
-        }?;
-        tokens.advance();
-
-        // make sure there are no instructions after the end
-        if let Some(token) = tokens.read() {
-            return Err(ParsingError::dangling_ops_after_program(token));
+        if !context.errors.is_empty() {
+            return Err(context.errors);
         }
+        let (body, start) = body.expect("no errors reported for a missing program body");
 
-        check_unused_imports(context.import_info);
+        let mut warnings = lints::check_unused_imports(
+            context.import_info,
+            &import_locations,
+            &context.reexported_modules,
+            &context.local_constants,
+        );
+        warnings.extend(lints::check_unused_constants(&context.local_constants));
 
+        let error_messages = context.local_constants.error_messages();
         let local_procs = sort_procs_into_vec(context.local_procs);
         let (nodes, locations) = body.into_parts();
-        Ok(Self::new(nodes, local_procs)?
+        let mut program = Self::new(nodes, local_procs)
+            .map_err(|err| vec![err])?
             .with_source_locations(locations, start)
-            .with_import_info(import_info))
+            .with_import_info(import_info)
+            .with_error_messages(error_messages)
+            .with_data_segments(data_segments);
+
+        warnings.extend(lints::check_unused_procedures(&program.local_procs, [&program.body]));
+        warnings.extend(lints::check_unused_locals(&program.local_procs));
+        for proc in program.local_procs.iter() {
+            warnings.extend(lints::check_unreachable_code(&proc.body));
+        }
+        warnings.extend(lints::check_unreachable_code(&program.body));
+
+        warnings.sort_by_key(|warning| *warning.source_location());
+        for warning in warnings.iter() {
+            event!(Level::WARN, "{}", warning);
+        }
+        program.warnings.clone_from(&warnings);
+
+        Ok((program, warnings))
     }
 
     // SERIALIZATION / DESERIALIZATION
@@ -211,6 +332,13 @@ impl ProgramAst {
             self.import_info.write_into(target);
         }
 
+        // serialize error messages
+        write_error_messages(target, &self.error_messages);
+
+        // serialize data segments
+        target.write_usize(self.data_segments.len());
+        target.write_many(&self.data_segments);
+
         // serialize procedures
         assert!(self.local_procs.len() <= MAX_LOCAL_PROCS, "too many local procs");
         target.write_u16(self.local_procs.len() as u16);
@@ -246,6 +374,13 @@ impl ProgramAst {
             ModuleImports::default()
         };
 
+        // deserialize error messages
+        let error_messages = read_error_messages(source)?;
+
+        // deserialize data segments
+        let num_data_segments = source.read_usize()?;
+        let data_segments = source.read_many::<DataSegment>(num_data_segments)?;
+
         // deserialize local procs
         let num_local_procs = source.read_u16()?.into();
         let local_procs = source.read_many::<ProcedureAst>(num_local_procs)?;
@@ -256,7 +391,10 @@ impl ProgramAst {
 
         match Self::new(nodes, local_procs) {
             Err(err) => Err(DeserializationError::UnknownError(err.message().clone())),
-            Ok(res) => Ok(res.with_import_info(import_info)),
+            Ok(res) => Ok(res
+                .with_import_info(import_info)
+                .with_error_messages(error_messages)
+                .with_data_segments(data_segments)),
         }
     }
 
@@ -346,6 +484,15 @@ impl fmt::Display for ProgramAst {
             writeln!(f)?;
         }
 
+        // Data segments
+        for segment in self.data_segments.iter() {
+            let values = segment.values().iter().map(|value| value.to_string());
+            writeln!(f, "data.{}=[{}]", segment.address(), values.collect::<Vec<_>>().join(", "))?;
+        }
+        if !self.data_segments.is_empty() {
+            writeln!(f)?;
+        }
+
         let invoked_procs = self.import_info.invoked_procs();
         let context = AstFormatterContext::new(&self.local_procs, invoked_procs);
 
@@ -360,3 +507,82 @@ impl fmt::Display for ProgramAst {
         writeln!(f, "end")
     }
 }
+
+impl PartialEq for ProgramAst {
+    fn eq(&self, other: &Self) -> bool {
+        // warnings are not a part of the program, and thus, are not compared
+        self.body == other.body
+            && self.local_procs == other.local_procs
+            && self.import_info == other.import_info
+            && self.error_messages == other.error_messages
+            && self.data_segments == other.data_segments
+            && self.start == other.start
+    }
+}
+
+impl Eq for ProgramAst {}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Parses the body of a program starting at the `begin` token of the token stream.
+///
+/// Returns the parsed body together with the location of the `begin` token, or None if the body
+/// is missing or is not terminated properly. Errors encountered while parsing the body are added
+/// to the list of errors in the parser context.
+fn parse_program_body(
+    context: &mut ParserContext,
+    tokens: &mut TokenStream,
+) -> Option<(CodeBody, SourceLocation)> {
+    // make sure program body is present
+    let Some(header) = tokens.read() else {
+        context.report_error(tokens, ParsingError::unexpected_eof(*tokens.eof_location()));
+        return None;
+    };
+    if header.parts()[0] != Token::BEGIN {
+        context.errors.push(ParsingError::unexpected_token(header, Token::BEGIN));
+        return None;
+    }
+
+    // consume the 'begin' token
+    let program_start = tokens.pos();
+    let start = *header.location();
+    if let Err(err) = header.validate_begin() {
+        context.errors.push(err);
+    }
+    tokens.advance();
+
+    // make sure there is something to be read
+    if tokens.eof() {
+        context.report_error(tokens, ParsingError::unexpected_eof(*tokens.eof_location()));
+        return None;
+    }
+
+    // parse the sequence of nodes and add each node to the list
+    let body = context.parse_body(tokens, false);
+
+    // consume the 'end' token
+    let result = match tokens.read() {
+        None => Err(ParsingError::unmatched_begin(
+            tokens.read_at(program_start).expect("no begin token"),
+        )),
+        Some(token) => match token.parts()[0] {
+            Token::END => token.validate_end(),
+            _ => Err(ParsingError::unmatched_begin(
+                tokens.read_at(program_start).expect("no begin token"),
+            )),
+        },
+    };
+    if let Err(err) = result {
+        context.report_error(tokens, err);
+        return None;
+    }
+    tokens.advance();
+
+    // make sure there are no instructions after the end
+    if let Some(token) = tokens.read() {
+        context.errors.push(ParsingError::dangling_ops_after_program(token));
+    }
+
+    Some((body, start))
+}
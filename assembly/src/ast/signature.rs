@@ -0,0 +1,262 @@
+use super::{
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, StackEffect,
+    MAX_LABEL_LEN,
+};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt, str::from_utf8};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Maximum number of inputs or outputs in a procedure signature.
+pub const MAX_SIGNATURE_PARAMS: usize = u8::MAX as usize;
+
+// PROCEDURE SIGNATURE
+// ================================================================================================
+
+/// A declared signature of a procedure, e.g. `(a: felt, b: u32) -> (c: word)`.
+///
+/// The signature describes the values which the procedure expects to find at the top of the
+/// stack when it is invoked (inputs), and the values it leaves at the top of the stack in place of
+/// the inputs once it returns (outputs). The first input and the first output are located at the
+/// top of the stack.
+///
+/// Signatures are optional; when a procedure declares a signature, the assembler checks that it
+/// matches the stack effect of the procedure body inferred via
+/// [StackEffectAnalyzer](super::StackEffectAnalyzer).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ProcedureSignature {
+    inputs: Vec<SignatureParam>,
+    outputs: Vec<SignatureParam>,
+}
+
+impl ProcedureSignature {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new [ProcedureSignature] with the specified inputs and outputs.
+    ///
+    /// # Panics
+    /// Panics if the number of inputs or outputs is greater than 255.
+    pub fn new(inputs: Vec<SignatureParam>, outputs: Vec<SignatureParam>) -> Self {
+        assert!(inputs.len() <= MAX_SIGNATURE_PARAMS, "too many signature inputs");
+        assert!(outputs.len() <= MAX_SIGNATURE_PARAMS, "too many signature outputs");
+        Self { inputs, outputs }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the inputs of this signature.
+    pub fn inputs(&self) -> &[SignatureParam] {
+        &self.inputs
+    }
+
+    /// Returns the outputs of this signature.
+    pub fn outputs(&self) -> &[SignatureParam] {
+        &self.outputs
+    }
+
+    /// Returns the stack effect declared by this signature, i.e., the number of stack elements
+    /// occupied by all of the inputs and by all of the outputs.
+    pub fn stack_effect(&self) -> StackEffect {
+        let num_elements = |params: &[SignatureParam]| -> usize {
+            params.iter().map(|param| param.ty().num_elements()).sum()
+        };
+        StackEffect::new(num_elements(&self.inputs), num_elements(&self.outputs))
+    }
+
+    /// Returns true if code with the specified stack effect satisfies this signature.
+    ///
+    /// The code satisfies the signature if it changes the depth of the stack the same way the
+    /// signature does, and does not access stack elements beyond the declared inputs.
+    pub fn is_satisfied_by(&self, effect: &StackEffect) -> bool {
+        let declared = self.stack_effect();
+        effect.net_effect() == declared.net_effect() && effect.inputs() <= declared.inputs()
+    }
+}
+
+impl fmt::Display for ProcedureSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_params(f, &self.inputs)?;
+        if !self.outputs.is_empty() {
+            write!(f, " -> ")?;
+            write_params(f, &self.outputs)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serializable for ProcedureSignature {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(self.inputs.len() as u8);
+        target.write_many(&self.inputs);
+        target.write_u8(self.outputs.len() as u8);
+        target.write_many(&self.outputs);
+    }
+}
+
+impl Deserializable for ProcedureSignature {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let num_inputs = source.read_u8()? as usize;
+        let inputs = source.read_many::<SignatureParam>(num_inputs)?;
+        let num_outputs = source.read_u8()? as usize;
+        let outputs = source.read_many::<SignatureParam>(num_outputs)?;
+        Ok(Self { inputs, outputs })
+    }
+}
+
+// SIGNATURE PARAMETER
+// ================================================================================================
+
+/// A named input or output of a procedure signature, e.g. `a: felt`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureParam {
+    name: String,
+    ty: ParamType,
+}
+
+impl SignatureParam {
+    /// Returns a new [SignatureParam] with the specified name and type.
+    ///
+    /// # Panics
+    /// Panics if the name is longer than 255 bytes.
+    pub fn new(name: String, ty: ParamType) -> Self {
+        assert!(name.len() <= MAX_LABEL_LEN, "parameter name too long");
+        Self { name, ty }
+    }
+
+    /// Returns the name of this parameter.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the type of this parameter.
+    pub fn ty(&self) -> ParamType {
+        self.ty
+    }
+}
+
+impl fmt::Display for SignatureParam {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.ty)
+    }
+}
+
+impl Serializable for SignatureParam {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(self.name.len() as u8);
+        target.write_bytes(self.name.as_bytes());
+        self.ty.write_into(target);
+    }
+}
+
+impl Deserializable for SignatureParam {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let name_len = source.read_u8()? as usize;
+        let name = source.read_vec(name_len)?;
+        let name = from_utf8(&name)
+            .map_err(|e| DeserializationError::InvalidValue(e.to_string()))?
+            .to_string();
+        let ty = ParamType::read_from(source)?;
+        Ok(Self { name, ty })
+    }
+}
+
+// PARAMETER TYPE
+// ================================================================================================
+
+/// The type of a procedure signature parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ParamType {
+    /// A single field element.
+    Felt = 0,
+    /// A field element which is either 0 or 1.
+    Bool = 1,
+    /// A field element which is a valid u32 value.
+    U32 = 2,
+    /// A u64 value represented by two u32 limbs.
+    U64 = 3,
+    /// A word of four field elements.
+    Word = 4,
+}
+
+impl ParamType {
+    /// Returns the type with the specified name, or None if the name is not a valid type name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "felt" => Some(Self::Felt),
+            "bool" => Some(Self::Bool),
+            "u32" => Some(Self::U32),
+            "u64" => Some(Self::U64),
+            "word" => Some(Self::Word),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of this type.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Felt => "felt",
+            Self::Bool => "bool",
+            Self::U32 => "u32",
+            Self::U64 => "u64",
+            Self::Word => "word",
+        }
+    }
+
+    /// Returns the number of stack elements occupied by a value of this type.
+    pub const fn num_elements(&self) -> usize {
+        match self {
+            Self::Felt | Self::Bool | Self::U32 => 1,
+            Self::U64 => 2,
+            Self::Word => 4,
+        }
+    }
+}
+
+impl fmt::Display for ParamType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl Serializable for ParamType {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(*self as u8);
+    }
+}
+
+impl Deserializable for ParamType {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            0 => Ok(Self::Felt),
+            1 => Ok(Self::Bool),
+            2 => Ok(Self::U32),
+            3 => Ok(Self::U64),
+            4 => Ok(Self::Word),
+            value => Err(DeserializationError::InvalidValue(format!(
+                "invalid signature parameter type: {value}"
+            ))),
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Writes a comma-separated list of parameters enclosed in parentheses.
+fn write_params(f: &mut fmt::Formatter<'_>, params: &[SignatureParam]) -> fmt::Result {
+    write!(f, "(")?;
+    for (idx, param) in params.iter().enumerate() {
+        if idx > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{param}")?;
+    }
+    write!(f, ")")
+}
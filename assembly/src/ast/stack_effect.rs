@@ -0,0 +1,390 @@
+use super::{CodeBody, Instruction, Node, ProcedureAst, SourceLocation};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+// STACK EFFECT
+// ================================================================================================
+
+/// The effect of executing a block of code on the operand stack.
+///
+/// The effect is described by the minimum number of elements which must be on the stack before
+/// the code is executed (i.e., the inputs of the code), and the number of elements which replace
+/// these inputs once the code is executed (i.e., the outputs of the code). For example, the effect
+/// of `add` is `2 -> 1`, and the effect of `swap` is `2 -> 2`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StackEffect {
+    inputs: usize,
+    outputs: usize,
+}
+
+impl StackEffect {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new [StackEffect] of code which consumes the specified number of inputs and
+    /// produces the specified number of outputs.
+    pub const fn new(inputs: usize, outputs: usize) -> Self {
+        Self { inputs, outputs }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the minimum depth of the stack required to execute the code.
+    pub const fn inputs(&self) -> usize {
+        self.inputs
+    }
+
+    /// Returns the number of elements which replace the inputs once the code is executed.
+    pub const fn outputs(&self) -> usize {
+        self.outputs
+    }
+
+    /// Returns the change of the stack depth caused by executing the code.
+    pub const fn net_effect(&self) -> isize {
+        self.outputs as isize - self.inputs as isize
+    }
+
+    // COMPOSITION
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the effect of executing code with this effect followed by code with the specified
+    /// effect.
+    pub fn then(self, next: Self) -> Self {
+        // inputs of the next block which are not produced by this block must be provided by the
+        // caller as well
+        let extra_inputs = next.inputs.saturating_sub(self.outputs);
+        Self {
+            inputs: self.inputs + extra_inputs,
+            outputs: self.outputs + extra_inputs - next.inputs + next.outputs,
+        }
+    }
+
+    /// Returns the effect of executing code with this effect the specified number of times.
+    pub fn repeat(self, times: usize) -> Self {
+        if times == 0 {
+            return Self::default();
+        }
+
+        // if the code consumes more elements than it produces, every iteration consumes extra
+        // inputs; otherwise, the inputs of the first iteration are sufficient for all iterations
+        let consumed = self.inputs.saturating_sub(self.outputs);
+        let produced = self.outputs.saturating_sub(self.inputs);
+        let inputs = self.inputs.saturating_add(consumed.saturating_mul(times - 1));
+        let outputs = inputs - consumed.saturating_mul(times) + produced.saturating_mul(times);
+        Self { inputs, outputs }
+    }
+}
+
+impl fmt::Display for StackEffect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {}", self.inputs, self.outputs)
+    }
+}
+
+// STACK EFFECT ERROR
+// ================================================================================================
+
+/// An error which can be generated when the stack effect of a block of code cannot be determined
+/// statically.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StackEffectError {
+    /// The stack effect depends on the number of iterations of a `while` loop.
+    WhileLoop(SourceLocation),
+    /// The stack effect depends on a procedure whose stack effect is not known (e.g., a procedure
+    /// from another module, or a procedure invoked dynamically).
+    UnknownInvocation(String, SourceLocation),
+    /// The branches of an `if.true` statement have different net effects on the stack.
+    MismatchedBranches {
+        location: SourceLocation,
+        true_case: StackEffect,
+        false_case: StackEffect,
+    },
+}
+
+impl StackEffectError {
+    /// Returns the location of the code which caused this error.
+    pub fn source_location(&self) -> &SourceLocation {
+        match self {
+            Self::WhileLoop(location)
+            | Self::UnknownInvocation(_, location)
+            | Self::MismatchedBranches { location, .. } => location,
+        }
+    }
+
+    /// Returns true if the stack effect is unknown because it depends on information which is not
+    /// available statically, rather than because the code is inconsistent.
+    pub fn is_unknown(&self) -> bool {
+        !matches!(self, Self::MismatchedBranches { .. })
+    }
+}
+
+impl fmt::Display for StackEffectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WhileLoop(_) => write!(f, "stack effect of a while loop is unknown"),
+            Self::UnknownInvocation(target, _) => {
+                write!(f, "stack effect of `{target}` is unknown")
+            }
+            Self::MismatchedBranches {
+                true_case,
+                false_case,
+                ..
+            } => write!(
+                f,
+                "branches of if-else statement have different stack effects: {true_case} and {false_case}"
+            ),
+        }
+    }
+}
+
+// STACK EFFECT ANALYZER
+// ================================================================================================
+
+/// Static analysis of the effect of Miden assembly code on the operand stack.
+///
+/// The analyzer computes stack effects of straight-line code, `if.true` statements (as long as
+/// both branches have the same net effect), and `repeat` and `for` loops. The effect of `while`
+/// loops, and of invocations of procedures which are not local to the analyzed module, cannot be
+/// determined statically and is reported as unknown.
+///
+/// Local procedures are analyzed in the order of their declaration, so that the effect of a local
+/// procedure is known by the time the procedure is invoked.
+pub struct StackEffectAnalyzer<'a> {
+    procedures: &'a [ProcedureAst],
+    effects: Vec<Result<StackEffect, StackEffectError>>,
+}
+
+impl<'a> StackEffectAnalyzer<'a> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new analyzer which computes stack effects of the specified local procedures of a
+    /// module or a program.
+    pub fn new(procedures: &'a [ProcedureAst]) -> Self {
+        let mut analyzer = Self {
+            procedures,
+            effects: Vec::with_capacity(procedures.len()),
+        };
+        for proc in procedures {
+            let effect = analyzer.analyze(&proc.body);
+            analyzer.effects.push(effect);
+        }
+        analyzer
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the stack effect of the local procedure at the specified index.
+    pub fn procedure_effect(&self, idx: usize) -> Option<&Result<StackEffect, StackEffectError>> {
+        self.effects.get(idx)
+    }
+
+    /// Returns an iterator over local procedures together with their stack effects.
+    pub fn procedure_effects(
+        &self,
+    ) -> impl Iterator<Item = (&ProcedureAst, &Result<StackEffect, StackEffectError>)> {
+        self.procedures.iter().zip(self.effects.iter())
+    }
+
+    // ANALYSIS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the stack effect of the specified code body (e.g., the body of a program).
+    ///
+    /// # Errors
+    /// Returns an error if the effect of the code cannot be determined statically.
+    pub fn analyze(&self, body: &CodeBody) -> Result<StackEffect, StackEffectError> {
+        let mut effect = StackEffect::default();
+        for (idx, node) in body.nodes().iter().enumerate() {
+            let location = body.source_locations().get(idx).copied().unwrap_or_default();
+            let node_effect = match node {
+                Node::Instruction(instruction) => self.instruction_effect(instruction, location)?,
+                Node::IfElse {
+                    true_case,
+                    false_case,
+                } => {
+                    let true_effect = self.analyze(true_case)?;
+                    let false_effect = self.analyze(false_case)?;
+                    if true_effect.net_effect() != false_effect.net_effect() {
+                        return Err(StackEffectError::MismatchedBranches {
+                            location,
+                            true_case: true_effect,
+                            false_case: false_effect,
+                        });
+                    }
+                    let inputs = true_effect.inputs.max(false_effect.inputs);
+                    let outputs = inputs - true_effect.inputs + true_effect.outputs;
+                    StackEffect::new(1, 0).then(StackEffect::new(inputs, outputs))
+                }
+                Node::Repeat { times, body } => self.analyze(body)?.repeat(*times as usize),
+                // the index of the iteration is pushed before the loop and dropped after it
+                Node::For { times, body } => StackEffect::new(0, 1)
+                    .then(self.analyze(body)?.repeat(*times as usize))
+                    .then(StackEffect::new(1, 0)),
+                Node::While { .. } => return Err(StackEffectError::WhileLoop(location)),
+            };
+            effect = effect.then(node_effect);
+        }
+        Ok(effect)
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the stack effect of the specified instruction.
+    fn instruction_effect(
+        &self,
+        instruction: &Instruction,
+        location: SourceLocation,
+    ) -> Result<StackEffect, StackEffectError> {
+        use Instruction::*;
+        let (inputs, outputs) = match instruction {
+            Assert | AssertWithError(_) | Assertz | AssertzWithError(_) => (1, 0),
+            AssertEq | AssertEqWithError(_) => (2, 0),
+            AssertEqw | AssertEqwWithError(_) => (8, 0),
+
+            // ----- field operations -------------------------------------------------------------
+            Add | Sub | Mul | Div | Exp | And | Or | Xor | Eq | Neq | Lt | Lte | Gt | Gte => (2, 1),
+            ExpBitLength(_) => (2, 1),
+            AddImm(_) | SubImm(_) | MulImm(_) | DivImm(_) | Neg | Inv | Incr | Pow2 | ExpImm(_)
+            | ILog2 | Not | EqImm(_) | NeqImm(_) | IsOdd => (1, 1),
+            Eqw => (8, 9),
+
+            // ----- ext2 operations --------------------------------------------------------------
+            Ext2Add | Ext2Sub | Ext2Mul | Ext2Div => (4, 2),
+            Ext2Neg | Ext2Inv => (2, 2),
+
+            // ----- u32 operations ---------------------------------------------------------------
+            U32Test => (1, 2),
+            U32TestW => (4, 5),
+            U32Assert | U32AssertWithError(_) | U32Cast => (1, 1),
+            U32Assert2 | U32Assert2WithError(_) => (2, 2),
+            U32AssertW | U32AssertWWithError(_) => (4, 4),
+            U32Split => (1, 2),
+            U32WrappingAdd | U32WrappingSub | U32WrappingMul | U32Div | U32Mod | U32And | U32Or
+            | U32Xor | U32Shr | U32Shl | U32Rotr | U32Rotl | U32Lt | U32Lte | U32Gt | U32Gte
+            | U32Min | U32Max => (2, 1),
+            U32WrappingAddImm(_) | U32WrappingSubImm(_) | U32WrappingMulImm(_) | U32DivImm(_)
+            | U32ModImm(_) | U32Not | U32ShrImm(_) | U32ShlImm(_) | U32RotrImm(_)
+            | U32RotlImm(_) | U32Popcnt | U32Clz | U32Ctz | U32Clo | U32Cto => (1, 1),
+            U32OverflowingAdd | U32OverflowingSub | U32OverflowingMul | U32DivMod => (2, 2),
+            U32OverflowingAddImm(_)
+            | U32OverflowingSubImm(_)
+            | U32OverflowingMulImm(_)
+            | U32DivModImm(_) => (1, 2),
+            U32OverflowingAdd3 | U32OverflowingMadd => (3, 2),
+            U32WrappingAdd3 | U32WrappingMadd => (3, 1),
+
+            // ----- stack manipulation -----------------------------------------------------------
+            Drop => (1, 0),
+            DropW => (4, 0),
+            PadW => (0, 4),
+            Dup0 => (1, 2),
+            Dup1 => (2, 3),
+            Dup2 => (3, 4),
+            Dup3 => (4, 5),
+            Dup4 => (5, 6),
+            Dup5 => (6, 7),
+            Dup6 => (7, 8),
+            Dup7 => (8, 9),
+            Dup8 => (9, 10),
+            Dup9 => (10, 11),
+            Dup10 => (11, 12),
+            Dup11 => (12, 13),
+            Dup12 => (13, 14),
+            Dup13 => (14, 15),
+            Dup14 => (15, 16),
+            Dup15 => (16, 17),
+            DupW0 => (4, 8),
+            DupW1 => (8, 12),
+            DupW2 => (12, 16),
+            DupW3 => (16, 20),
+            Swap1 => (2, 2),
+            Swap2 | MovUp2 | MovDn2 => (3, 3),
+            Swap3 | MovUp3 | MovDn3 => (4, 4),
+            Swap4 | MovUp4 | MovDn4 => (5, 5),
+            Swap5 | MovUp5 | MovDn5 => (6, 6),
+            Swap6 | MovUp6 | MovDn6 => (7, 7),
+            Swap7 | MovUp7 | MovDn7 => (8, 8),
+            Swap8 | MovUp8 | MovDn8 => (9, 9),
+            Swap9 | MovUp9 | MovDn9 => (10, 10),
+            Swap10 | MovUp10 | MovDn10 => (11, 11),
+            Swap11 | MovUp11 | MovDn11 => (12, 12),
+            Swap12 | MovUp12 | MovDn12 => (13, 13),
+            Swap13 | MovUp13 | MovDn13 => (14, 14),
+            Swap14 | MovUp14 | MovDn14 => (15, 15),
+            Swap15 | MovUp15 | MovDn15 => (16, 16),
+            SwapW1 => (8, 8),
+            SwapW2 | MovUpW2 | MovDnW2 => (12, 12),
+            SwapW3 | SwapDw | MovUpW3 | MovDnW3 => (16, 16),
+            CSwap => (3, 2),
+            CSwapW => (9, 8),
+            CDrop => (3, 1),
+            CDropW => (9, 4),
+
+            // ----- input / output operations ----------------------------------------------------
+            PushU8(_) | PushU16(_) | PushU32(_) | PushFelt(_) => (0, 1),
+            PushWord(_) => (0, 4),
+            PushU8List(values) => (0, values.len()),
+            PushU16List(values) => (0, values.len()),
+            PushU32List(values) => (0, values.len()),
+            PushFeltList(values) => (0, values.len()),
+            Locaddr(_) | Sdepth | Clk | MemLoadImm(_) | LocLoad(_) => (0, 1),
+            Caller | MemLoadWImm(_) | LocLoadW(_) | MemStoreWImm(_) | LocStoreW(_) | AdvLoadW => {
+                (4, 4)
+            }
+            MemLoad => (1, 1),
+            MemLoadW | MemStoreW => (5, 4),
+            MemStore => (2, 0),
+            MemStoreImm(_) | LocStore(_) => (1, 0),
+            MemStream | AdvPipe => (13, 13),
+            AdvPush(n) => (0, *n as usize),
+            AdvInject(_) => (0, 0),
+
+            // ----- cryptographic operations -----------------------------------------------------
+            Hash => (4, 4),
+            HMerge | MTreeMerge => (8, 4),
+            HPerm => (12, 12),
+            MTreeGet => (6, 8),
+            MTreeSet => (10, 8),
+            MTreeVerify => (10, 10),
+
+            // ----- STARK proof verification -----------------------------------------------------
+            FriExt2Fold4 => (17, 16),
+            RCombBase => (16, 16),
+
+            // ----- exec / call ------------------------------------------------------------------
+            ExecLocal(idx) | CallLocal(idx) => {
+                return match self.effects.get(*idx as usize) {
+                    Some(Ok(effect)) => Ok(*effect),
+                    _ => {
+                        let target = match self.procedures.get(*idx as usize) {
+                            Some(proc) => proc.name.to_string(),
+                            None => idx.to_string(),
+                        };
+                        let invocation = if matches!(instruction, ExecLocal(_)) {
+                            format!("exec.{target}")
+                        } else {
+                            format!("call.{target}")
+                        };
+                        Err(StackEffectError::UnknownInvocation(invocation, location))
+                    }
+                }
+            }
+            ExecImported(_) | CallImported(_) | CallMastRoot(_) | SysCall(_) | DynExec
+            | DynCall => {
+                return Err(StackEffectError::UnknownInvocation(instruction.to_string(), location))
+            }
+            ProcRefLocal(_) | ProcRefImported(_) => (0, 4),
+
+            // ----- debug and event decorators ---------------------------------------------------
+            Breakpoint | Debug(_) | Emit(_) | Trace(_) => (0, 0),
+        };
+        Ok(StackEffect::new(inputs, outputs))
+    }
+}
@@ -1,7 +1,10 @@
 use super::{
-    AstSerdeOptions, CodeBody, Felt, Instruction, LocalProcMap, ModuleAst, Node, ParsingError,
-    ProcedureAst, ProcedureId, ProcedureName, ProgramAst, SourceLocation, Token,
+    format_source, AstSerdeOptions, CodeBody, Felt, Instruction, LocalProcMap, ModuleAst, Node,
+    ParamType, ParsingError, ProcedureAst, ProcedureId, ProcedureName, ProcedureSignature,
+    ProgramAst, SignatureParam, SourceLocation, StackEffect, StackEffectAnalyzer, StackEffectError,
+    Token,
 };
+use crate::{Deserializable, Serializable, WarningKind};
 use alloc::{
     collections::BTreeMap,
     string::{String, ToString},
@@ -550,6 +553,551 @@ fn test_unterminated_if() {
     }
 }
 
+// ERROR RECOVERY TESTS
+// ================================================================================================
+
+#[test]
+fn test_parse_with_recovery_reports_all_errors() {
+    let source = "\
+proc.foo
+    push.1 add.x
+    while mul end
+end
+proc.bar
+    exec.baz
+end
+begin
+    push.2 use
+    exec.foo exec.bar
+    if.true foo end
+end";
+
+    let errors = ProgramAst::parse_with_recovery(source).unwrap_err();
+    let errors = errors
+        .iter()
+        .map(|err| (err.location().line(), err.location().column(), err.message().as_str()))
+        .collect::<Vec<_>>();
+    let expected = vec![
+        (2, 12, "malformed instruction `add.x`: parameter 'x' is invalid"),
+        (3, 5, "malformed instruction 'while': expected format `while.true`"),
+        (6, 5, "undefined local procedure: baz"),
+        (9, 12, "import in procedure body"),
+        (11, 13, "instruction 'foo' is invalid"),
+    ];
+    assert_eq!(expected, errors);
+
+    // the first error is returned when parsing without recovery
+    let err = ProgramAst::parse(source).unwrap_err();
+    assert_eq!("malformed instruction `add.x`: parameter 'x' is invalid", err.message());
+}
+
+#[test]
+fn test_parse_with_recovery_reports_errors_in_source_order() {
+    // errors in nested blocks are found before the errors of the enclosing statements are
+    // reported; nevertheless, all errors are reported in the order they appear in the source
+    let source = "\
+export.foo
+    push.1
+    while.true
+        add.x
+export.bar
+    if.true
+        mul.y";
+
+    let errors = ModuleAst::parse_with_recovery(source).unwrap_err();
+    let errors = errors
+        .iter()
+        .map(|err| (err.location().line(), err.location().column(), err.message().as_str()))
+        .collect::<Vec<_>>();
+    let expected = vec![
+        (1, 1, "procedure 'foo' has no matching end"),
+        (3, 5, "while without matching end"),
+        (4, 9, "malformed instruction `add.x`: parameter 'x' is invalid"),
+        (6, 5, "if without matching else/end"),
+        (7, 9, "malformed instruction `mul.y`: parameter 'y' is invalid"),
+    ];
+    assert_eq!(expected, errors);
+}
+
+#[test]
+fn test_parse_with_recovery_unterminated_blocks() {
+    // a missing `end` is reported only once, for the innermost block
+    let source = "\
+export.foo
+    if.true
+        repeat.2 add
+    end";
+
+    let errors = ModuleAst::parse_with_recovery(source).unwrap_err();
+    assert_eq!(1, errors.len());
+    assert_eq!("if without matching else/end", errors[0].message());
+    assert_eq!(2, errors[0].location().line());
+
+    // parsing resumes at the next procedure after a procedure without a matching `end`
+    let source = "\
+export.foo
+    add
+export.bar
+    mul.y
+end";
+
+    let errors = ModuleAst::parse_with_recovery(source).unwrap_err();
+    let errors = errors.iter().map(|err| err.message().as_str()).collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            "procedure 'foo' has no matching end",
+            "malformed instruction `mul.y`: parameter 'y' is invalid"
+        ],
+        errors
+    );
+}
+
+// WARNING TESTS
+// ================================================================================================
+
+#[test]
+fn test_parse_with_warnings_program() {
+    let source = "\
+use.std::math::u64
+use.std::crypto::hashes::blake3
+const.A=1
+const.B=2
+const.C=B*2
+proc.foo.1
+    add
+end
+proc.bar
+    exec.foo
+end
+proc.baz.2
+    loc_store.0 push.C
+end
+begin
+    exec.blake3::hash_1to1 exec.baz
+    if.true
+        push.0 assert
+        push.1
+    end
+    push.1 push.2 assert_eq
+end";
+
+    let (_, warnings) = ProgramAst::parse_with_warnings(source).unwrap();
+    let warnings = warnings
+        .iter()
+        .map(|warning| (warning.source_location().line(), warning.kind().clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            (1, WarningKind::UnusedImport("std::math::u64".try_into().unwrap())),
+            (3, WarningKind::UnusedConstant("A".into())),
+            (6, WarningKind::UnusedProcedure("foo".try_into().unwrap())),
+            (6, WarningKind::UnusedLocals("foo".try_into().unwrap(), 1)),
+            (9, WarningKind::UnusedProcedure("bar".try_into().unwrap())),
+            (19, WarningKind::UnreachableCode),
+        ],
+        warnings
+    );
+
+    // programs without suspicious code do not produce warnings
+    let source = "\
+use.std::math::u64
+const.A=1
+begin
+    push.A exec.u64::checked_add
+end";
+    let (_, warnings) = ProgramAst::parse_with_warnings(source).unwrap();
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn test_parse_with_warnings_module() {
+    let source = "\
+use.std::math::u64
+use.std::crypto::hashes::blake3
+proc.foo
+    push.1 assertz
+end
+proc.bar
+    push.1 assertz add
+end
+proc.baz
+    exec.bar
+end
+export.qux
+    exec.baz
+end
+export.blake3::hash_1to1->hash";
+
+    let (_, warnings) = ModuleAst::parse_with_warnings(source).unwrap();
+    let warnings = warnings.iter().map(|warning| warning.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            "unused import: \"std::math::u64\"",
+            "procedure \"foo\" is never used",
+            "unreachable code",
+        ],
+        warnings
+    );
+}
+
+#[test]
+fn test_parse_with_warnings_always_failing_assertions() {
+    let unreachable = |assertion: &str| {
+        let source = format!("begin {assertion} push.1 end");
+        let (_, warnings) = ProgramAst::parse_with_warnings(&source).unwrap();
+        warnings.iter().any(|warning| warning.kind() == &WarningKind::UnreachableCode)
+    };
+
+    // assertions which always fail
+    assert!(unreachable("push.0 assert"));
+    assert!(unreachable("push.2 assert"));
+    assert!(unreachable("push.0x0100000000 assert.err=7"));
+    assert!(unreachable("push.1 assertz"));
+    assert!(unreachable("push.255 assertz.err=7"));
+    assert!(unreachable("push.1.2 assert_eq"));
+    assert!(unreachable(
+        "push.0x0000000000000000010000000000000002000000000000000300000000000000 assert_eq"
+    ));
+    assert!(unreachable("push.1.0 assert"));
+    assert!(unreachable("push.4294967296 u32assert"));
+    assert!(unreachable("push.4294967296.1 u32assert2"));
+
+    // assertions which succeed, or which check values not known at compile time
+    assert!(!unreachable("push.1 assert"));
+    assert!(!unreachable("push.0 assertz"));
+    assert!(!unreachable("push.0.1 assert"));
+    assert!(!unreachable("push.3.3 assert_eq"));
+    assert!(!unreachable("push.4294967295 u32assert"));
+    assert!(!unreachable("push.1.2 u32assert2"));
+    assert!(!unreachable("push.2 add assert"));
+    assert!(!unreachable("push.2 assert_eq"));
+}
+
+// STACK EFFECT TESTS
+// ================================================================================================
+
+#[test]
+fn test_stack_effect_composition() {
+    // add followed by dup.1: the second input of dup.1 must be provided by the caller
+    let effect = StackEffect::new(2, 1).then(StackEffect::new(2, 3));
+    assert_eq!(StackEffect::new(3, 3), effect);
+
+    // code which consumes more than it produces needs more inputs with every iteration
+    assert_eq!(StackEffect::new(4, 1), StackEffect::new(2, 1).repeat(3));
+    assert_eq!(StackEffect::new(1, 4), StackEffect::new(1, 2).repeat(3));
+    assert_eq!(StackEffect::new(3, 3), StackEffect::new(3, 3).repeat(5));
+    assert_eq!(StackEffect::default(), StackEffect::new(3, 1).repeat(0));
+    assert_eq!(-2, StackEffect::new(4, 2).net_effect());
+    assert_eq!("4 -> 2", StackEffect::new(4, 2).to_string());
+}
+
+#[test]
+fn test_stack_effect_analysis() {
+    let source = "\
+use.std::math::u64
+proc.foo
+    add mul
+end
+proc.bar
+    push.1 push.2 movup.2 exec.foo
+end
+proc.baz
+    if.true
+        drop
+    else
+        add
+    end
+end
+proc.qux
+    repeat.3
+        exec.foo
+    end
+end
+proc.quux
+    while.true
+        drop
+    end
+end
+proc.corge
+    if.true
+        dup
+    else
+        drop
+    end
+end
+proc.grault
+    exec.u64::checked_add
+end
+proc.garply
+    exec.corge
+end
+begin
+    exec.bar exec.qux
+end";
+
+    let program = ProgramAst::parse(source).unwrap();
+    let analyzer = StackEffectAnalyzer::new(program.procedures());
+    let effects = analyzer
+        .procedure_effects()
+        .map(|(proc, effect)| (proc.name.to_string(), effect.clone()))
+        .collect::<Vec<_>>();
+
+    let Node::Instruction(exec_checked_add) = &program.procedures()[6].body.nodes()[0] else {
+        panic!("not an instruction");
+    };
+    let expected = vec![
+        ("foo", Ok(StackEffect::new(3, 1))),
+        ("bar", Ok(StackEffect::new(1, 1))),
+        ("baz", Ok(StackEffect::new(3, 1))),
+        ("qux", Ok(StackEffect::new(7, 1))),
+        ("quux", Err(StackEffectError::WhileLoop(SourceLocation::new(21, 5)))),
+        (
+            "corge",
+            Err(StackEffectError::MismatchedBranches {
+                location: SourceLocation::new(26, 5),
+                true_case: StackEffect::new(1, 2),
+                false_case: StackEffect::new(1, 0),
+            }),
+        ),
+        (
+            "grault",
+            Err(StackEffectError::UnknownInvocation(
+                exec_checked_add.to_string(),
+                SourceLocation::new(33, 5),
+            )),
+        ),
+        (
+            "garply",
+            Err(StackEffectError::UnknownInvocation(
+                "exec.corge".to_string(),
+                SourceLocation::new(36, 5),
+            )),
+        ),
+    ];
+    let expected = expected
+        .into_iter()
+        .map(|(name, effect)| (name.to_string(), effect))
+        .collect::<Vec<_>>();
+    assert_eq!(expected, effects);
+    assert!(!effects[5].1.as_ref().unwrap_err().is_unknown());
+    assert!(effects[6].1.as_ref().unwrap_err().is_unknown());
+
+    // bar needs one input and produces one output, followed by qux which needs 7 inputs
+    assert_eq!(Ok(StackEffect::new(7, 1)), analyzer.analyze(program.body()));
+}
+
+#[test]
+fn test_stack_effect_of_exp_with_bit_length() {
+    // exp.uXX pops the exponent and the base, and pushes the result
+    let program = ProgramAst::parse("begin exp.u64 end").unwrap();
+    let analyzer = StackEffectAnalyzer::new(program.procedures());
+    assert_eq!(Ok(StackEffect::new(2, 1)), analyzer.analyze(program.body()));
+
+    let program = ProgramAst::parse("begin exp.u32 exp.u64 exp.2 end").unwrap();
+    let analyzer = StackEffectAnalyzer::new(program.procedures());
+    assert_eq!(Ok(StackEffect::new(3, 1)), analyzer.analyze(program.body()));
+}
+
+// SIGNATURE TESTS
+// ================================================================================================
+
+#[test]
+fn test_ast_parsing_proc_signatures() {
+    let source = "\
+export.foo(a: felt, b: u32) -> (c: word)
+    add push.1.2.3
+end
+export.bar.2(x: u64)
+    loc_store.0 drop
+end
+proc.baz()->(flag: bool) push.1 end
+export.qux
+    exec.baz drop
+end";
+
+    let module = ModuleAst::parse(source).unwrap();
+    let param = |name: &str, ty| SignatureParam::new(name.to_string(), ty);
+    let procs = module.procs();
+
+    let foo_signature = ProcedureSignature::new(
+        vec![param("a", ParamType::Felt), param("b", ParamType::U32)],
+        vec![param("c", ParamType::Word)],
+    );
+    assert_eq!(Some(&foo_signature), procs[0].signature.as_ref());
+    assert_eq!(StackEffect::new(2, 4), foo_signature.stack_effect());
+    assert_eq!("(a: felt, b: u32) -> (c: word)", foo_signature.to_string());
+
+    let bar_signature = ProcedureSignature::new(vec![param("x", ParamType::U64)], vec![]);
+    assert_eq!(Some(&bar_signature), procs[1].signature.as_ref());
+    assert_eq!(2, procs[1].num_locals);
+    assert_eq!("(x: u64)", bar_signature.to_string());
+
+    let baz_signature = ProcedureSignature::new(vec![], vec![param("flag", ParamType::Bool)]);
+    assert_eq!(Some(&baz_signature), procs[2].signature.as_ref());
+    assert_eq!(1, procs[2].body.nodes().len());
+    assert_eq!(None, procs[3].signature);
+
+    // signatures are preserved by formatting and serialization
+    let formatted = ModuleAst::parse(&module.to_string()).unwrap();
+    assert_eq!(Some(&foo_signature), formatted.procs()[0].signature.as_ref());
+    assert_correct_module_serialization(source, false);
+}
+
+#[test]
+fn test_stack_effect_satisfies_signature() {
+    let signature = ProcedureSignature::new(
+        vec![
+            SignatureParam::new("a".to_string(), ParamType::Felt),
+            SignatureParam::new("b".to_string(), ParamType::Felt),
+        ],
+        vec![SignatureParam::new("c".to_string(), ParamType::Felt)],
+    );
+    assert!(signature.is_satisfied_by(&StackEffect::new(2, 1)));
+    assert!(signature.is_satisfied_by(&StackEffect::new(1, 0)));
+    assert!(!signature.is_satisfied_by(&StackEffect::new(3, 2)));
+    assert!(!signature.is_satisfied_by(&StackEffect::new(2, 2)));
+}
+
+#[test]
+fn test_ast_parsing_proc_signatures_fail() {
+    let assert_error = |source: &str, message: &str| {
+        let err = ModuleAst::parse(source).unwrap_err();
+        assert_eq!(message, err.message(), "unexpected error for `{source}`");
+    };
+
+    assert_error(
+        "export.foo(a: felt -> (b: felt) add end",
+        "invalid procedure signature: expected a list of parameters in parentheses",
+    );
+    assert_error(
+        "export.foo(a: felt, b) add end",
+        "invalid procedure signature: expected a parameter in the form `name: type`, found ` b`",
+    );
+    assert_error(
+        "export.foo(a: felt, a: felt) add end",
+        "invalid procedure signature: duplicate parameter name `a`",
+    );
+    assert_error(
+        "export.foo(1a: felt) add end",
+        "invalid procedure signature: invalid parameter name `1a`",
+    );
+    assert_error(
+        "export.foo(a: u16) -> (b: felt) add end",
+        "invalid procedure signature: unknown parameter type `u16`",
+    );
+}
+
+// FORMATTER TESTS
+// ================================================================================================
+
+#[test]
+fn test_format_source() {
+    let source = "
+
+#! Module docs.
+
+use.std::math::u64
+use.std::crypto::hashes::blake3   # hashing
+
+
+const.A=1
+const.T=[1,2,  A+1 ]
+const.B=T[1]+A
+data.8=[5,T,  B ]
+#! Docs of foo.
+proc.foo.2(a: felt,  b: u32)->(c: felt)
+
+
+        push.1    add   # add one
+  if.true
+mul
+      else
+  # comment in else
+        drop push.A push.T[2] push.B
+   end
+
+   loc_store.0   drop
+
+
+end
+proc.bar add end
+begin
+exec.foo     exec.bar
+  exec.u64::checked_add exec.blake3::hash_1to1
+end
+
+
+
+";
+    let expected = "\
+#! Module docs.
+
+use.std::crypto::hashes::blake3 # hashing
+use.std::math::u64
+
+const.A=1
+const.T=[1, 2, A+1]
+const.B=T[1]+A
+data.8=[5, T, B]
+#! Docs of foo.
+proc.foo.2(a: felt, b: u32) -> (c: felt)
+    push.1 add # add one
+    if.true
+        mul
+    else
+        # comment in else
+        drop push.A push.T[2] push.B
+    end
+
+    loc_store.0 drop
+end
+
+proc.bar add end
+
+begin
+    exec.foo exec.bar
+    exec.u64::checked_add exec.blake3::hash_1to1
+end
+";
+    let formatted = format_source(source).unwrap();
+    assert_eq!(expected, formatted);
+
+    // formatting is idempotent and does not change the meaning of the code
+    assert_eq!(formatted, format_source(&formatted).unwrap());
+    let serde_options = AstSerdeOptions::new(true);
+    assert_eq!(
+        ProgramAst::parse(source).unwrap().to_bytes(serde_options),
+        ProgramAst::parse(&formatted).unwrap().to_bytes(serde_options)
+    );
+
+    // invalid sources are not formatted
+    let err = format_source("export.foo add").unwrap_err();
+    assert_eq!("procedure 'foo' has no matching end", err.message());
+
+    // constants of imported modules are not resolved, as the modules are not available
+    let source = "use.std::math::u64\nexport.const.M=[u64::MAX,  2]\nexport.foo push.M[0] end";
+    let expected = "use.std::math::u64\nexport.const.M=[u64::MAX, 2]\nexport.foo push.M[0] end\n";
+    assert_eq!(expected, format_source(source).unwrap());
+
+    // external constants are not resolved either
+    let source = "const.B=TREE_DEPTH*2\nbegin push.TREE_DEPTH   push.B end";
+    let expected = "const.B=TREE_DEPTH*2\nbegin push.TREE_DEPTH push.B end\n";
+    assert_eq!(expected, format_source(source).unwrap());
+
+    // error messages are left intact
+    let source =
+        "const.E=\"a  =[b\"\nexport.foo assert.err=E  assert.err=\"bad  #(value)\" end # c";
+    let expected =
+        "const.E=\"a  =[b\"\nexport.foo assert.err=E assert.err=\"bad  #(value)\" end # c\n";
+    assert_eq!(expected, format_source(source).unwrap());
+
+    // declarations of named locals are rendered in the canonical form
+    let source = "proc.foo\nlocal.acc:word   local.buf:[ word ;4 ]\nloc_storew.buf[1]\nend";
+    let expected =
+        "proc.foo\n    local.acc: word local.buf: [word; 4]\n    loc_storew.buf[1]\nend\n";
+    assert_eq!(expected, format_source(source).unwrap());
+}
+
 // DOCUMENTATION PARSING TESTS
 // ================================================================================================
 
@@ -784,6 +1332,12 @@ fn test_ast_program_serde_simple() {
     assert_correct_program_serialization(source, true);
 }
 
+#[test]
+fn test_ast_program_serde_data_segments() {
+    let source = "const.T=[1, 2] data.4=[T, 3] data.100=[0xff] begin push.1 end";
+    assert_correct_program_serialization(source, true);
+}
+
 #[test]
 fn test_ast_program_serde_local_procs() {
     let source = "\
@@ -1003,6 +1557,50 @@ fn test_repeat_with_constant_count() {
     assert_program_output(source, BTreeMap::new(), nodes);
 }
 
+#[test]
+fn test_for_loop() {
+    let source = "\
+    const.N=10
+
+    begin
+        for.N
+            dup movup.2 add swap
+        end
+    end";
+
+    assert_correct_program_serialization(source, false);
+
+    let body = CodeBody::new(vec![
+        Node::Instruction(Instruction::Dup0),
+        Node::Instruction(Instruction::MovUp2),
+        Node::Instruction(Instruction::Add),
+        Node::Instruction(Instruction::Swap1),
+    ]);
+    let nodes = vec![Node::For { times: 10, body }];
+    assert_program_output(source, BTreeMap::new(), nodes);
+
+    // the index is pushed before the loop and dropped after it
+    let program = ProgramAst::parse(source).unwrap();
+    let analyzer = StackEffectAnalyzer::new(program.procedures());
+    assert_eq!(Ok(StackEffect::new(1, 1)), analyzer.analyze(&program.body));
+
+    let program = ProgramAst::parse("begin for.0 add end end");
+    let err = program.expect_err("for loop with zero iterations");
+    assert_eq!(
+        "malformed instruction 'for.0', parameter 0 is invalid: number of iterations must be \
+        greater than 0",
+        err.message()
+    );
+
+    let err = ProgramAst::parse("begin for.2 add").expect_err("for loop without end");
+    assert_eq!("for without matching end", err.message());
+
+    // loops without iterations cannot be deserialized
+    let body = CodeBody::new(vec![Node::Instruction(Instruction::Incr)]);
+    let bytes = Node::For { times: 0, body }.to_bytes();
+    assert!(Node::read_from_bytes(&bytes).is_err());
+}
+
 fn assert_program_output(source: &str, procedures: LocalProcMap, body: Vec<Node>) {
     let program = ProgramAst::parse(source).unwrap();
     assert_eq!(program.body.nodes(), body);
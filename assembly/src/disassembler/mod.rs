@@ -0,0 +1,444 @@
+use crate::ast::{AdviceInjectorNode, Instruction};
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::{string::ToString, vec::Vec};
+use core::fmt;
+use vm_core::{
+    code_blocks::{Dyn, Span},
+    utils::write_hex_bytes,
+    AdviceInjector, Decorator, MastNode, MastNodeId, Operation, Program, ZERO,
+};
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+const INDENT_STRING: &str = "    ";
+
+// DISASSEMBLER
+// ================================================================================================
+
+/// Renders a compiled [Program] as annotated Miden assembly.
+///
+/// The output is structured as follows:
+/// - Every node of the program MAST which is referenced from more than one place in the program,
+///   as well as every code block in the code block table of the program (i.e., targets of `call`
+///   instructions), is rendered as a separate procedure named `node_<id>`, where `<id>` is the ID
+///   of the node in the MAST forest. Procedures are preceded by comments with their MAST roots.
+/// - Operations of span blocks are grouped by operation batches, and decorators are rendered
+///   inline right before the operations they are attached to.
+/// - JOIN, SPLIT, and LOOP blocks are rendered as sequences of instructions, `if.true` and
+///   `while.true` statements respectively.
+///
+/// Operations which have a direct MASM equivalent are rendered as MASM instructions, and the
+/// decorators which affect execution (e.g., advice injectors and events) are rendered as the
+/// instructions which produce them. Thus, as long as the program consists of such operations
+/// only, the output can be assembled back into an equivalent program. Operations without a MASM
+/// equivalent (e.g., `fmpupdate`) are rendered using their VM operation names, so that assembling
+/// such output fails rather than silently producing a different program.
+pub struct Disassembler<'a> {
+    program: &'a Program,
+    procedures: Vec<MastNodeId>,
+}
+
+impl<'a> Disassembler<'a> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new [Disassembler] instantiated for the specified program.
+    pub fn new(program: &'a Program) -> Self {
+        let procedures = find_procedures(program);
+        Self {
+            program,
+            procedures,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the IDs of the MAST nodes which are rendered as separate procedures, in the order
+    /// in which they are rendered.
+    pub fn procedures(&self) -> &[MastNodeId] {
+        &self.procedures
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Renders the specified node, replacing it with an `exec` of the procedure if the node is
+    /// rendered as a separate procedure.
+    fn fmt_node(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        node_id: MastNodeId,
+        depth: usize,
+    ) -> fmt::Result {
+        if self.procedures.contains(&node_id) {
+            indent(f, depth)?;
+            writeln!(f, "exec.node_{}", node_id.as_u32())
+        } else {
+            self.fmt_node_body(f, node_id, depth)
+        }
+    }
+
+    /// Renders the body of the specified node.
+    fn fmt_node_body(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        node_id: MastNodeId,
+        depth: usize,
+    ) -> fmt::Result {
+        match self.program.get_node(node_id) {
+            MastNode::Span(span) => self.fmt_span(f, span, depth),
+            MastNode::Join(node) => {
+                self.fmt_node(f, node.first(), depth)?;
+                self.fmt_node(f, node.second(), depth)
+            }
+            MastNode::Split(node) => {
+                indent(f, depth)?;
+                writeln!(f, "if.true")?;
+                self.fmt_node(f, node.on_true(), depth + 1)?;
+                indent(f, depth)?;
+                writeln!(f, "else")?;
+                self.fmt_node(f, node.on_false(), depth + 1)?;
+                indent(f, depth)?;
+                writeln!(f, "end")
+            }
+            MastNode::Loop(node) => {
+                indent(f, depth)?;
+                writeln!(f, "while.true")?;
+                self.fmt_node(f, node.body(), depth + 1)?;
+                indent(f, depth)?;
+                writeln!(f, "end")
+            }
+            MastNode::Call(call) => {
+                indent(f, depth)?;
+                if call.is_syscall() {
+                    write!(f, "syscall.")?;
+                    write_hex_bytes(f, &call.fn_hash().as_bytes())?;
+                    return writeln!(f);
+                }
+                if call.fn_hash() == Dyn::dyn_hash() {
+                    return writeln!(f, "dyncall");
+                }
+                match self.program.cb_table().get(call.fn_hash()) {
+                    Some(callee) if self.procedures.contains(&callee) => {
+                        writeln!(f, "call.node_{}", callee.as_u32())
+                    }
+                    _ => {
+                        write!(f, "call.")?;
+                        write_hex_bytes(f, &call.fn_hash().as_bytes())?;
+                        writeln!(f)
+                    }
+                }
+            }
+            MastNode::Dyn(_) => {
+                indent(f, depth)?;
+                writeln!(f, "dynexec")
+            }
+            MastNode::Proxy(proxy) => {
+                indent(f, depth)?;
+                write!(f, "proxy.")?;
+                write_hex_bytes(f, &proxy.hash().as_bytes())?;
+                writeln!(f)
+            }
+        }
+    }
+
+    /// Renders operations of the specified span block grouped by operation batches, with the
+    /// decorators rendered right before the operations they are attached to.
+    fn fmt_span(&self, f: &mut fmt::Formatter<'_>, span: &Span, depth: usize) -> fmt::Result {
+        let mut decorators = span.decorators().iter().peekable();
+        let mut op_idx = 0;
+        for (batch_idx, batch) in span.op_batches().iter().enumerate() {
+            indent(f, depth)?;
+            writeln!(f, "# batch {batch_idx}")?;
+            for op in batch.ops() {
+                while let Some((_, decorator)) = decorators.next_if(|(idx, _)| *idx == op_idx) {
+                    fmt_decorator(f, decorator, depth)?;
+                }
+                fmt_operation(f, op, depth)?;
+                op_idx += 1;
+            }
+        }
+
+        // render decorators which are placed after the last operation of the span
+        for (_, decorator) in decorators {
+            fmt_decorator(f, decorator, depth)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Disassembler<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "# program hash: ")?;
+        write_hex_bytes(f, &self.program.hash().as_bytes())?;
+        writeln!(f)?;
+        for proc_hash in self.program.kernel().proc_hashes() {
+            write!(f, "# kernel procedure: ")?;
+            write_hex_bytes(f, &proc_hash.as_bytes())?;
+            writeln!(f)?;
+        }
+
+        for &node_id in self.procedures.iter() {
+            writeln!(f)?;
+            write!(f, "# mast root: ")?;
+            write_hex_bytes(f, &self.program.get_node(node_id).hash().as_bytes())?;
+            writeln!(f)?;
+            writeln!(f, "proc.node_{}", node_id.as_u32())?;
+            self.fmt_node_body(f, node_id, 1)?;
+            writeln!(f, "end")?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "begin")?;
+        self.fmt_node(f, self.program.entrypoint(), 1)?;
+        writeln!(f, "end")
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the IDs of the nodes which should be rendered as separate procedures, ordered such that
+/// every procedure comes after all procedures it invokes.
+///
+/// A node is rendered as a separate procedure if it is a root of a code block in the code block
+/// table of the program, or if it is referenced by more than one parent node. CALL, DYN, and PROXY
+/// nodes are never rendered as separate procedures since each of them maps to a single
+/// instruction.
+fn find_procedures(program: &Program) -> Vec<MastNodeId> {
+    let mut num_parents = BTreeMap::<MastNodeId, usize>::new();
+    let mut visited = BTreeSet::new();
+    let mut post_order = Vec::new();
+
+    visit_node(program, program.entrypoint(), &mut num_parents, &mut visited, &mut post_order);
+    for (_, node_id) in program.cb_table().iter() {
+        visit_node(program, node_id, &mut num_parents, &mut visited, &mut post_order);
+    }
+
+    post_order
+        .into_iter()
+        .filter(|node_id| {
+            let node = program.get_node(*node_id);
+            if matches!(node, MastNode::Call(_) | MastNode::Dyn(_) | MastNode::Proxy(_)) {
+                return false;
+            }
+            let is_shared = num_parents.get(node_id).is_some_and(|&count| count > 1);
+            is_shared || program.cb_table().has(node.hash())
+        })
+        .collect()
+}
+
+/// Traverses the MAST rooted at the specified node in post order, counting the number of parents
+/// of every node. Targets of CALL nodes are traversed as well, so that callees come before their
+/// callers in the resulting order.
+fn visit_node(
+    program: &Program,
+    node_id: MastNodeId,
+    num_parents: &mut BTreeMap<MastNodeId, usize>,
+    visited: &mut BTreeSet<MastNodeId>,
+    post_order: &mut Vec<MastNodeId>,
+) {
+    if !visited.insert(node_id) {
+        return;
+    }
+
+    let children = match program.get_node(node_id) {
+        MastNode::Join(node) => vec![node.first(), node.second()],
+        MastNode::Split(node) => vec![node.on_true(), node.on_false()],
+        MastNode::Loop(node) => vec![node.body()],
+        MastNode::Call(call) => {
+            if let Some(callee) = program.cb_table().get(call.fn_hash()) {
+                visit_node(program, callee, num_parents, visited, post_order);
+            }
+            Vec::new()
+        }
+        MastNode::Span(_) | MastNode::Dyn(_) | MastNode::Proxy(_) => Vec::new(),
+    };
+
+    for child in children {
+        *num_parents.entry(child).or_default() += 1;
+        visit_node(program, child, num_parents, visited, post_order);
+    }
+
+    post_order.push(node_id);
+}
+
+/// Renders a single operation, using its MASM equivalent if there is one.
+fn fmt_operation(f: &mut fmt::Formatter<'_>, op: &Operation, depth: usize) -> fmt::Result {
+    indent(f, depth)?;
+    match op {
+        // NOOPs are used to pad operation groups, and the assembler inserts them automatically
+        Operation::Noop => writeln!(f, "# noop"),
+        _ => match op_to_instruction(op) {
+            Some(instruction) => writeln!(f, "{instruction}"),
+            None => writeln!(f, "{op}"),
+        },
+    }
+}
+
+/// Renders a single decorator, using the MASM instruction which produces it if there is one.
+fn fmt_decorator(f: &mut fmt::Formatter<'_>, decorator: &Decorator, depth: usize) -> fmt::Result {
+    indent(f, depth)?;
+    let instruction = match decorator {
+        Decorator::Advice(injector) => {
+            advice_injector_to_node(injector).map(Instruction::AdvInject)
+        }
+        Decorator::Debug(options) => Some(Instruction::Debug(*options)),
+        Decorator::Event(event_id) => Some(Instruction::Emit(*event_id)),
+        Decorator::Trace(trace_id) => Some(Instruction::Trace(*trace_id)),
+        Decorator::AsmOp(_) => None,
+    };
+
+    match instruction {
+        // trim the output since some of the instructions are rendered with a trailing new line
+        Some(instruction) => writeln!(f, "{}", instruction.to_string().trim_end()),
+        None => writeln!(f, "# {decorator}"),
+    }
+}
+
+/// Returns the MASM instruction which compiles into exactly the specified operation, or None if
+/// there is no such instruction.
+///
+/// PUSH operations with values 0 and 1 are mapped to `push.0` and `push.1` instructions even
+/// though the assembler compiles these into `pad` and `pad incr` respectively.
+fn op_to_instruction(op: &Operation) -> Option<Instruction> {
+    use Instruction::*;
+
+    let instruction = match op {
+        Operation::Assert(0) => Assert,
+        Operation::Assert(err_code) => AssertWithError(*err_code),
+        Operation::SDepth => Sdepth,
+        Operation::Caller => Caller,
+        Operation::Clk => Clk,
+
+        Operation::Add => Add,
+        Operation::Neg => Neg,
+        Operation::Mul => Mul,
+        Operation::Inv => Inv,
+        Operation::Incr => Incr,
+        Operation::And => And,
+        Operation::Or => Or,
+        Operation::Not => Not,
+        Operation::Eq => Eq,
+        Operation::Eqz => EqImm(ZERO),
+
+        Operation::U32split => U32Split,
+        Operation::U32add => U32OverflowingAdd,
+        Operation::U32assert2(err_code) if err_code.as_int() == 0 => U32Assert2,
+        Operation::U32assert2(err_code) => {
+            U32Assert2WithError(u32::try_from(err_code.as_int()).ok()?)
+        }
+        Operation::U32add3 => U32OverflowingAdd3,
+        Operation::U32sub => U32OverflowingSub,
+        Operation::U32mul => U32OverflowingMul,
+        Operation::U32madd => U32OverflowingMadd,
+        Operation::U32div => U32DivMod,
+        Operation::U32and => U32And,
+        Operation::U32xor => U32Xor,
+
+        Operation::Pad => PushFelt(ZERO),
+        Operation::Drop => Drop,
+        Operation::Dup0 => Dup0,
+        Operation::Dup1 => Dup1,
+        Operation::Dup2 => Dup2,
+        Operation::Dup3 => Dup3,
+        Operation::Dup4 => Dup4,
+        Operation::Dup5 => Dup5,
+        Operation::Dup6 => Dup6,
+        Operation::Dup7 => Dup7,
+        Operation::Dup9 => Dup9,
+        Operation::Dup11 => Dup11,
+        Operation::Dup13 => Dup13,
+        Operation::Dup15 => Dup15,
+        Operation::Swap => Swap1,
+        Operation::SwapW => SwapW1,
+        Operation::SwapW2 => SwapW2,
+        Operation::SwapW3 => SwapW3,
+        Operation::SwapDW => SwapDw,
+        Operation::MovUp2 => MovUp2,
+        Operation::MovUp3 => MovUp3,
+        Operation::MovUp4 => MovUp4,
+        Operation::MovUp5 => MovUp5,
+        Operation::MovUp6 => MovUp6,
+        Operation::MovUp7 => MovUp7,
+        Operation::MovUp8 => MovUp8,
+        Operation::MovDn2 => MovDn2,
+        Operation::MovDn3 => MovDn3,
+        Operation::MovDn4 => MovDn4,
+        Operation::MovDn5 => MovDn5,
+        Operation::MovDn6 => MovDn6,
+        Operation::MovDn7 => MovDn7,
+        Operation::MovDn8 => MovDn8,
+        Operation::CSwap => CSwap,
+        Operation::CSwapW => CSwapW,
+
+        Operation::Push(value) => PushFelt(*value),
+        Operation::AdvPop => AdvPush(1),
+        Operation::AdvPopW => AdvLoadW,
+        Operation::MLoadW => MemLoadW,
+        Operation::MStoreW => MemStoreW,
+        Operation::MLoad => MemLoad,
+        Operation::MStream => MemStream,
+        Operation::Pipe => AdvPipe,
+
+        Operation::HPerm => HPerm,
+        Operation::MpVerify => MTreeVerify,
+        Operation::FriE2F4 => FriExt2Fold4,
+        Operation::RCombBase => RCombBase,
+
+        _ => return None,
+    };
+
+    Some(instruction)
+}
+
+/// Returns the MASM advice injector which produces the specified advice injector decorator, or
+/// None if the decorator is used only internally by the assembler.
+fn advice_injector_to_node(injector: &AdviceInjector) -> Option<AdviceInjectorNode> {
+    use AdviceInjectorNode::*;
+
+    let node = match injector {
+        AdviceInjector::U64Div => PushU64Div,
+        AdviceInjector::Ext2Intt => PushExt2intt,
+        AdviceInjector::SmtGet => PushSmtGet,
+        AdviceInjector::SmtSet => PushSmtSet,
+        AdviceInjector::SmtPeek => PushSmtPeek,
+        AdviceInjector::MapValueToStack {
+            include_len,
+            key_offset,
+        } => {
+            let offset = u8::try_from(*key_offset).ok()?;
+            match (include_len, offset) {
+                (false, 0) => PushMapVal,
+                (false, offset) => PushMapValImm { offset },
+                (true, 0) => PushMapValN,
+                (true, offset) => PushMapValNImm { offset },
+            }
+        }
+        AdviceInjector::MerkleNodeToStack => PushMtNode,
+        AdviceInjector::MemToMap => InsertMem,
+        AdviceInjector::HdwordToMap { domain } => match u8::try_from(domain.as_int()).ok()? {
+            0 => InsertHdword,
+            domain => InsertHdwordImm { domain },
+        },
+        AdviceInjector::HpermToMap => InsertHperm,
+        AdviceInjector::SigToStack { kind } => PushSignature { kind: *kind },
+        _ => return None,
+    };
+
+    Some(node)
+}
+
+/// Writes indentation for the specified nesting depth.
+fn indent(f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+    for _ in 0..depth {
+        write!(f, "{INDENT_STRING}")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use super::Disassembler;
+use crate::{utils::to_hex, Assembler};
+use alloc::string::ToString;
+
+// DISASSEMBLER TESTS
+// ================================================================================================
+
+#[test]
+fn disassemble_simple_program() {
+    let source = "begin push.3 push.7 add emit.5 assert.err=4 end";
+    let program = Assembler::default().compile(source).unwrap();
+
+    let disassembly = Disassembler::new(&program).to_string();
+    let expected = format!(
+        "\
+# program hash: 0x{}
+
+begin
+    # batch 0
+    push.3
+    push.7
+    add
+    emit.5
+    assert.err=4
+end
+",
+        to_hex(&program.hash().as_bytes()).unwrap()
+    );
+    assert_eq!(expected, disassembly);
+}
+
+#[test]
+fn disassemble_control_flow() {
+    let source = "begin if.true push.1 else push.2 end while.true push.0 end end";
+    let program = Assembler::default().compile(source).unwrap();
+
+    let disassembly = Disassembler::new(&program).to_string();
+    let body = disassembly.split_once("begin\n").unwrap().1;
+    let expected = "    if.true
+        # batch 0
+        push.0
+        add.1
+    else
+        # batch 0
+        push.2
+    end
+    while.true
+        # batch 0
+        push.0
+    end
+end
+";
+    assert_eq!(expected, body);
+}
+
+#[test]
+fn disassemble_shared_blocks_as_procedures() {
+    let source = "\
+        proc.foo push.1 if.true push.2 else push.3 end end
+        proc.bar push.4 end
+        begin
+            exec.foo push.5 exec.foo call.bar
+        end";
+    let program = Assembler::default().compile(source).unwrap();
+
+    let disassembler = Disassembler::new(&program);
+    let disassembly = disassembler.to_string();
+
+    // the body of `foo` is shared and `bar` is a CALL target, so both are rendered as procedures
+    assert_eq!(disassembler.procedures().len(), 2);
+    assert_eq!(disassembly.matches("proc.node_").count(), 2);
+    assert_eq!(disassembly.matches("# mast root: ").count(), 2);
+    assert_eq!(disassembly.matches("exec.node_").count(), 2);
+    assert_eq!(disassembly.matches("call.node_").count(), 1);
+}
+
+#[test]
+fn disassembled_program_can_be_reassembled() {
+    let source = "\
+        proc.foo push.1 if.true push.2 else push.3 end end
+        proc.bar push.4 push.9 mul end
+        begin
+            exec.foo push.5 exec.foo call.bar
+            while.true adv_push.1 u32overflowing_add swap.1 drop push.0 end
+            adv.push_u64div movup.2 swapw.2 dup.15 mem_loadw
+        end";
+    let program = Assembler::default().compile(source).unwrap();
+
+    let disassembly = Disassembler::new(&program).to_string();
+    let reassembled = Assembler::default().compile(&disassembly).unwrap();
+    assert_eq!(program.hash(), reassembled.hash());
+}
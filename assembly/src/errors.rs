@@ -1,17 +1,31 @@
 use super::{
-    ast::ProcReExport, crypto::hash::RpoDigest, tokens::SourceLocation, KernelError,
-    LibraryNamespace, ProcedureId, ProcedureName, Token,
+    ast::{ProcReExport, StackEffect},
+    crypto::hash::RpoDigest,
+    tokens::SourceLocation,
+    KernelError, LibraryNamespace, ProcedureId, ProcedureName, Token,
 };
 use alloc::{
+    boxed::Box,
     string::{String, ToString},
     vec::Vec,
 };
-use core::fmt;
+use core::fmt::{self, Write};
+use vm_core::CodeLocation;
 
 // ASSEMBLY ERROR
 // ================================================================================================
 
 /// An error which can be generated while compiling a Miden assembly program into a MAST.
+///
+/// Errors returned by the [Assembler](crate::Assembler) are annotated with the location in the
+/// source code at which they occurred via the `WithSourceLocation` variant, as long as the
+/// compiled source code contains location information. The location is not a part of the error
+/// message; instead, such errors can be rendered together with the offending source line via
+/// [AssemblyError::render()].
+///
+/// When several errors are found in a single pass (e.g., syntax errors in different parts of a
+/// source, or semantic errors in different procedures of a module), they are all reported via the
+/// `Multiple` variant; see [AssemblyError::errors()].
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AssemblyError {
     CallInKernel(String),
@@ -22,22 +36,31 @@ pub enum AssemblyError {
     DivisionByZero,
     DuplicateProcId(ProcedureId),
     DuplicateProcName(String, String),
+    ErrorCodeCollision(u32, String, String),
     ExportedProcInProgram(String),
     ImportedProcModuleNotFound(ProcedureId, String),
     ImportedProcNotFoundInModule(ProcedureId, String),
     InvalidCacheLock,
+    InvalidForLoopBody(Option<StackEffect>),
     InvalidProgramAssemblyContext,
     Io(String),
     KernelError(KernelError),
     KernelProcNotFound(ProcedureId),
     LibraryError(String),
     LocalProcNotFound(u16, String),
+    Multiple(Vec<AssemblyError>),
     ParamOutOfBounds(u64, u64, u64),
     ParsingError(String),
     PhantomCallsNotAllowed(RpoDigest),
+    ProcSignatureMismatch(String, StackEffect, StackEffect),
     ProcedureNameError(String),
+    ProceduresAlreadyCompiled,
     ReExportedProcModuleNotFound(ProcReExport),
     SysCallInKernel(String),
+    WithSourceLocation {
+        location: CodeLocation,
+        error: Box<AssemblyError>,
+    },
 }
 
 impl AssemblyError {
@@ -72,6 +95,10 @@ impl AssemblyError {
         Self::DuplicateProcId(*proc_id)
     }
 
+    pub fn error_code_collision(code: u32, error: &str, other: &str) -> Self {
+        Self::ErrorCodeCollision(code, error.to_string(), other.to_string())
+    }
+
     pub fn exported_proc_in_program(proc_name: &str) -> Self {
         Self::ExportedProcInProgram(proc_name.to_string())
     }
@@ -91,6 +118,10 @@ impl AssemblyError {
         Self::ImportedProcNotFoundInModule(*proc_id, module_path.to_string())
     }
 
+    pub fn invalid_for_loop_body(effect: Option<StackEffect>) -> Self {
+        Self::InvalidForLoopBody(effect)
+    }
+
     pub fn kernel_proc_not_found(kernel_proc_id: &ProcedureId) -> Self {
         Self::KernelProcNotFound(*kernel_proc_id)
     }
@@ -99,6 +130,35 @@ impl AssemblyError {
         Self::LocalProcNotFound(proc_idx, module_path.to_string())
     }
 
+    /// Returns an error combining the provided list of errors.
+    ///
+    /// Nested lists of errors are flattened and duplicate errors are removed. If the resulting
+    /// list contains a single error, this error is returned as is.
+    ///
+    /// # Panics
+    /// Panics if the provided list of errors is empty.
+    pub fn multiple(errors: Vec<AssemblyError>) -> Self {
+        let mut flattened: Vec<AssemblyError> = Vec::with_capacity(errors.len());
+        for error in errors {
+            let nested = match error {
+                Self::Multiple(errors) => errors,
+                error => vec![error],
+            };
+            for error in nested {
+                if !flattened.contains(&error) {
+                    flattened.push(error);
+                }
+            }
+        }
+        let mut errors = flattened;
+        assert!(!errors.is_empty(), "list of errors must not be empty");
+        if errors.len() == 1 {
+            errors.remove(0)
+        } else {
+            Self::Multiple(errors)
+        }
+    }
+
     pub fn param_out_of_bounds(value: u64, min: u64, max: u64) -> Self {
         Self::ParamOutOfBounds(value, min, max)
     }
@@ -107,6 +167,14 @@ impl AssemblyError {
         Self::PhantomCallsNotAllowed(mast_root)
     }
 
+    pub fn proc_signature_mismatch(
+        proc_name: &str,
+        declared: StackEffect,
+        inferred: StackEffect,
+    ) -> Self {
+        Self::ProcSignatureMismatch(proc_name.to_string(), declared, inferred)
+    }
+
     pub fn syscall_in_kernel(kernel_proc_name: &str) -> Self {
         Self::SysCallInKernel(kernel_proc_name.to_string())
     }
@@ -114,6 +182,140 @@ impl AssemblyError {
     pub fn invalid_cache_lock() -> Self {
         Self::InvalidCacheLock
     }
+
+    pub fn procedures_already_compiled() -> Self {
+        Self::ProceduresAlreadyCompiled
+    }
+
+    /// Returns an error built from the provided parsing error which is annotated with the
+    /// location of the parsing error in the module with the specified path.
+    pub fn parsing_error(err: ParsingError, module_path: &str) -> Self {
+        let location = CodeLocation::new(module_path, err.location.line(), err.location.column());
+        Self::from(err).with_source_location(Some(&location))
+    }
+
+    /// Returns an error built from the provided list of parsing errors, each of which is
+    /// annotated with its location in the module with the specified path.
+    ///
+    /// # Panics
+    /// Panics if the provided list of errors is empty.
+    pub fn parsing_errors(errors: Vec<ParsingError>, module_path: &str) -> Self {
+        let errors = errors.into_iter().map(|err| Self::parsing_error(err, module_path)).collect();
+        Self::multiple(errors)
+    }
+
+    // LOCATIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns this error annotated with the specified source location.
+    ///
+    /// If the location is None, the error is already annotated with a location, or the error
+    /// combines several errors, the error is returned unchanged.
+    pub fn with_source_location(self, location: Option<&CodeLocation>) -> Self {
+        match (self, location) {
+            (error @ (Self::WithSourceLocation { .. } | Self::Multiple(_)), _) | (error, None) => {
+                error
+            }
+            (error, Some(location)) => Self::WithSourceLocation {
+                location: location.clone(),
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// Returns the location in the source code at which this error occurred, if known.
+    pub fn source_location(&self) -> Option<&CodeLocation> {
+        match self {
+            Self::WithSourceLocation { location, .. } => Some(location),
+            _ => None,
+        }
+    }
+
+    /// Returns the list of errors combined in this error; for a single error, this is a list
+    /// containing only this error.
+    pub fn errors(&self) -> &[AssemblyError] {
+        match self {
+            Self::Multiple(errors) => errors,
+            error => core::slice::from_ref(error),
+        }
+    }
+
+    /// Returns this error without its source location annotation.
+    pub fn inner(&self) -> &Self {
+        match self {
+            Self::WithSourceLocation { error, .. } => error,
+            error => error,
+        }
+    }
+
+    // DIAGNOSTICS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a hint on how this error could be fixed, if one is available.
+    pub fn hint(&self) -> Option<String> {
+        use AssemblyError::*;
+        let hint = match self {
+            CallerOutOKernel => "`caller` instruction can be used only in kernel procedures".into(),
+            CircularModuleDependency(_) => "remove one of the imports to break the cycle".into(),
+            ConflictingNumLocals(_) => {
+                "procedures with identical bodies must declare the same number of locals".into()
+            }
+            DivisionByZero => "the divisor must be a non-zero value".into(),
+            DuplicateProcName(..) => "procedure names must be unique within a module".into(),
+            ErrorCodeCollision(..) => "change one of the error messages or error codes".into(),
+            ExportedProcInProgram(_) => {
+                "declare the procedure with `proc` instead of `export` in executable programs".into()
+            }
+            ImportedProcModuleNotFound(..) => {
+                "make sure the library containing the module is provided to the assembler".into()
+            }
+            ImportedProcNotFoundInModule(..) | KernelProcNotFound(_) => {
+                "make sure the procedure is exported from the module".into()
+            }
+            InvalidForLoopBody(_) => {
+                "the body must leave the index of the iteration at the top of the stack".into()
+            }
+            LocalProcNotFound(..) => {
+                "local procedures must be defined before the place they are invoked from".into()
+            }
+            ParamOutOfBounds(_, min, max) => format!("use a value between {min} and {max}"),
+            PhantomCallsNotAllowed(_) => {
+                "make sure the procedure with this MAST root is available to the assembler".into()
+            }
+            ProcSignatureMismatch(..) => {
+                "update the signature to match the inputs and outputs of the procedure".into()
+            }
+            ProceduresAlreadyCompiled => {
+                "configure the assembler before setting its kernel or compiling any code".into()
+            }
+            SysCallInKernel(_) => {
+                "kernel procedures cannot make syscalls; use `exec` to invoke other kernel procedures"
+                    .into()
+            }
+            WithSourceLocation { error, .. } => return error.hint(),
+            _ => return None,
+        };
+        Some(hint)
+    }
+
+    /// Renders this error in a human-readable form together with the offending line of the
+    /// provided source code and a hint on how the error could be fixed, if one is available.
+    ///
+    /// The source code is expected to be the source of the module at which the error occurred;
+    /// if the location of the error is not known or is out of the bounds of the source, only the
+    /// error message and the hint are rendered.
+    ///
+    /// If this error combines several errors, each of them is rendered in turn.
+    pub fn render(&self, source: &str) -> String {
+        if let Self::Multiple(errors) = self {
+            return errors.iter().map(|error| error.render(source)).collect::<Vec<_>>().join("\n");
+        }
+
+        let location = self
+            .source_location()
+            .map(|location| (location as &dyn fmt::Display, location.line(), location.column()));
+        render_diagnostic("error", self.inner(), location, self.hint(), source)
+    }
 }
 
 impl From<ParsingError> for AssemblyError {
@@ -146,20 +348,30 @@ impl fmt::Display for AssemblyError {
             DivisionByZero => write!(f, "division by zero"),
             DuplicateProcId(proc_id) => write!(f, "duplicate proc id {proc_id}"),
             DuplicateProcName(proc_name, module_path) => write!(f, "duplicate proc name '{proc_name}' in module {module_path}"),
+            ErrorCodeCollision(code, error, other) => write!(f, "error code {code} of {error} collides with the error code of {other}"),
             ExportedProcInProgram(proc_name) => write!(f, "exported procedure '{proc_name}' in executable program"),
             ImportedProcModuleNotFound(proc_id, proc_name) => write!(f, "module for imported procedure `{proc_name}` with ID {proc_id} not found"),
             ImportedProcNotFoundInModule(proc_id, module_path) => write!(f, "imported procedure {proc_id} not found in module {module_path}"),
             InvalidCacheLock => write!(f, "an attempt was made to lock a borrowed procedures cache"),
+            InvalidForLoopBody(Some(effect)) => write!(f, "body of a for loop must not change the depth of the stack, but has stack effect {effect}"),
+            InvalidForLoopBody(None) => write!(f, "body of a for loop must not change the depth of the stack, but its stack effect is unknown"),
             InvalidProgramAssemblyContext => write!(f, "assembly context improperly initialized for program compilation"),
             Io(description) => write!(f, "I/O error: {description}"),
             KernelError(error) => write!(f, "{}", error),
             KernelProcNotFound(proc_id) => write!(f, "procedure {proc_id} not found in kernel"),
             LibraryError(err) | ParsingError(err) | ProcedureNameError(err) => write!(f, "{err}"),
             LocalProcNotFound(proc_idx, module_path) => write!(f, "procedure at index {proc_idx} not found in module {module_path}"),
+            Multiple(errors) => {
+                let messages = errors.iter().map(|error| error.to_string()).collect::<Vec<_>>();
+                write!(f, "{}", messages.join("\n"))
+            }
             ParamOutOfBounds(value, min, max) => write!(f, "parameter value must be greater than or equal to {min} and less than or equal to {max}, but was {value}"),
             PhantomCallsNotAllowed(mast_root) => write!(f, "cannot call phantom procedure with MAST root {mast_root}: phantom calls not allowed"),
+            ProcSignatureMismatch(proc_name, declared, inferred) => write!(f, "procedure '{proc_name}' declares stack effect {declared} in its signature, but its body has stack effect {inferred}"),
+            ProceduresAlreadyCompiled => write!(f, "assembler has already compiled procedures"),
             ReExportedProcModuleNotFound(reexport) => write!(f, "re-exported proc {} with id {} not found", reexport.name(), reexport.proc_id()),
             SysCallInKernel(proc_name) => write!(f, "syscall instruction used in kernel procedure '{proc_name}'"),
+            WithSourceLocation { error, .. } => write!(f, "{error}"),
         }
     }
 }
@@ -223,6 +435,16 @@ impl ParsingError {
         }
     }
 
+    pub fn external_const_redeclared(token: &Token, label: &str) -> Self {
+        ParsingError {
+            message: format!(
+                "constant '{label}' is already defined externally - it cannot be redeclared"
+            ),
+            location: *token.location(),
+            op: token.to_string(),
+        }
+    }
+
     pub fn invalid_const_name(token: &Token, err: LabelError) -> Self {
         ParsingError {
             message: format!("invalid constant name: {err}"),
@@ -249,6 +471,16 @@ impl ParsingError {
         }
     }
 
+    pub fn const_export_not_allowed(token: &Token) -> Self {
+        ParsingError {
+            message: format!(
+                "invalid constant declaration: `{token}` - constants can be exported only from modules"
+            ),
+            location: *token.location(),
+            op: token.to_string(),
+        }
+    }
+
     pub fn const_not_found(token: &Token) -> Self {
         ParsingError {
             message: format!("constant used in operation `{token}` not found"),
@@ -275,6 +507,85 @@ impl ParsingError {
         }
     }
 
+    pub fn const_not_scalar(token: &Token, name: &str) -> Self {
+        ParsingError {
+            message: format!(
+                "constant '{name}' used in `{token}` is an array - use `{name}[<index>]` to access its elements"
+            ),
+            location: *token.location(),
+            op: token.to_string(),
+        }
+    }
+
+    pub fn const_not_array(token: &Token, name: &str) -> Self {
+        ParsingError {
+            message: format!(
+                "constant '{name}' used in `{token}` is not an array and cannot be indexed"
+            ),
+            location: *token.location(),
+            op: token.to_string(),
+        }
+    }
+
+    pub fn const_not_numeric(token: &Token, name: &str) -> Self {
+        ParsingError {
+            message: format!(
+                "constant '{name}' used in `{token}` is an error message and can be used only as an error code of an assertion"
+            ),
+            location: *token.location(),
+            op: token.to_string(),
+        }
+    }
+
+    pub fn const_index_out_of_bounds(token: &Token, name: &str, index: u64, len: usize) -> Self {
+        ParsingError {
+            message: format!(
+                "index {index} used in `{token}` is out of bounds for constant '{name}' with {len} elements"
+            ),
+            location: *token.location(),
+            op: token.to_string(),
+        }
+    }
+
+    // DATA SEGMENTS
+    // --------------------------------------------------------------------------------------------
+
+    pub fn invalid_data_segment(token: &Token, reason: &str) -> Self {
+        ParsingError {
+            message: format!("malformed data segment `{token}` - reason: {reason}"),
+            location: *token.location(),
+            op: token.to_string(),
+        }
+    }
+
+    pub fn data_invalid_scope(token: &Token) -> Self {
+        ParsingError {
+            message: format!("invalid data segment declaration: `{token}` - data segments can only be defined in programs below constants and above procedures"),
+            location: *token.location(),
+            op: token.to_string(),
+        }
+    }
+
+    pub fn error_code_collision(token: &Token, code: u32, other: &str) -> Self {
+        ParsingError {
+            message: format!(
+                "error code {code} of `{token}` collides with the error code of {other}"
+            ),
+            location: *token.location(),
+            op: token.to_string(),
+        }
+    }
+
+    pub fn data_segments_overlap(token: &Token, address: u32) -> Self {
+        ParsingError {
+            message: format!(
+                "data segment `{token}` overlaps with the data segment at address {address}"
+            ),
+            location: *token.location(),
+            op: token.to_string(),
+        }
+    }
+
     // INVALID / MALFORMED INSTRUCTIONS
     // --------------------------------------------------------------------------------------------
 
@@ -362,6 +673,14 @@ impl ParsingError {
         }
     }
 
+    pub fn unmatched_for(token: &Token) -> Self {
+        ParsingError {
+            message: "for without matching end".to_string(),
+            location: *token.location(),
+            op: token.to_string(),
+        }
+    }
+
     pub fn unmatched_else(token: &Token) -> Self {
         ParsingError {
             message: "else without matching end".to_string(),
@@ -403,6 +722,32 @@ impl ParsingError {
         }
     }
 
+    pub fn malformed_directive(location: SourceLocation, directive: &str) -> Self {
+        ParsingError {
+            message: format!(
+                "malformed directive `{directive}` - expected `#if <FEATURE>`, `#else`, or `#end`"
+            ),
+            location,
+            op: directive.to_string(),
+        }
+    }
+
+    pub fn unmatched_directive(location: SourceLocation, directive: &str) -> Self {
+        ParsingError {
+            message: format!("directive `{directive}` has no matching `#if`"),
+            location,
+            op: directive.to_string(),
+        }
+    }
+
+    pub fn unclosed_directive(location: SourceLocation) -> Self {
+        ParsingError {
+            message: "directive `#if` has no matching `#end`".to_string(),
+            location,
+            op: "#if".to_string(),
+        }
+    }
+
     pub fn not_a_library_module(token: &Token) -> Self {
         ParsingError {
             message: "not a module: `begin` instruction found".to_string(),
@@ -503,6 +848,57 @@ impl ParsingError {
         }
     }
 
+    pub fn invalid_local_decl(token: &Token, reason: &str) -> Self {
+        ParsingError {
+            message: format!("invalid local declaration: {reason}"),
+            location: *token.location(),
+            op: token.to_string(),
+        }
+    }
+
+    pub fn duplicate_local_name(token: &Token, name: &str) -> Self {
+        ParsingError {
+            message: format!("duplicate local name: {name}"),
+            location: *token.location(),
+            op: token.to_string(),
+        }
+    }
+
+    pub fn local_invalid_scope(token: &Token) -> Self {
+        ParsingError {
+            message: "local declarations must be placed at the start of a procedure body"
+                .to_string(),
+            location: *token.location(),
+            op: token.to_string(),
+        }
+    }
+
+    pub fn local_not_found(token: &Token, name: &str) -> Self {
+        ParsingError {
+            message: format!("local '{name}' used in `{token}` is not declared in the procedure"),
+            location: *token.location(),
+            op: token.to_string(),
+        }
+    }
+
+    pub fn local_index_out_of_bounds(token: &Token, name: &str, index: u64, size: u16) -> Self {
+        ParsingError {
+            message: format!(
+                "index {index} used in `{token}` is out of bounds for local '{name}' with {size} elements"
+            ),
+            location: *token.location(),
+            op: token.to_string(),
+        }
+    }
+
+    pub fn invalid_proc_signature(token: &Token, reason: &str) -> Self {
+        ParsingError {
+            message: format!("invalid procedure signature: {reason}"),
+            location: *token.location(),
+            op: token.to_string(),
+        }
+    }
+
     pub fn unmatched_proc(token: &Token, proc_name: &str) -> Self {
         ParsingError {
             message: format!("procedure '{proc_name}' has no matching end"),
@@ -1039,3 +1435,47 @@ impl fmt::Display for PathError {
 
 #[cfg(feature = "std")]
 impl std::error::Error for PathError {}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Renders a diagnostic message of the specified severity (e.g., "error") together with the line
+/// of the source code at the specified location and an optional hint.
+///
+/// The location is specified as a tuple of its displayable form, its line, and its column.
+pub(crate) fn render_diagnostic(
+    severity: &str,
+    message: &dyn fmt::Display,
+    location: Option<(&dyn fmt::Display, u32, u32)>,
+    hint: Option<String>,
+    source: &str,
+) -> String {
+    let mut output = format!("{severity}: {message}\n");
+    let mut gutter = String::from(" ");
+
+    if let Some((location, line_num, column)) = location {
+        let line = (line_num as usize)
+            .checked_sub(1)
+            .and_then(|line_idx| source.lines().nth(line_idx));
+        let line_num = line_num.to_string();
+        gutter = " ".repeat(line_num.len() + 1);
+
+        writeln!(output, "{}--> {location}", &gutter[1..]).expect("write failed");
+        if let Some(line) = line {
+            let column = (column as usize).saturating_sub(1);
+            let token_len =
+                line.chars().skip(column).take_while(|c| !c.is_whitespace()).count().max(1);
+            writeln!(output, "{gutter}|").expect("write failed");
+            writeln!(output, "{line_num} | {line}").expect("write failed");
+            writeln!(output, "{gutter}| {}{}", " ".repeat(column), "^".repeat(token_len))
+                .expect("write failed");
+        }
+    }
+
+    if let Some(hint) = hint {
+        writeln!(output, "{gutter}|").expect("write failed");
+        writeln!(output, "{gutter}= help: {hint}").expect("write failed");
+    }
+
+    output
+}
@@ -7,13 +7,13 @@ extern crate alloc;
 extern crate std;
 
 use vm_core::{
-    code_blocks::CodeBlock,
     crypto,
     errors::KernelError,
     utils::{
         ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
     },
-    CodeBlockTable, Felt, Kernel, Operation, Program, StarkField, ONE, ZERO,
+    CodeBlockTable, DataSegment, Felt, Kernel, MastForest, MastNodeId, Operation, Program,
+    StarkField, ONE, ZERO,
 };
 
 mod library;
@@ -32,9 +32,15 @@ use tokens::{Token, TokenStream};
 mod errors;
 pub use errors::{AssemblyError, LabelError, LibraryError, ParsingError, PathError};
 
+mod warnings;
+pub use warnings::{AssemblyWarning, WarningKind};
+
 mod assembler;
 pub use assembler::{Assembler, AssemblyContext};
 
+mod disassembler;
+pub use disassembler::Disassembler;
+
 #[cfg(test)]
 mod tests;
 
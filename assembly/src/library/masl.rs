@@ -3,6 +3,7 @@ use super::{
     LibraryError, LibraryNamespace, LibraryPath, Module, ModuleAst, Serializable, Version,
     MAX_DEPENDENCIES, MAX_MODULES,
 };
+use crate::AssemblyWarning;
 use alloc::{collections::BTreeSet, vec::Vec};
 use core::slice::Iter;
 
@@ -10,6 +11,16 @@ use core::slice::Iter;
 // ================================================================================================
 //
 
+/// Magic bytes identifying a serialized library.
+const MAGIC: &[u8; 4] = b"MASL";
+
+/// The current version of the library serialization format.
+///
+/// The version must be incremented whenever the serialization format of libraries (including the
+/// serialization of module ASTs) changes, so that libraries serialized in older formats are
+/// rejected instead of being misinterpreted.
+const VERSION: u8 = 1;
+
 /// Serialization options for [ModuleAst]. Imports and information about imported procedures are
 /// part of the ModuleAst serialization by default.
 const AST_DEFAULT_SERDE_OPTIONS: AstSerdeOptions = AstSerdeOptions {
@@ -108,6 +119,20 @@ impl MaslLibrary {
         })
     }
 
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns an iterator over the warnings about the source code of the modules of this library,
+    /// together with the paths of the modules to which they refer.
+    ///
+    /// Warnings are not serialized with the library, and thus, are available only if the library
+    /// was built from source code (e.g., via `MaslLibrary::read_from_dir()`).
+    pub fn warnings(&self) -> impl Iterator<Item = (&LibraryPath, &AssemblyWarning)> {
+        self.modules.iter().flat_map(|module| {
+            module.ast.warnings().iter().map(move |warning| (&module.path, warning))
+        })
+    }
+
     // STATE MUTATORS
     // --------------------------------------------------------------------------------------------
 
@@ -119,9 +144,15 @@ impl MaslLibrary {
 
 #[cfg(feature = "std")]
 mod use_std {
-    use alloc::{collections::BTreeMap, string::ToString};
-
-    use super::{super::super::ast::instrument, *};
+    use alloc::{
+        collections::BTreeMap,
+        string::{String, ToString},
+    };
+
+    use super::{
+        super::super::ast::{instrument, ImportedConstants},
+        *,
+    };
     use std::{fs, io, path::Path};
 
     impl MaslLibrary {
@@ -137,12 +168,40 @@ mod use_std {
         /// - ./crypto/hash.masm    -> ("crypto::hash", ast(./crypto/hash.masm))
         /// - ./math/u32.masm       -> ("math::u32",    ast(./math/u32.masm))
         /// - ./math/u64.masm       -> ("math::u64",    ast(./math/u64.masm))
+        ///
+        /// Modules of the library can reference constants exported from other modules of the same
+        /// library; to reference constants exported from the modules of other libraries, use
+        /// [MaslLibrary::read_from_dir_with_imported_constants()].
         pub fn read_from_dir<P>(
             path: P,
             namespace: LibraryNamespace,
             with_source_locations: bool,
             version: Version,
         ) -> io::Result<Self>
+        where
+            P: AsRef<Path>,
+        {
+            Self::read_from_dir_with_imported_constants(
+                path,
+                namespace,
+                with_source_locations,
+                version,
+                &ImportedConstants::new(),
+            )
+        }
+
+        /// Reads a library from a directory in the same way as [MaslLibrary::read_from_dir()],
+        /// resolving references to constants exported from the modules of other libraries (e.g.,
+        /// `push.u64::MAX` after `use.std::math::u64`) against the provided set of imported
+        /// constants. Code excluded by conditional compilation directives (e.g., `#if DEBUG`) is
+        /// determined by the features enabled in this set.
+        pub fn read_from_dir_with_imported_constants<P>(
+            path: P,
+            namespace: LibraryNamespace,
+            with_source_locations: bool,
+            version: Version,
+            imported_constants: &ImportedConstants,
+        ) -> io::Result<Self>
         where
             P: AsRef<Path>,
         {
@@ -156,7 +215,6 @@ mod use_std {
                 ));
             }
 
-            let mut dependencies_set = BTreeSet::new();
             let module_path = LibraryPath::new(&namespace)
                 .map_err(|err| io::Error::new(io::ErrorKind::Other, format!("{err}")))?;
 
@@ -168,16 +226,16 @@ mod use_std {
                 ));
             }
 
-            let modules = read_from_dir_helper(
-                Default::default(),
-                path,
-                &module_path,
-                &mut dependencies_set,
-            )?
-            .into_iter()
-            .map(|(path, ast)| Module { path, ast })
-            .collect();
+            let sources = read_from_dir_helper(Default::default(), path, &module_path)?;
+            let modules = parse_modules(sources, imported_constants.clone())?;
 
+            // collect dependencies of all modules of this library
+            let mut dependencies_set = BTreeSet::new();
+            for module in modules.iter() {
+                for path in module.ast.import_info().import_paths() {
+                    dependencies_set.insert(LibraryNamespace::new(path.first())?);
+                }
+            }
             let dependencies =
                 dependencies_set.into_iter().filter(|dep| dep != &namespace).collect();
 
@@ -221,15 +279,14 @@ mod use_std {
     // HELPER FUNCTIONS
     // --------------------------------------------------------------------------------------------
 
-    /// Read a directory and recursively feed the state map with path->ast tuples.
+    /// Read a directory and recursively feed the state map with path->source tuples.
     ///
     /// Helper for [`Self::read_from_dir`].
     fn read_from_dir_helper<P>(
-        mut state: BTreeMap<LibraryPath, ModuleAst>,
+        mut state: BTreeMap<LibraryPath, String>,
         dir: P,
         module_path: &LibraryPath,
-        deps: &mut BTreeSet<LibraryNamespace>,
-    ) -> io::Result<BTreeMap<LibraryPath, ModuleAst>>
+    ) -> io::Result<BTreeMap<LibraryPath, String>>
     where
         P: AsRef<Path>,
     {
@@ -246,8 +303,8 @@ mod use_std {
                 let module_path = module_path
                     .append(name)
                     .map_err(|err| io::Error::new(io::ErrorKind::Other, format!("{err}")))?;
-                state = read_from_dir_helper(state, path, &module_path, deps)?;
-            // if file, check if `masm`, parse & append; skip otherwise
+                state = read_from_dir_helper(state, path, &module_path)?;
+            // if file, check if `masm`, read & append; skip otherwise
             } else if ty.is_file() {
                 let path = entry.path();
 
@@ -270,15 +327,8 @@ mod use_std {
                         ));
                     }
 
-                    // read & parse file
+                    // read file
                     let contents = fs::read_to_string(&path)?;
-                    let ast = ModuleAst::parse(&contents)?;
-
-                    // add dependencies of this module to the dependencies of this library
-                    for path in ast.import_info().import_paths() {
-                        let ns = LibraryNamespace::new(path.first())?;
-                        deps.insert(ns);
-                    }
 
                     // build module path and add it to the map of modules
                     let module = if name == MaslLibrary::MOD {
@@ -289,7 +339,7 @@ mod use_std {
                             .map_err(|err| io::Error::new(io::ErrorKind::Other, format!("{err}")))?
                     };
 
-                    if state.insert(module, ast).is_some() {
+                    if state.insert(module, contents).is_some() {
                         unreachable!(
                             "the filesystem is inconsistent as it produced duplicated module paths"
                         );
@@ -299,10 +349,53 @@ mod use_std {
         }
         Ok(state)
     }
+
+    /// Parses the provided module sources into modules.
+    ///
+    /// Modules may reference constants exported from other modules of the same library, and thus
+    /// the modules are parsed in passes: every pass parses the modules whose references to
+    /// constants can be resolved using the provided imported constants (i.e., the constants of
+    /// the libraries this library depends on) and the constants exported from the modules parsed
+    /// so far.
+    ///
+    /// Helper for [`Self::read_from_dir_with_imported_constants`].
+    fn parse_modules(
+        mut sources: BTreeMap<LibraryPath, String>,
+        mut imported_constants: ImportedConstants,
+    ) -> io::Result<Vec<Module>> {
+        let mut modules = Vec::with_capacity(sources.len());
+        loop {
+            let mut errors = Vec::new();
+            let num_pending = sources.len();
+            sources.retain(|path, source| {
+                match ModuleAst::parse_with_imported_constants(source, &imported_constants) {
+                    Ok((ast, _)) => {
+                        imported_constants.add_module(path.clone(), &ast);
+                        modules.push(Module::new(path.clone(), ast));
+                        false
+                    }
+                    Err(mut module_errors) => {
+                        errors.push(module_errors.swap_remove(0));
+                        true
+                    }
+                }
+            });
+
+            // stop when all modules are parsed, or when no module could be parsed in this pass
+            if sources.is_empty() {
+                modules.sort_by(|a, b| a.path.cmp(&b.path));
+                return Ok(modules);
+            } else if sources.len() == num_pending {
+                return Err(errors.swap_remove(0).into());
+            }
+        }
+    }
 }
 
 impl Serializable for MaslLibrary {
     fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_bytes(MAGIC);
+        target.write_u8(VERSION);
         self.namespace.write_into(target);
         self.version.write_into(target);
 
@@ -334,6 +427,20 @@ impl Serializable for MaslLibrary {
 
 impl Deserializable for MaslLibrary {
     fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let magic: [u8; 4] = source.read_array()?;
+        if &magic != MAGIC {
+            return Err(DeserializationError::InvalidValue(format!(
+                "invalid magic bytes; expected {MAGIC:?}, but was {magic:?}"
+            )));
+        }
+
+        let format_version = source.read_u8()?;
+        if format_version != VERSION {
+            return Err(DeserializationError::InvalidValue(format!(
+                "unsupported library format version; expected {VERSION}, but was {format_version}"
+            )));
+        }
+
         let namespace = LibraryNamespace::read_from(source)?;
         let version = Version::read_from(source)?;
 
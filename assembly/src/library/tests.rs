@@ -1,5 +1,5 @@
 use super::{Library, LibraryNamespace, LibraryPath, MaslLibrary, Module, ModuleAst, Version};
-use alloc::vec::Vec;
+use alloc::{string::ToString, vec::Vec};
 use vm_core::utils::{Deserializable, Serializable, SliceReader};
 
 #[test]
@@ -91,3 +91,119 @@ end
 
     assert!(bundle.get_module_ast(&LibraryPath::new("test::bar").unwrap()).is_none());
 }
+
+#[test]
+fn masl_procedure_signatures() {
+    let source = r#"
+        export.foo(a: felt, b: felt) -> (c: felt)
+            add
+        end
+        export.bar
+            mul
+        end
+    "#;
+    let path = LibraryPath::new("test::foo").unwrap();
+    let ast = ModuleAst::parse(source).unwrap();
+    let modules = [Module::new(path.clone(), ast)].to_vec();
+
+    let namespace = LibraryNamespace::new("test").unwrap();
+    let bundle = MaslLibrary::new(namespace, Version::MIN, false, modules, Vec::new()).unwrap();
+
+    // signatures of exported procedures are preserved in the serialized library
+    let mut bytes = Vec::new();
+    bundle.write_into(&mut bytes);
+    let deserialized = MaslLibrary::read_from(&mut SliceReader::new(&bytes)).unwrap();
+    let procs = deserialized.get_module_ast(&path).unwrap().procs();
+    let signature = procs[0].signature.as_ref().unwrap();
+    assert_eq!("(a: felt, b: felt) -> (c: felt)", signature.to_string());
+    assert!(procs[1].signature.is_none());
+}
+
+#[test]
+fn masl_exported_constants() {
+    let source = r#"
+        export.const.MAX=100
+        export.const.IV=[1, 2, 3, 4]
+        const.MIN=1
+
+        export.foo
+            push.MIN
+        end
+    "#;
+    let path = LibraryPath::new("test::foo").unwrap();
+    let ast = ModuleAst::parse(source).unwrap();
+    let modules = [Module::new(path.clone(), ast)].to_vec();
+
+    let namespace = LibraryNamespace::new("test").unwrap();
+    let bundle = MaslLibrary::new(namespace, Version::MIN, false, modules, Vec::new()).unwrap();
+
+    // exported constants are preserved in the serialized library, while internal ones are not
+    let mut bytes = Vec::new();
+    bundle.write_into(&mut bytes);
+    let deserialized = MaslLibrary::read_from(&mut SliceReader::new(&bytes)).unwrap();
+    let constants = deserialized.get_module_ast(&path).unwrap().exported_constants();
+    let constants = constants.iter().map(|(name, value)| format!("{name}={value}"));
+    assert_eq!(vec!["IV=[1, 2, 3, 4]", "MAX=100"], constants.collect::<Vec<_>>());
+}
+
+#[test]
+fn masl_format_version() {
+    let path = LibraryPath::new("test::foo").unwrap();
+    let ast = ModuleAst::parse("export.foo add end").unwrap();
+    let modules = [Module::new(path, ast)].to_vec();
+    let namespace = LibraryNamespace::new("test").unwrap();
+    let bundle = MaslLibrary::new(namespace, Version::MIN, false, modules, Vec::new()).unwrap();
+
+    // serialized libraries start with the magic bytes followed by the format version
+    let mut bytes = Vec::new();
+    bundle.write_into(&mut bytes);
+    assert_eq!(b"MASL", &bytes[..4]);
+    assert_eq!(bundle, MaslLibrary::read_from(&mut SliceReader::new(&bytes)).unwrap());
+
+    // libraries serialized in a different format version are rejected
+    bytes[4] += 1;
+    let err = MaslLibrary::read_from(&mut SliceReader::new(&bytes)).unwrap_err();
+    assert!(err.to_string().contains("unsupported library format version"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn masl_read_from_dir_with_dependency_constants() {
+    use crate::ast::ImportedConstants;
+    use std::fs;
+
+    // a dependency library exporting a constant
+    let dep_path = LibraryPath::new("dep::math").unwrap();
+    let dep_ast = ModuleAst::parse("export.const.A=21 export.bar add end").unwrap();
+    let dep_namespace = LibraryNamespace::new("dep").unwrap();
+    let modules = [Module::new(dep_path, dep_ast)].to_vec();
+    let dep = MaslLibrary::new(dep_namespace, Version::MIN, false, modules, Vec::new()).unwrap();
+
+    // a library whose module references the constant of the dependency
+    let dir = std::env::temp_dir()
+        .join(format!("masl-deps-{}", std::process::id()))
+        .join("lib");
+    fs::create_dir_all(&dir).unwrap();
+    let source = "use.dep::math\nexport.const.B=math::A*2\nexport.foo\n    push.B\nend\n";
+    fs::write(dir.join("foo.masm"), source).unwrap();
+
+    // the constant cannot be resolved without the dependency
+    let namespace = LibraryNamespace::new("lib").unwrap();
+    assert!(MaslLibrary::read_from_dir(&dir, namespace.clone(), false, Version::MIN).is_err());
+
+    let imported_constants = ImportedConstants::new().with_library(&dep);
+    let library = MaslLibrary::read_from_dir_with_imported_constants(
+        &dir,
+        namespace,
+        false,
+        Version::MIN,
+        &imported_constants,
+    );
+    fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+
+    let library = library.unwrap();
+    let path = LibraryPath::new("lib::foo").unwrap();
+    let constants = library.get_module_ast(&path).unwrap().exported_constants();
+    assert_eq!("42", constants["B"].to_string());
+    assert_eq!(&[LibraryNamespace::new("dep").unwrap()], library.dependencies());
+}
@@ -1,7 +1,7 @@
 use super::{
     crypto::hash::{Blake3_160, RpoDigest},
-    ByteReader, ByteWriter, CodeBlock, Deserializable, DeserializationError, LabelError,
-    LibraryPath, Serializable, PROCEDURE_LABEL_PARSER,
+    ByteReader, ByteWriter, Deserializable, DeserializationError, LabelError, LibraryPath,
+    MastNodeId, Serializable, PROCEDURE_LABEL_PARSER,
 };
 use alloc::{
     collections::BTreeSet,
@@ -18,13 +18,17 @@ use core::{
 
 /// Miden assembly procedure consisting of procedure MAST and basic metadata.
 ///
+/// The procedure MAST is stored in the MAST forest of the assembler which compiled the procedure,
+/// and the procedure references it via the ID of its root node.
+///
 /// Procedure metadata includes:
 /// - Number of procedure locals available to the procedure.
 /// - A set of MAST roots of procedures which are invoked from this procedure.
 #[derive(Clone, Debug)]
 pub struct Procedure {
     num_locals: u32,
-    code: CodeBlock,
+    mast_root: RpoDigest,
+    code: MastNodeId,
     callset: CallSet,
 }
 
@@ -36,12 +40,12 @@ impl Procedure {
 
     /// Returns the root of this procedure's MAST.
     pub fn mast_root(&self) -> RpoDigest {
-        self.code.hash()
+        self.mast_root
     }
 
-    /// Returns a reference to the MAST of this procedure.
-    pub fn code(&self) -> &CodeBlock {
-        &self.code
+    /// Returns the ID of the root node of this procedure's MAST in the assembler's MAST forest.
+    pub fn code(&self) -> MastNodeId {
+        self.code
     }
 
     /// Returns a reference to a set of all procedures (identified by their MAST roots) which may
@@ -75,7 +79,8 @@ impl NamedProcedure {
         name: ProcedureName,
         is_export: bool,
         num_locals: u32,
-        code: CodeBlock,
+        mast_root: RpoDigest,
+        code: MastNodeId,
         callset: CallSet,
     ) -> Self {
         NamedProcedure {
@@ -83,6 +88,7 @@ impl NamedProcedure {
             is_export,
             procedure: Procedure {
                 num_locals,
+                mast_root,
                 code,
                 callset,
             },
@@ -109,12 +115,12 @@ impl NamedProcedure {
 
     /// Returns the root of this procedure's MAST.
     pub fn mast_root(&self) -> RpoDigest {
-        self.procedure.code.hash()
+        self.procedure.mast_root
     }
 
-    /// Returns a reference to the MAST of this procedure.
-    pub fn code(&self) -> &CodeBlock {
-        &self.procedure.code
+    /// Returns the ID of the root node of this procedure's MAST in the assembler's MAST forest.
+    pub fn code(&self) -> MastNodeId {
+        self.procedure.code
     }
 
     /// Returns a reference to a set of all procedures (identified by their MAST roots) which may
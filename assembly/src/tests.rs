@@ -1,7 +1,8 @@
 use crate::{
-    ast::{ModuleAst, ProgramAst},
-    Assembler, AssemblyContext, AssemblyError, Library, LibraryNamespace, LibraryPath, MaslLibrary,
-    Module, ProcedureName, Version,
+    ast::{error_code_from_msg, CodeBody, Instruction, ModuleAst, Node, ProgramAst, StackEffect},
+    Assembler, AssemblyContext, AssemblyError, DataSegment, Deserializable, Felt, Library,
+    LibraryNamespace, LibraryPath, MaslLibrary, Module, ProcedureName, Serializable, Version,
+    WarningKind,
 };
 use alloc::{string::ToString, vec::Vec};
 use core::slice::Iter;
@@ -347,7 +348,11 @@ fn get_proc_name_of_unknown_module() {
         ProcedureName::try_from("bar").ok(),
     );
 
-    assert_eq!(compilation_error, expected_error);
+    assert_eq!(compilation_error.inner(), &expected_error);
+
+    // the error points at the invocation in the module it occurred in
+    let location = compilation_error.source_location().unwrap();
+    assert_eq!("module::path::one:5:9", location.to_string());
 }
 
 // CONSTANTS
@@ -572,7 +577,8 @@ fn constants_defined_in_global_scope() {
     let result = assembler.compile(source);
     assert!(result.is_err());
     let err = result.err().unwrap();
-    let expected_error = "invalid constant declaration: `const.CONSTANT=12` - constants can only be defined below imports and above procedure / program bodies";
+    let expected_error = "invalid constant declaration: `const.CONSTANT=12` - constants can only be defined below imports and above procedure / program bodies\n\
+        constant used in operation `push.CONSTANT` not found";
     assert_eq!(expected_error, err.to_string());
 }
 
@@ -590,6 +596,477 @@ fn constant_not_found() {
     assert_eq!(expected_error, err.to_string());
 }
 
+#[test]
+fn array_constants() {
+    let assembler = Assembler::default();
+    let source = "const.A=2 \
+    const.IV=[1, 0x10, A*3, 18446744069414584320] \
+    const.TABLE=[7,8,9] \
+    const.B=TABLE[A]+IV[1] \
+    begin \
+    push.IV \
+    push.TABLE.5.TABLE \
+    push.TABLE[0] push.TABLE[A] push.B \
+    mem_load.TABLE[1] \
+    repeat.IV[2] add end \
+    end";
+    let expected = "\
+    begin \
+        span \
+            pad incr push(16) push(6) push(18446744069414584320) \
+            push(7) push(8) push(9) push(5) push(7) push(8) push(9) \
+            push(7) push(9) push(25) \
+            push(8) mload \
+            add add add add add add \
+        end \
+    end";
+    let program = assembler.compile(source).unwrap();
+    assert_eq!(expected, format!("{program}"));
+}
+
+#[test]
+fn array_constant_errors() {
+    let assembler = Assembler::default();
+    let compile = |source: &str| assembler.compile(source).unwrap_err().to_string();
+
+    let source = "const.IV=[1,2,3,4] begin mem_load.IV end";
+    let expected_error = "constant 'IV' used in `mem_load.IV` is an array - use `IV[<index>]` \
+        to access its elements";
+    assert_eq!(expected_error, compile(source));
+
+    let source = "const.A=1 begin push.A[0] end";
+    let expected_error = "constant 'A' used in `push.A[0]` is not an array and cannot be indexed";
+    assert_eq!(expected_error, compile(source));
+
+    let source = "const.IV=[1,2,3,4] begin push.IV[4] end";
+    let expected_error = "index 4 used in `push.IV[4]` is out of bounds for constant 'IV' with 4 \
+        elements";
+    assert_eq!(expected_error, compile(source));
+
+    // elements must fit into the type expected by the instruction
+    let source = "const.ADDRS=[1,4294967296] begin mem_load.ADDRS[1] end";
+    let expected_error = "failed to convert u64 constant used in `mem_load.ADDRS[1]` to required \
+        type u32";
+    assert_eq!(expected_error, compile(source));
+
+    let source = "const.IV=[1,2,3,4] const.W=[IV] begin push.W end";
+    let expected_error = "constant 'IV' used in `const.W=[IV]` is an array - use `IV[<index>]` \
+        to access its elements";
+    assert_eq!(expected_error, compile(source));
+
+    let source = "const.IV=[] begin push.IV end";
+    let expected_error = "malformed constant `const.IV=[]` - invalid value: `` - reason: array \
+        constants must contain at least one element";
+    assert_eq!(expected_error, compile(source));
+
+    let source = "const.IV=[1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16] begin push.IV.1 end";
+    let expected_error = "malformed instruction 'push.IV.1', parameter IV is invalid: parameters \
+        expand to 17 values, but at most 16 values can be pushed";
+    assert_eq!(expected_error, compile(source));
+}
+
+#[test]
+fn imported_constants() {
+    let namespace = LibraryNamespace::try_from("dummy".to_string()).unwrap();
+    let path = LibraryPath::try_from("dummy::consts".to_string()).unwrap();
+    let source = "\
+    export.const.MAX=100 \
+    export.const.IV=[1, 2, 3, 4] \
+    export.const.DOUBLE=MAX*2 \
+    const.OFFSET=5 \
+    export.foo push.OFFSET add end";
+    let ast = ModuleAst::parse(source).unwrap();
+    let library = DummyLibrary::new(namespace, vec![Module::new(path, ast)]);
+
+    let assembler = Assembler::default().with_library(&library).unwrap();
+    let source = "\
+    use.dummy::consts \
+    const.A=consts::MAX+1 \
+    begin \
+    push.consts::MAX push.consts::IV[3] push.A \
+    mem_load.consts::DOUBLE \
+    push.consts::IV \
+    end";
+    let expected = "\
+    begin \
+        span \
+            push(100) push(4) push(101) \
+            push(200) mload \
+            pad incr push(2) push(3) push(4) \
+        end \
+    end";
+    let (program, warnings) = assembler.compile_with_warnings(source).unwrap();
+    assert_eq!(expected, format!("{program}"));
+    // the module is used although none of its procedures are invoked
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn imported_constant_errors() {
+    let namespace = LibraryNamespace::try_from("dummy".to_string()).unwrap();
+    let path = LibraryPath::try_from("dummy::consts".to_string()).unwrap();
+    let ast = ModuleAst::parse("export.const.MAX=100 const.MIN=1 export.foo push.MIN end").unwrap();
+    let library = DummyLibrary::new(namespace, vec![Module::new(path, ast)]);
+    let assembler = Assembler::default().with_library(&library).unwrap();
+    let compile = |source: &str| assembler.compile(source).unwrap_err().to_string();
+
+    // constants which are not exported cannot be referenced
+    let source = "use.dummy::consts begin push.consts::MIN end";
+    let expected_error = "constant used in operation `push.consts::MIN` not found";
+    assert_eq!(expected_error, compile(source));
+
+    // constants can be referenced only via the alias of an imported module
+    let source = "begin push.consts::MAX end";
+    let expected_error = "constant used in operation `push.consts::MAX` not found";
+    assert_eq!(expected_error, compile(source));
+
+    // constants cannot be exported from programs
+    let source = "export.const.MAX=100 begin push.MAX end";
+    let expected_error = "invalid constant declaration: `export.const.MAX=100` - constants can be \
+        exported only from modules";
+    assert_eq!(expected_error, compile(source));
+
+    // exported constants must be declared above procedures
+    let source = "export.foo push.1 end export.const.MAX=100";
+    let expected_error = "invalid constant declaration: `export.const.MAX=100` - constants can \
+        only be defined below imports and above procedure / program bodies";
+    let err = ModuleAst::parse(source).unwrap_err();
+    assert_eq!(expected_error, err.message());
+}
+
+#[test]
+fn error_messages() {
+    let namespace = LibraryNamespace::try_from("dummy".to_string()).unwrap();
+    let path = LibraryPath::try_from("dummy::errors".to_string()).unwrap();
+    let source = r#"
+        export.const.ERR_OVERFLOW="value. is = too large"
+        export.foo u32assert.err="not a u32 value" end"#;
+    let ast = ModuleAst::parse(source).unwrap();
+    let library = DummyLibrary::new(namespace, vec![Module::new(path, ast)]);
+    let assembler = Assembler::default().with_library(&library).unwrap();
+
+    let source = r#"
+        use.dummy::errors
+        const.ERR_ZERO="value is zero"
+        const.ERR_CODE=7
+        begin
+            assert.err="invalid value"
+            assertz.err=ERR_ZERO
+            assert_eq.err=errors::ERR_OVERFLOW
+            assert.err=ERR_CODE
+            exec.errors::foo
+        end"#;
+    let program = assembler.compile(source).unwrap();
+
+    // the codes of the messages are derived from the messages
+    let code = error_code_from_msg("invalid value");
+    let zero_code = error_code_from_msg("value is zero");
+    let expected = format!("span assert({code}) eqz assert({zero_code})");
+    assert!(format!("{program}").contains(&expected));
+
+    // messages of the assertions in the imported procedures are attached to the program as well
+    let expected = ["invalid value", "not a u32 value", "value is zero", "value. is = too large"];
+    let mut messages = program.error_messages().values().collect::<Vec<_>>();
+    messages.sort();
+    assert_eq!(expected.to_vec(), messages);
+    assert_eq!(Some("invalid value"), program.get_error_message(code));
+    assert_eq!(None, program.get_error_message(7));
+
+    // messages of the assertions which are not a part of the program are not attached to it
+    let program = assembler.compile("begin push.1 assert.err=\"unused\" end").unwrap();
+    assert_eq!(1, program.error_messages().len());
+}
+
+#[test]
+fn error_message_errors() {
+    let assembler = Assembler::default();
+    let compile = |source: &str| assembler.compile(source).unwrap_err().to_string();
+
+    // unclosed messages extend until the end of the line
+    let source = "begin assert.err=\"invalid value\nend";
+    let expected_error = "malformed instruction 'assert.err=\"invalid value', parameter \
+        err=\"invalid value is invalid: error messages must be enclosed in double quotes";
+    assert_eq!(expected_error, compile(source));
+
+    let source = r#"begin assert.err="" end"#;
+    let expected_error = "malformed instruction 'assert.err=\"\"', parameter err=\"\" is \
+        invalid: error messages cannot be empty";
+    assert_eq!(expected_error, compile(source));
+
+    // error messages cannot be used as values
+    let source = r#"const.ERR="invalid value" begin push.ERR end"#;
+    let expected_error = "constant 'ERR' used in `push.ERR` is an error message and can be used \
+        only as an error code of an assertion";
+    assert_eq!(expected_error, compile(source));
+
+    // the codes of these messages are the same
+    let code = error_code_from_msg("error 70238");
+    assert_eq!(code, error_code_from_msg("error 84845"));
+
+    // codes of different messages, or of a message and an explicit code, must not collide
+    let source = r#"begin assert.err="error 70238" assert.err="error 84845" end"#;
+    let expected_error = format!(
+        "error code {code} of `assert.err=\"error 84845\"` collides with the error code of \
+        message \"error 70238\""
+    );
+    assert_eq!(expected_error, compile(source));
+
+    let source = format!(r#"const.ERR={code} begin assert.err=ERR assertz.err="error 70238" end"#);
+    let expected_error = format!(
+        "error code {code} of `assertz.err=\"error 70238\"` collides with the error code of an \
+        assertion without an error message"
+    );
+    assert_eq!(expected_error, compile(&source));
+
+    // collisions are detected across modules as well
+    let namespace = LibraryNamespace::try_from("dummy".to_string()).unwrap();
+    let path = LibraryPath::try_from("dummy::errors".to_string()).unwrap();
+    let source = r#"export.foo assert.err="error 70238" end"#;
+    let ast = ModuleAst::parse(source).unwrap();
+    let library = DummyLibrary::new(namespace, vec![Module::new(path, ast)]);
+    let assembler = Assembler::default().with_library(&library).unwrap();
+    let compile = |source: &str| assembler.compile(source).unwrap_err().to_string();
+
+    let source = r#"use.dummy::errors begin assert.err="error 84845" exec.errors::foo end"#;
+    let expected_error = format!(
+        "error code {code} of message \"error 70238\" collides with the error code of message \
+        \"error 84845\""
+    );
+    assert_eq!(expected_error, compile(source));
+
+    let source = format!("use.dummy::errors begin assert.err={code} exec.errors::foo end");
+    let expected_error = format!(
+        "error code {code} of message \"error 70238\" collides with the error code of an \
+        assertion without an error message"
+    );
+    assert_eq!(expected_error, compile(&source));
+}
+
+#[test]
+fn conditional_compilation() {
+    let source = "\
+        #if DEBUG
+        proc.check
+            dup assert
+        end
+        #end
+        begin
+            push.1
+            #if DEBUG
+                exec.check
+            #else
+                push.2 drop
+            #end
+        end";
+
+    let program = Assembler::default().with_features(["DEBUG"]).unwrap().compile(source).unwrap();
+    assert_eq!("begin span pad incr dup0 assert(0) end end", format!("{program}"));
+
+    let release = Assembler::default().compile(source).unwrap();
+    assert_eq!("begin span pad incr push(2) drop end end", format!("{release}"));
+    assert_ne!(program.hash(), release.hash());
+
+    // features of the assembler apply to kernels as well
+    let kernel = "export.foo\n#if DEBUG\npush.1 drop\n#end\nadd end";
+    let assembler = Assembler::default().with_features(["DEBUG"]).unwrap();
+    let assembler = assembler.with_kernel(kernel).unwrap();
+    let program = assembler.compile("begin syscall.foo end").unwrap();
+    let expected = Assembler::default().with_kernel("export.foo push.1 drop add end").unwrap();
+    assert_eq!(program.kernel(), expected.kernel());
+
+    let err = Assembler::default().compile("begin\n#if DEBUG\nnop end").unwrap_err();
+    assert_eq!("directive `#if` has no matching `#end`", err.to_string());
+}
+
+#[test]
+fn conditional_compilation_features_after_kernel() {
+    // the kernel has already been compiled without the features
+    let err = Assembler::default()
+        .with_kernel("export.foo add end")
+        .unwrap()
+        .with_features(["DEBUG"])
+        .err()
+        .unwrap();
+    assert_eq!(AssemblyError::procedures_already_compiled(), err);
+
+    // compiling programs does not cache any procedures
+    let assembler = Assembler::default();
+    assembler.compile("begin push.1 end").unwrap();
+    assert!(assembler.with_features(["DEBUG"]).is_ok());
+}
+
+#[test]
+fn for_loops() {
+    let assembler = Assembler::default();
+
+    // loops with few iterations are unrolled
+    let source = "begin push.5 for.3 dup.1 add swap end end";
+    let program = assembler.compile(source).unwrap();
+    let expected = "\
+        begin \
+            span \
+                push(5) pad dup1 add swap drop pad incr dup1 add swap drop push(2) dup1 add swap \
+                drop \
+            end \
+        end";
+    assert_eq!(expected, format!("{program}"));
+
+    // other loops are compiled into LOOP blocks which keep track of the index
+    let source = "begin push.5 for.10 dup.1 add swap end end";
+    let program = assembler.compile(source).unwrap();
+    let expected = "\
+        begin \
+            join \
+                join \
+                    span push(5) pad pad incr end \
+                    while.true \
+                        span dup1 add swap incr dup0 push(10) eq not end \
+                    end \
+                end \
+                span drop end \
+            end \
+        end";
+    assert_eq!(expected, format!("{program}"));
+
+    // the bodies of loops must not change the depth of the stack, so that the index stays at the
+    // top of the stack regardless of whether the loop is unrolled
+    let compile = |source: &str| assembler.compile(source).unwrap_err().to_string();
+    let expected_error =
+        "body of a for loop must not change the depth of the stack, but has stack effect 0 -> 1";
+    assert_eq!(expected_error, compile("begin for.10 push.1 end end"));
+    let expected_error =
+        "body of a for loop must not change the depth of the stack, but has stack effect 1 -> 0";
+    assert_eq!(expected_error, compile("proc.foo for.2 drop end end begin exec.foo end"));
+    let expected_error =
+        "body of a for loop must not change the depth of the stack, but its stack \
+        effect is unknown";
+    assert_eq!(expected_error, compile("begin for.2 push.0 while.true push.0 end end end"));
+
+    // loops without iterations are rejected even if they were not parsed from source
+    let body = CodeBody::new(vec![Node::Instruction(Instruction::Incr)]);
+    let program = ProgramAst::new(vec![Node::For { times: 0, body }], Vec::new()).unwrap();
+    let expected_error = "parameter value must be greater than or equal to 1 and less than or \
+        equal to 4294967295, but was 0";
+    assert_eq!(expected_error, assembler.compile_ast(&program).unwrap_err().to_string());
+}
+
+#[test]
+fn external_constants() {
+    let assembler = Assembler::default()
+        .with_constant("TREE_DEPTH", Felt::new(16))
+        .and_then(|assembler| assembler.with_constants([("A", Felt::new(3)), ("B", Felt::new(5))]))
+        .unwrap();
+
+    let source = "\
+        const.DOUBLE_DEPTH=TREE_DEPTH*2
+        begin
+            push.TREE_DEPTH push.DOUBLE_DEPTH mem_load.A push.B
+        end";
+    let program = assembler.compile(source).unwrap();
+    assert_eq!(
+        "begin span push(16) push(32) push(3) mload push(5) end end",
+        format!("{program}")
+    );
+
+    // external constants cannot be redeclared
+    let source = "const.A=4 begin push.A end";
+    let err = assembler.compile(source).unwrap_err();
+    assert_eq!(
+        "constant 'A' is already defined externally - it cannot be redeclared",
+        err.to_string()
+    );
+
+    // without the external constants, the references are not resolved
+    let err = Assembler::default().compile("begin push.TREE_DEPTH end").unwrap_err();
+    assert!(err.to_string().contains("TREE_DEPTH"));
+
+    // external constants cannot be defined after the assembler compiled procedures
+    let err = Assembler::default()
+        .with_kernel("export.foo add end")
+        .unwrap()
+        .with_constant("TREE_DEPTH", Felt::new(16))
+        .err()
+        .unwrap();
+    assert_eq!(AssemblyError::procedures_already_compiled(), err);
+}
+
+#[test]
+#[should_panic(expected = "invalid constant name")]
+fn external_constants_invalid_name() {
+    let _ = Assembler::default().with_constant("tree_depth", Felt::new(16));
+}
+
+#[test]
+fn data_segments() {
+    let assembler = Assembler::default();
+    let source = "\
+        const.ADDR=8 \
+        const.IV=[3, 4] \
+        data.ADDR=[1, 2, IV, IV[0]+2] \
+        data.ADDR+2=[0x10] \
+        begin push.1 end";
+    let program = assembler.compile(source).unwrap();
+
+    // the segments are attached to the program
+    let expected = vec![
+        DataSegment::new(8, [1, 2, 3, 4, 5].map(Felt::new).to_vec()),
+        DataSegment::new(10, vec![Felt::new(16)]),
+    ];
+    assert_eq!(expected, program.data_segments());
+
+    // the segments are loaded by a SPAN block executed before the program body
+    let expected = "begin join span push(";
+    assert!(format!("{program}").starts_with(expected));
+    assert!(format!("{program}").ends_with("span pad incr end end end"));
+
+    // programs without data segments are not affected
+    let program = assembler.compile("begin push.1 end").unwrap();
+    assert_eq!("begin span pad incr end end", format!("{program}"));
+    assert!(program.data_segments().is_empty());
+}
+
+#[test]
+fn data_segment_errors() {
+    let assembler = Assembler::default();
+    let compile = |source: &str| assembler.compile(source).unwrap_err().to_string();
+
+    let source = "data.8=[] begin push.1 end";
+    let expected_error = "malformed data segment `data.8=[]` - reason: data segments must \
+        contain at least one value";
+    assert_eq!(expected_error, compile(source));
+
+    let source = "data.8=1 begin push.1 end";
+    let expected_error = "malformed data segment `data.8=1` - reason: values must be enclosed in \
+        square brackets";
+    assert_eq!(expected_error, compile(source));
+
+    let source = "data.4294967296=[1] begin push.1 end";
+    let expected_error = "malformed data segment `data.4294967296=[1]` - reason: address must be \
+        a valid memory address";
+    assert_eq!(expected_error, compile(source));
+
+    let source = "data.4294967295=[1] begin push.1 end";
+    let expected_error = "malformed data segment `data.4294967295=[1]` - reason: data segment \
+        does not fit into memory";
+    assert_eq!(expected_error, compile(source));
+
+    // segments are padded to an even number of words, so the first segment occupies 8 and 9
+    let source = "data.8=[1, 2] data.9=[3] begin push.1 end";
+    let expected_error = "data segment `data.9=[3]` overlaps with the data segment at address 8";
+    assert_eq!(expected_error, compile(source));
+
+    let source = "proc.foo data.8=[1] end begin push.1 end";
+    let expected_error = "invalid data segment declaration: `data.8=[1]` - data segments can only \
+        be defined in programs below constants and above procedures";
+    assert_eq!(expected_error, compile(source));
+
+    let source = "proc.foo push.1 end data.8=[1] begin exec.foo end";
+    assert_eq!(expected_error, compile(source));
+
+    let err = ModuleAst::parse("data.8=[1] export.foo push.1 end").unwrap_err();
+    assert_eq!(expected_error, err.message());
+}
+
 #[test]
 fn mem_operations_with_constants() {
     let assembler = Assembler::default();
@@ -1016,6 +1493,80 @@ fn program_with_proc_locals() {
     assert_eq!(expected, format!("{program}"));
 }
 
+#[test]
+fn program_with_named_proc_locals() {
+    let assembler = Assembler::default();
+
+    // named locals are allocated after the locals declared in the procedure header
+    let source = "\
+        const.IDX=1
+        proc.foo.1
+            local.acc: word
+            local.buf: [word; 3]
+            loc_store.0 loc_storew.acc loc_loadw.buf locaddr.buf[2] loc_store.buf[IDX]
+        end
+        begin
+            exec.foo
+        end";
+    let expected = "\
+        proc.foo.5
+            loc_store.0 loc_storew.1 loc_loadw.2 locaddr.4 loc_store.3
+        end
+        begin
+            exec.foo
+        end";
+    let program = assembler.compile(source).unwrap();
+    assert_eq!(assembler.compile(expected).unwrap().hash(), program.hash());
+}
+
+#[test]
+fn named_proc_local_errors() {
+    let assembler = Assembler::default();
+    let compile = |source: &str| assembler.compile(source).unwrap_err().to_string();
+
+    let source = "proc.foo local.acc: word loc_load.buf end begin push.1 end";
+    let expected_error = "local 'buf' used in `loc_load.buf` is not declared in the procedure";
+    assert_eq!(expected_error, compile(source));
+
+    let source = "proc.foo local.buf: [word; 2] locaddr.buf[2] end begin push.1 end";
+    let expected_error = "index 2 used in `locaddr.buf[2]` is out of bounds for local 'buf' \
+        with 2 elements";
+    assert_eq!(expected_error, compile(source));
+
+    let source = "proc.foo local.acc: word local.acc: felt end begin push.1 end";
+    assert_eq!("duplicate local name: acc", compile(source));
+
+    let source = "proc.foo local.acc: word push.1 local.buf: word end begin push.1 end";
+    let expected_error = "local declarations must be placed at the start of a procedure body";
+    assert_eq!(expected_error, compile(source));
+
+    let source = "proc.foo local.Acc: word end begin push.1 end";
+    assert_eq!("invalid local declaration: invalid local name `Acc`", compile(source));
+
+    let source = "proc.foo local.buf: [word; 0] end begin push.1 end";
+    assert_eq!("invalid local declaration: invalid array length `0`", compile(source));
+
+    let source = "proc.foo local.buf: [hash; 2] end begin push.1 end";
+    assert_eq!("invalid local declaration: unknown local type `hash`", compile(source));
+}
+
+#[test]
+fn program_with_repeated_procedure_is_deduplicated() {
+    let assembler = Assembler::default();
+    let source = "\
+        proc.foo if.true push.1 else push.2 end end \
+        begin exec.foo push.3 exec.foo exec.foo end";
+    let program = assembler.compile(source).unwrap();
+    let foo = "if.true span pad incr end else span push(2) end end";
+    let expected =
+        format!("begin join join {foo} span push(3) end end join {foo} {foo} end end end");
+    assert_eq!(expected, format!("{program}"));
+
+    // the body of `foo` is stored in the program MAST only once: 3 nodes for `foo`, 1 node for
+    // the span between the calls, and 3 join nodes
+    assert_eq!(7, program.forest().num_nodes());
+}
+
 #[test]
 fn program_with_exported_procedure() {
     let assembler = Assembler::default();
@@ -1620,7 +2171,11 @@ fn invalid_proc() {
     let program = assembler.compile(source);
     assert!(program.is_err());
     if let Err(error) = program {
-        assert_eq!(error.to_string(), "invalid procedure name: '123' does not start with a letter");
+        assert_eq!(
+            error.to_string(),
+            "invalid procedure name: '123' does not start with a letter\n\
+            invalid procedure invocation: 123"
+        );
     }
 
     let source = "proc.foo add mul end proc.foo push.3 end begin push.1 end";
@@ -1662,7 +2217,10 @@ fn invalid_if_else() {
     let program = assembler.compile(source);
     assert!(program.is_err());
     if let Err(error) = program {
-        assert_eq!(error.to_string(), "else without matching if");
+        assert_eq!(
+            error.to_string(),
+            "else without matching if\ndangling instructions after program end"
+        );
     }
 
     let source = "begin push.1 add if.true mul else add";
@@ -1729,6 +2287,217 @@ fn invalid_while() {
     }
 }
 
+#[test]
+fn errors_are_rendered_with_source_snippets() {
+    let assembler = Assembler::default();
+
+    // compilation error
+    let source = "\
+proc.foo
+    push.1
+end
+begin
+    exec.foo
+    dup caller
+end";
+    let error = assembler.compile(source).unwrap_err();
+    assert_eq!("#exec:6:9", error.source_location().unwrap().to_string());
+    let expected = "\
+error: caller instruction used outside of kernel
+ --> #exec:6:9
+  |
+6 |     dup caller
+  |         ^^^^^^
+  |
+  = help: `caller` instruction can be used only in kernel procedures
+";
+    assert_eq!(expected, error.render(source));
+
+    // parsing error
+    let source = "begin\n    push.1\n    while mul end\nend";
+    let error = assembler.compile(source).unwrap_err();
+    let expected = "\
+error: malformed instruction 'while': expected format `while.true`
+ --> #exec:3:5
+  |
+3 |     while mul end
+  |     ^^^^^
+";
+    assert_eq!(expected, error.render(source));
+}
+
+#[test]
+fn errors_are_reported_for_all_procedures() {
+    let assembler = Assembler::default();
+
+    // syntax errors in different procedures are reported together
+    let source = "\
+proc.foo
+    push.1 add.x
+end
+proc.bar
+    while mul end
+end
+begin
+    exec.foo exec.bar
+end";
+    let error = assembler.compile(source).unwrap_err();
+    let locations = error
+        .errors()
+        .iter()
+        .map(|err| err.source_location().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["#exec:2:12", "#exec:5:5"], locations);
+
+    // semantic errors in different procedures and in the program body are reported together
+    let source = "\
+use.std::missing
+proc.foo
+    caller
+end
+proc.bar
+    exec.foo
+    exec.missing::baz
+end
+begin
+    exec.bar
+    caller
+end";
+    let error = assembler.compile(source).unwrap_err();
+    let errors = error.errors();
+    let locations = errors
+        .iter()
+        .map(|err| err.source_location().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["#exec:3:5", "#exec:7:5", "#exec:11:5"], locations);
+    assert_eq!(&AssemblyError::caller_out_of_kernel(), errors[0].inner());
+    assert!(matches!(errors[1].inner(), AssemblyError::ImportedProcModuleNotFound(..)));
+    assert_eq!(&AssemblyError::caller_out_of_kernel(), errors[2].inner());
+
+    // all errors are rendered
+    let rendered = error.render(source);
+    assert_eq!(3, rendered.matches("error: ").count());
+    assert!(rendered.contains("--> #exec:7:5"));
+}
+
+#[test]
+fn procedure_signatures_are_checked() {
+    let assembler = Assembler::default();
+
+    // procedures which match their signatures compile, and procedures whose stack effect cannot
+    // be determined statically are not checked
+    let source = "\
+proc.foo(a: felt, b: felt) -> (c: felt)
+    add
+end
+proc.bar(a: felt, b: word) -> (c: felt)
+    drop drop drop drop
+end
+proc.baz(a: felt) -> (b: u64)
+    while.true push.1 end
+end
+begin
+    exec.foo exec.bar exec.baz
+end";
+    assembler.compile(source).unwrap();
+
+    // a procedure which does not match its signature is reported at its declaration
+    let source = "\
+proc.foo(a: u64) -> (b: felt)
+    drop drop
+end
+begin
+    exec.foo
+end";
+    let error = assembler.compile(source).unwrap_err();
+    assert_eq!("#exec:1:1", error.source_location().unwrap().to_string());
+    assert_eq!(
+        &AssemblyError::proc_signature_mismatch(
+            "foo",
+            StackEffect::new(2, 1),
+            StackEffect::new(2, 0)
+        ),
+        error.inner()
+    );
+    assert_eq!(
+        "procedure 'foo' declares stack effect 2 -> 1 in its signature, but its body has stack effect 2 -> 0",
+        error.inner().to_string()
+    );
+}
+
+#[test]
+fn warnings_are_reported_next_to_program() {
+    let assembler = Assembler::default();
+    let source = "\
+const.A=1
+begin
+    push.0 assert
+    add
+end";
+    let (program, warnings) = assembler.compile_with_warnings(source).unwrap();
+    assert_eq!(assembler.compile(source).unwrap().hash(), program.hash());
+    assert_eq!(2, warnings.len());
+    assert_eq!(&WarningKind::UnusedConstant("A".into()), warnings[0].kind());
+
+    let expected = "\
+warning: unreachable code
+ --> 4:5
+  |
+4 |     add
+  |     ^^^
+  |
+  = help: the preceding assertion always fails; remove the code
+";
+    assert_eq!(expected, warnings[1].render(source));
+}
+
+#[test]
+fn warnings_are_reported_for_parsed_asts() {
+    // warnings are attached to the program AST when it is parsed
+    let assembler = Assembler::default();
+    let source = "\
+const.A=1
+begin
+    push.0 assert
+    add
+end";
+    let (_, warnings) = assembler.compile_with_warnings(source).unwrap();
+    let program = ProgramAst::parse(source).unwrap();
+    assert_eq!(warnings, program.warnings());
+    let (_, ast_warnings) = assembler.compile_ast_with_warnings(&program).unwrap();
+    assert_eq!(warnings, ast_warnings);
+
+    // warnings about the modules of a library are reported together with the module paths
+    let source = "\
+proc.foo
+    add
+end
+export.bar
+    mul
+end";
+    let module = ModuleAst::parse(source).unwrap();
+    assert_eq!(1, module.warnings().len());
+    let path = LibraryPath::new("lib::math").unwrap();
+    let library = MaslLibrary::new(
+        LibraryNamespace::new("lib").unwrap(),
+        Version::MIN,
+        false,
+        vec![Module::new(path.clone(), module)],
+        vec![],
+    )
+    .unwrap();
+    let warnings = library.warnings().collect::<Vec<_>>();
+    assert_eq!(1, warnings.len());
+    assert_eq!(&path, warnings[0].0);
+    assert!(
+        matches!(warnings[0].1.kind(), WarningKind::UnusedProcedure(name) if name.as_ref() == "foo")
+    );
+
+    // warnings are not serialized with the library
+    let library = MaslLibrary::read_from_bytes(&library.to_bytes()).unwrap();
+    assert_eq!(0, library.warnings().count());
+}
+
 // DUMMY LIBRARY
 // ================================================================================================
 
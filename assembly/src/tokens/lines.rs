@@ -1,17 +1,26 @@
-use super::{SourceLocation, Token};
-use alloc::vec::Vec;
+use super::{ParsingError, SourceLocation, Token};
+use alloc::{collections::BTreeSet, string::String, vec::Vec};
 use core::{iter, str::Lines};
 
 // LINES STREAM
 // ================================================================================================
 
 /// A [LineInfo] iterator that will bind lines with tokens with doc comments.
+///
+/// The stream evaluates conditional compilation directives: lines between `#if <FEATURE>` and the
+/// matching `#else` or `#end` are skipped unless the feature is enabled, and lines between `#else`
+/// and `#end` are skipped if it is. The directives themselves are treated as empty lines, so that
+/// line numbers of the remaining lines are preserved. If a directive is malformed or unmatched,
+/// the stream stops, and the error can be retrieved via [LinesStream::take_error()].
 #[derive(Debug, Clone)]
 pub struct LinesStream<'a> {
     lines: Lines<'a>,
     current_line: Option<&'a str>,
     current_line_num: u32,
     line_char_offset: u32,
+    features: BTreeSet<String>,
+    conditionals: Vec<Conditional>,
+    error: Option<ParsingError>,
 }
 
 impl<'a> From<&'a str> for LinesStream<'a> {
@@ -21,11 +30,31 @@ impl<'a> From<&'a str> for LinesStream<'a> {
             current_line: None,
             current_line_num: 0,
             line_char_offset: 0,
+            features: BTreeSet::new(),
+            conditionals: Vec::new(),
+            error: None,
         }
     }
 }
 
 impl<'a> LinesStream<'a> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Enables the specified features for the evaluation of conditional compilation directives.
+    pub fn with_features(mut self, features: &BTreeSet<String>) -> Self {
+        self.features = features.clone();
+        self
+    }
+
+    // STATE MUTATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Takes the error which stopped this stream, if any.
+    pub fn take_error(&mut self) -> Option<ParsingError> {
+        self.error.take()
+    }
+
     // HELPERS
     // --------------------------------------------------------------------------------------------
 
@@ -47,16 +76,87 @@ impl<'a> LinesStream<'a> {
     }
 
     /// Move the pointer to the next line, updating the control variables
+    ///
+    /// Directives and lines excluded by them are replaced with empty lines.
     fn go_to_next_line(&mut self) {
+        if self.error.is_some() {
+            self.current_line = None;
+            return;
+        }
+
         self.current_line = self.lines.next();
-        if let Some(line) = self.current_line {
-            let init_len = line.len();
-            let trimmed = line.trim_start();
+        match self.current_line {
+            Some(line) => {
+                let init_len = line.len();
+                let trimmed = line.trim_start();
+
+                self.current_line.replace(trimmed);
+                self.line_char_offset = (init_len - trimmed.len()) as u32;
+                self.current_line_num += 1;
+
+                let location =
+                    SourceLocation::new(self.current_line_num, self.line_char_offset + 1);
+                match self.apply_directive(trimmed.trim_end(), location) {
+                    Ok(true) => self.current_line = Some(""),
+                    Ok(false) if !self.is_active() => self.current_line = Some(""),
+                    Ok(false) => (),
+                    Err(err) => {
+                        self.error = Some(err);
+                        self.current_line = None;
+                    }
+                }
+            }
+            None => {
+                if let Some(conditional) = self.conditionals.pop() {
+                    self.error = Some(ParsingError::unclosed_directive(conditional.location));
+                }
+            }
+        }
+    }
 
-            self.current_line.replace(trimmed);
-            self.line_char_offset = (init_len - trimmed.len()) as u32;
-            self.current_line_num += 1;
+    /// Updates the state of conditional compilation if the specified line is a directive.
+    ///
+    /// A line is a directive only if it consists solely of `#if <FEATURE>`, `#else`, or `#end`;
+    /// other lines starting with `#` (e.g., `#end of loop` or `#if x is zero`) are comments. A
+    /// bare `#if` without a feature is a malformed directive.
+    ///
+    /// Returns true if the line is a directive, and false otherwise.
+    fn apply_directive(
+        &mut self,
+        line: &str,
+        location: SourceLocation,
+    ) -> Result<bool, ParsingError> {
+        let mut parts = line.split_whitespace();
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(Token::IF_DIRECTIVE), Some(feature), None) => {
+                self.conditionals.push(Conditional {
+                    location,
+                    is_enabled: self.features.contains(feature),
+                    in_else: false,
+                });
+            }
+            (Some(Token::IF_DIRECTIVE), None, _) => {
+                return Err(ParsingError::malformed_directive(location, line));
+            }
+            (Some(Token::ELSE_DIRECTIVE), None, _) => match self.conditionals.last_mut() {
+                Some(conditional) if !conditional.in_else => conditional.in_else = true,
+                _ => return Err(ParsingError::unmatched_directive(location, line)),
+            },
+            (Some(Token::END_DIRECTIVE), None, _) => {
+                if self.conditionals.pop().is_none() {
+                    return Err(ParsingError::unmatched_directive(location, line));
+                }
+            }
+            _ => return Ok(false),
         }
+        Ok(true)
+    }
+
+    /// Returns true if the lines at the current position are not excluded by any directive.
+    fn is_active(&self) -> bool {
+        self.conditionals
+            .iter()
+            .all(|conditional| conditional.is_enabled != conditional.in_else)
     }
 
     /// If the current line is a doc comment, take lines until EOF or not doc comment.
@@ -123,6 +223,17 @@ impl<'a> Iterator for LinesStream<'a> {
     }
 }
 
+// CONDITIONAL COMPILATION
+// ================================================================================================
+
+/// An `#if` directive which has not been closed yet.
+#[derive(Debug, Clone)]
+struct Conditional {
+    location: SourceLocation,
+    is_enabled: bool,
+    in_else: bool,
+}
+
 // LINE INFO
 // ================================================================================================
 
@@ -243,6 +354,7 @@ impl<'a> LineInfo<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::string::ToString;
 
     // UNIT TESTS
     // ============================================================================================
@@ -508,6 +620,79 @@ end
         assert_eq!(None, lines.next());
     }
 
+    #[test]
+    fn token_lines_conditional_directives() {
+        let source = "begin
+#if DEBUG
+    debug.stack
+    #if TRACE
+        trace.1
+    #else
+        push.1 drop
+    #end
+#else
+    nop
+#end
+end";
+        let features = BTreeSet::from(["DEBUG".to_string()]);
+        let mut lines = LinesStream::from(source).with_features(&features);
+        assert_eq!(t(1, 0, "begin"), lines.next());
+        assert_eq!(t(3, 4, "debug.stack"), lines.next());
+        assert_eq!(t(7, 8, "push.1 drop"), lines.next());
+        assert_eq!(t(12, 0, "end"), lines.next());
+        assert_eq!(None, lines.next());
+        assert_eq!(None, lines.take_error());
+
+        // without features, only the `#else` branches are taken
+        let mut lines = LinesStream::from(source);
+        assert_eq!(t(1, 0, "begin"), lines.next());
+        assert_eq!(t(10, 4, "nop"), lines.next());
+        assert_eq!(t(12, 0, "end"), lines.next());
+        assert_eq!(None, lines.next());
+        assert_eq!(None, lines.take_error());
+
+        // comments which merely start with a directive name are not directives
+        let mut lines = LinesStream::from("#iffy comment\n#endless comment\nbegin nop end");
+        assert_eq!(t(3, 0, "begin nop end"), lines.next());
+        assert_eq!(None, lines.take_error());
+
+        // neither are comments which start with a directive followed by other words
+        let source = "#if x is zero\n#else x\n#end of proc\nbegin nop end";
+        let mut lines = LinesStream::from(source);
+        assert_eq!(t(4, 0, "begin nop end"), lines.next());
+        assert_eq!(None, lines.next());
+        assert_eq!(None, lines.take_error());
+    }
+
+    #[test]
+    fn token_lines_malformed_directives() {
+        let error = |source: &str| {
+            let mut lines = LinesStream::from(source);
+            lines.by_ref().for_each(drop);
+            lines.take_error().map(|err| (err.message().clone(), *err.location()))
+        };
+
+        let expected = "malformed directive `#if` - expected `#if <FEATURE>`, `#else`, or `#end`";
+        assert_eq!(
+            Some((expected.to_string(), SourceLocation::new(2, 1))),
+            error("nop\n#if\n#end")
+        );
+
+        let expected = "malformed directive `#if` - expected `#if <FEATURE>`, `#else`, or `#end`";
+        assert_eq!(Some((expected.to_string(), SourceLocation::new(1, 3))), error("  #if"));
+
+        let expected = "directive `#end` has no matching `#if`";
+        assert_eq!(Some((expected.to_string(), SourceLocation::new(2, 1))), error("nop\n#end"));
+
+        let expected = "directive `#else` has no matching `#if`";
+        let source = "#if A\n#else\n#else\n#end";
+        assert_eq!(Some((expected.to_string(), SourceLocation::new(3, 1))), error(source));
+
+        let expected = "directive `#if` has no matching `#end`";
+        let source = "#if A\n#if B\n#end\nnop";
+        assert_eq!(Some((expected.to_string(), SourceLocation::new(1, 1))), error(source));
+    }
+
     // TESTS HELPERS
     // ============================================================================================
 
@@ -35,6 +35,11 @@ impl SourceLocation {
         self.line
     }
 
+    /// Returns the column of the location.
+    pub const fn column(&self) -> u32 {
+        self.column
+    }
+
     // STATE MUTATORS
     // -------------------------------------------------------------------------------------------------
 
@@ -1,10 +1,12 @@
 use super::{
-    ast::{parse_param_with_constant_lookup, InvocationTarget},
+    ast::{
+        parse_param_with_constant_lookup, InvocationTarget, LocalConstMap, ParamType,
+        ProcedureSignature, SignatureParam, MAX_SIGNATURE_PARAMS,
+    },
     ByteReader, ByteWriter, Deserializable, DeserializationError, LibraryPath, ParsingError,
     ProcedureName, Serializable,
 };
 use alloc::{
-    collections::BTreeMap,
     string::{String, ToString},
     vec::Vec,
 };
@@ -30,7 +32,8 @@ pub use tokenizer::LineTokenizer;
 /// which updates the token position and splits the token into its composing parts.
 #[derive(Clone, Debug, Default)]
 pub struct Token<'a> {
-    /// The dot-separated parts of a token, e.g. `push.1` is split into `['push', '1']`.
+    /// The dot-separated parts of a token, e.g. `push.1` is split into `['push', '1']`; dots
+    /// within quoted strings do not separate parts.
     parts: Vec<&'a str>,
     /// Source location linked to this token.
     location: SourceLocation,
@@ -41,8 +44,10 @@ impl<'a> Token<'a> {
     // --------------------------------------------------------------------------------------------
     pub const BEGIN: &'static str = "begin";
     pub const CONST: &'static str = "const";
+    pub const DATA: &'static str = "data";
     pub const END: &'static str = "end";
     pub const EXPORT: &'static str = "export";
+    pub const LOCAL: &'static str = "local";
     pub const PROC: &'static str = "proc";
     pub const USE: &'static str = "use";
 
@@ -51,6 +56,7 @@ impl<'a> Token<'a> {
     pub const CALL: &'static str = "call";
     pub const ELSE: &'static str = "else";
     pub const EXEC: &'static str = "exec";
+    pub const FOR: &'static str = "for";
     pub const IF: &'static str = "if";
     pub const REPEAT: &'static str = "repeat";
     pub const SYSCALL: &'static str = "syscall";
@@ -61,6 +67,13 @@ impl<'a> Token<'a> {
     pub const DOC_COMMENT_PREFIX: &'static str = "#!";
     pub const COMMENT_PREFIX: char = '#';
     pub const ALIAS_DELIM: &'static str = "->";
+    pub const STRING_DELIM: char = '"';
+
+    // CONDITIONAL COMPILATION DIRECTIVES
+    // --------------------------------------------------------------------------------------------
+    pub const IF_DIRECTIVE: &'static str = "#if";
+    pub const ELSE_DIRECTIVE: &'static str = "#else";
+    pub const END_DIRECTIVE: &'static str = "#end";
 
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
@@ -71,7 +84,7 @@ impl<'a> Token<'a> {
     pub fn new(token: &'a str, location: SourceLocation) -> Self {
         assert!(!token.is_empty(), "token cannot be an empty string");
         Self {
-            parts: token.split('.').collect(),
+            parts: split_parts(token).collect(),
             location,
         }
     }
@@ -111,7 +124,7 @@ impl<'a> Token<'a> {
     pub fn update(&mut self, token: &'a str, location: SourceLocation) {
         assert!(!token.is_empty(), "token cannot be an empty string");
         self.parts.clear();
-        token.split('.').for_each(|part| self.parts.push(part));
+        split_parts(token).for_each(|part| self.parts.push(part));
         self.location = location;
     }
 
@@ -148,28 +161,64 @@ impl<'a> Token<'a> {
         }
     }
 
-    pub fn parse_proc(&self) -> Result<(ProcedureName, u16, bool), ParsingError> {
+    pub fn parse_proc(
+        &self,
+    ) -> Result<(ProcedureName, u16, bool, Option<ProcedureSignature>), ParsingError> {
         assert!(
             self.parts[0] == Self::PROC || self.parts[0] == Self::EXPORT,
             "invalid procedure declaration"
         );
         let is_export = self.parts[0] == Self::EXPORT;
+        if self.num_parts() == 1 {
+            return Err(ParsingError::missing_param(self, "[proc|export].<procedure_name>"));
+        }
+
+        // the signature, if present, is attached to the last part of the declaration
+        let last_part = self.parts[self.num_parts() - 1];
+        let (last_part, signature) = match last_part.find('(') {
+            Some(pos) => {
+                let (last_part, signature) = last_part.split_at(pos);
+                (last_part, Some(parse_proc_signature(signature, self)?))
+            }
+            None => (last_part, None),
+        };
+
         let (name_str, num_locals) = match self.num_parts() {
-            0 => unreachable!(),
-            1 => return Err(ParsingError::missing_param(self, "[proc|export].<procedure_name>")),
-            2 => (self.parts[1], 0),
+            2 => (last_part, 0),
             3 => {
-                let num_locals = validate_proc_locals(self.parts[2], self)?;
+                let num_locals = validate_proc_locals(last_part, self)?;
                 (self.parts[1], num_locals)
             }
             _ => return Err(ParsingError::extra_param(self)),
         };
 
         ProcedureName::try_from(name_str.to_string())
-            .map(|proc_name| (proc_name, num_locals, is_export))
+            .map(|proc_name| (proc_name, num_locals, is_export, signature))
             .map_err(|err| ParsingError::invalid_proc_name(self, err))
     }
 
+    /// Parses a declaration of a named procedure local (e.g., `local.acc: word` or
+    /// `local.buf: [word; 4]`), and returns the name of the local together with the number of
+    /// locals it occupies.
+    pub fn parse_local(&self) -> Result<(String, u16), ParsingError> {
+        assert_eq!(Self::LOCAL, self.parts[0], "not a local declaration");
+        match self.num_parts() {
+            0 => unreachable!(),
+            1 => Err(ParsingError::missing_param(self, "local.<name>: <type>")),
+            2 => {
+                let (name, ty) = self.parts[1].split_once(':').ok_or_else(|| {
+                    let reason = "expected a declaration in the form `local.<name>: <type>`";
+                    ParsingError::invalid_local_decl(self, reason)
+                })?;
+                let name = name.trim();
+                validate_local_name(name, self)?;
+                let size = parse_local_size(ty.trim(), self)?;
+                Ok((name.to_string(), size))
+            }
+            _ => Err(ParsingError::extra_param(self)),
+        }
+    }
+
     pub fn parse_reexported_proc(
         &self,
     ) -> Result<(ProcedureName, ProcedureName, &str), ParsingError> {
@@ -244,7 +293,7 @@ impl<'a> Token<'a> {
         }
     }
 
-    pub fn parse_repeat(&self, constants: &BTreeMap<String, u64>) -> Result<u32, ParsingError> {
+    pub(crate) fn parse_repeat(&self, constants: &LocalConstMap) -> Result<u32, ParsingError> {
         assert_eq!(Self::REPEAT, self.parts[0], "not a repeat");
         match self.num_parts() {
             0 => unreachable!(),
@@ -254,6 +303,23 @@ impl<'a> Token<'a> {
         }
     }
 
+    pub(crate) fn parse_for(&self, constants: &LocalConstMap) -> Result<u32, ParsingError> {
+        assert_eq!(Self::FOR, self.parts[0], "not a for");
+        match self.num_parts() {
+            0 => unreachable!(),
+            1 => Err(ParsingError::missing_param(self, "for.<num_iterations>")),
+            2 => match parse_param_with_constant_lookup::<u32>(self, 1, constants)? {
+                0 => Err(ParsingError::invalid_param_with_reason(
+                    self,
+                    1,
+                    "number of iterations must be greater than 0",
+                )),
+                times => Ok(times),
+            },
+            _ => Err(ParsingError::extra_param(self)),
+        }
+    }
+
     pub fn parse_invocation(
         &self,
         invocation_token: &str,
@@ -289,6 +355,17 @@ impl<'a> fmt::Display for Token<'a> {
 // HELPER FUNCTIONS
 // ================================================================================================
 
+/// Splits the specified token into its dot-separated parts, ignoring dots within quoted strings.
+fn split_parts(token: &str) -> impl Iterator<Item = &str> {
+    let mut in_string = false;
+    token.split(move |c| {
+        if c == Token::STRING_DELIM {
+            in_string = !in_string;
+        }
+        c == '.' && !in_string
+    })
+}
+
 /// A module import path must comply with the following rules:
 /// - Path limbs must be separated by double-colons ("::").
 /// - Each limb must start with an ASCII letter.
@@ -310,6 +387,121 @@ fn validate_proc_locals(locals: &str, token: &Token) -> Result<u16, ParsingError
     }
 }
 
+/// A procedure signature must have the form `(<inputs>) -> (<outputs>)`, where the outputs part
+/// is optional and may be omitted if the procedure has no outputs.
+fn parse_proc_signature(
+    signature: &str,
+    token: &Token,
+) -> Result<ProcedureSignature, ParsingError> {
+    let (inputs, outputs) = match signature.split_once(Token::ALIAS_DELIM) {
+        Some((inputs, outputs)) => (inputs.trim_end(), outputs.trim_start()),
+        None => (signature, "()"),
+    };
+    let inputs = parse_signature_params(inputs, token)?;
+    let outputs = parse_signature_params(outputs, token)?;
+    Ok(ProcedureSignature::new(inputs, outputs))
+}
+
+/// A list of signature parameters must comply with the following rules:
+/// - The list must be enclosed in parentheses, and parameters must be separated by commas.
+/// - Each parameter must have the form `<name>: <type>`, where the name complies with the same
+///   rules as a module name, and the type is one of `felt`, `bool`, `u32`, `u64`, or `word`.
+/// - Parameter names must be unique within the list.
+/// - The list can contain at most 255 parameters.
+fn parse_signature_params(
+    params: &str,
+    token: &Token,
+) -> Result<Vec<SignatureParam>, ParsingError> {
+    let params = params
+        .strip_prefix('(')
+        .and_then(|params| params.strip_suffix(')'))
+        .ok_or_else(|| {
+            ParsingError::invalid_proc_signature(
+                token,
+                "expected a list of parameters in parentheses",
+            )
+        })?;
+    if params.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut result: Vec<SignatureParam> = Vec::new();
+    for param in params.split(',') {
+        let (name, ty) = param.split_once(':').ok_or_else(|| {
+            let reason = format!("expected a parameter in the form `name: type`, found `{param}`");
+            ParsingError::invalid_proc_signature(token, &reason)
+        })?;
+        let (name, ty) = (name.trim(), ty.trim());
+        if validate_module_name(name, token).is_err() {
+            let reason = format!("invalid parameter name `{name}`");
+            return Err(ParsingError::invalid_proc_signature(token, &reason));
+        }
+        if result.iter().any(|param| param.name() == name) {
+            let reason = format!("duplicate parameter name `{name}`");
+            return Err(ParsingError::invalid_proc_signature(token, &reason));
+        }
+        let ty = ParamType::from_name(ty).ok_or_else(|| {
+            let reason = format!("unknown parameter type `{ty}`");
+            ParsingError::invalid_proc_signature(token, &reason)
+        })?;
+        result.push(SignatureParam::new(name.to_string(), ty));
+    }
+
+    if result.len() > MAX_SIGNATURE_PARAMS {
+        let reason = format!("number of parameters cannot exceed {MAX_SIGNATURE_PARAMS}");
+        return Err(ParsingError::invalid_proc_signature(token, &reason));
+    }
+    Ok(result)
+}
+
+/// A local name must comply with the following rules:
+/// - The name must be between 1 and 255 characters long.
+/// - The name must start with a lowercase ASCII letter.
+/// - The name can contain only ASCII letters, numbers, or underscores.
+fn validate_local_name(name: &str, token: &Token) -> Result<(), ParsingError> {
+    if name.is_empty()
+        || name.len() > crate::MAX_LABEL_LEN
+        || !name.chars().next().unwrap().is_ascii_lowercase()
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        let reason = format!("invalid local name `{name}`");
+        Err(ParsingError::invalid_local_decl(token, &reason))
+    } else {
+        Ok(())
+    }
+}
+
+/// The type of a local must be either one of the types allowed in procedure signatures, which
+/// occupies a single local, or an array of such types in the form `[<type>; <length>]`, which
+/// occupies one local per element. Returns the number of locals occupied by the type.
+fn parse_local_size(ty: &str, token: &Token) -> Result<u16, ParsingError> {
+    let (elem_ty, len) = match ty.strip_prefix('[').and_then(|ty| ty.strip_suffix(']')) {
+        Some(array) => {
+            let (elem_ty, len) = array.split_once(';').ok_or_else(|| {
+                let reason =
+                    format!("expected an array type in the form `[type; length]`, found `{ty}`");
+                ParsingError::invalid_local_decl(token, &reason)
+            })?;
+            let len = match len.trim().parse::<u16>() {
+                Ok(len) if len > 0 => len,
+                _ => {
+                    let reason = format!("invalid array length `{}`", len.trim());
+                    return Err(ParsingError::invalid_local_decl(token, &reason));
+                }
+            };
+            (elem_ty.trim(), len)
+        }
+        None => (ty, 1),
+    };
+    match ParamType::from_name(elem_ty) {
+        Some(_) => Ok(len),
+        None => {
+            let reason = format!("unknown local type `{elem_ty}`");
+            Err(ParsingError::invalid_local_decl(token, &reason))
+        }
+    }
+}
+
 /// A module name must comply with the following rules:
 /// - The name must be between 1 and 255 characters long.
 /// - The name must start with an ASCII letter.
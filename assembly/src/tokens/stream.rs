@@ -1,5 +1,9 @@
 use super::{LineTokenizer, LinesStream, ParsingError, SourceLocation, Token};
-use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    vec::Vec,
+};
 use core::fmt;
 
 // TOKEN STREAM
@@ -19,15 +23,23 @@ pub struct TokenStream<'a> {
 impl<'a> TokenStream<'a> {
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
-    /// TODO: add comments
-    pub fn new(source: &'a str) -> Result<Self, ParsingError> {
+    /// Breaks the provided source into tokens.
+    ///
+    /// Conditional compilation directives in the source (e.g., `#if DEBUG`) are evaluated with
+    /// the specified features enabled; the code they exclude is not tokenized.
+    ///
+    /// # Errors
+    /// Returns an error if the source contains no tokens, contains a doc comment which is not
+    /// followed by a procedure declaration, or contains malformed or unmatched directives.
+    pub fn new(source: &'a str, features: &BTreeSet<String>) -> Result<Self, ParsingError> {
         // initialize the attributes
         let mut tokens = Vec::new();
         let mut locations = Vec::new();
         let mut proc_comments = BTreeMap::new();
         let mut module_comment = None;
 
-        for line_info in LinesStream::from(source) {
+        let mut lines = LinesStream::from(source).with_features(features);
+        for line_info in lines.by_ref() {
             match line_info.contents() {
                 Some(line) => {
                     // fill the doc comments for procedures
@@ -64,6 +76,10 @@ impl<'a> TokenStream<'a> {
             }
         }
 
+        if let Some(err) = lines.take_error() {
+            return Err(err);
+        }
+
         // invalid if no tokens
         if tokens.is_empty() {
             return Err(ParsingError::empty_source());
@@ -139,6 +155,19 @@ impl<'a> TokenStream<'a> {
         }
     }
 
+    /// Moves the current token position back to the specified position. The token at the
+    /// specified position must have been previously read.
+    ///
+    /// # Panics
+    /// Panics if the specified position is greater than the current token position in the stream.
+    pub fn seek(&mut self, pos: usize) {
+        assert!(pos <= self.pos, "cannot seek to future positions");
+        if pos < self.pos {
+            self.pos = pos;
+            self.current.update(self.tokens[pos], self.locations[pos]);
+        }
+    }
+
     pub fn take_doc_comment_at(&mut self, pos: usize) -> Option<String> {
         self.proc_comments.remove(&pos)?
     }
@@ -58,15 +58,9 @@ impl<'a> Iterator for LineTokenizer<'a> {
         }
 
         let token_loc = self.location;
-        let (token, offset) = match self.line.split_once(char::is_whitespace) {
-            Some((token, remainder)) => {
-                let offset = remainder.find(|c: char| !c.is_whitespace()).unwrap_or_default();
-                (token, token.len() + offset + 1)
-            }
-            None => (self.line, self.line.len()),
-        };
-
-        let (_, remainder) = self.line.split_at(offset);
+        let (token, remainder) = self.line.split_at(token_len(self.line));
+        let remainder = remainder.trim_start();
+        let offset = self.line.len() - remainder.len();
         self.line = remainder;
         self.location.move_column(offset as u32);
 
@@ -74,6 +68,113 @@ impl<'a> Iterator for LineTokenizer<'a> {
     }
 }
 
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the length of the token at the start of the specified line.
+///
+/// Tokens are separated by whitespace, except for:
+/// - Procedure declarations with a signature (e.g., `export.foo(a: felt, b: u32) -> (c: word)`),
+///   which extend until the end of the signature.
+/// - Declarations of array constants (e.g., `const.IV=[1, 2, 3, 4]`) and data segments (e.g.,
+///   `data.100=[1, 2, 3]`), which extend until the closing bracket.
+/// - Quoted strings (e.g., `assert.err="insufficient balance"`), which extend until the closing
+///   quote.
+/// - Declarations of named procedure locals (e.g., `local.buf: [word; 4]`), which extend until
+///   the end of the type of the local.
+///
+/// If the signature or the array is malformed, or the string is not closed, the token extends
+/// until the end of the line.
+fn token_len(line: &str) -> usize {
+    let len = whitespace_pos(line);
+    let mut parts = line[..len].split('.');
+    match (parts.next(), parts.next()) {
+        (Some(Token::CONST | Token::DATA), _) | (Some(Token::EXPORT), Some(Token::CONST)) => {
+            const_len(line, len)
+        }
+        (Some(Token::PROC | Token::EXPORT), _) => signature_len(line, len),
+        (Some(Token::LOCAL), _) => local_len(line, len),
+        _ => len,
+    }
+}
+
+/// Returns the position of the first whitespace in the specified line which is not a part of a
+/// quoted string, or the length of the line if there is no such whitespace.
+fn whitespace_pos(line: &str) -> usize {
+    let mut in_string = false;
+    for (idx, c) in line.char_indices() {
+        if c == Token::STRING_DELIM {
+            in_string = !in_string;
+        } else if c.is_whitespace() && !in_string {
+            return idx;
+        }
+    }
+    line.len()
+}
+
+/// Returns the length of the constant declaration at the start of the specified line, where `len`
+/// is the length of the declaration up to the first whitespace.
+fn const_len(line: &str, len: usize) -> usize {
+    match line[..len].find("=[") {
+        Some(pos) => find_closing(line, pos + 1, ('[', ']')).unwrap_or(line.len()),
+        None => len,
+    }
+}
+
+/// Returns the length of the procedure declaration at the start of the specified line, where
+/// `len` is the length of the declaration up to the first whitespace.
+fn signature_len(line: &str, len: usize) -> usize {
+    let Some(inputs_start) = line[..len].find('(') else {
+        return len;
+    };
+
+    let Some(inputs_end) = find_closing(line, inputs_start, ('(', ')')) else {
+        return line.len();
+    };
+    let Some(outputs) = line[inputs_end..].trim_start().strip_prefix(Token::ALIAS_DELIM) else {
+        return inputs_end;
+    };
+    let outputs_start = line.len() - outputs.trim_start().len();
+    find_closing(line, outputs_start, ('(', ')')).unwrap_or(line.len())
+}
+
+/// Returns the length of the local declaration at the start of the specified line, where `len` is
+/// the length of the declaration up to the first whitespace.
+fn local_len(line: &str, len: usize) -> usize {
+    let Some(colon_pos) = line[..len].find(':') else {
+        return len;
+    };
+    let ty = line[colon_pos + 1..].trim_start();
+    let ty_start = line.len() - ty.len();
+    if ty.starts_with('[') {
+        find_closing(line, ty_start, ('[', ']')).unwrap_or(line.len())
+    } else {
+        ty_start + whitespace_pos(ty)
+    }
+}
+
+/// Returns the position right after the bracket closing the one at the specified position, or
+/// None if there is no opening bracket at this position or it is never closed.
+fn find_closing(line: &str, start: usize, (open, close): (char, char)) -> Option<usize> {
+    if !line[start..].starts_with(open) {
+        return None;
+    }
+    let mut depth = 0;
+    for (idx, c) in line[start..].char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+        } else {
+            continue;
+        }
+        if depth == 0 {
+            return Some(start + idx + 1);
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -150,6 +251,94 @@ mod tests {
         assert_eq!(Some(SourceLocation::new(1, 24)), tokenizer.take_dangling());
     }
 
+    #[test]
+    fn procedure_signature() {
+        let info = LineInfo::new(1, 0)
+            .with_contents("export.foo.1(a: felt, b: u32) -> (c: word) loc_store.0 end");
+        let mut tokenizer = LineTokenizer::new(&info).unwrap();
+        assert_eq!(l("export.foo.1(a: felt, b: u32) -> (c: word)", 1, 1), tokenizer.next());
+        assert_eq!(l("loc_store.0", 1, 44), tokenizer.next());
+        assert_eq!(l("end", 1, 56), tokenizer.next());
+        assert_eq!(None, tokenizer.next());
+
+        let info = LineInfo::new(1, 0).with_contents("proc.foo(a: felt)  add end");
+        let mut tokenizer = LineTokenizer::new(&info).unwrap();
+        assert_eq!(l("proc.foo(a: felt)", 1, 1), tokenizer.next());
+        assert_eq!(l("add", 1, 20), tokenizer.next());
+        assert_eq!(l("end", 1, 24), tokenizer.next());
+        assert_eq!(None, tokenizer.next());
+
+        // unclosed signatures extend until the end of the line
+        let info = LineInfo::new(1, 0).with_contents("proc.foo(a: felt -> (b: felt) add");
+        let mut tokenizer = LineTokenizer::new(&info).unwrap();
+        assert_eq!(l("proc.foo(a: felt -> (b: felt) add", 1, 1), tokenizer.next());
+        assert_eq!(None, tokenizer.next());
+    }
+
+    #[test]
+    fn array_constant() {
+        let info = LineInfo::new(1, 0).with_contents("const.IV=[1, 2, A+1,  0xff] # comment");
+        let mut tokenizer = LineTokenizer::new(&info).unwrap();
+        assert_eq!(l("const.IV=[1, 2, A+1,  0xff]", 1, 1), tokenizer.next());
+        assert_eq!(None, tokenizer.next());
+
+        // unclosed arrays extend until the end of the line
+        let info = LineInfo::new(1, 0).with_contents("const.IV=[1, 2");
+        let mut tokenizer = LineTokenizer::new(&info).unwrap();
+        assert_eq!(l("const.IV=[1, 2", 1, 1), tokenizer.next());
+        assert_eq!(None, tokenizer.next());
+
+        // indexed elements of arrays in constant expressions are not grouped
+        let info = LineInfo::new(1, 0).with_contents("const.A=IV[1]+IV[2] const.B=1");
+        let mut tokenizer = LineTokenizer::new(&info).unwrap();
+        assert_eq!(l("const.A=IV[1]+IV[2]", 1, 1), tokenizer.next());
+        assert_eq!(l("const.B=1", 1, 21), tokenizer.next());
+        assert_eq!(None, tokenizer.next());
+
+        // exported arrays are grouped in the same way as local ones
+        let info = LineInfo::new(1, 0).with_contents("export.const.IV=[1, 2] export.foo");
+        let mut tokenizer = LineTokenizer::new(&info).unwrap();
+        assert_eq!(l("export.const.IV=[1, 2]", 1, 1), tokenizer.next());
+        assert_eq!(l("export.foo", 1, 24), tokenizer.next());
+        assert_eq!(None, tokenizer.next());
+
+        // data segments are grouped in the same way as arrays
+        let info = LineInfo::new(1, 0).with_contents("data.100=[1, IV, 3] begin");
+        let mut tokenizer = LineTokenizer::new(&info).unwrap();
+        assert_eq!(l("data.100=[1, IV, 3]", 1, 1), tokenizer.next());
+        assert_eq!(l("begin", 1, 21), tokenizer.next());
+        assert_eq!(None, tokenizer.next());
+    }
+
+    #[test]
+    fn quoted_string() {
+        let info = LineInfo::new(1, 0)
+            .with_contents(r#"assert.err="insufficient  balance # 1" const.E="a b" add"#);
+        let mut tokenizer = LineTokenizer::new(&info).unwrap();
+        assert_eq!(l(r#"assert.err="insufficient  balance # 1""#, 1, 1), tokenizer.next());
+        assert_eq!(l(r#"const.E="a b""#, 1, 40), tokenizer.next());
+        assert_eq!(l("add", 1, 54), tokenizer.next());
+        assert_eq!(None, tokenizer.next());
+
+        // unclosed strings extend until the end of the line
+        let info = LineInfo::new(1, 0).with_contents(r#"assert.err="foo bar"#);
+        let mut tokenizer = LineTokenizer::new(&info).unwrap();
+        assert_eq!(l(r#"assert.err="foo bar"#, 1, 1), tokenizer.next());
+        assert_eq!(None, tokenizer.next());
+    }
+
+    #[test]
+    fn local_declaration() {
+        let info = LineInfo::new(1, 0)
+            .with_contents("local.acc: word local.buf:  [word; 4] local.x:felt add");
+        let mut tokenizer = LineTokenizer::new(&info).unwrap();
+        assert_eq!(l("local.acc: word", 1, 1), tokenizer.next());
+        assert_eq!(l("local.buf:  [word; 4]", 1, 17), tokenizer.next());
+        assert_eq!(l("local.x:felt", 1, 39), tokenizer.next());
+        assert_eq!(l("add", 1, 52), tokenizer.next());
+        assert_eq!(None, tokenizer.next());
+    }
+
     // TESTS HELPERS
     // ============================================================================================
 
@@ -0,0 +1,123 @@
+use super::{errors::render_diagnostic, tokens::SourceLocation, LibraryPath, ProcedureName};
+use alloc::string::String;
+use core::fmt;
+
+// ASSEMBLY WARNING
+// ================================================================================================
+
+/// A warning about Miden assembly source code which is valid, but is likely to contain a mistake.
+///
+/// Unlike errors, warnings do not prevent the source code from being compiled. Warnings are
+/// reported by [ProgramAst::parse_with_warnings()](crate::ast::ProgramAst::parse_with_warnings),
+/// [ModuleAst::parse_with_warnings()](crate::ast::ModuleAst::parse_with_warnings),
+/// [Assembler::compile_with_warnings()](crate::Assembler::compile_with_warnings), and
+/// [Assembler::compile_ast_with_warnings()](crate::Assembler::compile_ast_with_warnings). They are
+/// also attached to the parsed ASTs, and are available via
+/// [ProgramAst::warnings()](crate::ast::ProgramAst::warnings),
+/// [ModuleAst::warnings()](crate::ast::ModuleAst::warnings), and
+/// [MaslLibrary::warnings()](crate::MaslLibrary::warnings) for libraries built from source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssemblyWarning {
+    kind: WarningKind,
+    location: SourceLocation,
+}
+
+/// The kind of an [AssemblyWarning], i.e., the lint which produced the warning.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WarningKind {
+    /// A module is imported via a `use` statement, but none of its procedures are used.
+    UnusedImport(LibraryPath),
+    /// A constant is declared, but is never used.
+    UnusedConstant(String),
+    /// A local procedure is never invoked from the program body or from an exported procedure.
+    UnusedProcedure(ProcedureName),
+    /// A procedure declares memory locals, but never accesses them.
+    UnusedLocals(ProcedureName, u16),
+    /// Code follows an assertion which always fails, and thus can never be executed.
+    UnreachableCode,
+}
+
+impl AssemblyWarning {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new warning of the specified kind located at the specified location.
+    pub fn new(kind: WarningKind, location: SourceLocation) -> Self {
+        Self { kind, location }
+    }
+
+    pub fn unused_import(path: &LibraryPath, location: SourceLocation) -> Self {
+        Self::new(WarningKind::UnusedImport(path.clone()), location)
+    }
+
+    pub fn unused_constant(name: &str, location: SourceLocation) -> Self {
+        Self::new(WarningKind::UnusedConstant(name.into()), location)
+    }
+
+    pub fn unused_procedure(name: &ProcedureName, location: SourceLocation) -> Self {
+        Self::new(WarningKind::UnusedProcedure(name.clone()), location)
+    }
+
+    pub fn unused_locals(name: &ProcedureName, num_locals: u16, location: SourceLocation) -> Self {
+        Self::new(WarningKind::UnusedLocals(name.clone(), num_locals), location)
+    }
+
+    pub fn unreachable_code(location: SourceLocation) -> Self {
+        Self::new(WarningKind::UnreachableCode, location)
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the kind of this warning.
+    pub fn kind(&self) -> &WarningKind {
+        &self.kind
+    }
+
+    /// Returns the location in the source code to which this warning refers.
+    pub fn source_location(&self) -> &SourceLocation {
+        &self.location
+    }
+
+    // DIAGNOSTICS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a hint on how the code could be fixed to address this warning.
+    pub fn hint(&self) -> String {
+        use WarningKind::*;
+        match &self.kind {
+            UnusedImport(_) => "remove the `use` statement".into(),
+            UnusedConstant(_) => "remove the constant declaration".into(),
+            UnusedProcedure(_) => "remove the procedure, or invoke it from reachable code".into(),
+            UnusedLocals(..) => "remove the number of locals from the procedure declaration".into(),
+            UnreachableCode => "the preceding assertion always fails; remove the code".into(),
+        }
+    }
+
+    /// Renders this warning in a human-readable form together with the line of the provided
+    /// source code to which it refers and a hint on how the warning could be addressed.
+    pub fn render(&self, source: &str) -> String {
+        let location = format!("{}:{}", self.location.line(), self.location.column());
+        let location =
+            Some((&location as &dyn fmt::Display, self.location.line(), self.location.column()));
+        render_diagnostic("warning", self, location, Some(self.hint()), source)
+    }
+}
+
+impl fmt::Display for AssemblyWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use WarningKind::*;
+        match &self.kind {
+            UnusedImport(path) => write!(f, "unused import: \"{path}\""),
+            UnusedConstant(name) => write!(f, "unused constant: \"{name}\""),
+            UnusedProcedure(name) => write!(f, "procedure \"{name}\" is never used"),
+            UnusedLocals(name, num_locals) => {
+                write!(
+                    f,
+                    "procedure \"{name}\" declares {num_locals} locals but never accesses them"
+                )
+            }
+            UnreachableCode => write!(f, "unreachable code"),
+        }
+    }
+}
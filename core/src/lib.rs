@@ -44,7 +44,11 @@ pub use math::{
 };
 
 mod program;
-pub use program::{blocks as code_blocks, CodeBlockTable, Kernel, Program, ProgramInfo};
+pub use program::{
+    blocks as code_blocks, CodeBlockTable, CodeLocation, DataSegment, JoinNode, Kernel, LoopNode,
+    MastForest, MastNode, MastNodeId, MastSerdeOptions, ProcedureLocation, Program, ProgramInfo,
+    SourceMap, SpanLocations, SplitNode,
+};
 
 mod operations;
 pub use operations::{
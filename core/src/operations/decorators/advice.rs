@@ -1,5 +1,8 @@
 use super::SignatureKind;
-use crate::Felt;
+use crate::{
+    utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable},
+    Felt,
+};
 use core::fmt;
 
 // ADVICE INJECTORS
@@ -317,3 +320,109 @@ impl fmt::Display for AdviceInjector {
         }
     }
 }
+
+// SERIALIZATION
+// ================================================================================================
+
+impl AdviceInjector {
+    // SERIALIZATION TAGS
+    // --------------------------------------------------------------------------------------------
+    const MERKLE_NODE_MERGE: u8 = 0;
+    const MERKLE_NODE_TO_STACK: u8 = 1;
+    const UPDATE_MERKLE_NODE: u8 = 2;
+    const MAP_VALUE_TO_STACK: u8 = 3;
+    const U64_DIV: u8 = 4;
+    const EXT2_INV: u8 = 5;
+    const EXT2_INTT: u8 = 6;
+    const SMT_GET: u8 = 7;
+    const SMT_SET: u8 = 8;
+    const SMT_PEEK: u8 = 9;
+    const U32_CLZ: u8 = 10;
+    const U32_CTZ: u8 = 11;
+    const U32_CLO: u8 = 12;
+    const U32_CTO: u8 = 13;
+    const ILOG2: u8 = 14;
+    const MEM_TO_MAP: u8 = 15;
+    const HDWORD_TO_MAP: u8 = 16;
+    const HPERM_TO_MAP: u8 = 17;
+    const SIG_TO_STACK: u8 = 18;
+}
+
+impl Serializable for AdviceInjector {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        match self {
+            Self::MerkleNodeMerge => target.write_u8(Self::MERKLE_NODE_MERGE),
+            Self::MerkleNodeToStack => target.write_u8(Self::MERKLE_NODE_TO_STACK),
+            Self::UpdateMerkleNode => target.write_u8(Self::UPDATE_MERKLE_NODE),
+            Self::MapValueToStack {
+                include_len,
+                key_offset,
+            } => {
+                target.write_u8(Self::MAP_VALUE_TO_STACK);
+                target.write_bool(*include_len);
+                target.write_usize(*key_offset);
+            }
+            Self::U64Div => target.write_u8(Self::U64_DIV),
+            Self::Ext2Inv => target.write_u8(Self::EXT2_INV),
+            Self::Ext2Intt => target.write_u8(Self::EXT2_INTT),
+            Self::SmtGet => target.write_u8(Self::SMT_GET),
+            Self::SmtSet => target.write_u8(Self::SMT_SET),
+            Self::SmtPeek => target.write_u8(Self::SMT_PEEK),
+            Self::U32Clz => target.write_u8(Self::U32_CLZ),
+            Self::U32Ctz => target.write_u8(Self::U32_CTZ),
+            Self::U32Clo => target.write_u8(Self::U32_CLO),
+            Self::U32Cto => target.write_u8(Self::U32_CTO),
+            Self::ILog2 => target.write_u8(Self::ILOG2),
+            Self::MemToMap => target.write_u8(Self::MEM_TO_MAP),
+            Self::HdwordToMap { domain } => {
+                target.write_u8(Self::HDWORD_TO_MAP);
+                domain.write_into(target);
+            }
+            Self::HpermToMap => target.write_u8(Self::HPERM_TO_MAP),
+            Self::SigToStack { kind } => {
+                target.write_u8(Self::SIG_TO_STACK);
+                kind.write_into(target);
+            }
+        }
+    }
+}
+
+impl Deserializable for AdviceInjector {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            Self::MERKLE_NODE_MERGE => Ok(Self::MerkleNodeMerge),
+            Self::MERKLE_NODE_TO_STACK => Ok(Self::MerkleNodeToStack),
+            Self::UPDATE_MERKLE_NODE => Ok(Self::UpdateMerkleNode),
+            Self::MAP_VALUE_TO_STACK => {
+                let include_len = source.read_bool()?;
+                let key_offset = source.read_usize()?;
+                Ok(Self::MapValueToStack {
+                    include_len,
+                    key_offset,
+                })
+            }
+            Self::U64_DIV => Ok(Self::U64Div),
+            Self::EXT2_INV => Ok(Self::Ext2Inv),
+            Self::EXT2_INTT => Ok(Self::Ext2Intt),
+            Self::SMT_GET => Ok(Self::SmtGet),
+            Self::SMT_SET => Ok(Self::SmtSet),
+            Self::SMT_PEEK => Ok(Self::SmtPeek),
+            Self::U32_CLZ => Ok(Self::U32Clz),
+            Self::U32_CTZ => Ok(Self::U32Ctz),
+            Self::U32_CLO => Ok(Self::U32Clo),
+            Self::U32_CTO => Ok(Self::U32Cto),
+            Self::ILOG2 => Ok(Self::ILog2),
+            Self::MEM_TO_MAP => Ok(Self::MemToMap),
+            Self::HDWORD_TO_MAP => Ok(Self::HdwordToMap {
+                domain: Felt::read_from(source)?,
+            }),
+            Self::HPERM_TO_MAP => Ok(Self::HpermToMap),
+            Self::SIG_TO_STACK => Ok(Self::SigToStack {
+                kind: SignatureKind::read_from(source)?,
+            }),
+            tag => Err(DeserializationError::InvalidValue(format!(
+                "invalid advice injector tag: {tag}"
+            ))),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
 use alloc::string::String;
 use core::fmt;
 
@@ -63,3 +64,22 @@ impl fmt::Display for AssemblyOp {
         )
     }
 }
+
+impl Serializable for AssemblyOp {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.context_name.write_into(target);
+        target.write_u8(self.num_cycles);
+        self.op.write_into(target);
+        target.write_bool(self.should_break);
+    }
+}
+
+impl Deserializable for AssemblyOp {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let context_name = String::read_from(source)?;
+        let num_cycles = source.read_u8()?;
+        let op = String::read_from(source)?;
+        let should_break = source.read_bool()?;
+        Ok(Self::new(context_name, num_cycles, op, should_break))
+    }
+}
@@ -1,3 +1,4 @@
+use crate::utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
 use core::fmt;
 
 // DEBUG OPTIONS
@@ -41,3 +42,61 @@ impl fmt::Display for DebugOptions {
         }
     }
 }
+
+impl DebugOptions {
+    // SERIALIZATION TAGS
+    // --------------------------------------------------------------------------------------------
+    const STACK_ALL: u8 = 0;
+    const STACK_TOP: u8 = 1;
+    const MEM_ALL: u8 = 2;
+    const MEM_INTERVAL: u8 = 3;
+    const LOCAL_INTERVAL: u8 = 4;
+}
+
+impl Serializable for DebugOptions {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        match self {
+            Self::StackAll => target.write_u8(Self::STACK_ALL),
+            Self::StackTop(n) => {
+                target.write_u8(Self::STACK_TOP);
+                target.write_u16(*n);
+            }
+            Self::MemAll => target.write_u8(Self::MEM_ALL),
+            Self::MemInterval(start, end) => {
+                target.write_u8(Self::MEM_INTERVAL);
+                target.write_u32(*start);
+                target.write_u32(*end);
+            }
+            Self::LocalInterval(start, end, num_locals) => {
+                target.write_u8(Self::LOCAL_INTERVAL);
+                target.write_u16(*start);
+                target.write_u16(*end);
+                target.write_u16(*num_locals);
+            }
+        }
+    }
+}
+
+impl Deserializable for DebugOptions {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            Self::STACK_ALL => Ok(Self::StackAll),
+            Self::STACK_TOP => Ok(Self::StackTop(source.read_u16()?)),
+            Self::MEM_ALL => Ok(Self::MemAll),
+            Self::MEM_INTERVAL => {
+                let start = source.read_u32()?;
+                let end = source.read_u32()?;
+                Ok(Self::MemInterval(start, end))
+            }
+            Self::LOCAL_INTERVAL => {
+                let start = source.read_u16()?;
+                let end = source.read_u16()?;
+                let num_locals = source.read_u16()?;
+                Ok(Self::LocalInterval(start, end, num_locals))
+            }
+            tag => {
+                Err(DeserializationError::InvalidValue(format!("invalid debug options tag: {tag}")))
+            }
+        }
+    }
+}
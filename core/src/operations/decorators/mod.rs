@@ -1,3 +1,4 @@
+use crate::utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
 use alloc::vec::Vec;
 use core::fmt;
 
@@ -36,6 +37,16 @@ pub enum Decorator {
     Trace(u32),
 }
 
+impl Decorator {
+    // SERIALIZATION TAGS
+    // --------------------------------------------------------------------------------------------
+    const ADVICE: u8 = 0;
+    const ASM_OP: u8 = 1;
+    const DEBUG: u8 = 2;
+    const EVENT: u8 = 3;
+    const TRACE: u8 = 4;
+}
+
 impl fmt::Display for Decorator {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -50,6 +61,46 @@ impl fmt::Display for Decorator {
     }
 }
 
+impl Serializable for Decorator {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        match self {
+            Self::Advice(injector) => {
+                target.write_u8(Self::ADVICE);
+                injector.write_into(target);
+            }
+            Self::AsmOp(assembly_op) => {
+                target.write_u8(Self::ASM_OP);
+                assembly_op.write_into(target);
+            }
+            Self::Debug(options) => {
+                target.write_u8(Self::DEBUG);
+                options.write_into(target);
+            }
+            Self::Event(event_id) => {
+                target.write_u8(Self::EVENT);
+                target.write_u32(*event_id);
+            }
+            Self::Trace(trace_id) => {
+                target.write_u8(Self::TRACE);
+                target.write_u32(*trace_id);
+            }
+        }
+    }
+}
+
+impl Deserializable for Decorator {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            Self::ADVICE => Ok(Self::Advice(AdviceInjector::read_from(source)?)),
+            Self::ASM_OP => Ok(Self::AsmOp(AssemblyOp::read_from(source)?)),
+            Self::DEBUG => Ok(Self::Debug(DebugOptions::read_from(source)?)),
+            Self::EVENT => Ok(Self::Event(source.read_u32()?)),
+            Self::TRACE => Ok(Self::Trace(source.read_u32()?)),
+            tag => Err(DeserializationError::InvalidValue(format!("invalid decorator tag: {tag}"))),
+        }
+    }
+}
+
 /// Vector consisting of a tuple of operation index (within a span block) and decorator at that index
 pub type DecoratorList = Vec<(usize, Decorator)>;
 
@@ -101,6 +152,25 @@ pub enum SignatureKind {
     RpoFalcon512,
 }
 
+impl Serializable for SignatureKind {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        match self {
+            Self::RpoFalcon512 => target.write_u8(0),
+        }
+    }
+}
+
+impl Deserializable for SignatureKind {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            0 => Ok(Self::RpoFalcon512),
+            tag => {
+                Err(DeserializationError::InvalidValue(format!("invalid signature kind: {tag}")))
+            }
+        }
+    }
+}
+
 impl fmt::Display for SignatureKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -0,0 +1,118 @@
+use super::{
+    hasher, serialization::read_elements, ByteReader, ByteWriter, Deserializable,
+    DeserializationError, Digest, Felt, Serializable,
+};
+use crate::{WORD_SIZE, ZERO};
+use alloc::vec::Vec;
+
+// DATA SEGMENT
+// ================================================================================================
+
+/// A segment of data which is written into the memory of the VM before a program is executed.
+///
+/// A data segment consists of a list of field elements and the memory address of the word at which
+/// the first of these elements is placed; subsequent elements fill the memory words at consecutive
+/// addresses, four elements per word.
+///
+/// The elements of a data segment are loaded into memory by the program itself: they are provided
+/// to the program via the advice map under the key equal to the [DataSegment::commitment()], and
+/// the program checks that the data it loaded hashes to this commitment. To make loading
+/// efficient, the elements are padded with zeros to a multiple of eight (i.e., an even number of
+/// words), and thus, a data segment occupies [DataSegment::num_words()] words of memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataSegment {
+    address: u32,
+    values: Vec<Felt>,
+}
+
+impl DataSegment {
+    // CONSTANTS
+    // --------------------------------------------------------------------------------------------
+
+    /// The number of elements the values of a data segment are padded to a multiple of.
+    pub const PADDING: usize = 2 * WORD_SIZE;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new [DataSegment] placing the specified values into memory starting at the
+    /// specified address.
+    ///
+    /// # Panics
+    /// Panics if the list of values is empty, or if the segment does not fit into the memory
+    /// address space.
+    pub fn new(address: u32, values: Vec<Felt>) -> Self {
+        assert!(!values.is_empty(), "data segment cannot be empty");
+        let segment = Self { address, values };
+        assert!(
+            segment.end_address() <= u32::MAX as u64 + 1,
+            "data segment out of memory bounds"
+        );
+        segment
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the memory address of the word holding the first elements of this segment.
+    pub fn address(&self) -> u32 {
+        self.address
+    }
+
+    /// Returns the values of this segment, without padding.
+    pub fn values(&self) -> &[Felt] {
+        &self.values
+    }
+
+    /// Returns the number of memory words occupied by this segment, including padding.
+    pub fn num_words(&self) -> u32 {
+        (self.values.len().next_multiple_of(Self::PADDING) / WORD_SIZE) as u32
+    }
+
+    /// Returns the address right after the last memory word occupied by this segment.
+    pub fn end_address(&self) -> u64 {
+        self.address as u64 + self.num_words() as u64
+    }
+
+    /// Returns the values of this segment padded with zeros to a multiple of eight elements, as
+    /// they are written into memory.
+    pub fn padded_values(&self) -> Vec<Felt> {
+        let mut values = self.values.clone();
+        values.resize(self.values.len().next_multiple_of(Self::PADDING), ZERO);
+        values
+    }
+
+    /// Returns a commitment to this segment, computed as the hash of its padded values.
+    pub fn commitment(&self) -> Digest {
+        hasher::hash_elements(&self.padded_values())
+    }
+
+    /// Returns true if this segment shares memory words with the other segment.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        (self.address as u64) < other.end_address() && (other.address as u64) < self.end_address()
+    }
+}
+
+impl Serializable for DataSegment {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u32(self.address);
+        target.write_usize(self.values.len());
+        target.write_many(&self.values);
+    }
+}
+
+impl Deserializable for DataSegment {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let address = source.read_u32()?;
+        let num_values = source.read_usize()?;
+        if num_values == 0 {
+            return Err(DeserializationError::InvalidValue("empty data segment".into()));
+        }
+        let values = read_elements::<_, Felt>(source, num_values)?;
+        let segment = Self { address, values };
+        if segment.end_address() > u32::MAX as u64 + 1 {
+            return Err(DeserializationError::InvalidValue("data segment out of bounds".into()));
+        }
+        Ok(segment)
+    }
+}
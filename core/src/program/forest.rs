@@ -0,0 +1,474 @@
+use super::{
+    blocks::{Call, CodeBlock, Dyn, Join, Loop, Proxy, Span, Split},
+    hasher, Digest, Felt, Operation,
+};
+use crate::{
+    utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable},
+    DecoratorList,
+};
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{fmt, ops::Index};
+
+// MAST NODE ID
+// ================================================================================================
+
+/// An identifier of a node in a [MastForest].
+///
+/// Node IDs are assigned by the forest when nodes are added to it, and are meaningful only in the
+/// context of the forest which issued them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MastNodeId(u32);
+
+impl MastNodeId {
+    /// Returns the index of the node in the forest as usize.
+    pub fn as_usize(&self) -> usize {
+        self.0 as usize
+    }
+
+    /// Returns the index of the node in the forest as u32.
+    pub fn as_u32(&self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for MastNodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MastNodeId({})", self.0)
+    }
+}
+
+impl Serializable for MastNodeId {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u32(self.0);
+    }
+}
+
+/// Deserialized node IDs are not guaranteed to reference a node in any given forest; thus, they
+/// should be checked via [MastForest::contains()] before being used to access nodes of a forest.
+impl Deserializable for MastNodeId {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        Ok(Self(source.read_u32()?))
+    }
+}
+
+// MAST NODE
+// ================================================================================================
+
+/// A node of a [MastForest].
+///
+/// Leaf nodes are the same as the corresponding [CodeBlock] variants. Internal nodes (i.e., JOIN,
+/// SPLIT and LOOP nodes) reference their children by [MastNodeId] rather than owning them, and
+/// thus, the same child node can be shared by any number of parents.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MastNode {
+    Span(Span),
+    Join(JoinNode),
+    Split(SplitNode),
+    Loop(LoopNode),
+    Call(Call),
+    Dyn(Dyn),
+    Proxy(Proxy),
+}
+
+impl MastNode {
+    /// Returns true if this node is a SPAN node.
+    pub fn is_span(&self) -> bool {
+        matches!(self, MastNode::Span(_))
+    }
+
+    /// Returns a hash of this node.
+    ///
+    /// The hash of a node is the same as the hash of the equivalent [CodeBlock].
+    pub fn hash(&self) -> Digest {
+        match self {
+            MastNode::Span(node) => node.hash(),
+            MastNode::Join(node) => node.hash(),
+            MastNode::Split(node) => node.hash(),
+            MastNode::Loop(node) => node.hash(),
+            MastNode::Call(node) => node.hash(),
+            MastNode::Dyn(node) => node.hash(),
+            MastNode::Proxy(node) => node.hash(),
+        }
+    }
+
+    /// Returns the domain of this node.
+    pub fn domain(&self) -> Felt {
+        match self {
+            MastNode::Call(node) => node.domain(),
+            MastNode::Dyn(_) => Dyn::DOMAIN,
+            MastNode::Join(_) => Join::DOMAIN,
+            MastNode::Loop(_) => Loop::DOMAIN,
+            MastNode::Span(_) => Span::DOMAIN,
+            MastNode::Split(_) => Split::DOMAIN,
+            MastNode::Proxy(_) => panic!("Can't fetch `domain` for a `Proxy` node!"),
+        }
+    }
+}
+
+/// A node for sequential execution of two child nodes.
+///
+/// The hash of a join node is computed in the same way as the hash of a [Join] block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JoinNode {
+    children: [MastNodeId; 2],
+    hash: Digest,
+}
+
+impl JoinNode {
+    /// Returns a hash of this node.
+    pub fn hash(&self) -> Digest {
+        self.hash
+    }
+
+    /// Returns the ID of the node which is to be executed first when this node is executed.
+    pub fn first(&self) -> MastNodeId {
+        self.children[0]
+    }
+
+    /// Returns the ID of the node which is to be executed second when this node is executed.
+    pub fn second(&self) -> MastNodeId {
+        self.children[1]
+    }
+}
+
+/// A node for conditional execution of one of two child nodes.
+///
+/// The hash of a split node is computed in the same way as the hash of a [Split] block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SplitNode {
+    branches: [MastNodeId; 2],
+    hash: Digest,
+}
+
+impl SplitNode {
+    /// Returns a hash of this node.
+    pub fn hash(&self) -> Digest {
+        self.hash
+    }
+
+    /// Returns the ID of the node which is to be executed if the top of the stack is `1`.
+    pub fn on_true(&self) -> MastNodeId {
+        self.branches[0]
+    }
+
+    /// Returns the ID of the node which is to be executed if the top of the stack is `0`.
+    pub fn on_false(&self) -> MastNodeId {
+        self.branches[1]
+    }
+}
+
+/// A node for a conditional loop.
+///
+/// The hash of a loop node is computed in the same way as the hash of a [Loop] block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoopNode {
+    body: MastNodeId,
+    hash: Digest,
+}
+
+impl LoopNode {
+    /// Returns a hash of this node.
+    pub fn hash(&self) -> Digest {
+        self.hash
+    }
+
+    /// Returns the ID of the node which represents the body of the loop.
+    pub fn body(&self) -> MastNodeId {
+        self.body
+    }
+}
+
+// MAST FOREST
+// ================================================================================================
+
+/// A deduplicated Merkelized Abstract Syntax Tree (MAST) forest.
+///
+/// All nodes of the forest are stored in a single arena and are referenced by [MastNodeId].
+/// Internal nodes reference their children by ID, and thus, a sub-tree which appears in many
+/// places (e.g., the body of a procedure which is `exec`'d from many call sites) is stored only
+/// once.
+///
+/// When a node is added to the forest, the forest first checks whether an identical node is
+/// already present in it. If so, the ID of the existing node is returned. Nodes which have the
+/// same hash but differ in their decorators are stored as separate nodes.
+///
+/// Nodes can be added to the forest only after all of their children have been added. Thus,
+/// children always have smaller IDs than their parents.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MastForest {
+    nodes: Vec<MastNode>,
+    node_ids_by_hash: BTreeMap<[u8; 32], Vec<MastNodeId>>,
+}
+
+impl MastForest {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new empty [MastForest].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of nodes in this forest.
+    pub fn num_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns true if this forest does not contain any nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the node with the specified ID, or None if the node is not present in this forest.
+    pub fn get(&self, node_id: MastNodeId) -> Option<&MastNode> {
+        self.nodes.get(node_id.as_usize())
+    }
+
+    /// Returns an iterator over all nodes of this forest in the order of their IDs.
+    pub fn nodes(&self) -> impl Iterator<Item = (MastNodeId, &MastNode)> {
+        self.nodes.iter().enumerate().map(|(idx, node)| (MastNodeId(idx as u32), node))
+    }
+
+    /// Returns the ID of a node with the specified hash, or None if no such node is present in
+    /// this forest.
+    ///
+    /// If there are several nodes with the specified hash, the ID of the node which was added to
+    /// the forest first is returned.
+    pub fn find_node(&self, hash: Digest) -> Option<MastNodeId> {
+        let key: [u8; 32] = hash.into();
+        self.node_ids_by_hash.get(&key).map(|ids| ids[0])
+    }
+
+    /// Returns true if a node with the specified ID is present in this forest.
+    pub fn contains(&self, node_id: MastNodeId) -> bool {
+        node_id.as_usize() < self.nodes.len()
+    }
+
+    /// Returns a [CodeBlock] tree equivalent to the sub-tree rooted at the specified node.
+    ///
+    /// # Panics
+    /// Panics if the node with the specified ID is not present in this forest.
+    pub fn to_code_block(&self, node_id: MastNodeId) -> CodeBlock {
+        match &self[node_id] {
+            MastNode::Span(span) => CodeBlock::Span(span.clone()),
+            MastNode::Join(node) => CodeBlock::new_join([
+                self.to_code_block(node.first()),
+                self.to_code_block(node.second()),
+            ]),
+            MastNode::Split(node) => CodeBlock::new_split(
+                self.to_code_block(node.on_true()),
+                self.to_code_block(node.on_false()),
+            ),
+            MastNode::Loop(node) => CodeBlock::new_loop(self.to_code_block(node.body())),
+            MastNode::Call(call) => CodeBlock::Call(call.clone()),
+            MastNode::Dyn(block) => CodeBlock::Dyn(block.clone()),
+            MastNode::Proxy(proxy) => CodeBlock::Proxy(proxy.clone()),
+        }
+    }
+
+    // NODE BUILDERS
+    // --------------------------------------------------------------------------------------------
+
+    /// Adds a SPAN node with the specified operations and decorators to this forest and returns
+    /// its ID.
+    pub fn add_span(
+        &mut self,
+        operations: Vec<Operation>,
+        decorators: DecoratorList,
+    ) -> MastNodeId {
+        self.add_node(MastNode::Span(Span::with_decorators(operations, decorators)))
+    }
+
+    /// Adds a JOIN node with the specified children to this forest and returns its ID.
+    ///
+    /// # Panics
+    /// Panics if any of the children is not present in this forest.
+    pub fn add_join(&mut self, first: MastNodeId, second: MastNodeId) -> MastNodeId {
+        let hash =
+            hasher::merge_in_domain(&[self[first].hash(), self[second].hash()], Join::DOMAIN);
+        self.add_node(MastNode::Join(JoinNode {
+            children: [first, second],
+            hash,
+        }))
+    }
+
+    /// Adds a SPLIT node with the specified branches to this forest and returns its ID.
+    ///
+    /// # Panics
+    /// Panics if any of the branches is not present in this forest.
+    pub fn add_split(&mut self, on_true: MastNodeId, on_false: MastNodeId) -> MastNodeId {
+        let hash =
+            hasher::merge_in_domain(&[self[on_true].hash(), self[on_false].hash()], Split::DOMAIN);
+        self.add_node(MastNode::Split(SplitNode {
+            branches: [on_true, on_false],
+            hash,
+        }))
+    }
+
+    /// Adds a LOOP node with the specified body to this forest and returns its ID.
+    ///
+    /// # Panics
+    /// Panics if the body is not present in this forest.
+    pub fn add_loop(&mut self, body: MastNodeId) -> MastNodeId {
+        let hash = hasher::merge_in_domain(&[self[body].hash(), Digest::default()], Loop::DOMAIN);
+        self.add_node(MastNode::Loop(LoopNode { body, hash }))
+    }
+
+    /// Adds a CALL node for a function with the specified hash to this forest and returns its ID.
+    pub fn add_call(&mut self, fn_hash: Digest) -> MastNodeId {
+        self.add_node(MastNode::Call(Call::new(fn_hash)))
+    }
+
+    /// Adds a SYSCALL node for a kernel function with the specified hash to this forest and
+    /// returns its ID.
+    pub fn add_syscall(&mut self, fn_hash: Digest) -> MastNodeId {
+        self.add_node(MastNode::Call(Call::new_syscall(fn_hash)))
+    }
+
+    /// Adds a DYN node to this forest and returns its ID.
+    pub fn add_dyn(&mut self) -> MastNodeId {
+        self.add_node(MastNode::Dyn(Dyn::new()))
+    }
+
+    /// Adds a CALL node whose target is DYN to this forest and returns its ID.
+    pub fn add_dyncall(&mut self) -> MastNodeId {
+        self.add_call(Dyn::dyn_hash())
+    }
+
+    /// Adds a PROXY node for the code with the specified hash to this forest and returns its ID.
+    pub fn add_proxy(&mut self, code_hash: Digest) -> MastNodeId {
+        self.add_node(MastNode::Proxy(Proxy::new(code_hash)))
+    }
+
+    /// Adds all nodes of the specified [CodeBlock] tree to this forest and returns the ID of the
+    /// node corresponding to the root of the tree.
+    pub fn add_code_block(&mut self, block: &CodeBlock) -> MastNodeId {
+        match block {
+            CodeBlock::Span(span) => self.add_node(MastNode::Span(span.clone())),
+            CodeBlock::Join(join) => {
+                let first = self.add_code_block(join.first());
+                let second = self.add_code_block(join.second());
+                self.add_join(first, second)
+            }
+            CodeBlock::Split(split) => {
+                let on_true = self.add_code_block(split.on_true());
+                let on_false = self.add_code_block(split.on_false());
+                self.add_split(on_true, on_false)
+            }
+            CodeBlock::Loop(loop_block) => {
+                let body = self.add_code_block(loop_block.body());
+                self.add_loop(body)
+            }
+            CodeBlock::Call(call) => self.add_node(MastNode::Call(call.clone())),
+            CodeBlock::Dyn(block) => self.add_node(MastNode::Dyn(block.clone())),
+            CodeBlock::Proxy(proxy) => self.add_node(MastNode::Proxy(proxy.clone())),
+        }
+    }
+
+    /// Copies the sub-tree rooted at the specified node of the `source` forest into this forest
+    /// and returns the ID of the root of the copied sub-tree in this forest.
+    ///
+    /// This can be used to extract a compact forest containing only the nodes reachable from a
+    /// given set of roots.
+    ///
+    /// # Panics
+    /// Panics if the node with the specified ID is not present in the `source` forest.
+    pub fn add_subtree(&mut self, source: &MastForest, root: MastNodeId) -> MastNodeId {
+        let mut id_map = BTreeMap::new();
+        self.copy_subtree(source, root, &mut id_map)
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Adds the specified node to this forest, unless an identical node is already present in
+    /// it, and returns the ID of the node.
+    fn add_node(&mut self, node: MastNode) -> MastNodeId {
+        let key: [u8; 32] = node.hash().into();
+        let ids = self.node_ids_by_hash.entry(key).or_default();
+        if let Some(&id) = ids.iter().find(|id| self.nodes[id.as_usize()] == node) {
+            return id;
+        }
+
+        assert!(self.nodes.len() < u32::MAX as usize, "too many nodes in MAST forest");
+        let id = MastNodeId(self.nodes.len() as u32);
+        self.nodes.push(node);
+        ids.push(id);
+        id
+    }
+
+    /// Copies the sub-tree rooted at the specified node of the `source` forest into this forest
+    /// using `id_map` to memoize the nodes which have already been copied.
+    fn copy_subtree(
+        &mut self,
+        source: &MastForest,
+        node_id: MastNodeId,
+        id_map: &mut BTreeMap<MastNodeId, MastNodeId>,
+    ) -> MastNodeId {
+        if let Some(&id) = id_map.get(&node_id) {
+            return id;
+        }
+
+        let id = match &source[node_id] {
+            MastNode::Join(node) => {
+                let first = self.copy_subtree(source, node.first(), id_map);
+                let second = self.copy_subtree(source, node.second(), id_map);
+                self.add_join(first, second)
+            }
+            MastNode::Split(node) => {
+                let on_true = self.copy_subtree(source, node.on_true(), id_map);
+                let on_false = self.copy_subtree(source, node.on_false(), id_map);
+                self.add_split(on_true, on_false)
+            }
+            MastNode::Loop(node) => {
+                let body = self.copy_subtree(source, node.body(), id_map);
+                self.add_loop(body)
+            }
+            leaf => self.add_node(leaf.clone()),
+        };
+
+        id_map.insert(node_id, id);
+        id
+    }
+
+    /// Writes a textual representation of the sub-tree rooted at the specified node into the
+    /// provided formatter. The output is the same as for the equivalent [CodeBlock].
+    pub(super) fn fmt_node(&self, f: &mut fmt::Formatter<'_>, node_id: MastNodeId) -> fmt::Result {
+        match &self[node_id] {
+            MastNode::Span(span) => write!(f, "{span}"),
+            MastNode::Join(node) => {
+                write!(f, "join ")?;
+                self.fmt_node(f, node.first())?;
+                write!(f, " ")?;
+                self.fmt_node(f, node.second())?;
+                write!(f, " end")
+            }
+            MastNode::Split(node) => {
+                write!(f, "if.true ")?;
+                self.fmt_node(f, node.on_true())?;
+                write!(f, " else ")?;
+                self.fmt_node(f, node.on_false())?;
+                write!(f, " end")
+            }
+            MastNode::Loop(node) => {
+                write!(f, "while.true ")?;
+                self.fmt_node(f, node.body())?;
+                write!(f, " end")
+            }
+            MastNode::Call(call) => write!(f, "{call}"),
+            MastNode::Dyn(block) => write!(f, "{block}"),
+            MastNode::Proxy(proxy) => write!(f, "{proxy}"),
+        }
+    }
+}
+
+impl Index<MastNodeId> for MastForest {
+    type Output = MastNode;
+
+    fn index(&self, node_id: MastNodeId) -> &Self::Output {
+        &self.nodes[node_id.as_usize()]
+    }
+}
@@ -55,8 +55,8 @@ impl ProgramInfo {
 
 impl From<Program> for ProgramInfo {
     fn from(program: Program) -> Self {
-        let Program { root, kernel, .. } = program;
-        let program_hash = root.hash();
+        let program_hash = program.hash();
+        let Program { kernel, .. } = program;
 
         Self {
             program_hash,
@@ -2,16 +2,33 @@ use super::{
     chiplets::hasher::{self, Digest},
     errors, Felt, Operation,
 };
-use crate::utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
-use alloc::{collections::BTreeMap, vec::Vec};
+use crate::utils::{
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
+};
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
 use core::fmt;
 
+#[cfg(feature = "std")]
+use std::{fs, io, path::Path, string::ToString};
+
 pub mod blocks;
 use blocks::CodeBlock;
 
+mod data;
+pub use data::DataSegment;
+
+mod forest;
+pub use forest::{JoinNode, LoopNode, MastForest, MastNode, MastNodeId, SplitNode};
+
 mod info;
 pub use info::ProgramInfo;
 
+mod serialization;
+pub use serialization::MastSerdeOptions;
+
+mod source_map;
+pub use source_map::{CodeLocation, ProcedureLocation, SourceMap, SpanLocations};
+
 #[cfg(test)]
 mod tests;
 
@@ -20,13 +37,24 @@ mod tests;
 /// A program which can be executed by the VM.
 ///
 /// A program is described by a Merkelized Abstract Syntax Tree (MAST), where each node is a
-/// [CodeBlock]. Internal nodes describe control flow semantics of the program, while leaf nodes
+/// [MastNode]. Internal nodes describe control flow semantics of the program, while leaf nodes
 /// contain linear sequences of instructions which contain no control flow.
-#[derive(Clone, Debug)]
+///
+/// The nodes of the program MAST are stored in a deduplicated [MastForest], which also contains
+/// the procedures referenced from the code block table of the program.
+///
+/// A program may also carry a table of error messages keyed by error codes, which is used to
+/// explain failed assertions with these error codes, and a list of [DataSegment]s which the
+/// program loads into memory at the start of its execution.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Program {
-    root: CodeBlock,
+    forest: MastForest,
+    entrypoint: MastNodeId,
     kernel: Kernel,
     cb_table: CodeBlockTable,
+    source_map: SourceMap,
+    error_messages: BTreeMap<u32, String>,
+    data_segments: Vec<DataSegment>,
 }
 
 impl Program {
@@ -34,29 +62,112 @@ impl Program {
     // --------------------------------------------------------------------------------------------
     /// Instantiates a new [Program] from the specified code block.
     pub fn new(root: CodeBlock) -> Self {
-        Self::with_kernel(root, Kernel::default(), CodeBlockTable::default())
+        Self::with_kernel(root, Kernel::default(), Vec::new())
     }
 
-    /// Instantiates a new [Program] from the specified code block and associated code block table.
-    pub fn with_kernel(root: CodeBlock, kernel: Kernel, cb_table: CodeBlockTable) -> Self {
+    /// Instantiates a new [Program] from the specified code block, kernel, and a list of code
+    /// blocks which are to be placed into the code block table of the program.
+    pub fn with_kernel(root: CodeBlock, kernel: Kernel, cb_table_blocks: Vec<CodeBlock>) -> Self {
+        let mut forest = MastForest::new();
+        let entrypoint = forest.add_code_block(&root);
+
+        let mut cb_table = CodeBlockTable::default();
+        for block in cb_table_blocks.iter() {
+            let node_id = forest.add_code_block(block);
+            cb_table.insert(block.hash(), node_id);
+        }
+
+        Self::from_forest(forest, entrypoint, kernel, cb_table)
+    }
+
+    /// Instantiates a new [Program] from the specified MAST forest, entrypoint, kernel, and code
+    /// block table.
+    ///
+    /// # Panics
+    /// Panics if the entrypoint or any of the nodes referenced from the code block table is not
+    /// present in the specified forest, or if the nodes referenced from the code block table do
+    /// not match their hashes in the table.
+    pub fn from_forest(
+        forest: MastForest,
+        entrypoint: MastNodeId,
+        kernel: Kernel,
+        cb_table: CodeBlockTable,
+    ) -> Self {
+        assert!(forest.contains(entrypoint), "entrypoint {entrypoint} not in MAST forest");
+        for (&key, &node_id) in cb_table.0.iter() {
+            assert!(forest.contains(node_id), "code block {node_id} not in MAST forest");
+            assert_eq!(key, <[u8; 32]>::from(forest[node_id].hash()), "code block hash mismatch");
+        }
+
         Self {
-            root,
+            forest,
+            entrypoint,
             kernel,
             cb_table,
+            source_map: SourceMap::default(),
+            error_messages: BTreeMap::new(),
+            data_segments: Vec::new(),
         }
     }
 
+    /// Returns this [Program] with the specified source map attached to it.
+    ///
+    /// The source map does not affect the hash of the program; it is used only to map operations
+    /// executed by the VM to the locations in the source code they were compiled from.
+    pub fn with_source_map(mut self, source_map: SourceMap) -> Self {
+        self.source_map = source_map;
+        self
+    }
+
+    /// Returns this [Program] with the specified error messages, keyed by error codes, attached
+    /// to it.
+    ///
+    /// The error messages do not affect the hash of the program; they are used only to explain
+    /// failed assertions.
+    pub fn with_error_messages(mut self, error_messages: BTreeMap<u32, String>) -> Self {
+        self.error_messages = error_messages;
+        self
+    }
+
+    /// Returns this [Program] with the specified data segments attached to it.
+    ///
+    /// The data segments must be loaded into memory by the code of the program; attaching them to
+    /// the program does not affect its hash, but makes them available to the VM (which provides
+    /// them to the program via the advice map) and to other tools.
+    pub fn with_data_segments(mut self, data_segments: Vec<DataSegment>) -> Self {
+        self.data_segments = data_segments;
+        self
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
-    /// Returns the root code block of this program.
-    pub fn root(&self) -> &CodeBlock {
-        &self.root
+    /// Returns the MAST forest containing all nodes of this program.
+    pub fn forest(&self) -> &MastForest {
+        &self.forest
+    }
+
+    /// Returns the ID of the root node of this program.
+    pub fn entrypoint(&self) -> MastNodeId {
+        self.entrypoint
+    }
+
+    /// Returns the root node of this program.
+    pub fn root(&self) -> &MastNode {
+        &self.forest[self.entrypoint]
+    }
+
+    /// Returns the node with the specified ID.
+    ///
+    /// # Panics
+    /// Panics if the node with the specified ID is not present in the forest of this program.
+    pub fn get_node(&self, node_id: MastNodeId) -> &MastNode {
+        &self.forest[node_id]
     }
 
     /// Returns a hash of this program.
     pub fn hash(&self) -> Digest {
-        self.root.hash()
+        self.root().hash()
     }
 
     /// Returns a kernel for this program.
@@ -68,31 +179,124 @@ impl Program {
     pub fn cb_table(&self) -> &CodeBlockTable {
         &self.cb_table
     }
+
+    /// Returns the source map of this program.
+    ///
+    /// The source map is empty unless the program was assembled with source maps enabled.
+    pub fn source_map(&self) -> &SourceMap {
+        &self.source_map
+    }
+
+    /// Returns the error messages of this program, keyed by error codes.
+    pub fn error_messages(&self) -> &BTreeMap<u32, String> {
+        &self.error_messages
+    }
+
+    /// Returns the error message for the specified error code, if this program has one.
+    pub fn get_error_message(&self, err_code: u32) -> Option<&str> {
+        self.error_messages.get(&err_code).map(String::as_str)
+    }
+
+    /// Returns the data segments which this program loads into memory at the start of its
+    /// execution.
+    pub fn data_segments(&self) -> &[DataSegment] {
+        &self.data_segments
+    }
+
+    // SERIALIZATION / DESERIALIZATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Writes byte representation of this [Program] into the specified target according with the
+    /// specified serde options.
+    ///
+    /// The serde options are serialized as header information for the purposes of deserialization.
+    pub fn write_into<W: ByteWriter>(&self, target: &mut W, options: MastSerdeOptions) {
+        serialization::write_program(self, target, options)
+    }
+
+    /// Returns byte representation of this [Program].
+    ///
+    /// The serde options are serialized as header information for the purposes of deserialization.
+    pub fn to_bytes(&self, options: MastSerdeOptions) -> Vec<u8> {
+        let mut target = Vec::<u8>::default();
+        self.write_into(&mut target, options);
+        target
+    }
+
+    /// Returns a [Program] deserialized from the specified reader.
+    ///
+    /// # Errors
+    /// Returns an error if the reader does not contain a valid serialized program, or if the hash
+    /// of the deserialized program differs from the hash of the program which was serialized.
+    pub fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        serialization::read_program(source)
+    }
+
+    /// Returns a [Program] deserialized from the provided bytes.
+    ///
+    /// # Errors
+    /// Returns an error if the bytes do not contain a valid serialized program, or if the hash of
+    /// the deserialized program differs from the hash of the program which was serialized.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializationError> {
+        let mut source = SliceReader::new(bytes);
+        Self::read_from(&mut source)
+    }
+
+    // FILE I/O
+    // --------------------------------------------------------------------------------------------
+
+    /// Writes this [Program] into the file at the specified path according with the specified
+    /// serde options.
+    #[cfg(feature = "std")]
+    pub fn write_to_file<P>(&self, file_path: P, options: MastSerdeOptions) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let path = file_path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        fs::write(path, self.to_bytes(options))
+    }
+
+    /// Reads a [Program] from the file at the specified path.
+    #[cfg(feature = "std")]
+    pub fn read_from_file<P>(file_path: P) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let bytes = fs::read(file_path)?;
+        Self::from_bytes(&bytes)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))
+    }
 }
 
 impl fmt::Display for Program {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "begin {} end", self.root)
+        write!(f, "begin ")?;
+        self.forest.fmt_node(f, self.entrypoint)?;
+        write!(f, " end")
     }
 }
 
 // CODE BLOCK TABLE
 // ================================================================================================
 
-/// A map of code block hashes to their underlying code blocks.
+/// A map of code block hashes to the IDs of their root nodes in the MAST forest of a program.
 ///
 /// This table is used to hold code blocks which are referenced from the program MAST but are
-/// actually not a part of the MAST itself. Thus, for example, multiple nodes in the MAST can
-/// reference the same code block in the table.
-#[derive(Clone, Debug, Default)]
-pub struct CodeBlockTable(BTreeMap<[u8; 32], CodeBlock>);
+/// actually not a part of the MAST itself (e.g., targets of CALL and SYSCALL blocks). Thus, for
+/// example, multiple nodes in the MAST can reference the same code block in the table.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CodeBlockTable(BTreeMap<[u8; 32], MastNodeId>);
 
 impl CodeBlockTable {
-    /// Returns a code block for the specified hash, or None if the code block is not present
-    /// in this table.
-    pub fn get(&self, hash: Digest) -> Option<&CodeBlock> {
+    /// Returns the ID of the root node of a code block with the specified hash, or None if the
+    /// code block is not present in this table.
+    pub fn get(&self, hash: Digest) -> Option<MastNodeId> {
         let key: [u8; 32] = hash.into();
-        self.0.get(&key)
+        self.0.get(&key).copied()
     }
 
     /// Returns true if a code block with the specified hash is present in this table.
@@ -101,16 +305,29 @@ impl CodeBlockTable {
         self.0.contains_key(&key)
     }
 
-    /// Inserts the provided code block into this table.
-    pub fn insert(&mut self, block: CodeBlock) {
-        let key: [u8; 32] = block.hash().into();
-        self.0.insert(key, block);
+    /// Inserts a code block with the specified hash and root node ID into this table.
+    pub fn insert(&mut self, hash: Digest, node_id: MastNodeId) {
+        let key: [u8; 32] = hash.into();
+        self.0.insert(key, node_id);
+    }
+
+    /// Returns the number of code blocks in this table.
+    pub fn len(&self) -> usize {
+        self.0.len()
     }
 
     /// Returns true if this code block table is empty.
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    /// Returns an iterator over the hashes and root node IDs of code blocks in this table.
+    pub fn iter(&self) -> impl Iterator<Item = (Digest, MastNodeId)> + '_ {
+        self.0.iter().map(|(key, &node_id)| {
+            let hash = Digest::try_from(key).expect("invalid code block hash");
+            (hash, node_id)
+        })
+    }
 }
 
 // KERNEL
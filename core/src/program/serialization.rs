@@ -0,0 +1,347 @@
+//! Serialization and deserialization of compiled programs.
+//!
+//! A program is serialized as its fully assembled MAST, so that it can be loaded and executed
+//! without re-running the assembler. The binary format is as follows:
+//!
+//! - 4 magic bytes (`MAST`) followed by a single format version byte.
+//! - Serialization options ([MastSerdeOptions]).
+//! - Program hash, used to verify the integrity of the deserialized program.
+//! - Program kernel.
+//! - Nodes of the program MAST forest in the order of their IDs. Internal nodes reference their
+//!   children by IDs, and since children are always added to the forest before their parents,
+//!   the nodes can be deserialized in a single pass.
+//! - ID of the program entrypoint node.
+//! - IDs of the root nodes of code blocks from the code block table of the program.
+//! - Error messages of the program, keyed by error codes.
+//! - Data segments of the program.
+//! - Source map of the program, only if debug info is serialized.
+
+use super::{
+    CodeBlockTable, DataSegment, Digest, Kernel, MastForest, MastNode, MastNodeId, Program,
+    SourceMap,
+};
+use crate::{
+    utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable},
+    Decorator, DecoratorList, Operation,
+};
+use alloc::{collections::BTreeMap, string::ToString, vec::Vec};
+use core::str::from_utf8;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Magic bytes identifying a serialized program MAST.
+const MAGIC: &[u8; 4] = b"MAST";
+
+/// The current version of the program MAST serialization format.
+const VERSION: u8 = 4;
+
+// MAST node tags
+const SPAN: u8 = 0;
+const JOIN: u8 = 1;
+const SPLIT: u8 = 2;
+const LOOP: u8 = 3;
+const CALL: u8 = 4;
+const SYSCALL: u8 = 5;
+const DYN: u8 = 6;
+const PROXY: u8 = 7;
+
+// SERIALIZATION OPTIONS
+// ================================================================================================
+
+/// Options used to enable or disable serialization of parts of the program MAST.
+///
+/// Serialization options are serialized along with the program to make the serialization format
+/// self-contained.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MastSerdeOptions {
+    /// Specifies whether decorators which carry only debug info (i.e., `AsmOp` and `Debug`
+    /// decorators) should be serialized. Decorators which may affect execution of a program
+    /// (advice injectors, events and traces) are always serialized. This also controls whether
+    /// the source map of the program is serialized.
+    pub serialize_debug_info: bool,
+}
+
+impl MastSerdeOptions {
+    pub const fn new(serialize_debug_info: bool) -> Self {
+        Self {
+            serialize_debug_info,
+        }
+    }
+}
+
+impl Default for MastSerdeOptions {
+    fn default() -> Self {
+        Self::new(true)
+    }
+}
+
+impl Serializable for MastSerdeOptions {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_bool(self.serialize_debug_info);
+    }
+}
+
+impl Deserializable for MastSerdeOptions {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let serialize_debug_info = source.read_bool()?;
+        Ok(Self::new(serialize_debug_info))
+    }
+}
+
+// PROGRAM
+// ================================================================================================
+
+/// Writes the provided program into the target according to the specified options.
+pub(super) fn write_program<W: ByteWriter>(
+    program: &Program,
+    target: &mut W,
+    options: MastSerdeOptions,
+) {
+    target.write_bytes(MAGIC);
+    target.write_u8(VERSION);
+    options.write_into(target);
+
+    program.hash().write_into(target);
+    program.kernel.write_into(target);
+
+    target.write_usize(program.forest.num_nodes());
+    for (_, node) in program.forest.nodes() {
+        write_node(target, node, options);
+    }
+
+    target.write_u32(program.entrypoint.as_u32());
+    target.write_usize(program.cb_table.len());
+    for (_, node_id) in program.cb_table.iter() {
+        target.write_u32(node_id.as_u32());
+    }
+
+    target.write_usize(program.error_messages.len());
+    for (err_code, msg) in program.error_messages.iter() {
+        target.write_u32(*err_code);
+        target.write_usize(msg.len());
+        target.write_bytes(msg.as_bytes());
+    }
+
+    target.write_usize(program.data_segments.len());
+    target.write_many(&program.data_segments);
+
+    if options.serialize_debug_info {
+        program.source_map.write_into(target);
+    }
+}
+
+/// Reads a program from the provided source.
+///
+/// # Errors
+/// Returns an error if the source does not contain a valid serialized program, or if the hash of
+/// the deserialized program does not match the hash it was serialized with.
+pub(super) fn read_program<R: ByteReader>(source: &mut R) -> Result<Program, DeserializationError> {
+    let magic: [u8; 4] = source.read_array()?;
+    if &magic != MAGIC {
+        return Err(DeserializationError::InvalidValue(format!(
+            "invalid magic bytes; expected {MAGIC:?}, but was {magic:?}"
+        )));
+    }
+
+    let version = source.read_u8()?;
+    if version != VERSION {
+        return Err(DeserializationError::InvalidValue(format!(
+            "unsupported MAST format version; expected {VERSION}, but was {version}"
+        )));
+    }
+    let options = MastSerdeOptions::read_from(source)?;
+
+    let program_hash = Digest::read_from(source)?;
+    let kernel = Kernel::read_from(source)?;
+
+    // when debug info is stripped, nodes which differed only in debug decorators become identical
+    // and are merged by the forest; thus, we need to map serialized node IDs to the actual ones.
+    let mut forest = MastForest::new();
+    let num_nodes = source.read_usize()?;
+    let mut node_ids = Vec::new();
+    for _ in 0..num_nodes {
+        let node_id = read_node(source, &mut forest, &node_ids, options)?;
+        node_ids.push(node_id);
+    }
+
+    let entrypoint = read_node_id(source, &node_ids)?;
+    let mut cb_table = CodeBlockTable::default();
+    let num_blocks = source.read_usize()?;
+    for _ in 0..num_blocks {
+        let node_id = read_node_id(source, &node_ids)?;
+        cb_table.insert(forest[node_id].hash(), node_id);
+    }
+
+    let mut error_messages = BTreeMap::new();
+    let num_messages = source.read_usize()?;
+    for _ in 0..num_messages {
+        let err_code = source.read_u32()?;
+        let len = source.read_usize()?;
+        let msg = source.read_vec(len)?;
+        let msg = from_utf8(&msg).map_err(|e| DeserializationError::InvalidValue(e.to_string()))?;
+        error_messages.insert(err_code, msg.to_string());
+    }
+
+    let num_segments = source.read_usize()?;
+    let data_segments = read_elements::<_, DataSegment>(source, num_segments)?;
+
+    let mut program = Program::from_forest(forest, entrypoint, kernel, cb_table)
+        .with_error_messages(error_messages)
+        .with_data_segments(data_segments);
+    if options.serialize_debug_info {
+        program = program.with_source_map(SourceMap::read_from(source)?);
+    }
+    if program.hash() != program_hash {
+        return Err(DeserializationError::InvalidValue(format!(
+            "program hash mismatch; expected {program_hash:?}, but was {:?}",
+            program.hash()
+        )));
+    }
+
+    Ok(program)
+}
+
+/// Reads the specified number of elements from the provided source.
+///
+/// Unlike [ByteReader::read_many()], memory is not allocated upfront for all elements, since the
+/// number of elements is read from untrusted input and may exceed the size of the source.
+pub(super) fn read_elements<R: ByteReader, D: Deserializable>(
+    source: &mut R,
+    num_elements: usize,
+) -> Result<Vec<D>, DeserializationError> {
+    (0..num_elements).map(|_| D::read_from(source)).collect()
+}
+
+// MAST NODES
+// ================================================================================================
+
+/// Writes the provided MAST node into the target. Children of internal nodes are written as IDs.
+fn write_node<W: ByteWriter>(target: &mut W, node: &MastNode, options: MastSerdeOptions) {
+    match node {
+        MastNode::Span(span) => {
+            target.write_u8(SPAN);
+
+            let ops = span.op_batches().iter().flat_map(|batch| batch.ops()).collect::<Vec<_>>();
+            target.write_usize(ops.len());
+            target.write_many(ops);
+
+            let decorators = span
+                .decorators()
+                .iter()
+                .filter(|(_, decorator)| options.serialize_debug_info || !is_debug_info(decorator))
+                .collect::<Vec<_>>();
+            target.write_usize(decorators.len());
+            for (op_idx, decorator) in decorators {
+                target.write_usize(*op_idx);
+                decorator.write_into(target);
+            }
+        }
+        MastNode::Join(node) => {
+            target.write_u8(JOIN);
+            target.write_u32(node.first().as_u32());
+            target.write_u32(node.second().as_u32());
+        }
+        MastNode::Split(node) => {
+            target.write_u8(SPLIT);
+            target.write_u32(node.on_true().as_u32());
+            target.write_u32(node.on_false().as_u32());
+        }
+        MastNode::Loop(node) => {
+            target.write_u8(LOOP);
+            target.write_u32(node.body().as_u32());
+        }
+        MastNode::Call(call) => {
+            target.write_u8(if call.is_syscall() { SYSCALL } else { CALL });
+            call.fn_hash().write_into(target);
+        }
+        MastNode::Dyn(_) => target.write_u8(DYN),
+        MastNode::Proxy(proxy) => {
+            target.write_u8(PROXY);
+            proxy.hash().write_into(target);
+        }
+    }
+}
+
+/// Reads a MAST node from the provided source, adds it to the forest, and returns its ID.
+///
+/// `node_ids` maps serialized IDs of the previously read nodes to their IDs in the forest.
+fn read_node<R: ByteReader>(
+    source: &mut R,
+    forest: &mut MastForest,
+    node_ids: &[MastNodeId],
+    options: MastSerdeOptions,
+) -> Result<MastNodeId, DeserializationError> {
+    match source.read_u8()? {
+        SPAN => {
+            let num_ops = source.read_usize()?;
+            if num_ops == 0 {
+                return Err(DeserializationError::InvalidValue(
+                    "span block must contain at least one operation".into(),
+                ));
+            }
+            let ops = read_elements::<_, Operation>(source, num_ops)?;
+
+            let num_decorators = source.read_usize()?;
+            let mut decorators = DecoratorList::new();
+            for _ in 0..num_decorators {
+                let op_idx = source.read_usize()?;
+                if op_idx > num_ops || decorators.last().is_some_and(|(idx, _)| *idx > op_idx) {
+                    return Err(DeserializationError::InvalidValue(format!(
+                        "invalid decorator operation index {op_idx}"
+                    )));
+                }
+                let decorator = Decorator::read_from(source)?;
+                if !options.serialize_debug_info && is_debug_info(&decorator) {
+                    return Err(DeserializationError::InvalidValue(format!(
+                        "unexpected debug decorator {decorator}"
+                    )));
+                }
+                decorators.push((op_idx, decorator));
+            }
+
+            Ok(forest.add_span(ops, decorators))
+        }
+        JOIN => {
+            let first = read_node_id(source, node_ids)?;
+            let second = read_node_id(source, node_ids)?;
+            Ok(forest.add_join(first, second))
+        }
+        SPLIT => {
+            let on_true = read_node_id(source, node_ids)?;
+            let on_false = read_node_id(source, node_ids)?;
+            Ok(forest.add_split(on_true, on_false))
+        }
+        LOOP => {
+            let body = read_node_id(source, node_ids)?;
+            Ok(forest.add_loop(body))
+        }
+        CALL => Ok(forest.add_call(Digest::read_from(source)?)),
+        SYSCALL => Ok(forest.add_syscall(Digest::read_from(source)?)),
+        DYN => Ok(forest.add_dyn()),
+        PROXY => Ok(forest.add_proxy(Digest::read_from(source)?)),
+        tag => Err(DeserializationError::InvalidValue(format!("invalid MAST node tag: {tag}"))),
+    }
+}
+
+/// Reads a serialized node ID from the provided source and maps it to the ID of the node in the
+/// forest. Only IDs of the nodes which have already been read are considered valid.
+fn read_node_id<R: ByteReader>(
+    source: &mut R,
+    node_ids: &[MastNodeId],
+) -> Result<MastNodeId, DeserializationError> {
+    let id = source.read_u32()?;
+    node_ids
+        .get(id as usize)
+        .copied()
+        .ok_or_else(|| DeserializationError::InvalidValue(format!("invalid MAST node ID {id}")))
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns true if the provided decorator carries only debug info and, thus, does not affect
+/// execution of a program.
+fn is_debug_info(decorator: &Decorator) -> bool {
+    matches!(decorator, Decorator::AsmOp(_) | Decorator::Debug(_))
+}
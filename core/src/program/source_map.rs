@@ -0,0 +1,402 @@
+use super::Digest;
+use crate::utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use core::{fmt, ops::Range};
+
+// CODE LOCATION
+// ================================================================================================
+
+/// A location in the source code of a module.
+///
+/// The location is described by the path of the module (e.g., `std::math::u64` or `#exec` for
+/// the executable module of a program) as well as the line and the column of the source item,
+/// both starting at 1.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CodeLocation {
+    path: String,
+    line: u32,
+    column: u32,
+}
+
+impl CodeLocation {
+    /// Returns a new [CodeLocation] instantiated with the specified module path, line, and column.
+    pub fn new<P: Into<String>>(path: P, line: u32, column: u32) -> Self {
+        Self {
+            path: path.into(),
+            line,
+            column,
+        }
+    }
+
+    /// Returns the path of the module this location belongs to.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the line of this location.
+    pub const fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// Returns the column of this location.
+    pub const fn column(&self) -> u32 {
+        self.column
+    }
+}
+
+impl fmt::Display for CodeLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.path, self.line, self.column)
+    }
+}
+
+impl Serializable for CodeLocation {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_usize(self.path.len());
+        target.write_bytes(self.path.as_bytes());
+        target.write_u32(self.line);
+        target.write_u32(self.column);
+    }
+}
+
+impl Deserializable for CodeLocation {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let path_len = source.read_usize()?;
+        let path = source.read_vec(path_len)?;
+        let path = String::from_utf8(path)
+            .map_err(|err| DeserializationError::InvalidValue(format!("{err}")))?;
+        let line = source.read_u32()?;
+        let column = source.read_u32()?;
+        Ok(Self { path, line, column })
+    }
+}
+
+// PROCEDURE LOCATION
+// ================================================================================================
+
+/// A location in the source code of a compiled procedure.
+///
+/// The location consists of the name of the procedure, the location of its declaration, and, if
+/// the procedure was compiled into a single SPAN block, the location of the instruction the first
+/// operation of the block was compiled from. Since such blocks are merged with adjacent SPAN
+/// blocks when procedures are inlined (e.g., via `exec`), the executions of such procedures can be
+/// identified only via the executions of their first instructions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcedureLocation {
+    name: String,
+    declaration: CodeLocation,
+    entry: Option<CodeLocation>,
+}
+
+impl ProcedureLocation {
+    /// Returns a new [ProcedureLocation] instantiated with the specified name, location of the
+    /// declaration, and location of the first instruction.
+    pub fn new<S: Into<String>>(
+        name: S,
+        declaration: CodeLocation,
+        entry: Option<CodeLocation>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            declaration,
+            entry,
+        }
+    }
+
+    /// Returns the name of the procedure.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the location of the declaration of the procedure.
+    pub fn declaration(&self) -> &CodeLocation {
+        &self.declaration
+    }
+
+    /// Returns the location of the first instruction of the procedure if the procedure was
+    /// compiled into a single SPAN block, or None otherwise.
+    pub fn entry(&self) -> Option<&CodeLocation> {
+        self.entry.as_ref()
+    }
+}
+
+impl Serializable for ProcedureLocation {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_usize(self.name.len());
+        target.write_bytes(self.name.as_bytes());
+        self.declaration.write_into(target);
+        self.entry.write_into(target);
+    }
+}
+
+impl Deserializable for ProcedureLocation {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let name_len = source.read_usize()?;
+        let name = String::from_utf8(source.read_vec(name_len)?)
+            .map_err(|err| DeserializationError::InvalidValue(format!("{err}")))?;
+        let declaration = CodeLocation::read_from(source)?;
+        let entry = Option::<CodeLocation>::read_from(source)?;
+        Ok(Self {
+            name,
+            declaration,
+            entry,
+        })
+    }
+}
+
+// SPAN LOCATIONS
+// ================================================================================================
+
+/// Locations in the source code of the operations of a single SPAN block.
+///
+/// Each entry maps a contiguous range of operation indexes to the location of the instruction the
+/// operations were compiled from. Operation indexes are the indexes of operations in the SPAN
+/// block, i.e., the same indexes decorators of the block are keyed by. The ranges are sorted and
+/// do not overlap; operations which are not covered by any range have no known location.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpanLocations(Vec<(Range<usize>, CodeLocation)>);
+
+impl SpanLocations {
+    /// Returns a new [SpanLocations] instantiated from the specified entries.
+    ///
+    /// Empty ranges are discarded.
+    ///
+    /// # Panics
+    /// Panics if the ranges are not sorted or if they overlap.
+    pub fn new(entries: Vec<(Range<usize>, CodeLocation)>) -> Self {
+        let entries: Vec<_> = entries.into_iter().filter(|(range, _)| !range.is_empty()).collect();
+        assert!(
+            entries.windows(2).all(|pair| pair[0].0.end <= pair[1].0.start),
+            "span location ranges must be sorted and must not overlap"
+        );
+        Self(entries)
+    }
+
+    /// Returns the location of the operation at the specified index, or None if the location of
+    /// the operation is not known.
+    pub fn get(&self, op_idx: usize) -> Option<&CodeLocation> {
+        let pos = self.0.partition_point(|(range, _)| range.end <= op_idx);
+        self.0
+            .get(pos)
+            .filter(|(range, _)| range.contains(&op_idx))
+            .map(|(_, location)| location)
+    }
+
+    /// Returns the number of entries in this list.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if this list does not contain any entries.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator over the operation ranges and their locations.
+    pub fn iter(&self) -> impl Iterator<Item = &(Range<usize>, CodeLocation)> {
+        self.0.iter()
+    }
+}
+
+impl Serializable for SpanLocations {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_usize(self.0.len());
+        for (range, location) in self.0.iter() {
+            target.write_usize(range.start);
+            target.write_usize(range.end);
+            location.write_into(target);
+        }
+    }
+}
+
+impl Deserializable for SpanLocations {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let num_entries = source.read_usize()?;
+        let mut entries: Vec<(Range<usize>, CodeLocation)> = Vec::new();
+        for _ in 0..num_entries {
+            let start = source.read_usize()?;
+            let end = source.read_usize()?;
+            let prev_end = entries.last().map_or(0, |(range, _)| range.end);
+            if start >= end || start < prev_end {
+                return Err(DeserializationError::InvalidValue(format!(
+                    "invalid span location range {start}..{end}"
+                )));
+            }
+            entries.push((start..end, CodeLocation::read_from(source)?));
+        }
+        Ok(Self(entries))
+    }
+}
+
+// SOURCE MAP
+// ================================================================================================
+
+/// A map from operations of a program to the locations in the source code they were compiled
+/// from.
+///
+/// The map is keyed by the hash of a SPAN block and the index of an operation in the block. Since
+/// SPAN blocks are deduplicated by their hashes, if identical blocks were compiled from several
+/// places in the source code, only the location of the block which was added to the map first is
+/// retained.
+///
+/// In addition, the map contains the locations of the compiled procedures (see
+/// [ProcedureLocation]), keyed by their MAST roots, and the locations of the conditional branches (i.e.,
+/// `if.true` and `while.true` statements), keyed by the hashes of the SPLIT and LOOP blocks they
+/// were compiled into. Identical branches are deduplicated in the same way as SPAN blocks; all
+/// procedures with the same MAST root are retained.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    spans: BTreeMap<[u8; 32], SpanLocations>,
+    procedures: BTreeMap<[u8; 32], Vec<ProcedureLocation>>,
+    branches: BTreeMap<[u8; 32], CodeLocation>,
+}
+
+impl SourceMap {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new empty [SourceMap].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the location of the operation at the specified index in the SPAN block with the
+    /// specified hash, or None if the location of the operation is not known.
+    pub fn get(&self, span_hash: Digest, op_idx: usize) -> Option<&CodeLocation> {
+        self.get_span(span_hash).and_then(|locations| locations.get(op_idx))
+    }
+
+    /// Returns the locations of the operations of the SPAN block with the specified hash, or None
+    /// if the block is not present in this map.
+    pub fn get_span(&self, span_hash: Digest) -> Option<&SpanLocations> {
+        let key: [u8; 32] = span_hash.into();
+        self.spans.get(&key)
+    }
+
+    /// Returns the number of SPAN blocks in this map.
+    pub fn num_spans(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Returns true if this map does not contain any locations.
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty() && self.procedures.is_empty() && self.branches.is_empty()
+    }
+
+    /// Returns an iterator over the hashes of SPAN blocks in this map and their locations.
+    pub fn iter(&self) -> impl Iterator<Item = (Digest, &SpanLocations)> + '_ {
+        self.spans.iter().map(|(key, locations)| {
+            let hash = Digest::try_from(key).expect("invalid span hash");
+            (hash, locations)
+        })
+    }
+
+    /// Returns an iterator over the MAST roots of the procedures in this map and the locations of
+    /// the procedures.
+    pub fn procedures(&self) -> impl Iterator<Item = (Digest, &ProcedureLocation)> + '_ {
+        self.procedures.iter().flat_map(|(key, procs)| {
+            let mast_root = Digest::try_from(key).expect("invalid procedure MAST root");
+            procs.iter().map(move |location| (mast_root, location))
+        })
+    }
+
+    /// Returns the location of the conditional branch compiled into the SPLIT or LOOP block with
+    /// the specified hash, or None if the block is not present in this map.
+    pub fn get_branch(&self, block_hash: Digest) -> Option<&CodeLocation> {
+        let key: [u8; 32] = block_hash.into();
+        self.branches.get(&key)
+    }
+
+    /// Returns an iterator over the hashes of the SPLIT and LOOP blocks in this map and the
+    /// locations of the conditional branches they were compiled from.
+    pub fn branches(&self) -> impl Iterator<Item = (Digest, &CodeLocation)> + '_ {
+        self.branches.iter().map(|(key, location)| {
+            let hash = Digest::try_from(key).expect("invalid branch hash");
+            (hash, location)
+        })
+    }
+
+    // STATE MUTATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Adds the locations of the operations of the SPAN block with the specified hash to this map.
+    ///
+    /// If locations for the block are already present in the map, or if the provided list of
+    /// locations is empty, this is a no-op.
+    pub fn insert_span(&mut self, span_hash: Digest, locations: SpanLocations) {
+        if !locations.is_empty() {
+            self.spans.entry(span_hash.into()).or_insert(locations);
+        }
+    }
+
+    /// Adds the location of the procedure with the specified MAST root to this map.
+    ///
+    /// If the same procedure is already present in the map, this is a no-op.
+    pub fn insert_procedure(&mut self, mast_root: Digest, location: ProcedureLocation) {
+        let procs = self.procedures.entry(mast_root.into()).or_default();
+        if !procs.contains(&location) {
+            procs.push(location);
+        }
+    }
+
+    /// Adds the location of the conditional branch compiled into the SPLIT or LOOP block with the
+    /// specified hash to this map.
+    ///
+    /// If a location for the block is already present in the map, this is a no-op.
+    pub fn insert_branch(&mut self, block_hash: Digest, location: CodeLocation) {
+        self.branches.entry(block_hash.into()).or_insert(location);
+    }
+}
+
+impl Serializable for SourceMap {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_usize(self.spans.len());
+        for (key, locations) in self.spans.iter() {
+            target.write_bytes(key);
+            locations.write_into(target);
+        }
+
+        let procedures: Vec<_> = self.procedures().collect();
+        target.write_usize(procedures.len());
+        for (mast_root, location) in procedures {
+            mast_root.write_into(target);
+            location.write_into(target);
+        }
+
+        target.write_usize(self.branches.len());
+        for (key, location) in self.branches.iter() {
+            target.write_bytes(key);
+            location.write_into(target);
+        }
+    }
+}
+
+impl Deserializable for SourceMap {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let num_spans = source.read_usize()?;
+        let mut source_map = Self::new();
+        for _ in 0..num_spans {
+            let span_hash = Digest::read_from(source)?;
+            let locations = SpanLocations::read_from(source)?;
+            source_map.insert_span(span_hash, locations);
+        }
+
+        let num_procedures = source.read_usize()?;
+        for _ in 0..num_procedures {
+            let mast_root = Digest::read_from(source)?;
+            let location = ProcedureLocation::read_from(source)?;
+            source_map.insert_procedure(mast_root, location);
+        }
+
+        let num_branches = source.read_usize()?;
+        for _ in 0..num_branches {
+            let block_hash = Digest::read_from(source)?;
+            let location = CodeLocation::read_from(source)?;
+            source_map.insert_branch(block_hash, location);
+        }
+        Ok(source_map)
+    }
+}
@@ -1,6 +1,14 @@
-use super::{blocks::Dyn, Deserializable, Digest, Felt, Kernel, ProgramInfo, Serializable};
-use crate::{chiplets::hasher, Word};
-use alloc::vec::Vec;
+use super::{
+    blocks::{CodeBlock, Dyn},
+    CodeLocation, DataSegment, Deserializable, Digest, Felt, Kernel, MastForest, MastNode,
+    MastSerdeOptions, ProcedureLocation, Program, ProgramInfo, Serializable, SourceMap,
+    SpanLocations,
+};
+use crate::{
+    chiplets::hasher, utils::ByteWriter, AdviceInjector, AssemblyOp, DebugOptions, Decorator,
+    Operation, Word,
+};
+use alloc::{string::ToString, vec::Vec};
 use proptest::prelude::*;
 use rand_utils::prng_array;
 
@@ -11,6 +19,265 @@ fn dyn_hash_is_correct() {
     assert_eq!(expected_constant, Dyn::new().hash());
 }
 
+// MAST FOREST
+// ------------------------------------------------------------------------------------------------
+
+#[test]
+fn mast_forest_deduplicates_nodes() {
+    let body = CodeBlock::new_join([
+        CodeBlock::new_span(vec![Operation::Add, Operation::Mul]),
+        CodeBlock::new_loop(CodeBlock::new_span(vec![Operation::Pad, Operation::Drop])),
+    ]);
+    let block = CodeBlock::new_join([
+        body.clone(),
+        CodeBlock::new_split(body.clone(), CodeBlock::new_join([body.clone(), body])),
+    ]);
+
+    let mut forest = MastForest::new();
+    let root = forest.add_code_block(&block);
+
+    // the body is stored only once: 4 nodes for the body, plus the split, the inner join and
+    // the root join
+    assert_eq!(7, forest.num_nodes());
+    assert_eq!(block.hash(), forest[root].hash());
+    assert_eq!(block, forest.to_code_block(root));
+
+    // adding the same tree again does not add any nodes
+    assert_eq!(root, forest.add_code_block(&block));
+    assert_eq!(7, forest.num_nodes());
+}
+
+#[test]
+fn mast_forest_keeps_nodes_with_different_decorators() {
+    let asm_op =
+        Decorator::AsmOp(AssemblyOp::new("#main".to_string(), 1, "add".to_string(), false));
+
+    let mut forest = MastForest::new();
+    let span1 = forest.add_span(vec![Operation::Add], vec![]);
+    let span2 = forest.add_span(vec![Operation::Add], vec![(0, asm_op)]);
+    assert_ne!(span1, span2);
+    assert_eq!(forest[span1].hash(), forest[span2].hash());
+    assert_eq!(Some(span1), forest.find_node(forest[span2].hash()));
+}
+
+#[test]
+fn mast_forest_subtree_extraction() {
+    let mut source = MastForest::new();
+    let span1 = source.add_span(vec![Operation::Add], vec![]);
+    let span2 = source.add_span(vec![Operation::Mul], vec![]);
+    let unused = source.add_join(span1, span2);
+    let root = source.add_loop(span2);
+
+    let mut target = MastForest::new();
+    let copied_root = target.add_subtree(&source, root);
+    assert_eq!(2, target.num_nodes());
+    assert_eq!(source[root].hash(), target[copied_root].hash());
+    assert_eq!(None, target.find_node(source[unused].hash()));
+}
+
+#[test]
+fn program_display_matches_code_block() {
+    let root = CodeBlock::new_join([
+        CodeBlock::new_split(
+            CodeBlock::new_span(vec![Operation::Add]),
+            CodeBlock::new_loop(CodeBlock::new_span(vec![Operation::Pad, Operation::Drop])),
+        ),
+        CodeBlock::new_dyn(),
+    ]);
+    let expected = format!("begin {root} end");
+    assert_eq!(expected, Program::new(root).to_string());
+}
+
+// SOURCE MAP
+// ------------------------------------------------------------------------------------------------
+
+#[test]
+fn source_map_lookup() {
+    let span = CodeBlock::new_span(vec![Operation::Pad, Operation::Drop]);
+    let locations = SpanLocations::new(vec![
+        (0..2, CodeLocation::new("#exec", 2, 5)),
+        (2..2, CodeLocation::new("#exec", 3, 5)),
+        (3..5, CodeLocation::new("std::foo", 7, 9)),
+    ]);
+    assert_eq!(2, locations.len());
+
+    let mut source_map = SourceMap::new();
+    source_map.insert_span(span.hash(), locations);
+    assert_eq!(1, source_map.num_spans());
+
+    let location = source_map.get(span.hash(), 1).unwrap();
+    assert_eq!("#exec:2:5", location.to_string());
+    assert_eq!(None, source_map.get(span.hash(), 2));
+    assert_eq!(Some(&CodeLocation::new("std::foo", 7, 9)), source_map.get(span.hash(), 4));
+    assert_eq!(None, source_map.get(span.hash(), 5));
+
+    // locations of a span which is already in the map are not overwritten
+    let other = SpanLocations::new(vec![(0..1, CodeLocation::new("#exec", 1, 1))]);
+    source_map.insert_span(span.hash(), other);
+    assert_eq!("#exec:2:5", source_map.get(span.hash(), 0).unwrap().to_string());
+
+    // all procedures with the same MAST root are retained, but each of them only once
+    let foo = ProcedureLocation::new("foo", CodeLocation::new("#exec", 1, 1), None);
+    let entry = Some(CodeLocation::new("std::foo", 7, 9));
+    let bar = ProcedureLocation::new("bar", CodeLocation::new("std::foo", 6, 1), entry);
+    source_map.insert_procedure(span.hash(), foo.clone());
+    source_map.insert_procedure(span.hash(), bar);
+    source_map.insert_procedure(span.hash(), foo);
+    let procs: Vec<_> = source_map.procedures().map(|(_, proc)| proc.name()).collect();
+    assert_eq!(vec!["foo", "bar"], procs);
+
+    // locations of a branch which is already in the map are not overwritten
+    let split = CodeBlock::new_split(span.clone(), span.clone());
+    source_map.insert_branch(split.hash(), CodeLocation::new("#exec", 4, 5));
+    source_map.insert_branch(split.hash(), CodeLocation::new("#exec", 8, 5));
+    assert_eq!("#exec:4:5", source_map.get_branch(split.hash()).unwrap().to_string());
+    assert_eq!(None, source_map.get_branch(span.hash()));
+}
+
+// DATA SEGMENTS
+// ------------------------------------------------------------------------------------------------
+
+#[test]
+fn data_segment_padding() {
+    let values: Vec<Felt> = (1..=9).map(Felt::new).collect();
+    let segment = DataSegment::new(100, values.clone());
+    assert_eq!(4, segment.num_words());
+    assert_eq!(104, segment.end_address());
+
+    let mut padded = values;
+    padded.resize(16, Felt::new(0));
+    assert_eq!(padded, segment.padded_values());
+    assert_eq!(hasher::hash_elements(&padded), segment.commitment());
+
+    assert!(segment.overlaps(&DataSegment::new(103, vec![Felt::new(1)])));
+    assert!(!segment.overlaps(&DataSegment::new(104, vec![Felt::new(1)])));
+    assert!(!segment.overlaps(&DataSegment::new(98, vec![Felt::new(1)])));
+}
+
+// SERIALIZATION
+// ------------------------------------------------------------------------------------------------
+
+#[test]
+fn program_serialization_round_trip() {
+    let program = build_test_program();
+
+    let bytes = program.to_bytes(MastSerdeOptions::default());
+    let deser = Program::from_bytes(&bytes).unwrap();
+    assert_eq!(program, deser);
+    assert_eq!(program.hash(), deser.hash());
+
+    // serializing the deserialized program should result in the same bytes
+    assert_eq!(bytes, deser.to_bytes(MastSerdeOptions::default()));
+}
+
+#[test]
+fn program_serialization_without_debug_info() {
+    let program = build_test_program();
+
+    let bytes = program.to_bytes(MastSerdeOptions::new(false));
+    let deser = Program::from_bytes(&bytes).unwrap();
+    assert_eq!(program.hash(), deser.hash());
+    assert_eq!(program.cb_table(), deser.cb_table());
+    assert!(deser.source_map().is_empty());
+
+    // error messages and data segments are not debug info, and thus, should be preserved
+    assert_eq!(Some("value too large"), deser.get_error_message(1));
+    assert_eq!(program.data_segments(), deser.data_segments());
+
+    // debug decorators should be removed, while advice injectors and events should be preserved
+    let MastNode::Join(join) = deser.root() else {
+        panic!("expected join block");
+    };
+    let MastNode::Span(span) = deser.get_node(join.first()) else {
+        panic!("expected span block");
+    };
+    let expected = vec![(1, Decorator::Advice(AdviceInjector::U64Div)), (3, Decorator::Event(17))];
+    assert_eq!(&expected, span.decorators());
+}
+
+#[test]
+fn program_serialization_without_debug_info_merges_nodes() {
+    let asm_op =
+        |op: &str| Decorator::AsmOp(AssemblyOp::new("#main".to_string(), 1, op.to_string(), false));
+
+    // the two spans differ only in debug info, and thus, should be merged when debug info is
+    // stripped
+    let span1 = CodeBlock::new_span_with_decorators(vec![Operation::Add], vec![(0, asm_op("a"))]);
+    let span2 = CodeBlock::new_span_with_decorators(vec![Operation::Add], vec![(0, asm_op("b"))]);
+    let program = Program::new(CodeBlock::new_join([span1, span2]));
+    assert_eq!(3, program.forest().num_nodes());
+
+    let bytes = program.to_bytes(MastSerdeOptions::new(false));
+    let deser = Program::from_bytes(&bytes).unwrap();
+    assert_eq!(program.hash(), deser.hash());
+    assert_eq!(2, deser.forest().num_nodes());
+}
+
+#[test]
+fn program_deserialization_fails_on_corrupted_data() {
+    let program = build_test_program();
+    let bytes = program.to_bytes(MastSerdeOptions::default());
+
+    // invalid magic bytes
+    let mut corrupted = bytes.clone();
+    corrupted[0] = b'X';
+    assert!(Program::from_bytes(&corrupted).is_err());
+
+    // unsupported version
+    let mut corrupted = bytes.clone();
+    corrupted[4] = u8::MAX;
+    assert!(Program::from_bytes(&corrupted).is_err());
+
+    // changed program hash
+    let mut corrupted = bytes.clone();
+    corrupted[6] ^= 1;
+    assert!(Program::from_bytes(&corrupted).is_err());
+
+    // truncated data
+    assert!(Program::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+}
+
+#[test]
+fn program_deserialization_fails_on_oversized_counts() {
+    let program = build_test_program();
+    let bytes = program.to_bytes(MastSerdeOptions::default());
+
+    // the number of nodes follows the magic bytes, the version, the options, the program hash, and
+    // the kernel; counts which exceed the size of the data must not be allocated upfront
+    let header_len = 4
+        + 1
+        + MastSerdeOptions::default().to_bytes().len()
+        + program.hash().to_bytes().len()
+        + program.kernel().to_bytes().len();
+    for num_nodes in [usize::MAX, u32::MAX as usize, 1 << 40] {
+        let mut corrupted = bytes[..header_len].to_vec();
+        corrupted.write_usize(num_nodes);
+        assert!(Program::from_bytes(&corrupted).is_err());
+
+        corrupted.extend_from_slice(&bytes[header_len + 1..]);
+        assert!(Program::from_bytes(&corrupted).is_err());
+    }
+}
+
+#[test]
+fn operation_serialization_round_trip() {
+    let ops = [
+        Operation::Noop,
+        Operation::Assert(u32::MAX),
+        Operation::U32assert2(Felt::new(7)),
+        Operation::Push(Felt::new(u64::MAX - u32::MAX as u64)),
+        Operation::MovDn8,
+        Operation::RCombBase,
+        Operation::Halt,
+    ];
+    for op in ops {
+        assert_eq!(op, Operation::read_from_bytes(&op.to_bytes()).unwrap());
+    }
+
+    // 0b0001_1111 is not assigned to any operation
+    assert!(Operation::read_from_bytes(&[0b0001_1111]).is_err());
+}
+
 proptest! {
     #[test]
     fn arbitrary_program_info_serialization_works(
@@ -35,6 +302,54 @@ proptest! {
 // HELPER FUNCTIONS
 // --------------------------------------------------------------------------------------------
 
+fn build_test_program() -> Program {
+    let asm_op =
+        |op: &str| Decorator::AsmOp(AssemblyOp::new("#main".to_string(), 1, op.to_string(), false));
+
+    let span1_ops = vec![Operation::Push(Felt::new(3)), Operation::Add, Operation::Assert(1)];
+    let span1 = CodeBlock::new_span_with_decorators(
+        span1_ops,
+        vec![
+            (0, asm_op("add.3")),
+            (1, Decorator::Advice(AdviceInjector::U64Div)),
+            (2, Decorator::Debug(DebugOptions::StackTop(4))),
+            (3, Decorator::Event(17)),
+        ],
+    );
+    let span1_hash = span1.hash();
+    let span2 = CodeBlock::new_span(vec![Operation::Pad, Operation::Drop]);
+    let callee = CodeBlock::new_span(vec![Operation::Incr]);
+
+    let split = CodeBlock::new_split(span2.clone(), CodeBlock::new_call(callee.hash()));
+    let split_hash = split.hash();
+    let body =
+        CodeBlock::new_join([split, CodeBlock::new_loop(CodeBlock::new_syscall(callee.hash()))]);
+    let root = CodeBlock::new_join([
+        span1,
+        CodeBlock::new_join([body, CodeBlock::new_join([CodeBlock::new_dyn(), span2])]),
+    ]);
+
+    let mut source_map = SourceMap::new();
+    source_map.insert_span(
+        span1_hash,
+        SpanLocations::new(vec![
+            (0..2, CodeLocation::new("#exec", 2, 9)),
+            (2..3, CodeLocation::new("#exec", 3, 9)),
+        ]),
+    );
+    let entry = Some(CodeLocation::new("#sys", 2, 5));
+    let foo = ProcedureLocation::new("foo", CodeLocation::new("#sys", 1, 1), entry);
+    source_map.insert_procedure(callee.hash(), foo);
+    source_map.insert_branch(split_hash, CodeLocation::new("#exec", 4, 9));
+
+    let kernel = Kernel::new(&[callee.hash()]).unwrap();
+    let error_messages = [(1, "value too large".to_string())].into_iter().collect();
+    Program::with_kernel(root, kernel, vec![callee])
+        .with_source_map(source_map)
+        .with_error_messages(error_messages)
+        .with_data_segments(vec![DataSegment::new(100, vec![Felt::new(7); 9])])
+}
+
 fn digest_from_seed(seed: [u8; 32]) -> Digest {
     let mut digest = Word::default();
     digest.iter_mut().enumerate().for_each(|(i, d)| {
@@ -1,6 +1,10 @@
-use assembly::{LibraryNamespace, MaslLibrary, Version};
+use assembly::{ast::ImportedConstants, LibraryNamespace, LibraryPath, MaslLibrary, Version};
 use clap::Parser;
-use std::path::PathBuf;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use stdlib::StdLibrary;
 
 #[derive(Debug, Clone, Parser)]
 #[clap(
@@ -39,14 +43,24 @@ impl BundleCmd {
             LibraryNamespace::try_from(namespace.clone()).expect("invalid base namespace");
         let version = Version::try_from(self.version.as_ref()).expect("invalid cargo version");
         let with_source_locations = true;
-        let stdlib = MaslLibrary::read_from_dir(
+
+        // modules of the library can reference constants exported from the standard library
+        let imported_constants = ImportedConstants::new().with_library(&StdLibrary::default());
+        let stdlib = MaslLibrary::read_from_dir_with_imported_constants(
             self.dir.clone(),
             library_namespace,
             with_source_locations,
             version,
+            &imported_constants,
         )
         .map_err(|e| e.to_string())?;
 
+        // warnings do not prevent the library from being built; print them out and move on
+        for (module_path, warning) in stdlib.warnings() {
+            let source = read_module_source(&self.dir, module_path);
+            eprintln!("{module_path}: {}", warning.render(&source));
+        }
+
         // write the masl output
         stdlib.write_to_dir(self.dir.clone()).map_err(|e| e.to_string())?;
 
@@ -55,3 +69,22 @@ impl BundleCmd {
         Ok(())
     }
 }
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the source code of the module with the specified path from the library directory, or
+/// an empty string if the source file of the module could not be read.
+///
+/// The source of a module `lib::foo::bar` is located either at `foo/bar.masm` or at
+/// `foo/bar/mod.masm` relative to the library directory.
+fn read_module_source(dir: &Path, module_path: &LibraryPath) -> String {
+    let mut path = dir.to_path_buf();
+    path.extend(module_path.components().skip(1));
+    let mod_path = path.join(MaslLibrary::MOD);
+    [path, mod_path]
+        .iter()
+        .map(|path| path.with_extension(MaslLibrary::MODULE_EXTENSION))
+        .find_map(|path| fs::read_to_string(path).ok())
+        .unwrap_or_default()
+}
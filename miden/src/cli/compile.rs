@@ -1,6 +1,7 @@
 use clap::Parser;
 
 use super::data::{Debug, Libraries, ProgramFile};
+use miden_vm::MastSerdeOptions;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone, Parser)]
@@ -9,12 +10,21 @@ pub struct CompileCmd {
     /// Path to .masm assembly file
     #[clap(short = 'a', long = "assembly", value_parser)]
     assembly_file: PathBuf,
+    /// Features enabled for conditional compilation directives, e.g. `-D DEBUG` for `#if DEBUG`
+    #[clap(short = 'D', long = "define", value_parser)]
+    defines: Vec<String>,
     /// Paths to .masl library files
     #[clap(short = 'l', long = "libraries", value_parser)]
     library_paths: Vec<PathBuf>,
     /// Path to output file
     #[clap(short = 'o', long = "output", value_parser)]
     output_file: Option<PathBuf>,
+    /// Write the assembled program MAST (.mast) instead of the program AST (.masb)
+    #[clap(long = "mast")]
+    mast: bool,
+    /// Include debug info (e.g., assembly op decorators) in the program MAST
+    #[clap(short = 'd', long = "debug", requires = "mast")]
+    debug: bool,
 }
 
 impl CompileCmd {
@@ -23,20 +33,26 @@ impl CompileCmd {
         println!("Compile program");
         println!("============================================================");
 
-        // load the program from file and parse it
-        let program = ProgramFile::read(&self.assembly_file)?;
-
         // load libraries from files
         let libraries = Libraries::new(&self.library_paths)?;
 
+        // load the program from file and parse it
+        let program = ProgramFile::read(&self.assembly_file, &libraries, &self.defines)?;
+
         // compile the program
-        let compiled_program = program.compile(&Debug::Off, libraries.libraries)?;
+        let debug = if self.debug { Debug::On } else { Debug::Off };
+        let compiled_program = program.compile(&debug, libraries.libraries)?;
 
         // report program hash to user
         let program_hash: [u8; 32] = compiled_program.hash().into();
         println!("program hash is {}", hex::encode(program_hash));
 
         // write the compiled file
-        program.write(self.output_file.clone())
+        if self.mast {
+            let options = MastSerdeOptions::new(self.debug);
+            program.write_mast(&compiled_program, self.output_file.clone(), options)
+        } else {
+            program.write(self.output_file.clone())
+        }
     }
 }
@@ -1,10 +1,10 @@
-use assembly::{Library, MaslLibrary};
+use assembly::{ast::ImportedConstants, AssemblyError, Library, LibraryPath, MaslLibrary};
 use miden_vm::{
     crypto::{MerkleStore, MerkleTree, NodeIndex, PartialMerkleTree, RpoDigest, SimpleSmt},
     math::Felt,
     utils::{Deserializable, SliceReader},
-    AdviceInputs, Assembler, Digest, ExecutionProof, MemAdviceProvider, Program, ProgramAst,
-    StackInputs, StackOutputs, Word,
+    AdviceInputs, Assembler, Digest, ExecutionProof, MastSerdeOptions, MemAdviceProvider, Program,
+    ProgramAst, StackInputs, StackOutputs, Word,
 };
 use serde_derive::{Deserialize, Serialize};
 use std::{
@@ -376,42 +376,99 @@ impl OutputFile {
 // PROGRAM FILE
 // ================================================================================================
 
+/// Contents of a program file, which can be either a masm source file or a compiled MAST file.
+enum ProgramSource {
+    /// An AST of a program parsed from masm source, together with the source itself.
+    Ast(ProgramAst, String),
+    /// An already assembled program loaded from a `.mast` file.
+    Mast(Program),
+}
+
 pub struct ProgramFile {
-    ast: ProgramAst,
+    source: ProgramSource,
     path: PathBuf,
 }
 
 /// Helper methods to interact with masm program file.
 impl ProgramFile {
-    /// Reads the masm file at the specified path and parses it into a [ProgramAst].
-    #[instrument(name = "read_program_file", fields(path = %path.display()))]
-    pub fn read(path: &PathBuf) -> Result<Self, String> {
+    /// File extension of compiled program MAST files.
+    pub const MAST_EXTENSION: &'static str = "mast";
+
+    /// Reads the program file at the specified path.
+    ///
+    /// If the file has `.mast` extension, the file is expected to contain a compiled program
+    /// MAST; otherwise, the file is parsed as masm source into a [ProgramAst]. References to
+    /// constants exported from the modules of the standard library and of the provided libraries
+    /// are resolved during parsing, and conditional compilation directives are evaluated with the
+    /// specified features enabled.
+    #[instrument(name = "read_program_file", skip(libraries, features), fields(path = %path.display()))]
+    pub fn read(
+        path: &PathBuf,
+        libraries: &Libraries,
+        features: &[String],
+    ) -> Result<Self, String> {
+        if path.extension().is_some_and(|ext| ext == Self::MAST_EXTENSION) {
+            let program = Program::read_from_file(path).map_err(|err| {
+                format!("Failed to read program MAST file `{}` - {}\n", path.display(), err)
+            })?;
+
+            return Ok(Self {
+                source: ProgramSource::Mast(program),
+                path: path.clone(),
+            });
+        }
+
         // read program file to string
         let source = fs::read_to_string(path).map_err(|err| {
             format!("Failed to open program file `{}` - {}\n", path.display(), err)
         })?;
 
         // parse the program into an AST
-        let ast = ProgramAst::parse(&source).map_err(|err| {
-            format!("Failed to parse program file `{}` - {}\n", path.display(), err)
+        let imported_constants = libraries.libraries.iter().fold(
+            ImportedConstants::new()
+                .with_library(&StdLibrary::default())
+                .with_features(features.iter().cloned()),
+            |constants, lib| constants.with_library(lib),
+        );
+        let (ast, warnings) = ProgramAst::parse_with_imported_constants(
+            &source,
+            &imported_constants,
+        )
+        .map_err(|errors| {
+            let err = AssemblyError::parsing_errors(errors, LibraryPath::EXEC_PATH);
+            format!("Failed to parse program file `{}`\n{}", path.display(), err.render(&source))
         })?;
 
+        // warnings do not prevent the program from being compiled; print them out and move on
+        for warning in warnings {
+            eprintln!("{}", warning.render(&source));
+        }
+
         Ok(Self {
-            ast,
+            source: ProgramSource::Ast(ast, source),
             path: path.clone(),
         })
     }
 
     /// Compiles this program file into a [Program].
+    ///
+    /// If this file contains an already compiled program MAST, the program is returned as is and
+    /// the provided libraries are ignored.
     #[instrument(name = "compile_program", skip_all)]
     pub fn compile<I, L>(&self, debug: &Debug, libraries: I) -> Result<Program, String>
     where
         I: IntoIterator<Item = L>,
         L: Library,
     {
+        let (ast, source) = match &self.source {
+            ProgramSource::Ast(ast, source) => (ast, source),
+            ProgramSource::Mast(program) => return Ok(program.clone()),
+        };
+
         // compile program
         let mut assembler = Assembler::default()
             .with_debug_mode(debug.is_on())
+            .with_source_map(true)
             .with_library(&StdLibrary::default())
             .map_err(|err| format!("Failed to load stdlib - {}", err))?;
 
@@ -419,26 +476,64 @@ impl ProgramFile {
             .with_libraries(libraries.into_iter())
             .map_err(|err| format!("Failed to load libraries `{}`", err))?;
 
-        let program = assembler
-            .compile_ast(&self.ast)
-            .map_err(|err| format!("Failed to compile program - {}", err))?;
+        let program = assembler.compile_ast(ast).map_err(|err| {
+            // source snippets can be rendered only for errors in the program file itself
+            let rendered = err
+                .errors()
+                .iter()
+                .map(|err| {
+                    let is_in_program = err
+                        .source_location()
+                        .is_some_and(|location| location.path() == LibraryPath::EXEC_PATH);
+                    err.render(if is_in_program { source.as_str() } else { "" })
+                })
+                .collect::<Vec<_>>();
+            format!("Failed to compile program\n{}", rendered.join("\n"))
+        })?;
 
         Ok(program)
     }
 
-    /// Writes this file into the specified path, if one is provided. If the path is not provided,
-    /// writes the file into the same directory as the source file, but with `.masb` extension.
+    /// Writes the AST of this file into the specified path, if one is provided. If the path is not
+    /// provided, writes the file into the same directory as the source file, but with `.masb`
+    /// extension.
     pub fn write(&self, out_path: Option<PathBuf>) -> Result<(), String> {
+        let ast = match &self.source {
+            ProgramSource::Ast(ast, _) => ast,
+            ProgramSource::Mast(_) => {
+                return Err("Failed to write the compiled file: program AST is not available".into())
+            }
+        };
+
         let out_path = out_path.unwrap_or_else(|| {
             let mut out_file = self.path.clone();
             out_file.set_extension("masb");
             out_file
         });
 
-        self.ast
-            .write_to_file(out_path)
+        ast.write_to_file(out_path)
             .map_err(|err| format!("Failed to write the compiled file: {err}"))
     }
+
+    /// Writes the MAST of the provided compiled program into the specified path, if one is
+    /// provided. If the path is not provided, writes the file into the same directory as the
+    /// source file, but with `.mast` extension.
+    pub fn write_mast(
+        &self,
+        program: &Program,
+        out_path: Option<PathBuf>,
+        options: MastSerdeOptions,
+    ) -> Result<(), String> {
+        let out_path = out_path.unwrap_or_else(|| {
+            let mut out_file = self.path.clone();
+            out_file.set_extension(Self::MAST_EXTENSION);
+            out_file
+        });
+
+        program
+            .write_to_file(out_path, options)
+            .map_err(|err| format!("Failed to write the compiled MAST file: {err}"))
+    }
 }
 
 // PROOF FILE
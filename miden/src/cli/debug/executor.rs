@@ -103,7 +103,7 @@ impl DebugExecutor {
             Some(next_vm_state_result) => match next_vm_state_result {
                 Ok(vm_state) => Some(vm_state),
                 Err(err) => {
-                    println!("Execution error: {err:?}");
+                    println!("Execution error: {err}");
                     None
                 }
             },
@@ -15,6 +15,9 @@ pub struct DebugCmd {
     /// Path to .masm assembly file
     #[clap(short = 'a', long = "assembly", value_parser)]
     assembly_file: PathBuf,
+    /// Features enabled for conditional compilation directives, e.g. `-D DEBUG` for `#if DEBUG`
+    #[clap(short = 'D', long = "define", value_parser)]
+    defines: Vec<String>,
     /// Path to input file
     #[clap(short = 'i', long = "input", value_parser)]
     input_file: Option<PathBuf>,
@@ -36,8 +39,8 @@ impl DebugCmd {
         let libraries = Libraries::new(&self.library_paths)?;
 
         // load program from file and compile
-        let program =
-            ProgramFile::read(&self.assembly_file)?.compile(&Debug::On, libraries.libraries)?;
+        let program = ProgramFile::read(&self.assembly_file, &libraries, &self.defines)?
+            .compile(&Debug::On, libraries.libraries)?;
 
         let program_hash: [u8; 32] = program.hash().into();
         println!("Debugging program with hash {}...", hex::encode(program_hash));
@@ -0,0 +1,45 @@
+use super::data::{Debug, Libraries, ProgramFile};
+use clap::Parser;
+use miden_vm::Disassembler;
+use std::{fs, path::PathBuf};
+
+#[derive(Debug, Clone, Parser)]
+#[clap(about = "Disassemble a compiled miden program into annotated miden assembly")]
+pub struct DisasmCmd {
+    /// Path to .mast program file (or .masm assembly file)
+    #[clap(short = 'a', long = "assembly", value_parser)]
+    assembly_file: PathBuf,
+    /// Features enabled for conditional compilation directives, e.g. `-D DEBUG` for `#if DEBUG`
+    #[clap(short = 'D', long = "define", value_parser)]
+    defines: Vec<String>,
+    /// Paths to .masl library files
+    #[clap(short = 'l', long = "libraries", value_parser)]
+    library_paths: Vec<PathBuf>,
+    /// Path to output file; if not provided, the disassembly is printed to stdout
+    #[clap(short = 'o', long = "output", value_parser)]
+    output_file: Option<PathBuf>,
+}
+
+impl DisasmCmd {
+    pub fn execute(&self) -> Result<(), String> {
+        // load libraries from files
+        let libraries = Libraries::new(&self.library_paths)?;
+
+        // load the program from file
+        let program = ProgramFile::read(&self.assembly_file, &libraries, &self.defines)?;
+
+        // compile the program; this is a no-op for programs loaded from .mast files
+        let program = program.compile(&Debug::Off, libraries.libraries)?;
+
+        let disassembly = Disassembler::new(&program).to_string();
+        match &self.output_file {
+            Some(path) => fs::write(path, disassembly).map_err(|err| {
+                format!("Failed to write disassembly to `{}` - {}", path.display(), err)
+            }),
+            None => {
+                print!("{disassembly}");
+                Ok(())
+            }
+        }
+    }
+}
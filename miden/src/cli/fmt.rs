@@ -0,0 +1,83 @@
+use assembly::{ast::format_source, AssemblyError};
+use clap::Parser;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Parser)]
+#[clap(about = "Format Miden assembly files in the canonical style, preserving comments")]
+pub struct FmtCmd {
+    /// Paths to .masm files, or to directories containing .masm files
+    #[clap(value_parser, required = true)]
+    paths: Vec<PathBuf>,
+    /// Check whether the files are formatted without modifying them
+    #[clap(long = "check")]
+    check: bool,
+}
+
+impl FmtCmd {
+    pub fn execute(&self) -> Result<(), String> {
+        let mut files = Vec::new();
+        for path in self.paths.iter() {
+            collect_masm_files(path, &mut files)?;
+        }
+
+        let mut unformatted = 0;
+        for path in files.iter() {
+            let source = fs::read_to_string(path)
+                .map_err(|err| format!("Failed to open file `{}` - {}", path.display(), err))?;
+
+            let formatted = format_source(&source).map_err(|err| {
+                let err = AssemblyError::parsing_error(err, &path.display().to_string());
+                format!("Failed to parse file `{}`\n{}", path.display(), err.render(&source))
+            })?;
+            if formatted == source {
+                continue;
+            }
+
+            if self.check {
+                println!("{} is not formatted", path.display());
+                unformatted += 1;
+            } else {
+                fs::write(path, formatted).map_err(|err| {
+                    format!("Failed to write file `{}` - {}", path.display(), err)
+                })?;
+                println!("Formatted {}", path.display());
+            }
+        }
+
+        if unformatted > 0 {
+            return Err(format!("{unformatted} of {} files are not formatted", files.len()));
+        }
+        Ok(())
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Adds the specified file to the provided list, or, if the path points to a directory, adds all
+/// .masm files located in the directory and its subdirectories.
+fn collect_masm_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    let mut entries = fs::read_dir(path)
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|err| format!("Failed to read directory `{}` - {}", path.display(), err))?;
+    entries.sort();
+
+    for entry in entries {
+        if entry.is_dir() || entry.extension().is_some_and(|ext| ext == "masm") {
+            collect_masm_files(&entry, files)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,18 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+use crate::lsp::start_server;
+
+#[derive(Debug, Clone, Parser)]
+#[clap(about = "Starts a Miden assembly language server communicating over stdin/stdout")]
+pub struct LspCmd {
+    /// Paths to .masl library files, or to directories with .masm sources of libraries
+    #[clap(short = 'l', long = "libraries", value_parser)]
+    library_paths: Vec<PathBuf>,
+}
+
+impl LspCmd {
+    pub fn execute(&self) -> Result<(), String> {
+        start_server(&self.library_paths)
+    }
+}
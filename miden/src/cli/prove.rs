@@ -12,6 +12,10 @@ pub struct ProveCmd {
     #[clap(short = 'a', long = "assembly", value_parser)]
     assembly_file: PathBuf,
 
+    /// Features enabled for conditional compilation directives, e.g. `-D DEBUG` for `#if DEBUG`
+    #[clap(short = 'D', long = "define", value_parser)]
+    defines: Vec<String>,
+
     /// Number of cycles the program is expected to consume
     #[clap(short = 'e', long = "exp-cycles", default_value = "64")]
     expected_cycles: u32,
@@ -124,8 +128,8 @@ fn load_data(params: &ProveCmd) -> Result<(Program, InputFile), String> {
     let libraries = Libraries::new(&params.library_paths)?;
 
     // load program from file and compile
-    let program =
-        ProgramFile::read(&params.assembly_file)?.compile(&Debug::Off, libraries.libraries)?;
+    let program = ProgramFile::read(&params.assembly_file, &libraries, &params.defines)?
+        .compile(&Debug::Off, libraries.libraries)?;
 
     // load input data from file
     let input_data = InputFile::read(&params.input_file, &params.assembly_file)?;
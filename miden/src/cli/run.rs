@@ -1,6 +1,6 @@
 use super::data::{instrument, Debug, InputFile, Libraries, OutputFile, ProgramFile};
 use clap::Parser;
-use processor::{DefaultHost, ExecutionOptions, ExecutionTrace};
+use processor::{DefaultHost, ExecutionOptions, FastProcess, ProcessState, StackOutputs};
 use std::{path::PathBuf, time::Instant};
 
 #[derive(Debug, Clone, Parser)]
@@ -10,6 +10,10 @@ pub struct RunCmd {
     #[clap(short = 'a', long = "assembly", value_parser)]
     assembly_file: PathBuf,
 
+    /// Features enabled for conditional compilation directives, e.g. `-D DEBUG` for `#if DEBUG`
+    #[clap(short = 'D', long = "define", value_parser)]
+    defines: Vec<String>,
+
     /// Number of cycles the program is expected to consume
     #[clap(short = 'e', long = "exp-cycles", default_value = "64")]
     expected_cycles: u32,
@@ -47,7 +51,7 @@ impl RunCmd {
 
         let now = Instant::now();
 
-        let (trace, program_hash) = run_program(self)?;
+        let (stack_outputs, num_cycles, program_hash) = run_program(self)?;
 
         println!(
             "Executed the program with hash {} in {} ms",
@@ -57,38 +61,15 @@ impl RunCmd {
 
         if let Some(output_path) = &self.output_file {
             // write outputs to file if one was specified
-            OutputFile::write(trace.stack_outputs(), output_path)?;
+            OutputFile::write(&stack_outputs, output_path)?;
         } else {
             // write the stack outputs to the screen.
-            println!("Output: {:?}", trace.stack_outputs().stack_truncated(self.num_outputs));
+            println!("Output: {:?}", stack_outputs.stack_truncated(self.num_outputs));
         }
 
-        // calculate the percentage of padded rows
-        let padding_percentage = (trace.trace_len_summary().padded_trace_len()
-            - trace.trace_len_summary().trace_len())
-            * 100
-            / trace.trace_len_summary().padded_trace_len();
-        // print the required cycles for each component
-        println!(
-            "VM cycles: {} extended to {} steps ({}% padding).
-├── Stack rows: {}
-├── Range checker rows: {}
-└── Chiplets rows: {}
-    ├── Hash chiplet rows: {}
-    ├── Bitwise chiplet rows: {}
-    ├── Memory chiplet rows: {}
-    └── Kernel ROM rows: {}",
-            trace.trace_len_summary().trace_len(),
-            trace.trace_len_summary().padded_trace_len(),
-            padding_percentage,
-            trace.trace_len_summary().main_trace_len(),
-            trace.trace_len_summary().range_trace_len(),
-            trace.trace_len_summary().chiplets_trace_len().trace_len(),
-            trace.trace_len_summary().chiplets_trace_len().hash_chiplet_len(),
-            trace.trace_len_summary().chiplets_trace_len().bitwise_chiplet_len(),
-            trace.trace_len_summary().chiplets_trace_len().memory_chiplet_len(),
-            trace.trace_len_summary().chiplets_trace_len().kernel_rom_len(),
-        );
+        // the program is executed without building an execution trace; thus, only the number of
+        // cycles is known (the lengths of the trace segments are reported by `miden prove`)
+        println!("VM cycles: {num_cycles}");
 
         Ok(())
     }
@@ -97,14 +78,16 @@ impl RunCmd {
 // HELPER FUNCTIONS
 // ================================================================================================
 
+/// Executes the program via the fast executor, and returns the outputs of the program, the number
+/// of cycles it took to execute, and the hash of the program.
 #[instrument(name = "run_program", skip_all)]
-fn run_program(params: &RunCmd) -> Result<(ExecutionTrace, [u8; 32]), String> {
+fn run_program(params: &RunCmd) -> Result<(StackOutputs, u32, [u8; 32]), String> {
     // load libraries from files
     let libraries = Libraries::new(&params.library_paths)?;
 
     // load program from file and compile
-    let program =
-        ProgramFile::read(&params.assembly_file)?.compile(&Debug::Off, libraries.libraries)?;
+    let program = ProgramFile::read(&params.assembly_file, &libraries, &params.defines)?
+        .compile(&Debug::Off, libraries.libraries)?;
 
     // load input data from file
     let input_data = InputFile::read(&params.input_file, &params.assembly_file)?;
@@ -121,8 +104,11 @@ fn run_program(params: &RunCmd) -> Result<(ExecutionTrace, [u8; 32]), String> {
     let program_hash: [u8; 32] = program.hash().into();
 
     // execute program and generate outputs
-    let trace = processor::execute(&program, stack_inputs, host, execution_options)
-        .map_err(|err| format!("Failed to generate execution trace = {:?}", err))?;
+    let mut process =
+        FastProcess::new(program.kernel().clone(), stack_inputs, host, execution_options);
+    let stack_outputs = process
+        .execute(&program)
+        .map_err(|err| format!("Failed to execute program = {}", err))?;
 
-    Ok((trace, program_hash))
+    Ok((stack_outputs, process.clk(), program_hash))
 }
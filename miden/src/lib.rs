@@ -6,12 +6,14 @@
 
 pub use assembly::{
     ast::{ModuleAst, ProgramAst},
-    Assembler, AssemblyError, ParsingError,
+    Assembler, AssemblyError, Disassembler, ParsingError,
 };
 pub use processor::{
-    crypto, execute, execute_iter, utils, AdviceInputs, AdviceProvider, AsmOpInfo, DefaultHost,
-    ExecutionError, ExecutionTrace, Host, Kernel, MemAdviceProvider, Operation, Program,
-    ProgramInfo, StackInputs, VmState, VmStateIterator, ZERO,
+    crypto, execute, execute_fast, execute_iter, execute_segmented, execute_segments, utils,
+    AdviceInputs, AdviceProvider, AsmOpInfo, DefaultHost, ExecutionError, ExecutionSegment,
+    ExecutionTrace, FastProcess, Host, Kernel, MastSerdeOptions, MemAdviceProvider, Operation,
+    ProcessSnapshot, Program, ProgramInfo, SegmentBoundary, SegmentedExecution, StackInputs,
+    VmState, VmStateIterator, ZERO,
 };
 pub use prover::{
     math, prove, Digest, ExecutionProof, FieldExtension, HashFunction, InputError, ProvingOptions,
@@ -0,0 +1,752 @@
+use assembly::{
+    ast::{ImportedConstants, ModuleAst, ProcedureAst, ProgramAst, SourceLocation},
+    Assembler, AssemblyContext, AssemblyError, Library, LibraryNamespace, LibraryPath, MaslLibrary,
+    Version,
+};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionTextEdit, Diagnostic, DiagnosticSeverity,
+    Location, Position, Range, TextEdit, Url,
+};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use stdlib::StdLibrary;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Name of the source reported with diagnostics.
+const DIAGNOSTICS_SOURCE: &str = "miden";
+
+/// Instructions which invoke procedures by name.
+const INVOCATIONS: [&str; 4] = ["exec", "call", "syscall", "procref"];
+
+/// Keywords and instruction mnemonics offered as completions outside of procedure invocations.
+const KEYWORDS: &[&str] = &[
+    "begin",
+    "end",
+    "if.true",
+    "if.false",
+    "else",
+    "while.true",
+    "repeat",
+    "for",
+    "proc",
+    "export",
+    "use",
+    "const",
+    "local",
+    "data",
+    "add",
+    "adv",
+    "adv_loadw",
+    "adv_pipe",
+    "adv_push",
+    "and",
+    "assert",
+    "assert_eq",
+    "assert_eqw",
+    "assertz",
+    "breakpoint",
+    "call",
+    "caller",
+    "cdrop",
+    "cdropw",
+    "clk",
+    "cswap",
+    "cswapw",
+    "debug",
+    "div",
+    "drop",
+    "dropw",
+    "dup",
+    "dupw",
+    "dyncall",
+    "dynexec",
+    "emit",
+    "eq",
+    "eqw",
+    "exec",
+    "exp",
+    "ext2add",
+    "ext2div",
+    "ext2inv",
+    "ext2mul",
+    "ext2neg",
+    "ext2sub",
+    "fri_ext2fold4",
+    "gt",
+    "gte",
+    "hash",
+    "hmerge",
+    "hperm",
+    "ilog2",
+    "inv",
+    "is_odd",
+    "loc_load",
+    "loc_loadw",
+    "loc_store",
+    "loc_storew",
+    "locaddr",
+    "lt",
+    "lte",
+    "mem_load",
+    "mem_loadw",
+    "mem_store",
+    "mem_storew",
+    "mem_stream",
+    "movdn",
+    "movdnw",
+    "movup",
+    "movupw",
+    "mtree_get",
+    "mtree_merge",
+    "mtree_set",
+    "mtree_verify",
+    "mul",
+    "neg",
+    "neq",
+    "not",
+    "or",
+    "padw",
+    "pow2",
+    "procref",
+    "push",
+    "rcomb_base",
+    "sdepth",
+    "sub",
+    "swap",
+    "swapdw",
+    "swapw",
+    "syscall",
+    "trace",
+    "u32and",
+    "u32assert",
+    "u32assert2",
+    "u32assertw",
+    "u32cast",
+    "u32clo",
+    "u32clz",
+    "u32cto",
+    "u32ctz",
+    "u32div",
+    "u32divmod",
+    "u32gt",
+    "u32gte",
+    "u32lt",
+    "u32lte",
+    "u32max",
+    "u32min",
+    "u32mod",
+    "u32not",
+    "u32or",
+    "u32overflowing_add",
+    "u32overflowing_add3",
+    "u32overflowing_madd",
+    "u32overflowing_mul",
+    "u32overflowing_sub",
+    "u32popcnt",
+    "u32rotl",
+    "u32rotr",
+    "u32shl",
+    "u32shr",
+    "u32split",
+    "u32test",
+    "u32testw",
+    "u32wrapping_add",
+    "u32wrapping_add3",
+    "u32wrapping_madd",
+    "u32wrapping_mul",
+    "u32wrapping_sub",
+    "u32xor",
+    "xor",
+];
+
+// LIBRARY INDEX
+// ================================================================================================
+
+/// Libraries whose procedures can be invoked from the edited sources, together with the logic of
+/// analyzing the edited sources against these libraries.
+pub struct LibraryIndex {
+    libraries: Vec<IndexedLibrary>,
+    /// Directory into which the modules of compiled libraries are rendered, so that editors can
+    /// navigate to them.
+    cache_dir: PathBuf,
+}
+
+/// A library loaded either from a .masl file or from a directory with .masm sources.
+struct IndexedLibrary {
+    library: MaslLibrary,
+    source_dir: Option<PathBuf>,
+}
+
+impl LibraryIndex {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns an index of the standard library and of the libraries at the specified paths.
+    ///
+    /// A path can point either to a .masl file, or to a directory with .masm sources of a library,
+    /// in which case the name of the directory is used as the namespace of the library.
+    pub fn load(paths: &[PathBuf]) -> Result<Self, String> {
+        let mut libraries = vec![IndexedLibrary {
+            library: StdLibrary::default().into(),
+            source_dir: None,
+        }];
+
+        for path in paths {
+            let library = if path.is_dir() {
+                // modules of the library can reference constants of the libraries loaded before it
+                let imported_constants =
+                    libraries.iter().fold(ImportedConstants::new(), |constants, lib| {
+                        constants.with_library(&lib.library)
+                    });
+                IndexedLibrary {
+                    library: read_library_dir(path, &imported_constants)?,
+                    source_dir: Some(path.clone()),
+                }
+            } else {
+                let library = MaslLibrary::read_from_file(path)
+                    .map_err(|err| format!("Failed to read library: {err}"))?;
+                IndexedLibrary {
+                    library,
+                    source_dir: None,
+                }
+            };
+            libraries.push(library);
+        }
+
+        Ok(Self {
+            libraries,
+            cache_dir: std::env::temp_dir().join("miden-lsp"),
+        })
+    }
+
+    // DIAGNOSTICS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns errors and warnings found in the specified source of a program or a module.
+    ///
+    /// Syntax errors are reported if the source cannot be parsed; otherwise, the source is
+    /// compiled against the libraries of this index, and compilation errors and warnings are
+    /// reported.
+    pub fn diagnostics(&self, source: &str) -> Vec<Diagnostic> {
+        let imported_constants = self
+            .libraries
+            .iter()
+            .fold(ImportedConstants::new(), |constants, lib| constants.with_library(&lib.library));
+        let result = if is_program(source) {
+            ProgramAst::parse_with_imported_constants(source, &imported_constants).map(
+                |(program, warnings)| {
+                    let result =
+                        self.assembler().and_then(|assembler| assembler.compile_ast(&program));
+                    (result.err(), warnings)
+                },
+            )
+        } else {
+            ModuleAst::parse_with_imported_constants(source, &imported_constants).map(
+                |(module, warnings)| {
+                    let mut context = AssemblyContext::for_module(false);
+                    let result = self.assembler().and_then(|assembler| {
+                        assembler.compile_module(&module, None, &mut context)
+                    });
+                    (result.err(), warnings)
+                },
+            )
+        };
+
+        match result {
+            Err(errors) => errors
+                .iter()
+                .map(|err| {
+                    let range = token_range(source, err.location());
+                    diagnostic(range, DiagnosticSeverity::ERROR, err.message().clone())
+                })
+                .collect(),
+            Ok((error, warnings)) => {
+                let errors = error.iter().flat_map(|error| error.errors()).map(|error| {
+                    let range = error_range(source, error);
+                    let message = match error.hint() {
+                        Some(hint) => format!("{}\nhelp: {hint}", error.inner()),
+                        None => error.inner().to_string(),
+                    };
+                    diagnostic(range, DiagnosticSeverity::ERROR, message)
+                });
+                let warnings = warnings.iter().map(|warning| {
+                    let range = token_range(source, warning.source_location());
+                    let message = format!("{warning}\nhelp: {}", warning.hint());
+                    diagnostic(range, DiagnosticSeverity::WARNING, message)
+                });
+                errors.chain(warnings).collect()
+            }
+        }
+    }
+
+    // NAVIGATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns information about the procedure invoked at the specified position of the source
+    /// located at the specified URI, or None if there is no invocation at this position or the
+    /// invoked procedure cannot be found.
+    ///
+    /// Procedures declared in the source itself, and procedures exported from modules imported
+    /// from the libraries of this index, are resolved.
+    pub fn resolve(&self, uri: &Url, source: &str, position: Position) -> Option<ProcedureInfo> {
+        let (token, _) = token_at(source, position)?;
+        let (instruction, target) = token.split_once('.')?;
+        if !INVOCATIONS.contains(&instruction) {
+            return None;
+        }
+
+        let Some((alias, name)) = target.split_once("::") else {
+            let (line, header) = find_declaration(source, target)?;
+            return Some(ProcedureInfo {
+                location: Some(Location::new(uri.clone(), line_range(line))),
+                header,
+                docs: doc_comment(source, line),
+            });
+        };
+
+        let path = imports(source).remove(alias)?;
+        let (library, module) = self.find_module(&path)?;
+        let (header, docs) =
+            match module.procs().iter().find(|p| p.is_export && p.name.as_str() == name) {
+                Some(proc) => (declaration_header(proc), proc.docs.clone()),
+                None => {
+                    let proc = module
+                        .reexported_procs()
+                        .iter()
+                        .find(|proc| proc.name().as_str() == name)?;
+                    (format!("export.{name}"), proc.docs().map(String::from))
+                }
+            };
+        let location = self.module_source(library, &path, module).map(|(uri, text)| {
+            let line = find_declaration(&text, name).map_or(0, |(line, _)| line);
+            Location::new(uri, line_range(line))
+        });
+
+        Some(ProcedureInfo {
+            location,
+            header,
+            docs,
+        })
+    }
+
+    /// Returns completions for the token at the specified position of the source.
+    ///
+    /// For procedure invocations, names of local procedures and of procedures exported from the
+    /// imported modules are offered; otherwise, keywords and instruction mnemonics are offered.
+    pub fn completions(&self, source: &str, position: Position) -> Vec<CompletionItem> {
+        let prefix = match token_at(source, position) {
+            Some((token, range)) => {
+                &token
+                    [..byte_offset(token, position.character.saturating_sub(range.start.character))]
+            }
+            None => "",
+        };
+
+        match prefix.split_once('.') {
+            Some((instruction, target)) if INVOCATIONS.contains(&instruction) => {
+                // replace the procedure name typed so far with the selected completion
+                let target_len = target.encode_utf16().count() as u32;
+                let start =
+                    Position::new(position.line, position.character.saturating_sub(target_len));
+                let range = Range::new(start, position);
+                self.procedure_names(source)
+                    .into_iter()
+                    .map(|name| CompletionItem {
+                        label: name.clone(),
+                        kind: Some(CompletionItemKind::FUNCTION),
+                        text_edit: Some(CompletionTextEdit::Edit(TextEdit::new(range, name))),
+                        ..Default::default()
+                    })
+                    .collect()
+            }
+            _ => KEYWORDS
+                .iter()
+                .map(|keyword| CompletionItem {
+                    label: keyword.to_string(),
+                    kind: Some(CompletionItemKind::KEYWORD),
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns an assembler instantiated with all libraries of this index.
+    fn assembler(&self) -> Result<Assembler, AssemblyError> {
+        self.libraries
+            .iter()
+            .try_fold(Assembler::default(), |assembler, lib| assembler.with_library(&lib.library))
+    }
+
+    /// Returns the AST of the module at the specified path, together with the library which
+    /// contains the module.
+    fn find_module(&self, path: &LibraryPath) -> Option<(&IndexedLibrary, &ModuleAst)> {
+        self.libraries
+            .iter()
+            .find_map(|lib| lib.library.get_module_ast(path).map(|module| (lib, module)))
+    }
+
+    /// Returns the URI and the contents of a file with the source of the specified module.
+    ///
+    /// Sources of compiled libraries are not available, so their modules are rendered from the
+    /// module ASTs into files in the cache directory.
+    fn module_source(
+        &self,
+        library: &IndexedLibrary,
+        path: &LibraryPath,
+        module: &ModuleAst,
+    ) -> Option<(Url, String)> {
+        let file = match &library.source_dir {
+            Some(dir) => module_file(dir, path.components().skip(1)),
+            None => {
+                let file = module_file(&self.cache_dir, path.components());
+                fs::create_dir_all(file.parent()?).ok()?;
+                fs::write(&file, module.to_string()).ok()?;
+                file
+            }
+        };
+        let text = fs::read_to_string(&file).ok()?;
+        Some((Url::from_file_path(&file).ok()?, text))
+    }
+
+    /// Returns the names of local procedures declared in the specified source, and of procedures
+    /// exported from the modules imported into the source, qualified with module aliases.
+    fn procedure_names(&self, source: &str) -> Vec<String> {
+        let mut names = source
+            .lines()
+            .filter_map(|line| declared_name(line.split_whitespace().next()?))
+            .map(String::from)
+            .collect::<Vec<_>>();
+
+        for (alias, path) in imports(source) {
+            if let Some((_, module)) = self.find_module(&path) {
+                let exported = module.procs().iter().filter(|proc| proc.is_export);
+                let exported = exported
+                    .map(|proc| proc.name.as_str())
+                    .chain(module.reexported_procs().iter().map(|proc| proc.name().as_str()));
+                names.extend(exported.map(|name| format!("{alias}::{name}")));
+            }
+        }
+        names
+    }
+}
+
+// PROCEDURE INFO
+// ================================================================================================
+
+/// Information about an invoked procedure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcedureInfo {
+    /// Location of the procedure declaration, if known.
+    pub location: Option<Location>,
+    /// Declaration of the procedure, e.g. `export.foo.2(a: felt) -> (b: felt)`.
+    pub header: String,
+    /// Doc comment of the procedure, if any.
+    pub docs: Option<String>,
+}
+
+impl ProcedureInfo {
+    /// Returns the Markdown description of the procedure displayed on hover.
+    pub fn hover_text(&self) -> String {
+        let mut text = format!("```masm\n{}\n```", self.header);
+        if let Some(docs) = &self.docs {
+            text.push_str("\n\n");
+            text.push_str(docs);
+        }
+        text
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Reads a library from a directory with .masm sources; the name of the directory is used as the
+/// namespace of the library.
+///
+/// References to constants of other libraries are resolved against the provided imported
+/// constants.
+fn read_library_dir(
+    dir: &Path,
+    imported_constants: &ImportedConstants,
+) -> Result<MaslLibrary, String> {
+    let namespace = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| format!("Invalid library directory `{}`", dir.display()))?;
+    let namespace = LibraryNamespace::try_from(namespace)
+        .map_err(|err| format!("Invalid library namespace: {err}"))?;
+    MaslLibrary::read_from_dir_with_imported_constants(
+        dir,
+        namespace,
+        true,
+        Version::MIN,
+        imported_constants,
+    )
+    .map_err(|err| format!("Failed to read library from `{}`: {err}", dir.display()))
+}
+
+/// Returns the path of a .masm file with the specified path components relative to `dir`.
+fn module_file<'a>(dir: &Path, components: impl Iterator<Item = &'a str>) -> PathBuf {
+    components
+        .fold(dir.to_path_buf(), |file, component| file.join(component))
+        .with_extension("masm")
+}
+
+/// Returns true if the specified source is a program, i.e., it contains a `begin` block.
+fn is_program(source: &str) -> bool {
+    source.lines().any(|line| line.split_whitespace().next() == Some("begin"))
+}
+
+/// Returns a diagnostic with the specified range, severity, and message.
+fn diagnostic(range: Range, severity: DiagnosticSeverity, message: String) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(severity),
+        source: Some(DIAGNOSTICS_SOURCE.to_string()),
+        message,
+        ..Default::default()
+    }
+}
+
+/// Returns the range of the source covered by the location of the specified assembly error.
+///
+/// Errors which occurred in other modules (e.g., in imported libraries) are reported at the start
+/// of the source.
+fn error_range(source: &str, error: &AssemblyError) -> Range {
+    match error.source_location() {
+        Some(location)
+            if location.path() == LibraryPath::EXEC_PATH
+                || location.path() == LibraryPath::anon_path().path() =>
+        {
+            token_range(source, &SourceLocation::new(location.line(), location.column()))
+        }
+        _ => Range::default(),
+    }
+}
+
+/// Returns the range of the token starting at the specified location of the source.
+fn token_range(source: &str, location: &SourceLocation) -> Range {
+    if location.line() == 0 {
+        return Range::default();
+    }
+    let line = location.line() - 1;
+    let start = location.column().saturating_sub(1);
+    match token_at(source, Position::new(line, start)) {
+        Some((_, range)) => range,
+        None => Range::new(Position::new(line, start), Position::new(line, start)),
+    }
+}
+
+/// Returns the whitespace-delimited token at the specified position of the source, together with
+/// its range.
+fn token_at(source: &str, position: Position) -> Option<(&str, Range)> {
+    let line = source.lines().nth(position.line as usize)?;
+    let offset = byte_offset(line, position.character);
+    let start = line[..offset].rfind(char::is_whitespace).map_or(0, |pos| pos + 1);
+    let end = line[offset..].find(char::is_whitespace).map_or(line.len(), |pos| offset + pos);
+    if start >= end {
+        return None;
+    }
+    let range = Range::new(
+        Position::new(position.line, utf16_column(line, start)),
+        Position::new(position.line, utf16_column(line, end)),
+    );
+    Some((&line[start..end], range))
+}
+
+/// Returns the byte offset of the specified column within the text.
+///
+/// Columns of LSP positions are counted in UTF-16 code units; columns beyond the end of the text
+/// are mapped to its end, and columns within a character to the end of the character.
+fn byte_offset(text: &str, column: u32) -> usize {
+    let mut num_units = 0;
+    for (offset, c) in text.char_indices() {
+        if num_units >= column as usize {
+            return offset;
+        }
+        num_units += c.len_utf16();
+    }
+    text.len()
+}
+
+/// Returns the column (in UTF-16 code units) of the specified byte offset within the text.
+fn utf16_column(text: &str, offset: usize) -> u32 {
+    text[..offset].encode_utf16().count() as u32
+}
+
+/// Returns the range covering the entire specified line.
+fn line_range(line: usize) -> Range {
+    Range::new(Position::new(line as u32, 0), Position::new(line as u32 + 1, 0))
+}
+
+/// Returns the modules imported into the specified source, keyed by their aliases.
+fn imports(source: &str) -> BTreeMap<String, LibraryPath> {
+    source
+        .lines()
+        .filter_map(|line| {
+            let path = line.split_whitespace().next()?.strip_prefix("use.")?;
+            let (path, alias) = match path.split_once("->") {
+                Some((path, alias)) => (path, alias),
+                None => (path, path.rsplit("::").next()?),
+            };
+            Some((alias.to_string(), LibraryPath::try_from(path.to_string()).ok()?))
+        })
+        .collect()
+}
+
+/// Returns the name of the procedure declared or re-exported by the specified token, if the token
+/// is a procedure declaration.
+fn declared_name(token: &str) -> Option<&str> {
+    let (keyword, declaration) = token.split_once('.')?;
+    if keyword != "proc" && keyword != "export" || declaration.starts_with("const.") {
+        return None;
+    }
+    let declaration = &declaration[..declaration.find('(').unwrap_or(declaration.len())];
+    match (declaration.split_once("->"), declaration.split_once("::")) {
+        (Some((_, alias)), _) => Some(alias),
+        (None, Some((_, name))) => Some(name),
+        (None, None) => declaration.split('.').next(),
+    }
+}
+
+/// Returns the line number and the declaration of the procedure with the specified name declared
+/// in the source.
+fn find_declaration(source: &str, name: &str) -> Option<(usize, String)> {
+    source.lines().enumerate().find_map(|(idx, line)| {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        let token = line.split_whitespace().next()?;
+        (declared_name(token)? == name).then(|| {
+            // the declaration includes the signature of the procedure, if any
+            let end = line
+                .rfind(')')
+                .filter(|_| token.contains('('))
+                .map_or(token.len(), |pos| pos + 1);
+            (idx, line[..end].to_string())
+        })
+    })
+}
+
+/// Returns the doc comment preceding the declaration at the specified line of the source.
+fn doc_comment(source: &str, line: usize) -> Option<String> {
+    let lines = source.lines().take(line).collect::<Vec<_>>();
+    let docs = lines
+        .iter()
+        .rev()
+        .map(|line| line.trim())
+        .take_while(|line| line.starts_with("#!"))
+        .map(|line| line.trim_start_matches("#!").trim())
+        .collect::<Vec<_>>();
+    (!docs.is_empty()).then(|| docs.into_iter().rev().collect::<Vec<_>>().join("\n"))
+}
+
+/// Returns the declaration of the specified procedure, e.g. `export.foo.2(a: felt) -> (b: felt)`.
+fn declaration_header(proc: &ProcedureAst) -> String {
+    let keyword = if proc.is_export { "export" } else { "proc" };
+    let signature = proc.signature.as_ref().map(|signature| signature.to_string());
+    format!("{keyword}.{}.{}{}", proc.name, proc.num_locals, signature.unwrap_or_default())
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::LibraryIndex;
+    use lsp_types::{CompletionTextEdit, DiagnosticSeverity, Position, Range, Url};
+
+    const SOURCE: &str = "\
+use.std::math::u64
+
+#! Adds two u64 values.
+proc.add_u64(a: u64, b: u64) -> (c: u64)
+    exec.u64::wrapping_add
+end
+
+begin
+    exec.add_u64
+end
+";
+
+    #[test]
+    fn diagnostics_test() {
+        let index = LibraryIndex::load(&[]).unwrap();
+        assert!(index.diagnostics(SOURCE).is_empty());
+
+        // syntax errors are reported at the offending token
+        let diagnostics = index.diagnostics("begin\n    push.1 foo\nend");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(diagnostics[0].range, Range::new(Position::new(1, 11), Position::new(1, 14)));
+
+        // compilation errors and warnings are reported together
+        let source = "use.std::math::u64\nexport.foo(a: felt) -> (b: felt)\n    drop\nend\n";
+        let diagnostics = index.diagnostics(source);
+        let severities = diagnostics.iter().map(|d| d.severity.unwrap()).collect::<Vec<_>>();
+        assert_eq!(severities, [DiagnosticSeverity::ERROR, DiagnosticSeverity::WARNING]);
+        assert!(diagnostics[0].message.contains("procedure 'foo' declares stack effect"));
+        assert_eq!(diagnostics[0].range.start, Position::new(1, 0));
+        assert_eq!(diagnostics[1].range, Range::new(Position::new(0, 0), Position::new(0, 18)));
+    }
+
+    #[test]
+    fn resolve_test() {
+        let index = LibraryIndex::load(&[]).unwrap();
+        let uri = Url::parse("file:///test.masm").unwrap();
+
+        // local procedure
+        let info = index.resolve(&uri, SOURCE, Position::new(8, 10)).unwrap();
+        assert_eq!(info.header, "proc.add_u64(a: u64, b: u64) -> (c: u64)");
+        assert_eq!(info.docs.as_deref(), Some("Adds two u64 values."));
+        let location = info.location.unwrap();
+        assert_eq!(location.uri, uri);
+        assert_eq!(location.range.start, Position::new(3, 0));
+
+        // procedure of the standard library
+        let info = index.resolve(&uri, SOURCE, Position::new(4, 12)).unwrap();
+        assert_eq!(info.header, "export.wrapping_add.0");
+        assert!(info.docs.unwrap().contains("c = (a + b) % 2^64"));
+        assert!(info.location.unwrap().uri.path().ends_with("std/math/u64.masm"));
+
+        // tokens which are not invocations are not resolved
+        assert!(index.resolve(&uri, SOURCE, Position::new(0, 5)).is_none());
+    }
+
+    #[test]
+    fn completions_test() {
+        let index = LibraryIndex::load(&[]).unwrap();
+        let source = SOURCE.replace("exec.add_u64", "exec.u64::");
+        let labels = |position| {
+            index
+                .completions(&source, position)
+                .into_iter()
+                .map(|item| item.label)
+                .collect::<Vec<_>>()
+        };
+
+        let procedures = labels(Position::new(8, 14));
+        assert!(procedures.contains(&"add_u64".to_string()));
+        assert!(procedures.contains(&"u64::wrapping_add".to_string()));
+
+        let keywords = labels(Position::new(9, 1));
+        assert!(keywords.contains(&"while.true".to_string()));
+        assert!(keywords.contains(&"u32wrapping_add".to_string()));
+
+        // columns are counted in UTF-16 code units, and may point into or beyond non-ASCII tokens
+        let source = "proc.foo\nend\nbegin\n    exec.ü𝔸::\nend";
+        for column in 0..=24 {
+            index.completions(source, Position::new(3, column));
+        }
+        let completions = index.completions(source, Position::new(3, 14));
+        let Some(CompletionTextEdit::Edit(edit)) = &completions[0].text_edit else {
+            panic!("expected procedure completions");
+        };
+        assert_eq!(edit.range, Range::new(Position::new(3, 9), Position::new(3, 14)));
+    }
+}
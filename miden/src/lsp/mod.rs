@@ -0,0 +1,250 @@
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+        Notification as _, PublishDiagnostics,
+    },
+    request::{Completion, GotoDefinition, HoverRequest, Request as _},
+    CompletionOptions, CompletionParams, CompletionResponse, DidChangeTextDocumentParams,
+    DidCloseTextDocumentParams, DidOpenTextDocumentParams, DidSaveTextDocumentParams,
+    GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents, HoverParams,
+    HoverProviderCapability, MarkupContent, MarkupKind, OneOf, PublishDiagnosticsParams,
+    SaveOptions, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind,
+    TextDocumentSyncOptions, TextDocumentSyncSaveOptions, Url,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::BTreeMap, path::PathBuf};
+
+mod analysis;
+use analysis::LibraryIndex;
+
+// LANGUAGE SERVER
+// ================================================================================================
+
+/// Starts a Miden assembly language server communicating with the editor over stdin/stdout, and
+/// runs it until the editor requests the server to shut down.
+///
+/// Procedures of the standard library, and of the libraries at the specified paths, can be
+/// resolved by the server.
+pub fn start_server(library_paths: &[PathBuf]) -> Result<(), String> {
+    let index = LibraryIndex::load(library_paths)?;
+
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = serde_json::to_value(server_capabilities())
+        .map_err(|err| format!("Failed to serialize server capabilities: {err}"))?;
+    connection
+        .initialize(capabilities)
+        .map_err(|err| format!("Failed to initialize language server: {err}"))?;
+
+    let mut server = LanguageServer {
+        connection,
+        index,
+        documents: BTreeMap::new(),
+    };
+    server.run()?;
+    drop(server);
+
+    io_threads
+        .join()
+        .map_err(|err| format!("Failed to shut down language server: {err}"))
+}
+
+/// Returns the capabilities of the language server announced to the editor.
+fn server_capabilities() -> ServerCapabilities {
+    let text_document_sync = TextDocumentSyncOptions {
+        open_close: Some(true),
+        change: Some(TextDocumentSyncKind::FULL),
+        save: Some(TextDocumentSyncSaveOptions::SaveOptions(SaveOptions {
+            include_text: Some(true),
+        })),
+        ..Default::default()
+    };
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(text_document_sync)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![".".to_string(), ":".to_string()]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// State of the language server.
+struct LanguageServer {
+    connection: Connection,
+    index: LibraryIndex,
+    /// Current contents of the documents opened in the editor.
+    documents: BTreeMap<Url, String>,
+}
+
+impl LanguageServer {
+    /// Handles messages from the editor until the editor requests the server to shut down.
+    fn run(&mut self) -> Result<(), String> {
+        while let Ok(message) = self.connection.receiver.recv() {
+            match message {
+                Message::Request(request) => {
+                    let shutdown = self
+                        .connection
+                        .handle_shutdown(&request)
+                        .map_err(|err| format!("Failed to shut down language server: {err}"))?;
+                    if shutdown {
+                        return Ok(());
+                    }
+                    self.handle_request(request)?;
+                }
+                Message::Notification(notification) => {
+                    // notifications cannot be answered with errors, and a malformed notification
+                    // should not stop the server; thus, errors are only logged
+                    if let Err(err) = self.handle_notification(notification) {
+                        eprintln!("{err}");
+                    }
+                }
+                Message::Response(_) => (),
+            }
+        }
+        Ok(())
+    }
+
+    // REQUESTS
+    // --------------------------------------------------------------------------------------------
+
+    /// Responds to a request from the editor.
+    fn handle_request(&mut self, request: Request) -> Result<(), String> {
+        let response = match request.method.as_str() {
+            GotoDefinition::METHOD => respond(request, |params: GotoDefinitionParams| {
+                let params = params.text_document_position_params;
+                let source = self.documents.get(&params.text_document.uri)?;
+                let info =
+                    self.index.resolve(&params.text_document.uri, source, params.position)?;
+                info.location.map(GotoDefinitionResponse::Scalar)
+            }),
+            HoverRequest::METHOD => respond(request, |params: HoverParams| {
+                let params = params.text_document_position_params;
+                let source = self.documents.get(&params.text_document.uri)?;
+                let info =
+                    self.index.resolve(&params.text_document.uri, source, params.position)?;
+                Some(Hover {
+                    contents: HoverContents::Markup(MarkupContent {
+                        kind: MarkupKind::Markdown,
+                        value: info.hover_text(),
+                    }),
+                    range: None,
+                })
+            }),
+            Completion::METHOD => respond(request, |params: CompletionParams| {
+                let params = params.text_document_position;
+                let source = self.documents.get(&params.text_document.uri)?;
+                let items = self.index.completions(source, params.position);
+                Some(CompletionResponse::Array(items))
+            }),
+            _ => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unsupported request `{}`", request.method),
+            ),
+        };
+        self.send(Message::Response(response))
+    }
+
+    // NOTIFICATIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Updates the state of the server according to a notification from the editor.
+    ///
+    /// Diagnostics are published when a document is opened or saved, and cleared when the
+    /// document is closed.
+    fn handle_notification(&mut self, notification: Notification) -> Result<(), String> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams = extract(notification.params)?;
+                let uri = params.text_document.uri;
+                self.documents.insert(uri.clone(), params.text_document.text);
+                self.publish_diagnostics(uri)
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams = extract(notification.params)?;
+                // the server requests full document sync, so the last change contains the entire
+                // contents of the document
+                if let Some(change) = params.content_changes.into_iter().last() {
+                    self.documents.insert(params.text_document.uri, change.text);
+                }
+                Ok(())
+            }
+            DidSaveTextDocument::METHOD => {
+                let params: DidSaveTextDocumentParams = extract(notification.params)?;
+                let uri = params.text_document.uri;
+                if let Some(text) = params.text {
+                    self.documents.insert(uri.clone(), text);
+                }
+                self.publish_diagnostics(uri)
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams = extract(notification.params)?;
+                let uri = params.text_document.uri;
+                self.documents.remove(&uri);
+                self.send_diagnostics(uri, Vec::new())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Publishes diagnostics for the document at the specified URI.
+    fn publish_diagnostics(&self, uri: Url) -> Result<(), String> {
+        let diagnostics = match self.documents.get(&uri) {
+            Some(source) => self.index.diagnostics(source),
+            None => Vec::new(),
+        };
+        self.send_diagnostics(uri, diagnostics)
+    }
+
+    /// Sends the specified diagnostics for the document at the specified URI to the editor.
+    fn send_diagnostics(
+        &self,
+        uri: Url,
+        diagnostics: Vec<lsp_types::Diagnostic>,
+    ) -> Result<(), String> {
+        let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+        let notification = Notification::new(PublishDiagnostics::METHOD.to_string(), params);
+        self.send(Message::Notification(notification))
+    }
+
+    /// Sends the specified message to the editor.
+    fn send(&self, message: Message) -> Result<(), String> {
+        self.connection
+            .sender
+            .send(message)
+            .map_err(|err| format!("Failed to send message to the editor: {err}"))
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns a response to the specified request, computed by applying the handler to the request
+/// parameters.
+fn respond<P, R, F>(request: Request, handler: F) -> Response
+where
+    P: DeserializeOwned,
+    R: Serialize,
+    F: FnOnce(P) -> Option<R>,
+{
+    match serde_json::from_value(request.params) {
+        Ok(params) => Response::new_ok(request.id, handler(params)),
+        Err(err) => invalid_params(request.id, err),
+    }
+}
+
+/// Returns an error response for a request with malformed parameters.
+fn invalid_params(id: RequestId, err: serde_json::Error) -> Response {
+    Response::new_err(id, ErrorCode::InvalidParams as i32, format!("invalid parameters: {err}"))
+}
+
+/// Deserializes the parameters of a notification.
+fn extract<P: DeserializeOwned>(params: serde_json::Value) -> Result<P, String> {
+    serde_json::from_value(params).map_err(|err| format!("Invalid notification parameters: {err}"))
+}
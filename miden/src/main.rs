@@ -4,11 +4,12 @@ use miden_vm::{AssemblyError, ExecutionError};
 #[cfg(feature = "tracing-forest")]
 use tracing_forest::ForestLayer;
 #[cfg(not(feature = "tracing-forest"))]
-use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::{format::FmtSpan, writer::BoxMakeWriter};
 use tracing_subscriber::{prelude::*, EnvFilter};
 
 mod cli;
 mod examples;
+mod lsp;
 mod repl;
 mod tools;
 
@@ -27,7 +28,10 @@ pub enum Actions {
     Compile(cli::CompileCmd),
     Bundle(cli::BundleCmd),
     Debug(cli::DebugCmd),
+    Disasm(cli::DisasmCmd),
     Example(examples::ExampleOptions),
+    Fmt(cli::FmtCmd),
+    Lsp(cli::LspCmd),
     Prove(cli::ProveCmd),
     Run(cli::RunCmd),
     Verify(cli::VerifyCmd),
@@ -43,7 +47,10 @@ impl Cli {
             Actions::Compile(compile) => compile.execute(),
             Actions::Bundle(compile) => compile.execute(),
             Actions::Debug(debug) => debug.execute(),
+            Actions::Disasm(disasm) => disasm.execute(),
             Actions::Example(example) => example.execute(),
+            Actions::Fmt(fmt) => fmt.execute(),
+            Actions::Lsp(lsp) => lsp.execute(),
             Actions::Prove(prove) => prove.execute(),
             Actions::Run(run) => run.execute(),
             Actions::Verify(verify) => verify.execute(),
@@ -79,6 +86,14 @@ pub fn main() {
             .with_ansi(false)
             .compact();
 
+        // the language server communicates with the editor over stdout, so its logs must be
+        // written to stderr
+        let format = if matches!(cli.action, Actions::Lsp(_)) {
+            format.with_writer(BoxMakeWriter::new(std::io::stderr))
+        } else {
+            format.with_writer(BoxMakeWriter::new(std::io::stdout))
+        };
+
         registry.with(format).init();
     }
 
@@ -101,8 +116,11 @@ pub enum ProgramError {
 impl fmt::Display for ProgramError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ProgramError::AssemblyError(e) => write!(f, "Assembly Error: {:?}", e),
-            ProgramError::ExecutionError(e) => write!(f, "Execution Error: {:?}", e),
+            ProgramError::AssemblyError(e) => match e.source_location() {
+                Some(location) => write!(f, "Assembly Error: {e} at {location}"),
+                None => write!(f, "Assembly Error: {e}"),
+            },
+            ProgramError::ExecutionError(e) => write!(f, "Execution Error: {e}"),
         }
     }
 }
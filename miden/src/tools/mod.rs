@@ -1,11 +1,18 @@
 use super::{cli::InputFile, ProgramError};
+use assembly::{
+    ast::{ImportedConstants, ProgramAst, StackEffect, StackEffectAnalyzer, StackEffectError},
+    AssemblyError, LibraryPath,
+};
 use clap::Parser;
 use core::fmt;
-use miden_vm::{Assembler, DefaultHost, Host, Operation, StackInputs};
+use miden_vm::{Assembler, DefaultHost, Host, Operation, Program, StackInputs};
 use processor::{AsmOpInfo, TraceLenSummary};
 use std::{fs, path::PathBuf};
 use stdlib::StdLibrary;
 
+mod profiler;
+use profiler::{CallTreeBuilder, Profile, ProfileMetric};
+
 // CLI
 // ================================================================================================
 
@@ -19,6 +26,18 @@ pub struct Analyze {
     /// Path to .inputs file
     #[clap(short = 'i', long = "input", value_parser)]
     input_file: Option<PathBuf>,
+    /// Features enabled for conditional compilation directives, e.g. `-D DEBUG` for `#if DEBUG`
+    #[clap(short = 'D', long = "define", value_parser)]
+    defines: Vec<String>,
+    /// Path to the file to which the profile is written in the folded-stack format
+    #[clap(long = "folded", value_parser)]
+    folded_file: Option<PathBuf>,
+    /// Metric by which the folded stacks are weighted
+    #[clap(long = "folded-metric", value_enum, default_value_t = ProfileMetric::Cycles)]
+    folded_metric: ProfileMetric,
+    /// Path to the file to which the profile is written in the JSON format
+    #[clap(long = "json", value_parser)]
+    json_file: Option<PathBuf>,
 }
 
 /// Implements CLI execution logic
@@ -34,8 +53,9 @@ impl Analyze {
         let stack_inputs = input_data.parse_stack_inputs()?;
         let host = DefaultHost::new(input_data.parse_advice_provider()?);
 
-        let execution_details: ExecutionDetails = analyze(program.as_str(), stack_inputs, host)
-            .expect("Could not retrieve execution details");
+        let (execution_details, profile) =
+            analyze(program.as_str(), &self.defines, stack_inputs, host)
+                .expect("Could not retrieve execution details");
         let program_name = self
             .assembly_file
             .file_name()
@@ -56,6 +76,21 @@ impl Analyze {
 
         println!("{}", execution_details);
 
+        let stack_effects = analyze_stack_effects(program.as_str(), &self.defines)
+            .expect("Could not retrieve stack effects");
+        println!("{}", stack_effects);
+
+        println!("{}", profile);
+
+        if let Some(folded_file) = &self.folded_file {
+            fs::write(folded_file, profile.to_folded_stacks(self.folded_metric))
+                .map_err(|e| format!("could not write folded stacks: {e}"))?;
+        }
+        if let Some(json_file) = &self.json_file {
+            fs::write(json_file, profile.to_json())
+                .map_err(|e| format!("could not write profile: {e}"))?;
+        }
+
         Ok(())
     }
 }
@@ -201,37 +236,127 @@ impl fmt::Display for ExecutionDetails {
     }
 }
 
-/// Returns program analysis of a given program.
+/// Returns program analysis of a given program together with its cycle profile, both collected
+/// in a single execution of the program. The program is compiled with the specified features
+/// enabled for the evaluation of conditional compilation directives.
 pub fn analyze<H>(
     program: &str,
+    features: &[String],
     stack_inputs: StackInputs,
     host: H,
-) -> Result<ExecutionDetails, ProgramError>
+) -> Result<(ExecutionDetails, Profile), ProgramError>
 where
     H: Host,
 {
-    let program = Assembler::default()
-        .with_debug_mode(true)
-        .with_library(&StdLibrary::default())
-        .map_err(ProgramError::AssemblyError)?
-        .compile(program)
-        .map_err(ProgramError::AssemblyError)?;
+    let ast = parse_program(program, features)?;
+    let program = compile_program(&ast)?;
+    let mut call_tree_builder = CallTreeBuilder::for_program(&ast);
     let mut execution_details = ExecutionDetails::default();
 
-    let vm_state_iterator = processor::execute_iter(&program, stack_inputs, host);
+    let mut vm_state_iterator = processor::execute_iter(&program, stack_inputs, host);
     execution_details.set_trace_len_summary(vm_state_iterator.trace_len_summary());
 
-    for state in vm_state_iterator {
+    for state in vm_state_iterator.by_ref() {
         let vm_state = state.map_err(ProgramError::ExecutionError)?;
         if matches!(vm_state.op, Some(Operation::Noop)) {
             execution_details.incr_noop_count();
         }
+        call_tree_builder.record_state(&vm_state);
         if let Some(asmop_info) = vm_state.asmop {
             execution_details.record_asmop(asmop_info);
         }
     }
 
-    Ok(execution_details)
+    Ok((execution_details, call_tree_builder.build(&vm_state_iterator)))
+}
+
+/// Parses a given program with the constants of the standard library available to it, and with
+/// the specified features enabled for the evaluation of conditional compilation directives.
+fn parse_program(program: &str, features: &[String]) -> Result<ProgramAst, ProgramError> {
+    let imported_constants = ImportedConstants::new()
+        .with_library(&StdLibrary::default())
+        .with_features(features.iter().cloned());
+    let (program, _) = ProgramAst::parse_with_imported_constants(program, &imported_constants)
+        .map_err(|errors| {
+            let err = AssemblyError::parsing_errors(errors, LibraryPath::EXEC_PATH);
+            ProgramError::AssemblyError(err)
+        })?;
+    Ok(program)
+}
+
+/// Compiles a given program in debug mode, with the standard library available to it.
+fn compile_program(program: &ProgramAst) -> Result<Program, ProgramError> {
+    Assembler::default()
+        .with_debug_mode(true)
+        .with_library(&StdLibrary::default())
+        .map_err(ProgramError::AssemblyError)?
+        .compile_ast(program)
+        .map_err(ProgramError::AssemblyError)
+}
+
+// STACK EFFECTS
+// ================================================================================================
+
+/// Contains stack effects of the procedures and of the body of a program, computed statically.
+#[derive(Debug, PartialEq, Eq)]
+pub struct StackEffects {
+    /// Names of local procedures of a program together with their stack effects.
+    procedures: Vec<(String, Result<StackEffect, StackEffectError>)>,
+    /// Stack effect of the program body.
+    program: Result<StackEffect, StackEffectError>,
+}
+
+impl fmt::Display for StackEffects {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let effects = self
+            .procedures
+            .iter()
+            .map(|(name, effect)| (name.as_str(), effect))
+            .chain([("begin", &self.program)])
+            .collect::<Vec<_>>();
+
+        // calculate the total length of padding for the procedure name column
+        let padding = effects.iter().fold(20, |max, (name, _)| name.len().max(max));
+
+        writeln!(f, "{0: <padding$} | Stack effect (inputs -> outputs)", "Procedure")?;
+        writeln!(f, "{}", "-".repeat(padding + 35))?;
+        for (name, effect) in effects {
+            match effect {
+                Ok(effect) => writeln!(f, "{name: <padding$} | {effect}")?,
+                Err(err) => {
+                    let location = err.source_location();
+                    let kind = if err.is_unknown() { "unknown" } else { "invalid" };
+                    writeln!(
+                        f,
+                        "{name: <padding$} | {kind}: {err} at {}:{}",
+                        location.line(),
+                        location.column()
+                    )?
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns stack effects of the procedures and of the body of a given program, computed via
+/// static analysis of the program source with the specified features enabled.
+pub fn analyze_stack_effects(
+    program: &str,
+    features: &[String],
+) -> Result<StackEffects, ProgramError> {
+    let program = parse_program(program, features)?;
+    let analyzer = StackEffectAnalyzer::new(program.procedures());
+    let procedures = analyzer
+        .procedure_effects()
+        .map(|(proc, effect)| (proc.name.to_string(), effect.clone()))
+        .collect();
+
+    Ok(StackEffects {
+        procedures,
+        program: analyzer.analyze(program.body()),
+    })
 }
 
 // ASMOP STATS
@@ -290,7 +415,10 @@ impl AsmOpStats {
 
 #[cfg(test)]
 mod tests {
-    use super::{AsmOpStats, ExecutionDetails, StackInputs};
+    use super::{
+        AsmOpStats, ExecutionDetails, StackEffect, StackEffectError, StackEffects, StackInputs,
+    };
+    use assembly::ast::SourceLocation;
     use processor::{ChipletsLengths, DefaultHost, TraceLenSummary};
 
     #[test]
@@ -299,8 +427,8 @@ mod tests {
             "proc.foo.1 loc_store.0 end begin mem_storew.1 dropw push.17 push.1 movdn.2 exec.foo end";
         let stack_inputs = StackInputs::default();
         let host = DefaultHost::default();
-        let execution_details =
-            super::analyze(source, stack_inputs, host).expect("analyze_test: Unexpected Error");
+        let (execution_details, _) = super::analyze(source, &[], stack_inputs, host)
+            .expect("analyze_test: Unexpected Error");
         let expected_details = ExecutionDetails {
             total_noops: 2,
             asm_op_stats: vec![
@@ -319,14 +447,45 @@ mod tests {
         assert_eq!(execution_details, expected_details);
     }
 
+    #[test]
+    fn analyze_stack_effects_test() {
+        let source = "proc.foo.1 loc_store.0 end proc.bar while.true drop end end \
+            begin mem_storew.1 dropw push.17 push.1 movdn.2 exec.foo end";
+        let stack_effects = super::analyze_stack_effects(source, &[]).unwrap();
+        let expected = StackEffects {
+            procedures: vec![
+                ("foo".to_string(), Ok(StackEffect::new(1, 0))),
+                ("bar".to_string(), Err(StackEffectError::WhileLoop(SourceLocation::new(1, 37)))),
+            ],
+            program: Ok(StackEffect::new(5, 2)),
+        };
+        assert_eq!(expected, stack_effects);
+    }
+
+    #[test]
+    fn analyze_with_features() {
+        let source = "begin\npush.1 push.2\n#if DEBUG\ndrop\n#end\nend";
+        let stack_effects = super::analyze_stack_effects(source, &[]).unwrap();
+        assert_eq!(Ok(StackEffect::new(0, 2)), stack_effects.program);
+
+        // code enabled by the features is both analyzed and executed
+        let features = ["DEBUG".to_string()];
+        let stack_effects = super::analyze_stack_effects(source, &features).unwrap();
+        assert_eq!(Ok(StackEffect::new(0, 1)), stack_effects.program);
+        let host = DefaultHost::default();
+        let (execution_details, _) =
+            super::analyze(source, &features, StackInputs::default(), host).unwrap();
+        assert!(execution_details.asm_op_stats().iter().any(|stats| stats.op == "drop"));
+    }
+
     #[test]
     fn analyze_test_execution_error() {
         let source = "begin div end";
         let stack_inputs = vec![1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
         let stack_inputs = StackInputs::try_from_ints(stack_inputs).unwrap();
         let host = DefaultHost::default();
-        let execution_details = super::analyze(source, stack_inputs, host);
-        let expected_error = "Execution Error: DivideByZero(1)";
+        let execution_details = super::analyze(source, &[], stack_inputs, host);
+        let expected_error = "Execution Error: Division by zero at clock cycle 1";
         assert_eq!(execution_details.err().unwrap().to_string(), expected_error);
     }
 
@@ -335,8 +494,9 @@ mod tests {
         let source = "proc.foo.1 loc_store.0 end mem_storew.1 dropw push.17 exec.foo end";
         let stack_inputs = StackInputs::default();
         let host = DefaultHost::default();
-        let execution_details = super::analyze(source, stack_inputs, host);
-        let expected_error = "Assembly Error: ParsingError(\"unexpected token: expected 'begin' but was 'mem_storew.1'\")";
+        let execution_details = super::analyze(source, &[], stack_inputs, host);
+        let expected_error =
+            "Assembly Error: unexpected token: expected 'begin' but was 'mem_storew.1' at #exec:1:28";
         assert_eq!(execution_details.err().unwrap().to_string(), expected_error);
     }
 }
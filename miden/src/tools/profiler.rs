@@ -0,0 +1,602 @@
+use assembly::{
+    ast::{CodeBody, Instruction, ModuleImports, Node, ProcedureAst, ProgramAst},
+    Library,
+};
+use clap::ValueEnum;
+use core::fmt;
+use miden_vm::Operation;
+use processor::{VmState, VmStateIterator};
+use serde_derive::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use stdlib::StdLibrary;
+
+// PROFILE
+// ================================================================================================
+
+/// Name of the root of the call tree, which is the context name of the program body.
+const ROOT_NAME: &str = "#main";
+
+/// Cycle profile of a program, organized as a call tree.
+///
+/// Every node of the tree corresponds to a procedure invoked from a specific call path, and
+/// contains the number of VM cycles and chiplet trace rows attributed to the procedure along this
+/// path. Repeated invocations of a procedure from the same call path are merged into one node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    root: ProfileNode,
+}
+
+impl Profile {
+    /// Returns statistics for every procedure of the program, aggregated across all call paths
+    /// and sorted by the total number of cycles in descending order.
+    pub fn procedures(&self) -> Vec<ProcedureStats> {
+        let mut procedures = BTreeMap::new();
+        self.root.for_each(&mut Vec::new(), &mut |_, node| {
+            procedures
+                .entry(node.name.clone())
+                .or_insert_with(|| ProcedureStats::new(node.name.clone()))
+                .record(node);
+        });
+
+        let mut procedures = procedures.into_values().collect::<Vec<_>>();
+        procedures.sort_by(|a, b| b.total_cycles.cmp(&a.total_cycles).then(a.name.cmp(&b.name)));
+        procedures
+    }
+
+    /// Returns the profile in the folded-stack format consumed by flamegraph tools.
+    ///
+    /// Every line contains the call path of a node (with procedure names separated by `;`)
+    /// followed by the exclusive value of the specified metric for this node. Nodes with a zero
+    /// value are omitted.
+    pub fn to_folded_stacks(&self, metric: ProfileMetric) -> String {
+        let mut folded = String::new();
+        self.root.for_each(&mut Vec::new(), &mut |path, node| {
+            let value = metric.self_value(node);
+            if value > 0 {
+                folded.push_str(&path.join(";"));
+                folded.push_str(&format!(" {value}\n"));
+            }
+        });
+        folded
+    }
+
+    /// Returns the call tree of the profile serialized as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.root).expect("failed to serialize profile")
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let procedures = self.procedures();
+
+        // calculate the total length of padding for the procedure name column
+        let padding = procedures.iter().fold(20, |max, proc| proc.name.len().max(max));
+
+        writeln!(
+            f,
+            "{0: <padding$} | {1: <8} | {2: <12} | {3: <12} | {4: <12} | {5: <12} | Memory rows",
+            "Procedure", "Calls", "Self cycles", "Total cycles", "Hasher rows", "Bitwise rows",
+        )?;
+        writeln!(f, "{}", "-".repeat(padding + 87))?;
+
+        for proc in procedures {
+            writeln!(
+                f,
+                "{0: <padding$} | {1: <8} | {2: <12} | {3: <12} | {4: <12} | {5: <12} | {6:}",
+                proc.name,
+                proc.num_calls,
+                proc.self_cycles,
+                proc.total_cycles,
+                proc.total_rows.hasher,
+                proc.total_rows.bitwise,
+                proc.total_rows.memory,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// PROFILE NODE
+// ================================================================================================
+
+/// A node of the call tree of a [Profile].
+///
+/// Self (exclusive) values include only the cycles and rows attributed to the procedure itself,
+/// while total (inclusive) values also include the values of all procedures it invoked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProfileNode {
+    name: String,
+    num_calls: usize,
+    self_cycles: usize,
+    total_cycles: usize,
+    self_rows: ChipletRows,
+    total_rows: ChipletRows,
+    children: Vec<ProfileNode>,
+}
+
+impl ProfileNode {
+    /// Calls the provided function for this node and all of its descendants in depth-first order,
+    /// passing the call path of the node along with the node.
+    fn for_each<'a, F>(&'a self, path: &mut Vec<&'a str>, f: &mut F)
+    where
+        F: FnMut(&[&'a str], &'a ProfileNode),
+    {
+        path.push(&self.name);
+        f(path, self);
+        for child in self.children.iter() {
+            child.for_each(path, f);
+        }
+        path.pop();
+    }
+}
+
+/// Numbers of rows added to the hasher, bitwise and memory chiplet traces.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ChipletRows {
+    hasher: usize,
+    bitwise: usize,
+    memory: usize,
+}
+
+impl ChipletRows {
+    fn add(&mut self, other: &ChipletRows) {
+        self.hasher += other.hasher;
+        self.bitwise += other.bitwise;
+        self.memory += other.memory;
+    }
+}
+
+/// Statistics of a single procedure aggregated across all call paths of a [Profile].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcedureStats {
+    name: String,
+    num_calls: usize,
+    self_cycles: usize,
+    total_cycles: usize,
+    total_rows: ChipletRows,
+}
+
+impl ProcedureStats {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            num_calls: 0,
+            self_cycles: 0,
+            total_cycles: 0,
+            total_rows: ChipletRows::default(),
+        }
+    }
+
+    fn record(&mut self, node: &ProfileNode) {
+        self.num_calls += node.num_calls;
+        self.self_cycles += node.self_cycles;
+        self.total_cycles += node.total_cycles;
+        self.total_rows.add(&node.total_rows);
+    }
+}
+
+/// Metric by which the folded stacks of a [Profile] are weighted.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProfileMetric {
+    /// Number of VM cycles.
+    #[default]
+    Cycles,
+    /// Number of rows in the hasher chiplet trace.
+    Hasher,
+    /// Number of rows in the bitwise chiplet trace.
+    Bitwise,
+    /// Number of rows in the memory chiplet trace.
+    Memory,
+}
+
+impl ProfileMetric {
+    fn self_value(&self, node: &ProfileNode) -> usize {
+        match self {
+            Self::Cycles => node.self_cycles,
+            Self::Hasher => node.self_rows.hasher,
+            Self::Bitwise => node.self_rows.bitwise,
+            Self::Memory => node.self_rows.memory,
+        }
+    }
+}
+
+// PROFILER
+// ================================================================================================
+
+/// A procedure invoked from a specific call path.
+struct CallNode {
+    name: String,
+    children: Vec<usize>,
+    num_calls: usize,
+}
+
+/// A procedure which is currently being executed.
+struct Frame {
+    node: usize,
+    /// Number of open code blocks at the point where the root block of the procedure is started.
+    /// The procedure returns when this block ends.
+    entry_depth: usize,
+}
+
+/// Reconstructs the call tree of a program from the sequence of executed operations.
+///
+/// Procedure boundaries are not marked in the operation sequence explicitly. Instead, the builder
+/// tracks the nesting of code blocks via the operations which start and end them, and detects
+/// invocations of procedures via the changes of the context names of assembly instructions:
+/// - When a new procedure is invoked, all code blocks started since the last assembly instruction
+///   of the caller are attributed to the callee, and the callee returns when the outermost of
+///   these blocks ends.
+/// - Procedures consisting of a single SPAN block are merged into the SPAN blocks of their
+///   callers by the assembler. A return from such a procedure is detected when an instruction of
+///   one of its callers is executed, and the caller of a newly invoked procedure is determined via
+///   the static call graph of the program. Consecutive invocations of such a procedure from the
+///   same SPAN block are indistinguishable from a single invocation.
+pub(super) struct CallTreeBuilder {
+    call_graph: CallGraph,
+    nodes: Vec<CallNode>,
+    frames: Vec<Frame>,
+    /// Clock cycles at which the currently open code blocks were started.
+    open_blocks: Vec<usize>,
+    /// Minimum number of open code blocks since the last assembly instruction.
+    min_depth: usize,
+    /// Call tree node to which every executed cycle is attributed.
+    cycle_nodes: Vec<usize>,
+}
+
+impl CallTreeBuilder {
+    /// Returns a new builder for the call tree of the specified program.
+    pub(super) fn for_program(program: &ProgramAst) -> Self {
+        Self::new(build_call_graph(program))
+    }
+
+    fn new(call_graph: CallGraph) -> Self {
+        let root = CallNode {
+            name: ROOT_NAME.to_string(),
+            children: Vec::new(),
+            num_calls: 1,
+        };
+        Self {
+            call_graph,
+            nodes: vec![root],
+            frames: vec![Frame {
+                node: 0,
+                entry_depth: 1,
+            }],
+            open_blocks: Vec::new(),
+            min_depth: 0,
+            cycle_nodes: Vec::new(),
+        }
+    }
+
+    /// Records the operation executed at the specified state of the VM (if any).
+    pub(super) fn record_state(&mut self, vm_state: &VmState) {
+        if let Some(op) = vm_state.op {
+            let context_name = vm_state.asmop.as_ref().map(|asmop| asmop.context_name());
+            self.record_cycle(op, context_name);
+        }
+    }
+
+    /// Records an operation executed in the next clock cycle, together with the context name of
+    /// the assembly instruction the operation belongs to (if any).
+    fn record_cycle(&mut self, op: Operation, context_name: Option<&str>) {
+        let clk = self.cycle_nodes.len();
+        match op {
+            Operation::Join
+            | Operation::Split
+            | Operation::Loop
+            | Operation::Span
+            | Operation::Call
+            | Operation::SysCall
+            | Operation::Dyn => {
+                self.cycle_nodes.push(self.current_node());
+                self.open_blocks.push(clk);
+            }
+            Operation::End => {
+                self.cycle_nodes.push(self.current_node());
+                self.open_blocks.pop();
+                let depth = self.open_blocks.len();
+                self.min_depth = self.min_depth.min(depth);
+                while self.frames.len() > 1 && self.frames.last().unwrap().entry_depth > depth {
+                    self.frames.pop();
+                }
+            }
+            _ => {
+                if let Some(name) = context_name {
+                    self.enter_context(name);
+                    self.min_depth = self.open_blocks.len();
+                }
+                self.cycle_nodes.push(self.current_node());
+            }
+        }
+    }
+
+    /// Updates the stack of frames for an assembly instruction with the specified context name.
+    fn enter_context(&mut self, name: &str) {
+        if self.nodes[self.current_node()].name == name {
+            return;
+        }
+
+        // return to one of the callers of the current procedure
+        if let Some(pos) = self.frames.iter().rposition(|frame| self.nodes[frame.node].name == name)
+        {
+            self.frames.truncate(pos + 1);
+            return;
+        }
+
+        // invoke a new procedure from the innermost procedure which can invoke it; the blocks
+        // started since the last instruction of the caller belong to the callee
+        if let Some(pos) = self.frames.iter().rposition(|frame| {
+            self.call_graph
+                .get(&self.nodes[frame.node].name)
+                .is_some_and(|callees| callees.contains(name))
+        }) {
+            self.frames.truncate(pos + 1);
+        }
+        let parent = self.current_node();
+        let node = match self.nodes[parent].children.iter().find(|&&c| self.nodes[c].name == name) {
+            Some(&node) => node,
+            None => {
+                self.nodes.push(CallNode {
+                    name: name.to_string(),
+                    children: Vec::new(),
+                    num_calls: 0,
+                });
+                let node = self.nodes.len() - 1;
+                self.nodes[parent].children.push(node);
+                node
+            }
+        };
+        self.nodes[node].num_calls += 1;
+
+        // blocks started before the caller was invoked cannot belong to the callee
+        let start_depth = self.min_depth.max(self.frames.last().expect("no frames").entry_depth);
+        for &clk in self.open_blocks.iter().skip(start_depth) {
+            self.cycle_nodes[clk] = node;
+        }
+        self.frames.push(Frame {
+            node,
+            entry_depth: start_depth + 1,
+        });
+    }
+
+    fn current_node(&self) -> usize {
+        self.frames.last().expect("no frames").node
+    }
+
+    /// Builds the profile, attributing the chiplet trace rows added at every clock cycle to the
+    /// procedure which executed that cycle.
+    pub(super) fn build(self, vm_state_iterator: &VmStateIterator) -> Profile {
+        let mut self_cycles = vec![0; self.nodes.len()];
+        let mut self_rows = vec![ChipletRows::default(); self.nodes.len()];
+
+        for (clk, &node) in self.cycle_nodes.iter().enumerate() {
+            self_cycles[node] += 1;
+
+            let before = vm_state_iterator.chiplets_lengths_at(clk as u32);
+            let after = vm_state_iterator.chiplets_lengths_at(clk as u32 + 1);
+            if let (Some(before), Some(after)) = (before, after) {
+                self_rows[node].add(&ChipletRows {
+                    hasher: after.hash_chiplet_len() - before.hash_chiplet_len(),
+                    bitwise: after.bitwise_chiplet_len() - before.bitwise_chiplet_len(),
+                    memory: after.memory_chiplet_len() - before.memory_chiplet_len(),
+                });
+            }
+        }
+
+        Profile {
+            root: self.build_node(0, &self_cycles, &self_rows),
+        }
+    }
+
+    fn build_node(
+        &self,
+        idx: usize,
+        self_cycles: &[usize],
+        self_rows: &[ChipletRows],
+    ) -> ProfileNode {
+        let node = &self.nodes[idx];
+        let children = node
+            .children
+            .iter()
+            .map(|&child| self.build_node(child, self_cycles, self_rows))
+            .collect::<Vec<_>>();
+
+        let mut total_cycles = self_cycles[idx];
+        let mut total_rows = self_rows[idx];
+        for child in children.iter() {
+            total_cycles += child.total_cycles;
+            total_rows.add(&child.total_rows);
+        }
+
+        ProfileNode {
+            name: node.name.clone(),
+            num_calls: node.num_calls,
+            self_cycles: self_cycles[idx],
+            total_cycles,
+            self_rows: self_rows[idx],
+            total_rows,
+            children,
+        }
+    }
+}
+
+// CALL GRAPH
+// ================================================================================================
+
+/// Names of the procedures which can be invoked by every procedure, keyed by procedure name.
+type CallGraph = BTreeMap<String, BTreeSet<String>>;
+
+/// Returns the static call graph of a given program and of the modules of the standard library.
+///
+/// Procedures are identified by their names only, and thus, invocations of all procedures with
+/// the same name are merged.
+fn build_call_graph(program: &ProgramAst) -> CallGraph {
+    let mut call_graph = CallGraph::new();
+    add_procedures(&mut call_graph, program.procedures(), program.import_info());
+    let mut callees = BTreeSet::new();
+    collect_callees(program.body(), program.procedures(), program.import_info(), &mut callees);
+    call_graph.insert(ROOT_NAME.to_string(), callees);
+
+    for module in StdLibrary::default().modules() {
+        add_procedures(&mut call_graph, module.ast.procs(), module.ast.import_info());
+    }
+
+    call_graph
+}
+
+/// Adds the callees of the specified procedures to the provided call graph.
+fn add_procedures(call_graph: &mut CallGraph, procs: &[ProcedureAst], imports: &ModuleImports) {
+    for proc in procs {
+        let callees = call_graph.entry(proc.name.to_string()).or_default();
+        collect_callees(&proc.body, procs, imports, callees);
+    }
+}
+
+/// Adds the names of the procedures invoked from the specified body to the provided set.
+fn collect_callees(
+    body: &CodeBody,
+    procs: &[ProcedureAst],
+    imports: &ModuleImports,
+    callees: &mut BTreeSet<String>,
+) {
+    for node in body.nodes() {
+        match node {
+            Node::Instruction(Instruction::ExecLocal(idx) | Instruction::CallLocal(idx)) => {
+                if let Some(proc) = procs.get(*idx as usize) {
+                    callees.insert(proc.name.to_string());
+                }
+            }
+            Node::Instruction(
+                Instruction::ExecImported(id)
+                | Instruction::CallImported(id)
+                | Instruction::SysCall(id),
+            ) => {
+                if let Some(name) = imports.get_procedure_name(id) {
+                    callees.insert(name.to_string());
+                }
+            }
+            Node::Instruction(_) => (),
+            Node::IfElse {
+                true_case,
+                false_case,
+            } => {
+                collect_callees(true_case, procs, imports, callees);
+                collect_callees(false_case, procs, imports, callees);
+            }
+            Node::Repeat { body, .. } | Node::For { body, .. } | Node::While { body } => {
+                collect_callees(body, procs, imports, callees)
+            }
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{Profile, ProfileMetric};
+    use miden_vm::{DefaultHost, StackInputs};
+
+    fn profile(source: &str) -> Profile {
+        let host = DefaultHost::default();
+        let (_, profile) =
+            super::super::analyze(source, &[], StackInputs::default(), host).unwrap();
+        profile
+    }
+
+    #[test]
+    fn profile_call_tree() {
+        let source = "
+            proc.foo
+                push.1 push.2 u32and drop
+                push.1 if.true push.4 drop end
+            end
+
+            proc.bar.1
+                push.3 loc_store.0 exec.foo exec.foo
+            end
+
+            proc.baz
+                hperm
+            end
+
+            begin
+                exec.bar
+                push.1 drop
+                exec.foo
+                push.2 drop
+                exec.baz
+                push.3 drop
+            end";
+        let profile = profile(source);
+
+        let root = &profile.root;
+        assert_eq!("#main", root.name);
+        let children = root.children.iter().map(|n| n.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(vec!["bar", "foo", "baz"], children);
+
+        // bar invokes foo twice, and foo is invoked once more from the program body
+        let bar = &root.children[0];
+        assert_eq!(1, bar.num_calls);
+        assert_eq!(1, bar.children.len());
+        let bar_foo = &bar.children[0];
+        assert_eq!("foo", bar_foo.name);
+        assert_eq!(2, bar_foo.num_calls);
+        assert_eq!(bar.self_cycles + bar_foo.total_cycles, bar.total_cycles);
+        assert_eq!(1, root.children[1].num_calls);
+
+        // bitwise rows are attributed to foo, memory rows to bar and hasher rows to baz
+        assert_eq!(16, bar_foo.self_rows.bitwise);
+        assert_eq!(0, bar.self_rows.bitwise);
+        assert_eq!(1, bar.self_rows.memory);
+        assert_eq!(8, root.children[2].self_rows.hasher);
+
+        // all cycles of the program are attributed to the call tree
+        let procedures = profile.procedures();
+        let foo = procedures.iter().find(|proc| proc.name == "foo").unwrap();
+        assert_eq!(3, foo.num_calls);
+        assert_eq!(
+            root.total_cycles,
+            procedures.iter().map(|proc| proc.self_cycles).sum::<usize>()
+        );
+
+        // folded stacks contain a line for every call path
+        let folded = profile.to_folded_stacks(ProfileMetric::Cycles);
+        let paths = folded.lines().map(|line| line.rsplit_once(' ').unwrap().0).collect::<Vec<_>>();
+        assert_eq!(vec!["#main", "#main;bar", "#main;bar;foo", "#main;foo", "#main;baz"], paths);
+        let folded = profile.to_folded_stacks(ProfileMetric::Bitwise);
+        assert_eq!("#main;bar;foo 16\n#main;foo 8\n", folded);
+
+        let json: serde_json::Value = serde_json::from_str(&profile.to_json()).unwrap();
+        assert_eq!("bar", json["children"][0]["name"]);
+        assert_eq!(2, json["children"][0]["children"][0]["num_calls"]);
+    }
+
+    #[test]
+    fn profile_calls_and_syscalls() {
+        let source = "
+            proc.foo
+                push.1 push.2 add drop
+            end
+
+            begin
+                call.foo
+                push.3 drop
+                call.foo
+            end";
+        let profile = profile(source);
+
+        let root = &profile.root;
+        assert_eq!(1, root.children.len());
+        let foo = &root.children[0];
+        assert_eq!("foo", foo.name);
+        assert_eq!(2, foo.num_calls);
+
+        // CALL blocks and their END operations are attributed to the callee
+        assert!(foo.self_cycles >= 2 * 8);
+        assert_eq!(root.total_cycles, root.self_cycles + foo.total_cycles);
+    }
+}
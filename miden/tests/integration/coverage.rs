@@ -0,0 +1,136 @@
+use miden_vm::{Assembler, DefaultHost, StackInputs};
+use processor::{execute, ExecutionOptions};
+use test_utils::build_test;
+
+// CODE COVERAGE TESTS
+// ================================================================================================
+
+const SOURCE: &str = "\
+proc.foo
+    push.2 mul
+end
+begin
+    push.1
+    if.true
+        push.3
+    else
+        push.4
+    end
+    repeat.2
+        exec.foo
+    end
+    drop
+    while.true
+        push.0
+    end
+end";
+
+#[test]
+fn line_coverage() {
+    let coverage = build_test!(SOURCE, &[1, 1]).line_coverage().unwrap();
+
+    let lines = coverage.lines("#exec").collect::<Vec<_>>();
+    assert_eq!(vec![(2, 2), (5, 1), (7, 1), (9, 0), (14, 1), (16, 1)], lines);
+    assert_eq!(Some(0), coverage.hits("#exec", 9));
+    assert_eq!(None, coverage.hits("#exec", 6));
+
+    let lcov = coverage.to_lcov(|path| Some(format!("{}.masm", path.trim_start_matches('#'))));
+    let expected = "\
+TN:
+SF:exec.masm
+FN:1,foo
+FNDA:2,foo
+FNF:1
+FNH:1
+BRDA:6,0,0,1
+BRDA:6,0,1,0
+BRDA:15,1,0,1
+BRDA:15,1,1,1
+BRF:4
+BRH:3
+DA:2,2
+DA:5,1
+DA:7,1
+DA:9,0
+DA:14,1
+DA:16,1
+LF:6
+LH:5
+end_of_record
+";
+    assert_eq!(expected, lcov);
+    assert!(coverage.to_lcov(|_| None).is_empty());
+}
+
+#[test]
+fn procedure_and_branch_coverage() {
+    let source = "\
+proc.foo
+    if.true
+        push.1 drop
+    else
+        push.2 drop
+    end
+end
+proc.bar
+    push.3 drop
+end
+proc.baz
+    while.true
+        push.0
+    end
+end
+begin
+    push.0 call.foo
+    exec.bar exec.bar
+    push.0
+    if.true
+        exec.baz
+    end
+end";
+    let coverage = build_test!(source).line_coverage().unwrap();
+
+    // inlined procedures are counted via their first instructions, and called procedures via
+    // their MAST roots
+    let procedures = coverage.procedures("#exec").collect::<Vec<_>>();
+    assert_eq!(vec![("bar", 8, 2), ("baz", 11, 0), ("foo", 1, 1)], procedures);
+
+    let branches = coverage.branches("#exec").collect::<Vec<_>>();
+    assert_eq!(vec![(2, [0, 1]), (12, [0, 0]), (20, [0, 1])], branches);
+
+    // branches of conditions which were never evaluated are reported as not executed
+    let lcov = coverage.to_lcov(|_| Some("exec.masm".to_string()));
+    assert!(lcov.contains("FNF:3\nFNH:2\n"));
+    assert!(lcov.contains("BRDA:12,1,0,-\nBRDA:12,1,1,-\n"));
+    assert!(lcov.contains("BRF:6\nBRH:2\n"));
+}
+
+#[test]
+fn block_coverage() {
+    let program = Assembler::default().with_source_map(true).compile(SOURCE).unwrap();
+    let stack_inputs = StackInputs::try_from_ints([1, 1]).unwrap();
+
+    // coverage is collected only if it is enabled in the execution options
+    let trace = execute(
+        &program,
+        stack_inputs.clone(),
+        DefaultHost::default(),
+        ExecutionOptions::default(),
+    )
+    .unwrap();
+    assert!(trace.coverage().is_none());
+
+    let options = ExecutionOptions::default().with_coverage();
+    let trace = execute(&program, stack_inputs, DefaultHost::default(), options).unwrap();
+    let coverage = trace.coverage().unwrap();
+
+    // the program is executed once, and only executed blocks are recorded
+    assert_eq!(1, coverage.block_count(program.hash()));
+    assert!(coverage.blocks().all(|(_, count)| count > 0));
+
+    // coverage of several executions can be merged
+    let mut merged = coverage.clone();
+    merged.merge(coverage);
+    assert_eq!(2, merged.block_count(program.hash()));
+    assert_eq!(Some(4), merged.line_coverage(&program).hits("#exec", 2));
+}
@@ -1,4 +1,4 @@
-use processor::{AsmOpInfo, ContextId, VmState};
+use processor::{AsmOpInfo, ContextId, DefaultHost, StackInputs, VmState};
 use test_utils::{build_debug_test, Felt, ToElements, ONE};
 use vm_core::{AssemblyOp, Operation};
 
@@ -22,6 +22,7 @@ fn test_exec_iter() {
             ctx: ContextId::root(),
             op: None,
             asmop: None,
+            location: None,
             stack: [16, 15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1].to_elements(),
             fmp,
             memory: Vec::new(),
@@ -31,6 +32,7 @@ fn test_exec_iter() {
             ctx: ContextId::root(),
             op: Some(Operation::Span),
             asmop: None,
+            location: None,
             stack: [16, 15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 1].to_elements(),
             fmp,
             memory: Vec::new(),
@@ -43,6 +45,7 @@ fn test_exec_iter() {
                 AssemblyOp::new("#main".to_string(), 3, "mem_storew.1".to_string(), false),
                 1,
             )),
+            location: None,
             stack: [0, 16, 15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1].to_elements(),
             fmp,
             memory: Vec::new(),
@@ -55,6 +58,7 @@ fn test_exec_iter() {
                 AssemblyOp::new("#main".to_string(), 3, "mem_storew.1".to_string(), false),
                 2,
             )),
+            location: None,
             stack: [1, 16, 15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2].to_elements(),
             fmp,
             memory: Vec::new(),
@@ -67,6 +71,7 @@ fn test_exec_iter() {
                 AssemblyOp::new("#main".to_string(), 3, "mem_storew.1".to_string(), false),
                 3,
             )),
+            location: None,
             stack: [16, 15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1].to_elements(),
             fmp,
             memory: mem.clone(),
@@ -79,6 +84,7 @@ fn test_exec_iter() {
                 AssemblyOp::new("#main".to_string(), 4, "dropw".to_string(), false),
                 1,
             )),
+            location: None,
             stack: [15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0].to_elements(),
             fmp,
             memory: mem.clone(),
@@ -91,6 +97,7 @@ fn test_exec_iter() {
                 AssemblyOp::new("#main".to_string(), 4, "dropw".to_string(), false),
                 2,
             )),
+            location: None,
             stack: [14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 0].to_elements(),
             fmp,
             memory: mem.clone(),
@@ -103,6 +110,7 @@ fn test_exec_iter() {
                 AssemblyOp::new("#main".to_string(), 4, "dropw".to_string(), false),
                 3,
             )),
+            location: None,
             stack: [13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 0, 0].to_elements(),
             fmp,
             memory: mem.clone(),
@@ -115,6 +123,7 @@ fn test_exec_iter() {
                 AssemblyOp::new("#main".to_string(), 4, "dropw".to_string(), false),
                 4,
             )),
+            location: None,
             stack: [12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 0, 0, 0, 0].to_elements(),
             fmp,
             memory: mem.clone(),
@@ -127,6 +136,7 @@ fn test_exec_iter() {
                 AssemblyOp::new("#main".to_string(), 1, "push.17".to_string(), false),
                 1,
             )),
+            location: None,
             stack: [17, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 0, 0, 0].to_elements(),
             fmp,
             memory: mem.clone(),
@@ -136,6 +146,7 @@ fn test_exec_iter() {
             ctx: ContextId::root(),
             op: Some(Operation::Noop),
             asmop: None,
+            location: None,
             stack: [17, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 0, 0, 0, 0].to_elements(),
             fmp,
             memory: mem.clone(),
@@ -145,6 +156,7 @@ fn test_exec_iter() {
             ctx: ContextId::root(),
             op: Some(Operation::Push(ONE)),
             asmop: None,
+            location: None,
             stack: [1, 17, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 0, 0].to_elements(),
             fmp,
             memory: mem.clone(),
@@ -154,6 +166,7 @@ fn test_exec_iter() {
             ctx: ContextId::root(),
             op: Some(Operation::FmpUpdate),
             asmop: None,
+            location: None,
             stack: [17, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 0, 0, 0, 0].to_elements(),
             fmp: next_fmp,
             memory: mem.clone(),
@@ -166,6 +179,7 @@ fn test_exec_iter() {
                 AssemblyOp::new("foo".to_string(), 4, "loc_store.0".to_string(), false),
                 1,
             )),
+            location: None,
             stack: [0, 17, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 0, 0, 0].to_elements(),
             fmp: next_fmp,
             memory: mem.clone(),
@@ -178,6 +192,7 @@ fn test_exec_iter() {
                 AssemblyOp::new("foo".to_string(), 4, "loc_store.0".to_string(), false),
                 2,
             )),
+            location: None,
             stack: [2u64.pow(30) + 1, 17, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 0, 0]
                 .to_elements(),
             fmp: next_fmp,
@@ -191,6 +206,7 @@ fn test_exec_iter() {
                 AssemblyOp::new("foo".to_string(), 4, "loc_store.0".to_string(), false),
                 3,
             )),
+            location: None,
             stack: [17, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 0, 0].to_elements(),
             fmp: next_fmp,
             memory: vec![
@@ -206,6 +222,7 @@ fn test_exec_iter() {
                 AssemblyOp::new("foo".to_string(), 4, "loc_store.0".to_string(), false),
                 4,
             )),
+            location: None,
             stack: [12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 0, 0, 0, 0].to_elements(),
             fmp: next_fmp,
             memory: vec![
@@ -230,3 +247,41 @@ fn slice_to_word(values: &[i32]) -> [Felt; 4] {
         Felt::new(values[3] as u64),
     ]
 }
+
+#[test]
+fn test_exec_iter_source_locations() {
+    let source = "\
+proc.foo
+    push.0 assert
+end
+begin
+    push.1
+    exec.foo
+end";
+    let program = assembly::Assembler::default()
+        .with_debug_mode(true)
+        .with_source_map(true)
+        .compile(source)
+        .unwrap();
+    let host = DefaultHost::default();
+    let states =
+        processor::execute_iter(&program, StackInputs::default(), host).collect::<Vec<_>>();
+
+    // the execution fails at the assertion, and the error points at the failing instruction
+    let err = states.last().unwrap().as_ref().unwrap_err();
+    assert_eq!("#exec:2:12", err.source_location().unwrap().to_string());
+
+    // VM states report the locations of the executed operations: SPAN, PAD, INCR, PAD
+    let locations = states[..5]
+        .iter()
+        .map(|state| state.as_ref().unwrap().location.as_ref().map(ToString::to_string))
+        .collect::<Vec<_>>();
+    let expected = vec![
+        None,
+        None,
+        Some("#exec:5:5".to_string()),
+        Some("#exec:5:5".to_string()),
+        Some("#exec:2:5".to_string()),
+    ];
+    assert_eq!(expected, locations);
+}
@@ -69,6 +69,45 @@ fn counter_controlled_loop() {
     test.expect_stack(&[1024]);
 }
 
+#[test]
+fn indexed_loop() {
+    // compute the sum of indexes; the loop is compiled into a LOOP block
+    let source = "
+        begin
+            push.0
+            for.10
+                dup movup.2 add swap
+            end
+        end";
+
+    let test = build_test!(source, &[7]);
+    test.expect_stack(&[45, 7]);
+
+    // compute the sum of squares of indexes; the loop is unrolled
+    let source = "
+        begin
+            for.4
+                dup dup mul movup.2 add swap
+            end
+        end";
+
+    let test = build_test!(source, &[1]);
+    test.expect_stack(&[15]);
+
+    // the index is available within nested loops
+    let source = "
+        begin
+            for.3
+                for.5
+                    dup dup.2 mul movup.3 add movdn.2
+                end
+            end
+        end";
+
+    let test = build_test!(source, &[0]);
+    test.expect_stack(&[30]);
+}
+
 // NESTED CONTROL FLOW
 // ================================================================================================
 
@@ -2,9 +2,11 @@ use test_utils::build_test;
 
 mod air;
 mod cli;
+mod coverage;
 mod exec_iters;
 mod flow_control;
 mod operations;
+mod optimizer;
 
 // TESTS
 // ================================================================================================
@@ -2,7 +2,7 @@ use processor::{
     AdviceExtractor, AdviceProvider, ExecutionError, Host, HostResponse, MemAdviceProvider,
     ProcessState,
 };
-use vm_core::AdviceInjector;
+use vm_core::{AdviceInjector, Felt, Word};
 
 mod advice;
 mod asmop;
@@ -43,6 +43,10 @@ impl<A: AdviceProvider> Host for TestHost<A> {
         self.adv_provider.set_advice(process, &injector)
     }
 
+    fn insert_into_adv_map(&mut self, key: Word, values: Vec<Felt>) -> Result<(), ExecutionError> {
+        self.adv_provider.insert_into_map(key, values)
+    }
+
     fn on_event<S: ProcessState>(
         &mut self,
         _process: &S,
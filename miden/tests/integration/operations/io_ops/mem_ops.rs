@@ -1,4 +1,10 @@
 use super::{apply_permutation, build_op_test, build_test, Felt, ToElements};
+use assembly::Assembler;
+use processor::{
+    AdviceExtractor, DefaultHost, ExecutionError, Host, HostResponse, MemAdviceProvider,
+    ProcessState, StackInputs,
+};
+use vm_core::AdviceInjector;
 
 // LOADING SINGLE ELEMENT ONTO THE STACK (MLOAD)
 // ================================================================================================
@@ -213,3 +219,62 @@ fn read_after_write() {
     let test = build_op_test!("mem_storew.0 dropw mem_loadw.0", &[1, 2, 3, 4, 5, 6, 7, 8]);
     test.expect_stack(&[8, 7, 6, 5]);
 }
+
+// DATA SEGMENTS
+// ================================================================================================
+
+#[test]
+fn data_segments() {
+    let source = "
+        const.TABLE=[5, 6, 7]
+        data.10=[1, 2, 3, 4, TABLE, 8, 9]
+        data.20=[11]
+        begin
+            padw push.10 mem_loadw
+            padw mem_loadw.11
+            padw mem_loadw.12
+            mem_load.20
+            mem_load.13
+        end";
+
+    let test = build_test!(source, &[]);
+    test.expect_stack(&[0, 11, 0, 0, 0, 9, 8, 7, 6, 5, 4, 3, 2, 1]);
+
+    // the loaded data is visible in memory, including the padding of the segments
+    let test = build_test!("data.10=[1, 2, 3, 4, 5] begin push.0 end", &[]);
+    test.expect_stack_and_memory(&[0], 10, &[1, 2, 3, 4, 5, 0, 0, 0]);
+}
+
+#[test]
+fn data_segments_require_host_support() {
+    /// A host which does not support data segments.
+    struct TestHost(DefaultHost<MemAdviceProvider>);
+
+    impl Host for TestHost {
+        fn get_advice<S: ProcessState>(
+            &mut self,
+            process: &S,
+            extractor: AdviceExtractor,
+        ) -> Result<HostResponse, ExecutionError> {
+            self.0.get_advice(process, extractor)
+        }
+
+        fn set_advice<S: ProcessState>(
+            &mut self,
+            process: &S,
+            injector: AdviceInjector,
+        ) -> Result<HostResponse, ExecutionError> {
+            self.0.set_advice(process, injector)
+        }
+    }
+
+    let program = Assembler::default().compile("data.10=[1, 2] begin push.0 end").unwrap();
+    let host = TestHost(DefaultHost::default());
+    let result = processor::execute(&program, StackInputs::default(), host, Default::default());
+    assert!(matches!(result, Err(ExecutionError::DataSegmentsNotSupported)));
+
+    let host = TestHost(DefaultHost::default());
+    let result =
+        processor::execute_fast(&program, StackInputs::default(), host, Default::default());
+    assert!(matches!(result, Err(ExecutionError::DataSegmentsNotSupported)));
+}
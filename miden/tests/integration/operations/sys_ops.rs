@@ -1,5 +1,6 @@
+use assembly::ast::error_code_from_msg;
 use processor::ExecutionError;
-use test_utils::{build_op_test, TestError};
+use test_utils::{build_op_test, build_test, TestError};
 
 // SYSTEM OPS ASSERTIONS - MANUAL TESTS
 // ================================================================================================
@@ -28,6 +29,35 @@ fn assert_with_code() {
     }));
 }
 
+#[test]
+fn assert_with_message() {
+    let asm_op = r#"assert.err="value must be non-zero""#;
+
+    let test = build_op_test!(asm_op, &[1]);
+    test.expect_stack(&[]);
+
+    // triggered assertion captures the message of the error code
+    let test = build_op_test!(asm_op, &[0]);
+    test.expect_error(TestError::ExecutionError(ExecutionError::FailedAssertion {
+        clk: 1,
+        err_code: error_code_from_msg("value must be non-zero"),
+        err_msg: Some("value must be non-zero".to_string()),
+    }));
+
+    // messages can also be declared via constants
+    let source = r#"
+        const.ERR_NOT_EQUAL="values must be equal"
+        begin
+            assert_eq.err=ERR_NOT_EQUAL
+        end"#;
+    let test = build_test!(source, &[1, 2]);
+    test.expect_error(TestError::ExecutionError(ExecutionError::FailedAssertion {
+        clk: 2,
+        err_code: error_code_from_msg("values must be equal"),
+        err_msg: Some("values must be equal".to_string()),
+    }));
+}
+
 #[test]
 fn assert_fail() {
     let asm_op = "assert";
@@ -0,0 +1,71 @@
+use miden_vm::{Assembler, DefaultHost, StackInputs};
+use processor::{execute, ExecutionOptions, ExecutionTrace};
+
+// PEEPHOLE OPTIMIZER TESTS
+// ================================================================================================
+
+/// Instruction sequences exercising every peephole optimization rule of the assembler. The
+/// sequences expect the two topmost stack items to be binary values.
+const SEQUENCES: &[&str] = &[
+    "swap swap",
+    "swapw swapw",
+    "swapw.2 swapw.2",
+    "swapw.3 swapw.3",
+    "swapdw swapdw",
+    "movup.2 movdn.2",
+    "movup.3 movdn.3",
+    "movup.4 movdn.4",
+    "movup.5 movdn.5",
+    "movup.6 movdn.6",
+    "movup.7 movdn.7",
+    "movup.8 movdn.8",
+    "movdn.2 movup.2",
+    "movdn.3 movup.3",
+    "movdn.4 movup.4",
+    "movdn.5 movup.5",
+    "movdn.6 movup.6",
+    "movdn.7 movup.7",
+    "movdn.8 movup.8",
+    "dup.5 drop",
+    "push.0 drop",
+    "push.7 drop",
+    "push.0 add",
+    "push.1 add",
+    "push.1 mul",
+    "push.0 eq",
+    "neg neg",
+    "swap add",
+    "swap mul",
+    "swap eq",
+    "swap and",
+    "swap or",
+];
+
+#[test]
+fn optimized_programs_produce_same_outputs() {
+    let inputs = [16, 15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 0, 1];
+
+    for sequence in SEQUENCES {
+        // the sequence is surrounded by other instructions to make sure it is optimized in the
+        // middle of a SPAN block as well
+        let source = format!("begin push.2 movdn.3 {sequence} push.3 mul end");
+
+        let expected = execute_program(&source, &inputs, false);
+        let optimized = execute_program(&source, &inputs, true);
+        assert_eq!(expected.stack_outputs(), optimized.stack_outputs(), "{sequence}");
+        assert!(
+            optimized.trace_len_summary().main_trace_len()
+                < expected.trace_len_summary().main_trace_len(),
+            "{sequence} was not optimized"
+        );
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn execute_program(source: &str, inputs: &[u64], optimize: bool) -> ExecutionTrace {
+    let program = Assembler::default().with_optimizations(optimize).compile(source).unwrap();
+    let stack_inputs = StackInputs::try_from_ints(inputs.iter().copied()).unwrap();
+    execute(&program, stack_inputs, DefaultHost::default(), ExecutionOptions::default()).unwrap()
+}
@@ -9,6 +9,7 @@ use miden_air::trace::chiplets::hasher::{Digest, HasherState};
 use vm_core::{code_blocks::OpBatch, Kernel};
 
 mod bitwise;
+pub(crate) use bitwise::assert_u32;
 use bitwise::Bitwise;
 
 mod hasher;
@@ -13,7 +13,7 @@ use miden_air::trace::{
     },
     CHIPLETS_RANGE, CHIPLETS_WIDTH,
 };
-use vm_core::{CodeBlockTable, Felt, ONE, ZERO};
+use vm_core::{Felt, Program, ONE, ZERO};
 
 type ChipletsTrace = [Vec<Felt>; CHIPLETS_WIDTH];
 
@@ -114,8 +114,8 @@ fn build_trace(
     let stack_inputs = StackInputs::try_from_ints(stack_inputs.iter().copied()).unwrap();
     let host = DefaultHost::default();
     let mut process = Process::new(kernel, stack_inputs, host, ExecutionOptions::default());
-    let program = CodeBlock::new_span(operations);
-    process.execute_code_block(&program, &CodeBlockTable::default()).unwrap();
+    let program = Program::new(CodeBlock::new_span(operations));
+    process.execute(&program).unwrap();
 
     let (trace, _, _) = ExecutionTrace::test_finalize_trace(process);
     let trace_len = trace.num_rows() - ExecutionTrace::NUM_RAND_ROWS;
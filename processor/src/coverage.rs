@@ -0,0 +1,370 @@
+use super::{CodeLocation, Digest, Program};
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use core::fmt::Write;
+
+// EXECUTION COVERAGE
+// ================================================================================================
+
+/// Code coverage collected during the execution of a program.
+///
+/// The coverage consists of the number of times every code block was executed, keyed by the hash
+/// of the block, and the number of times every operation of a SPAN block was executed, keyed by
+/// the hash of the block and the index of the operation in the block (i.e., the same way the
+/// source map of a program is keyed). For every SPLIT and LOOP block, the coverage also contains
+/// the number of times the condition of the block evaluated to true and to false; for a LOOP
+/// block, the condition is evaluated before every iteration as well as when the loop exits. Since
+/// code blocks are deduplicated by their hashes, executions of identical code blocks are counted
+/// together.
+///
+/// Coverage is collected by [execute()](crate::execute) if it is enabled via
+/// [ExecutionOptions::with_coverage()](crate::ExecutionOptions::with_coverage), and is available
+/// via [ExecutionTrace::coverage()](crate::ExecutionTrace::coverage) once the execution completes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecutionCoverage {
+    blocks: BTreeMap<[u8; 32], u64>,
+    ops: BTreeMap<[u8; 32], Vec<u64>>,
+    branches: BTreeMap<[u8; 32], [u64; 2]>,
+}
+
+impl ExecutionCoverage {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new empty [ExecutionCoverage].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of times the code block with the specified hash was executed.
+    pub fn block_count(&self, block_hash: Digest) -> u64 {
+        let key: [u8; 32] = block_hash.into();
+        self.blocks.get(&key).copied().unwrap_or(0)
+    }
+
+    /// Returns the number of times the operation at the specified index of the SPAN block with
+    /// the specified hash was executed.
+    pub fn op_count(&self, span_hash: Digest, op_idx: usize) -> u64 {
+        let key: [u8; 32] = span_hash.into();
+        self.ops.get(&key).and_then(|counts| counts.get(op_idx)).copied().unwrap_or(0)
+    }
+
+    /// Returns the number of times the condition of the SPLIT or LOOP block with the specified
+    /// hash evaluated to true and to false, respectively.
+    pub fn branch_counts(&self, block_hash: Digest) -> [u64; 2] {
+        let key: [u8; 32] = block_hash.into();
+        self.branches.get(&key).copied().unwrap_or_default()
+    }
+
+    /// Returns an iterator over the hashes of the executed code blocks and the number of times
+    /// each of them was executed.
+    pub fn blocks(&self) -> impl Iterator<Item = (Digest, u64)> + '_ {
+        self.blocks.iter().map(|(key, &count)| {
+            let hash = Digest::try_from(key).expect("invalid block hash");
+            (hash, count)
+        })
+    }
+
+    /// Returns the line coverage of the source code of the provided program.
+    ///
+    /// The operations of the program are mapped to the lines of the source code via the source
+    /// map of the program; thus, the program must have been compiled with source maps enabled.
+    ///
+    /// An instruction is considered to be executed as many times as the most executed operation
+    /// compiled from it; the executions of the same instruction compiled into several places
+    /// (e.g., of an instruction of an inlined procedure) are added up. The number of hits of a
+    /// line is the largest number of executions of any instruction on the line. The number of
+    /// hits of a procedure is the number of executions of its first instruction if the procedure
+    /// was compiled into a single SPAN block, or the number of executions of its MAST root
+    /// otherwise. The branch counts of a conditional branch are the counts of the SPLIT or LOOP
+    /// block it was compiled into.
+    pub fn line_coverage(&self, program: &Program) -> LineCoverage {
+        let source_map = program.source_map();
+
+        let mut instruction_hits: BTreeMap<&CodeLocation, u64> = BTreeMap::new();
+        for (span_hash, locations) in source_map.iter() {
+            let key: [u8; 32] = span_hash.into();
+            let counts = self.ops.get(&key).map(|counts| counts.as_slice()).unwrap_or_default();
+            for (range, location) in locations.iter() {
+                let hits = range.clone().filter_map(|op_idx| counts.get(op_idx)).max();
+                *instruction_hits.entry(location).or_default() += hits.copied().unwrap_or(0);
+            }
+        }
+
+        let mut coverage = LineCoverage::new();
+        for (location, &hits) in instruction_hits.iter() {
+            coverage.record_line(location.path(), location.line(), hits);
+        }
+        for (mast_root, procedure) in source_map.procedures() {
+            let hits = match procedure.entry() {
+                Some(entry) => instruction_hits.get(entry).copied().unwrap_or(0),
+                None => self.block_count(mast_root),
+            };
+            let declaration = procedure.declaration();
+            coverage.record_procedure(
+                declaration.path(),
+                procedure.name(),
+                declaration.line(),
+                hits,
+            );
+        }
+        for (block_hash, location) in source_map.branches() {
+            let counts = self.branch_counts(block_hash);
+            coverage.record_branch(location.path(), location.line(), location.column(), counts);
+        }
+        coverage
+    }
+
+    // STATE MUTATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Adds the counts of the provided coverage to this coverage.
+    ///
+    /// This can be used to combine the coverage collected over several executions.
+    pub fn merge(&mut self, other: &ExecutionCoverage) {
+        for (key, count) in other.blocks.iter() {
+            *self.blocks.entry(*key).or_default() += count;
+        }
+        for (key, other_counts) in other.ops.iter() {
+            let counts = self.ops.entry(*key).or_default();
+            if counts.len() < other_counts.len() {
+                counts.resize(other_counts.len(), 0);
+            }
+            for (count, other_count) in counts.iter_mut().zip(other_counts) {
+                *count += other_count;
+            }
+        }
+        for (key, other_counts) in other.branches.iter() {
+            let counts = self.branches.entry(*key).or_default();
+            counts[0] += other_counts[0];
+            counts[1] += other_counts[1];
+        }
+    }
+
+    /// Records an execution of the code block with the specified hash.
+    pub(crate) fn record_block(&mut self, block_hash: Digest) {
+        *self.blocks.entry(block_hash.into()).or_default() += 1;
+    }
+
+    /// Records an execution of the operation at the specified index of the SPAN block with the
+    /// specified hash.
+    pub(crate) fn record_op(&mut self, span_hash: Digest, op_idx: usize) {
+        let counts = self.ops.entry(span_hash.into()).or_default();
+        if counts.len() <= op_idx {
+            counts.resize(op_idx + 1, 0);
+        }
+        counts[op_idx] += 1;
+    }
+
+    /// Records an evaluation of the condition of the SPLIT or LOOP block with the specified hash.
+    pub(crate) fn record_branch(&mut self, block_hash: Digest, taken: bool) {
+        let counts = self.branches.entry(block_hash.into()).or_default();
+        counts[usize::from(!taken)] += 1;
+    }
+}
+
+// LINE COVERAGE
+// ================================================================================================
+
+/// Line coverage of the source code of a program.
+///
+/// For every module of the program with known source locations, the coverage contains the lines
+/// which have operations compiled from them, together with the number of times these lines were
+/// executed. It also contains the procedures declared in the module together with the number of
+/// times they were executed, and the conditional branches (i.e., `if.true` and `while.true`
+/// statements) of the module together with the number of times their conditions evaluated to true
+/// and to false. Modules are identified by their paths (e.g., `std::math::u64` or `#exec` for the
+/// executable module of a program). Only the code compiled into the program is covered; e.g.,
+/// procedures of a library which are not invoked by the program have no lines in the coverage.
+///
+/// Since SPAN blocks are deduplicated by their hashes, lines compiled into identical SPAN blocks
+/// are attributed to the location of the block which was compiled first (see the source map of
+/// the program for details).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineCoverage {
+    modules: BTreeMap<String, ModuleCoverage>,
+}
+
+/// Coverage of a single module of a program.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ModuleCoverage {
+    /// Number of hits keyed by line.
+    lines: BTreeMap<u32, u64>,
+    /// Line of the declaration and number of hits keyed by procedure name.
+    procedures: BTreeMap<String, (u32, u64)>,
+    /// True and false counts of the conditions keyed by line and column.
+    branches: BTreeMap<(u32, u32), [u64; 2]>,
+}
+
+impl LineCoverage {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new empty [LineCoverage].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns an iterator over the paths of the modules in this coverage.
+    pub fn modules(&self) -> impl Iterator<Item = &str> {
+        self.modules.keys().map(|path| path.as_str())
+    }
+
+    /// Returns the number of times the specified line of the specified module was executed, or
+    /// None if no operations were compiled from this line.
+    pub fn hits(&self, module_path: &str, line: u32) -> Option<u64> {
+        self.modules
+            .get(module_path)
+            .and_then(|module| module.lines.get(&line))
+            .copied()
+    }
+
+    /// Returns an iterator over the lines of the specified module which have operations compiled
+    /// from them, and the number of times each of these lines was executed.
+    pub fn lines(&self, module_path: &str) -> impl Iterator<Item = (u32, u64)> + '_ {
+        self.modules
+            .get(module_path)
+            .into_iter()
+            .flat_map(|module| module.lines.iter())
+            .map(|(&line, &hits)| (line, hits))
+    }
+
+    /// Returns an iterator over the procedures declared in the specified module, together with
+    /// the lines of their declarations and the number of times each of them was executed.
+    ///
+    /// The procedures are sorted by name.
+    pub fn procedures(&self, module_path: &str) -> impl Iterator<Item = (&str, u32, u64)> + '_ {
+        self.modules
+            .get(module_path)
+            .into_iter()
+            .flat_map(|module| module.procedures.iter())
+            .map(|(name, &(line, hits))| (name.as_str(), line, hits))
+    }
+
+    /// Returns an iterator over the conditional branches of the specified module, together with
+    /// their lines and the number of times their conditions evaluated to true and to false,
+    /// respectively.
+    ///
+    /// The branches are sorted by their locations.
+    pub fn branches(&self, module_path: &str) -> impl Iterator<Item = (u32, [u64; 2])> + '_ {
+        self.modules
+            .get(module_path)
+            .into_iter()
+            .flat_map(|module| module.branches.iter())
+            .map(|(&(line, _), &counts)| (line, counts))
+    }
+
+    /// Returns the coverage in the LCOV tracefile format.
+    ///
+    /// The source file of every module is determined via the provided function, which maps the
+    /// path of a module to the path of its source file. Modules for which the function returns
+    /// None are omitted.
+    ///
+    /// Procedures are reported as functions (`FN` and `FNDA` records), and every conditional
+    /// branch is reported as a block with two branches (`BRDA` records): the first one is taken
+    /// when the condition evaluates to true, and the second one when it evaluates to false. The
+    /// branches of a block whose condition was never evaluated are reported as not executed.
+    pub fn to_lcov<F>(&self, mut source_file: F) -> String
+    where
+        F: FnMut(&str) -> Option<String>,
+    {
+        let mut lcov = String::new();
+        for (module_path, module) in self.modules.iter() {
+            let Some(source_file) = source_file(module_path) else {
+                continue;
+            };
+
+            writeln!(lcov, "TN:").unwrap();
+            writeln!(lcov, "SF:{source_file}").unwrap();
+
+            let mut procedures: Vec<_> = module.procedures.iter().collect();
+            procedures.sort_by_key(|&(name, &(line, _))| (line, name));
+            for (name, (line, _)) in procedures.iter() {
+                writeln!(lcov, "FN:{line},{name}").unwrap();
+            }
+            for (name, (_, hits)) in procedures.iter() {
+                writeln!(lcov, "FNDA:{hits},{name}").unwrap();
+            }
+            writeln!(lcov, "FNF:{}", procedures.len()).unwrap();
+            let num_hit = procedures.iter().filter(|(_, &(_, hits))| hits > 0).count();
+            writeln!(lcov, "FNH:{num_hit}").unwrap();
+
+            for (block, (&(line, _), counts)) in module.branches.iter().enumerate() {
+                let is_evaluated = counts.iter().any(|&count| count > 0);
+                for (branch, count) in counts.iter().enumerate() {
+                    if is_evaluated {
+                        writeln!(lcov, "BRDA:{line},{block},{branch},{count}").unwrap();
+                    } else {
+                        writeln!(lcov, "BRDA:{line},{block},{branch},-").unwrap();
+                    }
+                }
+            }
+            writeln!(lcov, "BRF:{}", module.branches.len() * 2).unwrap();
+            let num_hit = module.branches.values().flatten().filter(|&&count| count > 0).count();
+            writeln!(lcov, "BRH:{num_hit}").unwrap();
+
+            for (line, hits) in module.lines.iter() {
+                writeln!(lcov, "DA:{line},{hits}").unwrap();
+            }
+            writeln!(lcov, "LF:{}", module.lines.len()).unwrap();
+            let num_hit = module.lines.values().filter(|&&hits| hits > 0).count();
+            writeln!(lcov, "LH:{num_hit}").unwrap();
+            writeln!(lcov, "end_of_record").unwrap();
+        }
+        lcov
+    }
+
+    // STATE MUTATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Adds the hits of the provided coverage to this coverage.
+    ///
+    /// This can be used to combine the coverage collected over several executions.
+    pub fn merge(&mut self, other: &LineCoverage) {
+        for (module_path, other_module) in other.modules.iter() {
+            let module = self.modules.entry(module_path.clone()).or_default();
+            for (line, hits) in other_module.lines.iter() {
+                *module.lines.entry(*line).or_default() += hits;
+            }
+            for (name, &(line, hits)) in other_module.procedures.iter() {
+                module.procedures.entry(name.clone()).or_insert((line, 0)).1 += hits;
+            }
+            for (location, other_counts) in other_module.branches.iter() {
+                let counts = module.branches.entry(*location).or_default();
+                counts[0] += other_counts[0];
+                counts[1] += other_counts[1];
+            }
+        }
+    }
+
+    /// Returns the coverage of the specified module, adding an empty one if the module is not yet
+    /// present in this coverage.
+    fn module_mut(&mut self, module_path: &str) -> &mut ModuleCoverage {
+        self.modules.entry(module_path.into()).or_default()
+    }
+
+    /// Records the specified number of hits of a line, keeping the largest number of hits if the
+    /// line was already recorded.
+    fn record_line(&mut self, module_path: &str, line: u32, hits: u64) {
+        let entry = self.module_mut(module_path).lines.entry(line).or_default();
+        *entry = (*entry).max(hits);
+    }
+
+    /// Records the specified number of hits of a procedure, keeping the largest number of hits if
+    /// the procedure was already recorded.
+    fn record_procedure(&mut self, module_path: &str, name: &str, line: u32, hits: u64) {
+        let procedures = &mut self.module_mut(module_path).procedures;
+        let entry = procedures.entry(name.into()).or_insert((line, 0));
+        entry.1 = entry.1.max(hits);
+    }
+
+    /// Records the specified true and false counts of a conditional branch.
+    fn record_branch(&mut self, module_path: &str, line: u32, column: u32, counts: [u64; 2]) {
+        self.module_mut(module_path).branches.insert((line, column), counts);
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
-use vm_core::{AssemblyOp, Operation, StackOutputs, Word};
+use vm_core::{AssemblyOp, CodeLocation, Operation, StackOutputs, Word};
 
 /// VmState holds a current process state information at a specific clock cycle.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -14,6 +14,7 @@ pub struct VmState {
     pub ctx: ContextId,
     pub op: Option<Operation>,
     pub asmop: Option<AsmOpInfo>,
+    pub location: Option<CodeLocation>,
     pub fmp: Felt,
     pub stack: Vec<Felt>,
     pub memory: Vec<(u64, Word)>,
@@ -26,7 +27,7 @@ impl fmt::Display for VmState {
             self.memory.iter().map(|x| (x.0, word_to_ints(&x.1))).collect();
         write!(
             f,
-            "clk={}{}{}, fmp={}, stack={stack:?}, memory={memory:?}",
+            "clk={}{}{}{}, fmp={}, stack={stack:?}, memory={memory:?}",
             self.clk,
             match self.op {
                 Some(op) => format!(", op={op}"),
@@ -36,6 +37,10 @@ impl fmt::Display for VmState {
                 Some(op) => format!(", {op}"),
                 None => "".to_string(),
             },
+            match &self.location {
+                Some(location) => format!(", at {location}"),
+                None => "".to_string(),
+            },
             self.fmp
         )
     }
@@ -133,6 +138,15 @@ impl VmStateIterator {
         }
     }
 
+    /// Returns the source location of the operation executed at the previous clock cycle, if
+    /// known.
+    fn get_location(&self) -> Option<CodeLocation> {
+        if self.clk == 0 {
+            return None;
+        }
+        self.decoder.debug_info().get_source_location(self.clk - 1).cloned()
+    }
+
     pub fn back(&mut self) -> Option<VmState> {
         if self.clk == 0 {
             return None;
@@ -162,6 +176,7 @@ impl VmStateIterator {
             ctx,
             op,
             asmop,
+            location: self.get_location(),
             fmp: self.system.get_fmp_at(self.clk),
             stack: self.stack.get_state_at(self.clk),
             memory: self.chiplets.get_mem_state_at(ctx, self.clk),
@@ -180,6 +195,12 @@ impl VmStateIterator {
         &self.trace_len_summary
     }
 
+    /// Returns the lengths of the chiplet traces after the specified number of clock cycles have
+    /// been executed, or None if the execution did not reach the specified clock cycle.
+    pub fn chiplets_lengths_at(&self, clk: u32) -> Option<ChipletsLengths> {
+        self.decoder.debug_info().chiplets_lengths().get(clk as usize).copied()
+    }
+
     /// Returns an instance of [TraceLenSummary] based on provided data.
     fn build_trace_len_summary(
         system: &System,
@@ -232,6 +253,7 @@ impl Iterator for VmStateIterator {
             ctx,
             op,
             asmop,
+            location: self.get_location(),
             fmp: self.system.get_fmp_at(self.clk),
             stack: self.stack.get_state_at(self.clk),
             memory: self.chiplets.get_mem_state_at(ctx, self.clk),
@@ -1,6 +1,7 @@
 use super::{
-    Call, Dyn, ExecutionError, Felt, Host, Join, Loop, OpBatch, Operation, Process, Span, Split,
-    Word, EMPTY_WORD, MIN_TRACE_LEN, ONE, OP_BATCH_SIZE, ZERO,
+    Call, ChipletsLengths, Dyn, ExecutionError, Felt, Host, Join, JoinNode, Loop, LoopNode,
+    OpBatch, Operation, Process, Program, Span, Split, SplitNode, Word, EMPTY_WORD, MIN_TRACE_LEN,
+    ONE, OP_BATCH_SIZE, ZERO,
 };
 use alloc::vec::Vec;
 use miden_air::trace::{
@@ -10,7 +11,9 @@ use miden_air::trace::{
         OP_BATCH_1_GROUPS, OP_BATCH_2_GROUPS, OP_BATCH_4_GROUPS, OP_BATCH_8_GROUPS,
     },
 };
-use vm_core::{code_blocks::get_span_op_group_count, stack::STACK_TOP_SIZE, AssemblyOp};
+use vm_core::{
+    code_blocks::get_span_op_group_count, stack::STACK_TOP_SIZE, AssemblyOp, CodeLocation,
+};
 
 mod trace;
 use trace::DecoderTrace;
@@ -43,12 +46,16 @@ where
     // --------------------------------------------------------------------------------------------
 
     /// Starts decoding of a JOIN block.
-    pub(super) fn start_join_block(&mut self, block: &Join) -> Result<(), ExecutionError> {
+    pub(super) fn start_join_block(
+        &mut self,
+        block: &JoinNode,
+        program: &Program,
+    ) -> Result<(), ExecutionError> {
         // use the hasher to compute the hash of the JOIN block; the row address returned by the
         // hasher is used as the ID of the block; the result of the hash is expected to be in
         // row addr + 7.
-        let child1_hash = block.first().hash().into();
-        let child2_hash = block.second().hash().into();
+        let child1_hash = program.get_node(block.first()).hash().into();
+        let child2_hash = program.get_node(block.second()).hash().into();
         let addr =
             self.chiplets
                 .hash_control_block(child1_hash, child2_hash, Join::DOMAIN, block.hash());
@@ -60,7 +67,7 @@ where
     }
 
     ///  Ends decoding of a JOIN block.
-    pub(super) fn end_join_block(&mut self, block: &Join) -> Result<(), ExecutionError> {
+    pub(super) fn end_join_block(&mut self, block: &JoinNode) -> Result<(), ExecutionError> {
         // this appends a row with END operation to the decoder trace. when END operation is
         // executed the rest of the VM state does not change
         self.decoder.end_control_block(block.hash().into());
@@ -73,14 +80,18 @@ where
 
     /// Starts decoding a SPLIT block. This also pops the value from the top of the stack and
     /// returns it.
-    pub(super) fn start_split_block(&mut self, block: &Split) -> Result<Felt, ExecutionError> {
+    pub(super) fn start_split_block(
+        &mut self,
+        block: &SplitNode,
+        program: &Program,
+    ) -> Result<Felt, ExecutionError> {
         let condition = self.stack.peek();
 
         // use the hasher to compute the hash of the SPLIT block; the row address returned by the
         // hasher is used as the ID of the block; the result of the hash is expected to be in
         // row addr + 7.
-        let child1_hash = block.on_true().hash().into();
-        let child2_hash = block.on_false().hash().into();
+        let child1_hash = program.get_node(block.on_true()).hash().into();
+        let child2_hash = program.get_node(block.on_false()).hash().into();
         let addr =
             self.chiplets
                 .hash_control_block(child1_hash, child2_hash, Split::DOMAIN, block.hash());
@@ -93,7 +104,7 @@ where
     }
 
     /// Ends decoding of a SPLIT block.
-    pub(super) fn end_split_block(&mut self, block: &Split) -> Result<(), ExecutionError> {
+    pub(super) fn end_split_block(&mut self, block: &SplitNode) -> Result<(), ExecutionError> {
         // this appends a row with END operation to the decoder trace. when END operation is
         // executed the rest of the VM state does not change
         self.decoder.end_control_block(block.hash().into());
@@ -106,14 +117,18 @@ where
 
     /// Starts decoding a LOOP block. This also pops the value from the top of the stack and
     /// returns it.
-    pub(super) fn start_loop_block(&mut self, block: &Loop) -> Result<Felt, ExecutionError> {
+    pub(super) fn start_loop_block(
+        &mut self,
+        block: &LoopNode,
+        program: &Program,
+    ) -> Result<Felt, ExecutionError> {
         let condition = self.stack.peek();
 
         // use the hasher to compute the hash of the LOOP block; for LOOP block there is no
         // second child so we set the second hash to ZEROs; the row address returned by the
         // hasher is used as the ID of the block; the result of the hash is expected to be in
         // row addr + 7.
-        let body_hash = block.body().hash().into();
+        let body_hash = program.get_node(block.body()).hash().into();
         let addr =
             self.chiplets
                 .hash_control_block(body_hash, EMPTY_WORD, Loop::DOMAIN, block.hash());
@@ -132,7 +147,7 @@ where
     /// value at the top of the stack.
     pub(super) fn end_loop_block(
         &mut self,
-        block: &Loop,
+        block: &LoopNode,
         pop_stack: bool,
     ) -> Result<(), ExecutionError> {
         // this appends a row with END operation to the decoder trace.
@@ -627,6 +642,17 @@ impl Decoder {
         self.debug_info.append_asmop(clk, asmop);
     }
 
+    /// Sets the source location of the operations executed starting from the specified clock
+    /// cycle in debug mode.
+    pub fn append_source_location(&mut self, clk: u32, location: Option<&CodeLocation>) {
+        self.debug_info.append_source_location(clk, location);
+    }
+
+    /// Records the lengths of the chiplet traces at the current clock cycle in debug mode.
+    pub fn append_chiplets_lengths(&mut self, lengths: ChipletsLengths) {
+        self.debug_info.append_chiplets_lengths(lengths);
+    }
+
     // TEST METHODS
     // --------------------------------------------------------------------------------------------
 
@@ -707,6 +733,8 @@ pub struct DebugInfo {
     in_debug_mode: bool,
     operations: Vec<Operation>,
     assembly_ops: Vec<(usize, AssemblyOp)>,
+    source_locations: Vec<(usize, Option<CodeLocation>)>,
+    chiplets_lengths: Vec<ChipletsLengths>,
 }
 
 impl DebugInfo {
@@ -715,6 +743,8 @@ impl DebugInfo {
             in_debug_mode,
             operations: Vec::<Operation>::new(),
             assembly_ops: Vec::<(usize, AssemblyOp)>::new(),
+            source_locations: Vec::new(),
+            chiplets_lengths: Vec::new(),
         }
     }
 
@@ -734,6 +764,29 @@ impl DebugInfo {
         &self.assembly_ops
     }
 
+    /// Returns the list of source locations in debug mode.
+    ///
+    /// Each entry contains the clock cycle starting from which operations were executed from the
+    /// specified source location, or None if the location of the operations is not known.
+    pub fn source_locations(&self) -> &[(usize, Option<CodeLocation>)] {
+        &self.source_locations
+    }
+
+    /// Returns the source location of the operation executed at the specified clock cycle in
+    /// debug mode.
+    pub fn get_source_location(&self, clk: u32) -> Option<&CodeLocation> {
+        let pos = self.source_locations.partition_point(|(start, _)| *start <= clk as usize);
+        pos.checked_sub(1).and_then(|pos| self.source_locations[pos].1.as_ref())
+    }
+
+    /// Returns the lengths of the chiplet traces at every clock cycle in debug mode.
+    ///
+    /// The entry at index `i` contains the lengths of the chiplet traces after `i` clock cycles
+    /// have been executed.
+    pub fn chiplets_lengths(&self) -> &[ChipletsLengths] {
+        &self.chiplets_lengths
+    }
+
     /// Adds an operation to the operations vector in debug mode.
     #[inline(always)]
     pub fn append_operation(&mut self, op: Operation) {
@@ -746,4 +799,25 @@ impl DebugInfo {
     pub fn append_asmop(&mut self, clk: u32, asmop: AssemblyOp) {
         self.assembly_ops.push((clk as usize, asmop));
     }
+
+    /// Sets the source location of the operations executed starting from the specified clock
+    /// cycle. The location is added to the location list only if it differs from the last
+    /// location in the list.
+    pub fn append_source_location(&mut self, clk: u32, location: Option<&CodeLocation>) {
+        if self
+            .source_locations
+            .last()
+            .map_or(location.is_some(), |(_, last)| last.as_ref() != location)
+        {
+            self.source_locations.push((clk as usize, location.cloned()));
+        }
+    }
+
+    /// Adds the lengths of the chiplet traces at the current clock cycle in debug mode.
+    #[inline(always)]
+    pub fn append_chiplets_lengths(&mut self, lengths: ChipletsLengths) {
+        if self.in_debug_mode {
+            self.chiplets_lengths.push(lengths);
+        }
+    }
 }
@@ -19,7 +19,7 @@ use miden_air::trace::{
 use test_utils::rand::rand_value;
 use vm_core::{
     code_blocks::{CodeBlock, Span, OP_BATCH_SIZE},
-    CodeBlockTable, EMPTY_WORD, ONE, ZERO,
+    Program, EMPTY_WORD, ONE, ZERO,
 };
 
 // CONSTANTS
@@ -1198,7 +1198,7 @@ fn build_trace(stack_inputs: &[u64], program: &CodeBlock) -> (DecoderTrace, usiz
     let host = DefaultHost::default();
     let mut process =
         Process::new(Kernel::default(), stack_inputs, host, ExecutionOptions::default());
-    process.execute_code_block(program, &CodeBlockTable::default()).unwrap();
+    process.execute(&Program::new(program.clone())).unwrap();
 
     let (trace, _, _) = ExecutionTrace::test_finalize_trace(process);
     let trace_len = trace.num_rows() - ExecutionTrace::NUM_RAND_ROWS;
@@ -1222,11 +1222,9 @@ fn build_dyn_trace(
     let mut process =
         Process::new(Kernel::default(), stack_inputs, host, ExecutionOptions::default());
 
-    // build code block table
-    let mut cb_table = CodeBlockTable::default();
-    cb_table.insert(fn_block);
-
-    process.execute_code_block(program, &cb_table).unwrap();
+    // build a program with the function in its code block table
+    let program = Program::with_kernel(program.clone(), Kernel::default(), vec![fn_block]);
+    process.execute(&program).unwrap();
 
     let (trace, _, _) = ExecutionTrace::test_finalize_trace(process);
     let trace_len = trace.num_rows() - ExecutionTrace::NUM_RAND_ROWS;
@@ -1251,16 +1249,14 @@ fn build_call_trace(
     };
     let host = DefaultHost::default();
     let stack_inputs = crate::StackInputs::default();
-    let mut process = Process::new(kernel, stack_inputs, host, ExecutionOptions::default());
 
-    // build code block table
-    let mut cb_table = CodeBlockTable::default();
-    cb_table.insert(fn_block);
-    if let Some(proc) = kernel_proc {
-        cb_table.insert(proc);
-    }
+    // build a program with the function and the kernel procedure in its code block table
+    let mut cb_table_blocks = vec![fn_block];
+    cb_table_blocks.extend(kernel_proc);
+    let program = Program::with_kernel(program.clone(), kernel.clone(), cb_table_blocks);
 
-    process.execute_code_block(program, &cb_table).unwrap();
+    let mut process = Process::new(kernel, stack_inputs, host, ExecutionOptions::default());
+    process.execute(&program).unwrap();
 
     let (trace, _, _) = ExecutionTrace::test_finalize_trace(process);
     let trace_len = trace.num_rows() - ExecutionTrace::NUM_RAND_ROWS;
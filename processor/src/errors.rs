@@ -3,9 +3,9 @@ use super::{
     system::{FMP_MAX, FMP_MIN},
     CodeBlock, Digest, Felt, QuadFelt, Word,
 };
-use alloc::string::String;
+use alloc::{boxed::Box, string::String};
 use core::fmt::{Display, Formatter};
-use vm_core::{stack::STACK_TOP_SIZE, utils::to_hex};
+use vm_core::{stack::STACK_TOP_SIZE, utils::to_hex, CodeLocation};
 use winter_prover::{math::FieldElement, ProverError};
 
 #[cfg(feature = "std")]
@@ -21,6 +21,7 @@ pub enum ExecutionError {
     CallerNotInSyscall,
     CodeBlockNotFound(Digest),
     CycleLimitExceeded(u32),
+    DataSegmentsNotSupported,
     DivideByZero(u32),
     DynamicCodeBlockNotFound(Digest),
     EventError(String),
@@ -38,6 +39,9 @@ pub enum ExecutionError {
         start_addr: u64,
         end_addr: u64,
     },
+    InvalidSegment(usize, String),
+    InvalidSegmentLength,
+    InvalidSnapshot(String),
     InvalidStackDepthOnReturn(usize),
     InvalidStackWordOffset(usize),
     InvalidTreeDepth {
@@ -65,6 +69,34 @@ pub enum ExecutionError {
     SmtNodePreImageNotValid(Word, usize),
     SyscallTargetNotInKernel(Digest),
     UnexecutableCodeBlock(CodeBlock),
+    WithSourceLocation {
+        location: CodeLocation,
+        error: Box<ExecutionError>,
+    },
+}
+
+impl ExecutionError {
+    /// Returns this error annotated with the specified source location.
+    ///
+    /// If the location is None or the error is already annotated with a location, the error is
+    /// returned unchanged.
+    pub fn with_source_location(self, location: Option<&CodeLocation>) -> Self {
+        match (self, location) {
+            (error @ Self::WithSourceLocation { .. }, _) | (error, None) => error,
+            (error, Some(location)) => Self::WithSourceLocation {
+                location: location.clone(),
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// Returns the location in the source code at which this error occurred, if known.
+    pub fn source_location(&self) -> Option<&CodeLocation> {
+        match self {
+            Self::WithSourceLocation { location, .. } => Some(location),
+            _ => None,
+        }
+    }
 }
 
 impl Display for ExecutionError {
@@ -90,6 +122,9 @@ impl Display for ExecutionError {
             CycleLimitExceeded(max_cycles) => {
                 write!(f, "Exceeded the allowed number of cycles (max cycles = {max_cycles})")
             }
+            DataSegmentsNotSupported => {
+                write!(f, "Failed to load data segments; the host does not support data segments")
+            }
             DivideByZero(clk) => write!(f, "Division by zero at clock cycle {clk}"),
             DynamicCodeBlockNotFound(digest) => {
                 let hex = to_hex(&digest.as_bytes())?;
@@ -132,6 +167,13 @@ impl Display for ExecutionError {
             } => {
                 write!(f, "Memory range start address cannot exceed end address, but was ({start_addr}, {end_addr})")
             }
+            InvalidSegment(index, reason) => {
+                write!(f, "Execution segment {index} is invalid: {reason}")
+            }
+            InvalidSegmentLength => write!(f, "Execution segments must be at least 1 cycle long"),
+            InvalidSnapshot(reason) => {
+                write!(f, "Failed to resume execution from snapshot: {reason}")
+            }
             InvalidStackDepthOnReturn(depth) => {
                 write!(f, "When returning from a call, stack depth must be {STACK_TOP_SIZE}, but was {depth}")
             }
@@ -193,6 +235,7 @@ impl Display for ExecutionError {
             UnexecutableCodeBlock(block) => {
                 write!(f, "Execution reached unexecutable code block {block:?}")
             }
+            WithSourceLocation { location, error } => write!(f, "{error} at {location}"),
         }
     }
 }
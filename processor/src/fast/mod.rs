@@ -0,0 +1,601 @@
+use super::{
+    system::FMP_MIN, Call, CodeBlock, ContextId, Decorator, Digest, Dyn, ExecutionError,
+    ExecutionOptions, Felt, Host, Kernel, LoopNode, MastNode, MastNodeId, OpBatch, Operation,
+    ProcessState, Program, SplitNode, StackInputs, StackOutputs, Word, EMPTY_WORD, ONE,
+    OP_GROUP_SIZE, SYSCALL_FMP_MIN, ZERO,
+};
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use core::cell::RefCell;
+use vm_core::stack::STACK_TOP_SIZE;
+
+mod operations;
+
+mod segments;
+pub use segments::{ExecutionSegment, ExecutionSegments, SegmentBoundary, SegmentedExecution};
+
+mod snapshot;
+pub use snapshot::ProcessSnapshot;
+
+mod stack;
+use stack::FastStack;
+
+#[cfg(test)]
+mod tests;
+
+// FAST PROCESS
+// ================================================================================================
+
+/// A lightweight process which executes programs without building an execution trace.
+///
+/// The process evaluates the same operations and interacts with the host in the same way as the
+/// tracing [Process](super::Process) does, but it keeps track of the current state of the VM
+/// only. Clock cycles are accounted for exactly as in the tracing process, and thus, executing a
+/// program in this process results in the same outputs and the same errors (including errors
+/// which depend on the current clock cycle, such as exceeding the maximum number of cycles).
+///
+/// Instead of recursing into the code blocks of a program, the process keeps the work remaining to
+/// be done on an explicit stack of continuations (similarly to how the decoder keeps track of the
+/// blocks being executed). Thus, the execution can be paused at any clock cycle, and the state of
+/// a paused process can be saved into a [ProcessSnapshot] and resumed later.
+pub struct FastProcess<H>
+where
+    H: Host,
+{
+    clk: u32,
+    ctx: ContextId,
+    fmp: Felt,
+    in_syscall: bool,
+    fn_hash: Word,
+    stack: FastStack,
+    memory: BTreeMap<ContextId, BTreeMap<u32, Word>>,
+    kernel: Kernel,
+    host: RefCell<H>,
+    max_cycles: u32,
+    enable_tracing: bool,
+    error_messages: BTreeMap<u32, String>,
+    program_hash: Option<Digest>,
+    continuations: Vec<Continuation>,
+}
+
+impl<H> FastProcess<H>
+where
+    H: Host,
+{
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Creates a new fast process with the provided inputs.
+    pub fn new(
+        kernel: Kernel,
+        stack_inputs: StackInputs,
+        host: H,
+        execution_options: ExecutionOptions,
+    ) -> Self {
+        Self {
+            clk: 0,
+            ctx: ContextId::root(),
+            fmp: Felt::new(FMP_MIN),
+            in_syscall: false,
+            fn_hash: EMPTY_WORD,
+            stack: FastStack::new(&stack_inputs),
+            memory: BTreeMap::new(),
+            kernel,
+            host: RefCell::new(host),
+            max_cycles: execution_options.max_cycles(),
+            enable_tracing: execution_options.enable_tracing(),
+            error_messages: BTreeMap::new(),
+            program_hash: None,
+            continuations: Vec::new(),
+        }
+    }
+
+    // PROGRAM EXECUTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Executes the provided [Program] in this process until the program completes.
+    ///
+    /// If the execution of the program has been paused (see [FastProcess::execute_until()]) or if
+    /// this process has been resumed from a [ProcessSnapshot], the execution continues from the
+    /// point at which it was paused.
+    ///
+    /// The error messages and the data segments of the program are handled in the same way as in
+    /// [Process::execute()](super::Process::execute).
+    ///
+    /// # Panics
+    /// Panics if the execution of a different program has already been started in this process.
+    pub fn execute(&mut self, program: &Program) -> Result<StackOutputs, ExecutionError> {
+        self.start_program(program)?;
+        self.run(program, None)?;
+        Ok(self.stack.build_stack_outputs())
+    }
+
+    /// Executes the provided [Program] in this process until the clock cycle of the process
+    /// reaches the specified value, or until the program completes, whichever comes first.
+    ///
+    /// Returns the outputs of the program if the program has completed, or None if the execution
+    /// has been paused. A paused execution can be continued by calling this method (or
+    /// [FastProcess::execute()]) again, and the state of a paused process can be saved via
+    /// [FastProcess::snapshot()].
+    ///
+    /// # Panics
+    /// Panics if the execution of a different program has already been started in this process.
+    pub fn execute_until(
+        &mut self,
+        program: &Program,
+        clk: u32,
+    ) -> Result<Option<StackOutputs>, ExecutionError> {
+        self.start_program(program)?;
+        if self.run(program, Some(clk))? {
+            Ok(Some(self.stack.build_stack_outputs()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Prepares this process for the execution of the provided program, unless the execution of
+    /// the program has already been started.
+    fn start_program(&mut self, program: &Program) -> Result<(), ExecutionError> {
+        match self.program_hash {
+            Some(program_hash) => {
+                assert_eq!(program_hash, program.hash(), "a different program is being executed");
+            }
+            None => {
+                self.program_hash = Some(program.hash());
+                self.error_messages = program.error_messages().clone();
+                for segment in program.data_segments() {
+                    let key = segment.commitment().into();
+                    self.host.borrow_mut().insert_into_adv_map(key, segment.padded_values())?;
+                }
+                self.continuations.push(Continuation::StartNode(program.entrypoint()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Executes the continuations of this process until either none are left, or the clock cycle
+    /// of the process reaches the specified value.
+    ///
+    /// Returns true if the program has completed.
+    fn run(&mut self, program: &Program, pause_at: Option<u32>) -> Result<bool, ExecutionError> {
+        while let Some(continuation) = self.continuations.pop() {
+            if self.should_pause(pause_at) {
+                self.continuations.push(continuation);
+                return Ok(false);
+            }
+
+            match continuation {
+                Continuation::StartNode(node_id) => self.start_mast_node(node_id, program)?,
+                Continuation::StartDyn => self.start_dyn_block(program)?,
+                Continuation::ResumeSpan(node_id, cursor) => {
+                    self.resume_span_block(node_id, cursor, program, pause_at)?
+                }
+                Continuation::FinishLoop(body) => self.finish_loop_iteration(body)?,
+                Continuation::FinishCall(caller) => self.finish_call_block(caller)?,
+                Continuation::End => self.execute_op(Operation::Noop)?,
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Returns true if the execution should be paused at the current clock cycle.
+    #[inline(always)]
+    fn should_pause(&self, pause_at: Option<u32>) -> bool {
+        pause_at.is_some_and(|clk| self.clk >= clk)
+    }
+
+    // CODE BLOCK EXECUTORS
+    // --------------------------------------------------------------------------------------------
+    // Each of the methods below executes a single clock cycle (with the exception of SPAN block
+    // execution) and pushes the work remaining to be done onto the continuation stack. The
+    // continuations are pushed in reverse order, so that the one pushed last is executed first.
+
+    /// Starts executing the [MastNode] with the specified ID in the MAST forest of the provided
+    /// program.
+    fn start_mast_node(
+        &mut self,
+        node_id: MastNodeId,
+        program: &Program,
+    ) -> Result<(), ExecutionError> {
+        match program.get_node(node_id) {
+            MastNode::Join(node) => {
+                // JOIN operation
+                self.execute_op(Operation::Noop)?;
+                self.continuations.push(Continuation::End);
+                self.continuations.push(Continuation::StartNode(node.second()));
+                self.continuations.push(Continuation::StartNode(node.first()));
+                Ok(())
+            }
+            MastNode::Split(node) => self.start_split_node(node),
+            MastNode::Loop(node) => self.start_loop_node(node),
+            MastNode::Call(block) => self.start_call_block(block, program),
+            MastNode::Dyn(_) => self.start_dyn_block(program),
+            MastNode::Span(_) => {
+                // SPAN operation
+                self.execute_op(Operation::Noop)?;
+                self.continuations.push(Continuation::ResumeSpan(node_id, SpanCursor::new(0)));
+                Ok(())
+            }
+            MastNode::Proxy(block) => {
+                Err(ExecutionError::UnexecutableCodeBlock(CodeBlock::Proxy(block.clone())))
+            }
+        }
+    }
+
+    /// Starts executing the specified SPLIT node.
+    #[inline(always)]
+    fn start_split_node(&mut self, node: &SplitNode) -> Result<(), ExecutionError> {
+        // SPLIT operation pops the condition off the stack
+        let condition = self.stack.peek();
+        self.execute_op(Operation::Drop)?;
+
+        let branch = if condition == ONE {
+            node.on_true()
+        } else if condition == ZERO {
+            node.on_false()
+        } else {
+            return Err(ExecutionError::NotBinaryValue(condition));
+        };
+
+        self.continuations.push(Continuation::End);
+        self.continuations.push(Continuation::StartNode(branch));
+        Ok(())
+    }
+
+    /// Starts executing the specified LOOP node.
+    #[inline(always)]
+    fn start_loop_node(&mut self, node: &LoopNode) -> Result<(), ExecutionError> {
+        // LOOP operation pops the condition off the stack
+        let condition = self.stack.peek();
+        self.execute_op(Operation::Drop)?;
+
+        if condition == ONE {
+            self.continuations.push(Continuation::FinishLoop(node.body()));
+            self.continuations.push(Continuation::StartNode(node.body()));
+        } else if condition == ZERO {
+            // the body is never entered, and thus, the END operation leaves the stack unchanged
+            self.continuations.push(Continuation::End);
+        } else {
+            return Err(ExecutionError::NotBinaryValue(condition));
+        }
+        Ok(())
+    }
+
+    /// Either starts another iteration of a loop with the specified body, or exits the loop,
+    /// depending on the value at the top of the stack.
+    #[inline(always)]
+    fn finish_loop_iteration(&mut self, body: MastNodeId) -> Result<(), ExecutionError> {
+        if self.stack.peek() == ONE {
+            // each subsequent iteration is preceded by a REPEAT operation which drops the
+            // condition from the stack
+            self.execute_op(Operation::Drop)?;
+            self.continuations.push(Continuation::FinishLoop(body));
+            self.continuations.push(Continuation::StartNode(body));
+            Ok(())
+        } else {
+            // END operation drops the condition from the stack when exiting the loop
+            debug_assert_eq!(ZERO, self.stack.peek());
+            self.execute_op(Operation::Drop)
+        }
+    }
+
+    /// Starts executing the specified [Call] block.
+    #[inline(always)]
+    fn start_call_block(&mut self, block: &Call, program: &Program) -> Result<(), ExecutionError> {
+        // if this is a syscall, make sure the call target exists in the kernel
+        if block.is_syscall() && !self.kernel.contains_proc(block.fn_hash()) {
+            return Err(ExecutionError::SyscallTargetNotInKernel(block.fn_hash()));
+        }
+
+        // start a new execution context; this has the effect of resetting stack depth to 16
+        let caller = CallerContext {
+            ctx: self.ctx,
+            fmp: self.fmp,
+            fn_hash: self.fn_hash,
+            stack_floor: self.stack.start_context(),
+        };
+
+        if block.is_syscall() {
+            self.ctx = ContextId::root();
+            self.fmp = Felt::from(SYSCALL_FMP_MIN);
+            self.in_syscall = true;
+        } else {
+            self.ctx = (self.clk + 1).into();
+            self.fmp = Felt::new(FMP_MIN);
+            self.fn_hash = block.fn_hash().into();
+        }
+
+        // CALL or SYSCALL operation
+        self.execute_op(Operation::Noop)?;
+
+        self.continuations.push(Continuation::FinishCall(caller));
+        if block.fn_hash() == Dyn::dyn_hash() {
+            self.continuations.push(Continuation::StartDyn);
+        } else {
+            let fn_body = program
+                .cb_table()
+                .get(block.fn_hash())
+                .ok_or(ExecutionError::CodeBlockNotFound(block.fn_hash()))?;
+            self.continuations.push(Continuation::StartNode(fn_body));
+        }
+        Ok(())
+    }
+
+    /// Returns from a CALL or a SYSCALL block, restoring the specified context of the caller.
+    #[inline(always)]
+    fn finish_call_block(&mut self, caller: CallerContext) -> Result<(), ExecutionError> {
+        // when a CALL block ends, stack depth must be exactly 16
+        let stack_depth = self.stack.depth();
+        if stack_depth > STACK_TOP_SIZE {
+            return Err(ExecutionError::InvalidStackDepthOnReturn(stack_depth));
+        }
+
+        // restore the context of the system registers and the operand stack to what it was prior
+        // to the call
+        self.ctx = caller.ctx;
+        self.fmp = caller.fmp;
+        self.in_syscall = false;
+        self.fn_hash = caller.fn_hash;
+        self.stack.restore_context(caller.stack_floor);
+
+        // END operation
+        self.execute_op(Operation::Noop)
+    }
+
+    /// Starts executing a DYN block.
+    #[inline(always)]
+    fn start_dyn_block(&mut self, program: &Program) -> Result<(), ExecutionError> {
+        // get target hash from the stack
+        let dyn_hash = self.stack.get_word(0);
+
+        // DYN operation
+        self.execute_op(Operation::Noop)?;
+
+        let dyn_digest = dyn_hash.into();
+        let dyn_code = program
+            .cb_table()
+            .get(dyn_digest)
+            .ok_or(ExecutionError::DynamicCodeBlockNotFound(dyn_digest))?;
+
+        self.continuations.push(Continuation::End);
+        self.continuations.push(Continuation::StartNode(dyn_code));
+        Ok(())
+    }
+
+    /// Executes the SPAN block with the specified ID starting at the position specified by the
+    /// cursor, until either the block is completed, or the clock cycle of the process reaches the
+    /// specified value.
+    ///
+    /// This also executes the NOOPs which the tracing process executes to satisfy the alignment
+    /// rules of op batches, so that the clock cycles of both processes stay in sync. Errors raised
+    /// while executing the block are annotated with source locations in the same way as in the
+    /// tracing process.
+    fn resume_span_block(
+        &mut self,
+        node_id: MastNodeId,
+        mut cursor: SpanCursor,
+        program: &Program,
+        pause_at: Option<u32>,
+    ) -> Result<(), ExecutionError> {
+        let block = match program.get_node(node_id) {
+            MastNode::Span(block) => block,
+            _ => panic!("node {node_id} is not a SPAN block"),
+        };
+        let op_batches = block.op_batches();
+        let decorators = block.decorators();
+        let locations = program.source_map().get_span(block.hash());
+
+        // decorators which precede the current operation have already been executed
+        let mut op_offset: usize =
+            op_batches[..cursor.batch_idx].iter().map(|batch| batch.ops().len()).sum();
+        let mut decorator_idx =
+            decorators.partition_point(|(pos, _)| *pos < op_offset + cursor.op_idx);
+
+        loop {
+            let batch = &op_batches[cursor.batch_idx];
+
+            while cursor.num_noops > 0 {
+                if self.should_pause(pause_at) {
+                    self.continuations.push(Continuation::ResumeSpan(node_id, cursor));
+                    return Ok(());
+                }
+                self.execute_op(Operation::Noop)?;
+                cursor.num_noops -= 1;
+            }
+
+            if self.should_pause(pause_at) {
+                self.continuations.push(Continuation::ResumeSpan(node_id, cursor));
+                return Ok(());
+            }
+
+            if cursor.op_idx == batch.ops().len() {
+                if cursor.batch_idx + 1 < op_batches.len() {
+                    // each batch after the first one is preceded by a RESPAN operation
+                    op_offset += batch.ops().len();
+                    cursor = SpanCursor::new(cursor.batch_idx + 1);
+                    cursor.num_noops = 1;
+                    continue;
+                }
+
+                // END operation
+                self.execute_op(Operation::Noop)?;
+
+                // execute any decorators which have not been executed during span ops execution
+                for (_, decorator) in &decorators[decorator_idx..] {
+                    self.execute_decorator(decorator)?;
+                }
+                return Ok(());
+            }
+
+            let op_pos = op_offset + cursor.op_idx;
+            let location = locations.and_then(|locations| locations.get(op_pos));
+
+            while decorator_idx < decorators.len() && decorators[decorator_idx].0 == op_pos {
+                self.execute_decorator(&decorators[decorator_idx].1)
+                    .map_err(|err| err.with_source_location(location))?;
+                decorator_idx += 1;
+            }
+
+            let op = batch.ops()[cursor.op_idx];
+            self.execute_op(op).map_err(|err| err.with_source_location(location))?;
+            cursor.advance(op, batch);
+        }
+    }
+
+    /// Executes the specified decorator.
+    fn execute_decorator(&mut self, decorator: &Decorator) -> Result<(), ExecutionError> {
+        match decorator {
+            Decorator::Advice(injector) => {
+                self.host.borrow_mut().set_advice(self, *injector)?;
+            }
+            Decorator::Debug(options) => {
+                self.host.borrow_mut().on_debug(self, options)?;
+            }
+            Decorator::AsmOp(_) => {}
+            Decorator::Event(id) => {
+                self.host.borrow_mut().on_event(self, *id)?;
+            }
+            Decorator::Trace(id) => {
+                if self.enable_tracing {
+                    self.host.borrow_mut().on_trace(self, *id)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// CONTINUATIONS
+// ================================================================================================
+
+/// A unit of work remaining to be done by a [FastProcess].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Continuation {
+    /// Start executing the node with the specified ID.
+    StartNode(MastNodeId),
+    /// Start executing a DYN block; this is used for the targets of dynamic calls.
+    StartDyn,
+    /// Continue executing the SPAN block with the specified ID from the specified position.
+    ResumeSpan(MastNodeId, SpanCursor),
+    /// Either repeat or exit the loop with the specified body.
+    FinishLoop(MastNodeId),
+    /// Return from a CALL or a SYSCALL block to the specified context.
+    FinishCall(CallerContext),
+    /// Execute the END operation of a JOIN, SPLIT or DYN block, or of a LOOP block whose body has
+    /// never been entered.
+    End,
+}
+
+/// Position of the execution within a SPAN block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SpanCursor {
+    /// Index of the current op batch.
+    batch_idx: usize,
+    /// Index of the next operation to be executed within the current op batch.
+    op_idx: usize,
+    /// Index of the current op group within the current op batch.
+    group_idx: usize,
+    /// Index of the next op group which does not contain an immediate value.
+    next_group_idx: usize,
+    /// Index of the next operation to be executed within the current op group.
+    group_op_idx: usize,
+    /// Number of NOOPs (including RESPANs) to be executed before the next operation.
+    num_noops: usize,
+}
+
+impl SpanCursor {
+    /// Returns a cursor pointing to the first operation of the specified op batch.
+    fn new(batch_idx: usize) -> Self {
+        Self {
+            batch_idx,
+            op_idx: 0,
+            group_idx: 0,
+            next_group_idx: 1,
+            group_op_idx: 0,
+            num_noops: 0,
+        }
+    }
+
+    /// Moves the cursor past the specified operation, which must be the operation the cursor
+    /// currently points to.
+    #[inline(always)]
+    fn advance(&mut self, op: Operation, batch: &OpBatch) {
+        let has_imm = op.imm_value().is_some();
+        if has_imm {
+            self.next_group_idx += 1;
+        }
+
+        if self.group_op_idx == batch.op_counts()[self.group_idx] - 1 {
+            // an operation with an immediate value cannot be the last operation in a group
+            if has_imm {
+                debug_assert!(self.group_op_idx < OP_GROUP_SIZE - 1, "invalid op index");
+                self.num_noops += 1;
+            }
+
+            self.group_idx = self.next_group_idx;
+            self.next_group_idx += 1;
+            self.group_op_idx = 0;
+        } else {
+            self.group_op_idx += 1;
+        }
+
+        self.op_idx += 1;
+        if self.op_idx == batch.ops().len() {
+            // pad the batch with NOOPs to the next power of two number of groups
+            let num_batch_groups = batch.num_groups().next_power_of_two();
+            self.num_noops += num_batch_groups.saturating_sub(self.group_idx);
+        }
+    }
+}
+
+/// Context of the caller of a CALL or a SYSCALL block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CallerContext {
+    ctx: ContextId,
+    fmp: Felt,
+    fn_hash: Word,
+    stack_floor: usize,
+}
+
+// PROCESS STATE
+// ================================================================================================
+
+impl<H: Host> ProcessState for FastProcess<H> {
+    fn clk(&self) -> u32 {
+        self.clk
+    }
+
+    fn ctx(&self) -> ContextId {
+        self.ctx
+    }
+
+    fn fmp(&self) -> u64 {
+        self.fmp.as_int()
+    }
+
+    fn get_stack_item(&self, pos: usize) -> Felt {
+        self.stack.get(pos)
+    }
+
+    fn get_stack_word(&self, word_idx: usize) -> Word {
+        self.stack.get_word(word_idx)
+    }
+
+    fn get_stack_state(&self) -> Vec<Felt> {
+        self.stack.get_state()
+    }
+
+    fn get_mem_value(&self, ctx: ContextId, addr: u32) -> Option<Word> {
+        self.memory.get(&ctx).and_then(|segment| segment.get(&addr)).copied()
+    }
+
+    fn get_mem_state(&self, ctx: ContextId) -> Vec<(u64, Word)> {
+        match self.memory.get(&ctx) {
+            Some(segment) => segment.iter().map(|(&addr, &word)| (addr.into(), word)).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn get_error_message(&self, err_code: u32) -> Option<&str> {
+        self.error_messages.get(&err_code).map(String::as_str)
+    }
+}
@@ -0,0 +1,698 @@
+use super::{
+    super::{
+        chiplets::assert_u32,
+        crypto::MerklePath,
+        operations::{
+            assert_binary, compute_evaluation_points, fold4, get_domain_segment_flags,
+            get_tau_factor, DOMAIN_OFFSET,
+        },
+        system::{FMP_MAX, FMP_MIN},
+        utils::split_element,
+        QuadFelt,
+    },
+    ExecutionError, FastProcess, Felt, Host, Operation, Word, EMPTY_WORD, ONE, ZERO,
+};
+use vm_core::{
+    chiplets::hasher::{apply_permutation, merge},
+    AdviceInjector, FieldElement,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+const TWO: Felt = Felt::new(2);
+
+// OPERATION DISPATCHER
+// ================================================================================================
+
+impl<H> FastProcess<H>
+where
+    H: Host,
+{
+    /// Executes the specified operation and advances the clock cycle.
+    ///
+    /// The semantics of all operations are the same as in the tracing process. Since the state is
+    /// updated in place, every operation first reads all of its inputs, then shifts the stack (if
+    /// needed), and only then writes its outputs to the stack.
+    pub(super) fn execute_op(&mut self, op: Operation) -> Result<(), ExecutionError> {
+        match op {
+            // ----- system operations ------------------------------------------------------------
+            Operation::Noop => (),
+            Operation::Assert(err_code) => self.op_assert(err_code)?,
+
+            Operation::FmpAdd => self.op_fmpadd(),
+            Operation::FmpUpdate => self.op_fmpupdate()?,
+
+            Operation::SDepth => self.op_sdepth(),
+            Operation::Caller => self.op_caller()?,
+
+            Operation::Clk => self.op_push(Felt::from(self.clk)),
+
+            // ----- flow control operations ------------------------------------------------------
+            // control flow operations are never executed directly
+            Operation::Join
+            | Operation::Split
+            | Operation::Loop
+            | Operation::Call
+            | Operation::SysCall
+            | Operation::Dyn
+            | Operation::Span
+            | Operation::Repeat
+            | Operation::Respan
+            | Operation::End
+            | Operation::Halt => unreachable!("control flow operation"),
+
+            // ----- field operations -------------------------------------------------------------
+            Operation::Add => self.op_binary(|a, b| a + b),
+            Operation::Neg => self.stack.set(0, -self.stack.get(0)),
+            Operation::Mul => self.op_binary(|a, b| a * b),
+            Operation::Inv => self.op_inv()?,
+            Operation::Incr => self.stack.set(0, self.stack.get(0) + ONE),
+
+            Operation::And => self.op_and()?,
+            Operation::Or => self.op_or()?,
+            Operation::Not => self.op_not()?,
+
+            Operation::Eq => self.op_binary(|a, b| if a == b { ONE } else { ZERO }),
+            Operation::Eqz => self.stack.set(0, if self.stack.get(0) == ZERO { ONE } else { ZERO }),
+
+            Operation::Expacc => self.op_expacc(),
+
+            // ----- ext2 operations --------------------------------------------------------------
+            Operation::Ext2Mul => self.op_ext2mul(),
+
+            // ----- u32 operations ---------------------------------------------------------------
+            Operation::U32split => self.op_u32split(),
+            Operation::U32add => self.op_u32add(),
+            Operation::U32add3 => self.op_u32add3(),
+            Operation::U32sub => self.op_u32sub(),
+            Operation::U32mul => self.op_u32mul(),
+            Operation::U32madd => self.op_u32madd(),
+            Operation::U32div => self.op_u32div()?,
+
+            Operation::U32and => self.op_u32bitwise(|a, b| a & b)?,
+            Operation::U32xor => self.op_u32bitwise(|a, b| a ^ b)?,
+            Operation::U32assert2(err_code) => self.op_u32assert2(err_code)?,
+
+            // ----- stack manipulation -----------------------------------------------------------
+            Operation::Pad => self.op_push(ZERO),
+            Operation::Drop => self.stack.shift_left(1),
+
+            Operation::Dup0 => self.op_push(self.stack.get(0)),
+            Operation::Dup1 => self.op_push(self.stack.get(1)),
+            Operation::Dup2 => self.op_push(self.stack.get(2)),
+            Operation::Dup3 => self.op_push(self.stack.get(3)),
+            Operation::Dup4 => self.op_push(self.stack.get(4)),
+            Operation::Dup5 => self.op_push(self.stack.get(5)),
+            Operation::Dup6 => self.op_push(self.stack.get(6)),
+            Operation::Dup7 => self.op_push(self.stack.get(7)),
+            Operation::Dup9 => self.op_push(self.stack.get(9)),
+            Operation::Dup11 => self.op_push(self.stack.get(11)),
+            Operation::Dup13 => self.op_push(self.stack.get(13)),
+            Operation::Dup15 => self.op_push(self.stack.get(15)),
+
+            Operation::Swap => self.op_swap_words(&[(0, 1)], 1),
+            Operation::SwapW => self.op_swap_words(&[(0, 4)], 4),
+            Operation::SwapW2 => self.op_swap_words(&[(0, 8)], 4),
+            Operation::SwapW3 => self.op_swap_words(&[(0, 12)], 4),
+            Operation::SwapDW => self.op_swap_words(&[(0, 8), (4, 12)], 4),
+
+            Operation::MovUp2 => self.op_movup(2),
+            Operation::MovUp3 => self.op_movup(3),
+            Operation::MovUp4 => self.op_movup(4),
+            Operation::MovUp5 => self.op_movup(5),
+            Operation::MovUp6 => self.op_movup(6),
+            Operation::MovUp7 => self.op_movup(7),
+            Operation::MovUp8 => self.op_movup(8),
+
+            Operation::MovDn2 => self.op_movdn(2),
+            Operation::MovDn3 => self.op_movdn(3),
+            Operation::MovDn4 => self.op_movdn(4),
+            Operation::MovDn5 => self.op_movdn(5),
+            Operation::MovDn6 => self.op_movdn(6),
+            Operation::MovDn7 => self.op_movdn(7),
+            Operation::MovDn8 => self.op_movdn(8),
+
+            Operation::CSwap => self.op_cswap(1)?,
+            Operation::CSwapW => self.op_cswap(4)?,
+
+            // ----- input / output ---------------------------------------------------------------
+            Operation::Push(value) => self.op_push(value),
+
+            Operation::AdvPop => self.op_advpop()?,
+            Operation::AdvPopW => self.op_advpopw()?,
+
+            Operation::MLoadW => self.op_mloadw()?,
+            Operation::MStoreW => self.op_mstorew()?,
+
+            Operation::MLoad => self.op_mload()?,
+            Operation::MStore => self.op_mstore()?,
+
+            Operation::MStream => self.op_mstream()?,
+            Operation::Pipe => self.op_pipe()?,
+
+            // ----- cryptographic operations -----------------------------------------------------
+            Operation::HPerm => self.op_hperm(),
+            Operation::MpVerify => self.op_mpverify()?,
+            Operation::MrUpdate => self.op_mrupdate()?,
+            Operation::FriE2F4 => self.op_fri_ext2fold4()?,
+            Operation::RCombBase => self.op_rcomb_base(),
+        }
+
+        self.advance_clock()
+    }
+
+    /// Increments the clock cycle.
+    ///
+    /// # Errors
+    /// Returns an error if the maximum number of cycles is exceeded.
+    fn advance_clock(&mut self) -> Result<(), ExecutionError> {
+        self.clk += 1;
+        if self.clk > self.max_cycles {
+            return Err(ExecutionError::CycleLimitExceeded(self.max_cycles));
+        }
+        Ok(())
+    }
+
+    // SYSTEM OPERATIONS
+    // --------------------------------------------------------------------------------------------
+
+    fn op_assert(&mut self, err_code: u32) -> Result<(), ExecutionError> {
+        if self.stack.get(0) != ONE {
+            return Err(self.host.borrow_mut().on_assert_failed(self, err_code));
+        }
+        self.stack.shift_left(1);
+        Ok(())
+    }
+
+    fn op_fmpadd(&mut self) {
+        let offset = self.stack.get(0);
+        self.stack.set(0, self.fmp + offset);
+    }
+
+    fn op_fmpupdate(&mut self) -> Result<(), ExecutionError> {
+        let new_fmp = self.fmp + self.stack.get(0);
+        if new_fmp.as_int() < FMP_MIN || new_fmp.as_int() > FMP_MAX {
+            return Err(ExecutionError::InvalidFmpValue(self.fmp, new_fmp));
+        }
+
+        self.fmp = new_fmp;
+        self.stack.shift_left(1);
+        Ok(())
+    }
+
+    fn op_sdepth(&mut self) {
+        let stack_depth = self.stack.depth();
+        self.op_push(Felt::new(stack_depth as u64));
+    }
+
+    fn op_caller(&mut self) -> Result<(), ExecutionError> {
+        if !self.in_syscall {
+            return Err(ExecutionError::CallerNotInSyscall);
+        }
+        self.set_word(0, self.fn_hash);
+        Ok(())
+    }
+
+    // FIELD OPERATIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Pops two elements `[b, a, ...]` off the stack and pushes the result of `f(a, b)`.
+    fn op_binary(&mut self, f: impl FnOnce(Felt, Felt) -> Felt) {
+        let b = self.stack.get(0);
+        let a = self.stack.get(1);
+        self.stack.shift_left(2);
+        self.stack.set(0, f(a, b));
+    }
+
+    fn op_inv(&mut self) -> Result<(), ExecutionError> {
+        let a = self.stack.get(0);
+        if a == ZERO {
+            return Err(ExecutionError::DivideByZero(self.clk));
+        }
+        self.stack.set(0, a.inv());
+        Ok(())
+    }
+
+    fn op_and(&mut self) -> Result<(), ExecutionError> {
+        let b = assert_binary(self.stack.get(0))?;
+        let a = assert_binary(self.stack.get(1))?;
+        self.op_binary(|_, _| if a == ONE && b == ONE { ONE } else { ZERO });
+        Ok(())
+    }
+
+    fn op_or(&mut self) -> Result<(), ExecutionError> {
+        let b = assert_binary(self.stack.get(0))?;
+        let a = assert_binary(self.stack.get(1))?;
+        self.op_binary(|_, _| if a == ONE || b == ONE { ONE } else { ZERO });
+        Ok(())
+    }
+
+    fn op_not(&mut self) -> Result<(), ExecutionError> {
+        let a = assert_binary(self.stack.get(0))?;
+        self.stack.set(0, ONE - a);
+        Ok(())
+    }
+
+    fn op_expacc(&mut self) {
+        let mut exp = self.stack.get(1);
+        let mut acc = self.stack.get(2);
+        let mut b = self.stack.get(3);
+
+        let bit = b.as_int() & 1;
+        let value = Felt::new((exp.as_int() - 1) * bit + 1);
+        acc *= value;
+        b = Felt::new(b.as_int() >> 1);
+        exp *= exp;
+
+        self.stack.set(0, Felt::new(bit));
+        self.stack.set(1, exp);
+        self.stack.set(2, acc);
+        self.stack.set(3, b);
+    }
+
+    fn op_ext2mul(&mut self) {
+        let [a0, a1, b0, b1] = self.stack.get_word(0);
+        self.stack.set(0, b1);
+        self.stack.set(1, b0);
+        self.stack.set(2, (b0 + b1) * (a1 + a0) - b0 * a0);
+        self.stack.set(3, b0 * a0 - TWO * b1 * a1);
+    }
+
+    // U32 OPERATIONS
+    // --------------------------------------------------------------------------------------------
+
+    fn op_u32split(&mut self) {
+        let (hi, lo) = split_element(self.stack.get(0));
+        self.stack.shift_right(1, self.clk);
+        self.stack.set(0, hi);
+        self.stack.set(1, lo);
+    }
+
+    fn op_u32assert2(&mut self, err_code: Felt) -> Result<(), ExecutionError> {
+        let a = self.stack.get(0);
+        let b = self.stack.get(1);
+        if a.as_int() >> 32 != 0 {
+            return Err(ExecutionError::NotU32Value(a, err_code));
+        }
+        if b.as_int() >> 32 != 0 {
+            return Err(ExecutionError::NotU32Value(b, err_code));
+        }
+        Ok(())
+    }
+
+    fn op_u32add(&mut self) {
+        let b = self.stack.get(0);
+        let a = self.stack.get(1);
+        let (hi, lo) = split_element(a + b);
+        self.stack.set(0, hi);
+        self.stack.set(1, lo);
+    }
+
+    fn op_u32add3(&mut self) {
+        let c = self.stack.get(0).as_int();
+        let b = self.stack.get(1).as_int();
+        let a = self.stack.get(2).as_int();
+        let (hi, lo) = split_element(Felt::new(a + b + c));
+        self.stack.shift_left(3);
+        self.stack.set(0, hi);
+        self.stack.set(1, lo);
+    }
+
+    fn op_u32sub(&mut self) {
+        let b = self.stack.get(0).as_int();
+        let a = self.stack.get(1).as_int();
+        let result = a.wrapping_sub(b);
+        self.stack.set(0, Felt::new(result >> 63));
+        self.stack.set(1, Felt::new((result as u32) as u64));
+    }
+
+    fn op_u32mul(&mut self) {
+        let b = self.stack.get(0).as_int();
+        let a = self.stack.get(1).as_int();
+        let (hi, lo) = split_element(Felt::new(a * b));
+        self.stack.set(0, hi);
+        self.stack.set(1, lo);
+    }
+
+    fn op_u32madd(&mut self) {
+        let b = self.stack.get(0).as_int();
+        let a = self.stack.get(1).as_int();
+        let c = self.stack.get(2).as_int();
+        let (hi, lo) = split_element(Felt::new(a * b + c));
+        self.stack.shift_left(3);
+        self.stack.set(0, hi);
+        self.stack.set(1, lo);
+    }
+
+    fn op_u32div(&mut self) -> Result<(), ExecutionError> {
+        let b = self.stack.get(0).as_int();
+        let a = self.stack.get(1).as_int();
+        if b == 0 {
+            return Err(ExecutionError::DivideByZero(self.clk));
+        }
+
+        let q = a / b;
+        let r = a - q * b;
+        self.stack.set(0, Felt::new(r));
+        self.stack.set(1, Felt::new(q));
+        Ok(())
+    }
+
+    /// Pops two elements `[b, a, ...]` off the stack and pushes the result of `f(a, b)`. Both
+    /// elements are required to be 32-bit values, as enforced by the bitwise chiplet.
+    fn op_u32bitwise(&mut self, f: impl FnOnce(u64, u64) -> u64) -> Result<(), ExecutionError> {
+        let b = self.stack.get(0);
+        let a = self.stack.get(1);
+        let a = assert_u32(a)?.as_int();
+        let b = assert_u32(b)?.as_int();
+        self.op_binary(|_, _| Felt::new(f(a, b)));
+        Ok(())
+    }
+
+    // STACK MANIPULATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Pushes the provided value onto the stack.
+    fn op_push(&mut self, value: Felt) {
+        self.stack.shift_right(0, self.clk);
+        self.stack.set(0, value);
+    }
+
+    /// Swaps groups of `len` consecutive stack elements starting at each of the provided pairs
+    /// of positions.
+    fn op_swap_words(&mut self, pairs: &[(usize, usize)], len: usize) {
+        for &(a, b) in pairs {
+            for i in 0..len {
+                let a_value = self.stack.get(a + i);
+                let b_value = self.stack.get(b + i);
+                self.stack.set(a + i, b_value);
+                self.stack.set(b + i, a_value);
+            }
+        }
+    }
+
+    fn op_movup(&mut self, n: usize) {
+        let value = self.stack.get(n);
+        for i in (0..n).rev() {
+            self.stack.set(i + 1, self.stack.get(i));
+        }
+        self.stack.set(0, value);
+    }
+
+    fn op_movdn(&mut self, n: usize) {
+        let value = self.stack.get(0);
+        for i in 0..n {
+            self.stack.set(i, self.stack.get(i + 1));
+        }
+        self.stack.set(n, value);
+    }
+
+    /// Pops the condition off the stack and, if it is ONE, swaps the two groups of `len`
+    /// elements located below it.
+    fn op_cswap(&mut self, len: usize) -> Result<(), ExecutionError> {
+        let c = self.stack.get(0);
+        let swap = match c.as_int() {
+            0 => false,
+            1 => true,
+            _ => return Err(ExecutionError::NotBinaryValue(c)),
+        };
+
+        self.stack.shift_left(1);
+        if swap {
+            self.op_swap_words(&[(0, len)], len);
+        }
+        Ok(())
+    }
+
+    // INPUT / OUTPUT OPERATIONS
+    // --------------------------------------------------------------------------------------------
+
+    fn op_advpop(&mut self) -> Result<(), ExecutionError> {
+        let value = self.host.borrow_mut().pop_adv_stack(self)?;
+        self.op_push(value);
+        Ok(())
+    }
+
+    fn op_advpopw(&mut self) -> Result<(), ExecutionError> {
+        let word = self.host.borrow_mut().pop_adv_stack_word(self)?;
+        self.set_word(0, word);
+        Ok(())
+    }
+
+    fn op_mloadw(&mut self) -> Result<(), ExecutionError> {
+        let addr = get_valid_address(self.stack.get(0))?;
+        let word = self.read_mem(addr);
+        self.stack.shift_left(1);
+        self.set_word(0, word);
+        Ok(())
+    }
+
+    fn op_mload(&mut self) -> Result<(), ExecutionError> {
+        let addr = get_valid_address(self.stack.get(0))?;
+        let word = self.read_mem(addr);
+        self.stack.set(0, word[0]);
+        Ok(())
+    }
+
+    fn op_mstream(&mut self) -> Result<(), ExecutionError> {
+        let addr = get_valid_address(self.stack.get(12))?;
+        let words = [self.read_mem(addr), self.read_mem(addr + 1)];
+        self.set_dword(words, addr);
+        Ok(())
+    }
+
+    fn op_mstorew(&mut self) -> Result<(), ExecutionError> {
+        let addr = get_valid_address(self.stack.get(0))?;
+        self.stack.shift_left(1);
+        let word = self.stack.get_word(0);
+        self.write_mem(addr, word);
+        Ok(())
+    }
+
+    fn op_mstore(&mut self) -> Result<(), ExecutionError> {
+        let addr = get_valid_address(self.stack.get(0))?;
+        let value = self.stack.get(1);
+        let old_word = self.get_mem(addr).unwrap_or(EMPTY_WORD);
+        self.write_mem(addr, [value, old_word[1], old_word[2], old_word[3]]);
+        self.stack.shift_left(1);
+        Ok(())
+    }
+
+    fn op_pipe(&mut self) -> Result<(), ExecutionError> {
+        let addr = get_valid_address(self.stack.get(12))?;
+        let words = self.host.borrow_mut().pop_adv_stack_dword(self)?;
+        self.write_mem(addr, words[0]);
+        self.write_mem(addr + 1, words[1]);
+        self.set_dword(words, addr);
+        Ok(())
+    }
+
+    // CRYPTOGRAPHIC OPERATIONS
+    // --------------------------------------------------------------------------------------------
+
+    fn op_hperm(&mut self) {
+        let mut state = [ZERO; 12];
+        for (i, value) in state.iter_mut().rev().enumerate() {
+            *value = self.stack.get(i);
+        }
+        apply_permutation(&mut state);
+        for (i, &value) in state.iter().rev().enumerate() {
+            self.stack.set(i, value);
+        }
+    }
+
+    fn op_mpverify(&mut self) -> Result<(), ExecutionError> {
+        let node = self.stack.get_word(0);
+        let index = self.stack.get(5);
+        let root = [self.stack.get(9), self.stack.get(8), self.stack.get(7), self.stack.get(6)];
+
+        let path = self.host.borrow_mut().get_adv_merkle_path(self)?;
+        if root != compute_merkle_root(node, &path, index) {
+            return Err(ExecutionError::MerklePathVerificationFailed {
+                value: node,
+                index,
+                root: root.into(),
+            });
+        }
+        Ok(())
+    }
+
+    fn op_mrupdate(&mut self) -> Result<(), ExecutionError> {
+        let old_node = self.stack.get_word(0);
+        let depth = self.stack.get(4);
+        let index = self.stack.get(5);
+        let old_root = [self.stack.get(9), self.stack.get(8), self.stack.get(7), self.stack.get(6)];
+        let new_node =
+            [self.stack.get(13), self.stack.get(12), self.stack.get(11), self.stack.get(10)];
+
+        let path: MerklePath = self
+            .host
+            .borrow_mut()
+            .set_advice(self, AdviceInjector::UpdateMerkleNode)?
+            .into();
+        assert_eq!(path.len(), depth.as_int() as usize);
+
+        let computed_old_root = compute_merkle_root(old_node, &path, index);
+        assert_eq!(old_root, computed_old_root, "inconsistent Merkle tree root");
+
+        let new_root = compute_merkle_root(new_node, &path, index);
+        self.set_word(0, new_root);
+        Ok(())
+    }
+
+    fn op_fri_ext2fold4(&mut self) -> Result<(), ExecutionError> {
+        let query_values = [
+            QuadFelt::new(self.stack.get(7), self.stack.get(6)),
+            QuadFelt::new(self.stack.get(5), self.stack.get(4)),
+            QuadFelt::new(self.stack.get(3), self.stack.get(2)),
+            QuadFelt::new(self.stack.get(1), self.stack.get(0)),
+        ];
+        let f_pos = self.stack.get(8);
+        let d_seg = self.stack.get(9).as_int();
+        let poe = self.stack.get(10);
+        let prev_value = QuadFelt::new(self.stack.get(12), self.stack.get(11));
+        let alpha = QuadFelt::new(self.stack.get(14), self.stack.get(13));
+        let layer_ptr = self.stack.get(15);
+
+        if d_seg > 3 {
+            return Err(ExecutionError::InvalidFriDomainSegment(d_seg));
+        }
+        let d_seg = d_seg as usize;
+        if query_values[d_seg] != prev_value {
+            return Err(ExecutionError::InvalidFriLayerFolding(prev_value, query_values[d_seg]));
+        }
+
+        let f_tau = get_tau_factor(d_seg);
+        let x = poe * f_tau * DOMAIN_OFFSET;
+        let (ev, es) = compute_evaluation_points(alpha, x.inv());
+        let (folded_value, tmp0, tmp1) = fold4(query_values, ev, es);
+
+        let tmp0 = tmp0.to_base_elements();
+        let tmp1 = tmp1.to_base_elements();
+        let ds = get_domain_segment_flags(d_seg);
+        let folded_value = folded_value.to_base_elements();
+        let poe2 = poe.square();
+        let poe4 = poe2.square();
+
+        self.stack.shift_left(16);
+        let values = [
+            tmp0[1],
+            tmp0[0],
+            tmp1[1],
+            tmp1[0],
+            ds[3],
+            ds[2],
+            ds[1],
+            ds[0],
+            poe2,
+            f_tau,
+            layer_ptr + TWO,
+            poe4,
+            f_pos,
+            folded_value[1],
+            folded_value[0],
+        ];
+        for (i, value) in values.into_iter().enumerate() {
+            self.stack.set(i, value);
+        }
+        Ok(())
+    }
+
+    fn op_rcomb_base(&mut self) {
+        let alpha_word = self.read_mem(self.stack.get(14).as_int() as u32);
+        let ood_word = self.read_mem(self.stack.get(13).as_int() as u32);
+        let alpha = QuadFelt::new(alpha_word[0], alpha_word[1]);
+        let tz = QuadFelt::new(ood_word[0], ood_word[1]);
+        let tgz = QuadFelt::new(ood_word[2], ood_word[3]);
+
+        let p = QuadFelt::new(self.stack.get(9), self.stack.get(8));
+        let r = QuadFelt::new(self.stack.get(11), self.stack.get(10));
+
+        let tx = QuadFelt::new(self.stack.get(7), ZERO);
+        let p_new = (p + alpha * (tx - tz)).to_base_elements();
+        let r_new = (r + alpha * (tx - tgz)).to_base_elements();
+
+        // rotate the top 8 elements of the stack
+        self.op_movup(7);
+
+        self.stack.set(8, p_new[1]);
+        self.stack.set(9, p_new[0]);
+        self.stack.set(10, r_new[1]);
+        self.stack.set(11, r_new[0]);
+        self.stack.set(13, self.stack.get(13) + ONE);
+        self.stack.set(14, self.stack.get(14) + ONE);
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Overwrites the top four stack elements with the provided word (in stack order).
+    fn set_word(&mut self, word_idx: usize, word: Word) {
+        for (i, &value) in word.iter().rev().enumerate() {
+            self.stack.set(word_idx * 4 + i, value);
+        }
+    }
+
+    /// Overwrites the top eight stack elements with the provided words (in stack order), and sets
+    /// the memory address at stack position 12 to `addr + 2`.
+    fn set_dword(&mut self, words: [Word; 2], addr: u32) {
+        self.set_word(0, words[1]);
+        self.set_word(1, words[0]);
+        self.stack.set(12, Felt::from(addr + 2));
+    }
+
+    /// Returns a word located in memory of the current context at the specified address, or None
+    /// if the address hasn't been accessed previously.
+    fn get_mem(&self, addr: u32) -> Option<Word> {
+        self.memory.get(&self.ctx).and_then(|segment| segment.get(&addr)).copied()
+    }
+
+    /// Returns a word located in memory of the current context at the specified address.
+    ///
+    /// If the specified address hasn't been previously accessed, it is initialized to ZEROs.
+    fn read_mem(&mut self, addr: u32) -> Word {
+        *self.memory.entry(self.ctx).or_default().entry(addr).or_default()
+    }
+
+    /// Writes the provided word to memory of the current context at the specified address.
+    fn write_mem(&mut self, addr: u32, word: Word) {
+        self.memory.entry(self.ctx).or_default().insert(addr, word);
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Checks that provided address is less than u32::MAX and returns it cast to u32.
+fn get_valid_address(addr: Felt) -> Result<u32, ExecutionError> {
+    let addr = addr.as_int();
+    if addr > u32::MAX as u64 {
+        return Err(ExecutionError::MemoryAddressOutOfBounds(addr));
+    }
+    Ok(addr as u32)
+}
+
+/// Computes the root of the provided Merkle path for the node with the specified value and index.
+///
+/// # Panics
+/// Panics if the path is empty or if the index is out of range for the path, as the hasher
+/// chiplet of the tracing process does.
+fn compute_merkle_root(value: Word, path: &MerklePath, index: Felt) -> Word {
+    assert!(!path.is_empty(), "path is empty");
+    let mut index = index.as_int();
+    assert!(
+        index.checked_shr(path.len() as u32).unwrap_or(0) == 0,
+        "invalid index for the path"
+    );
+
+    path.iter().fold(value, |node, &sibling| {
+        let children = if index & 1 == 0 {
+            [node.into(), sibling]
+        } else {
+            [sibling, node.into()]
+        };
+        index >>= 1;
+        merge(&children).into()
+    })
+}
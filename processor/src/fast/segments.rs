@@ -0,0 +1,489 @@
+//! Segmented execution of programs.
+//!
+//! A long execution of a program can be split into segments of a fixed number of clock cycles.
+//! The state of the VM at the boundary between two consecutive segments is captured in a
+//! [ProcessSnapshot], and is committed to via a [SegmentBoundary]. Since every segment starts from
+//! a snapshot, segments can be processed independently of each other (and in any order), while
+//! the boundary commitments link the segments into a single execution of the program.
+//!
+//! Segments are checked by executing them again from their starting snapshots. The total number
+//! of cycles of a segmented execution is bounded by [ExecutionOptions::max_cycles()] in the same
+//! way as for any other execution.
+
+use super::{
+    Digest, ExecutionError, ExecutionOptions, FastProcess, Felt, ProcessSnapshot, Program,
+    StackInputs, StackOutputs, ZERO,
+};
+use crate::{AdviceInputs, DefaultHost, MemAdviceProvider};
+use alloc::{string::ToString, vec::Vec};
+use vm_core::{
+    crypto::{hash::Rpo256, merkle::Smt},
+    utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable},
+};
+
+// SEGMENT BOUNDARY
+// ================================================================================================
+
+/// Commitments to the state of the VM at the boundary between two execution segments.
+///
+/// A boundary consists of the clock cycle at which the boundary is located, and of commitments to:
+/// - the hash of the program and the system registers of the VM,
+/// - the operand stack, including the items in the overflow table,
+/// - the memory of all execution contexts, as the root of a sparse Merkle tree which maps
+///   `[ctx, addr, 0, 0]` keys to the words stored at address `addr` of context `ctx`,
+/// - the continuations of the process, i.e., the position of the execution within the MAST of the
+///   program.
+///
+/// The state of the advice provider is not committed to, since it is a private input of the
+/// program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentBoundary {
+    clk: u32,
+    is_final: bool,
+    system: Digest,
+    stack: Digest,
+    memory: Digest,
+    continuations: Digest,
+}
+
+impl SegmentBoundary {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns the boundary commitments to the state of the VM captured in the provided snapshot.
+    pub fn new(snapshot: &ProcessSnapshot) -> Self {
+        let mut system = Vec::with_capacity(12);
+        system.extend_from_slice(snapshot.program_hash.as_elements());
+        system.push(Felt::from(snapshot.clk));
+        system.push(Felt::from(u32::from(snapshot.ctx)));
+        system.push(snapshot.fmp);
+        system.push(Felt::from(snapshot.in_syscall as u32));
+        system.extend_from_slice(&snapshot.fn_hash);
+
+        // memory is committed to via a sparse Merkle tree, so that individual memory cells can be
+        // opened against the commitment; uninitialized memory and memory set to zeros are
+        // indistinguishable, as both are read as zeros
+        let mut memory = Smt::new();
+        for (ctx, segment) in snapshot.memory.iter() {
+            for (addr, word) in segment.iter() {
+                let key = [Felt::from(u32::from(*ctx)), Felt::from(*addr), ZERO, ZERO];
+                memory.insert(key.into(), *word);
+            }
+        }
+
+        Self {
+            clk: snapshot.clk,
+            is_final: snapshot.is_finished(),
+            system: Rpo256::hash_elements(&system),
+            stack: snapshot.stack.commitment(),
+            memory: memory.root(),
+            continuations: Rpo256::hash(&snapshot.continuations.to_bytes()),
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the clock cycle at which this boundary is located.
+    pub fn clk(&self) -> u32 {
+        self.clk
+    }
+
+    /// Returns true if the program has completed at this boundary.
+    pub fn is_final(&self) -> bool {
+        self.is_final
+    }
+
+    /// Returns the commitment to the hash of the program and the system registers of the VM.
+    pub fn system_commitment(&self) -> Digest {
+        self.system
+    }
+
+    /// Returns the commitment to the operand stack, including the items in the overflow table.
+    pub fn stack_commitment(&self) -> Digest {
+        self.stack
+    }
+
+    /// Returns the commitment to the memory of all execution contexts, i.e., the root of the sparse
+    /// Merkle tree which maps `[ctx, addr, 0, 0]` keys to the words stored in memory.
+    pub fn memory_commitment(&self) -> Digest {
+        self.memory
+    }
+
+    /// Returns the commitment to the continuations of the process, i.e., to the position of the
+    /// execution within the MAST of the program.
+    pub fn continuations_commitment(&self) -> Digest {
+        self.continuations
+    }
+
+    /// Returns a single commitment to the entire state of the VM at this boundary.
+    pub fn commitment(&self) -> Digest {
+        let mut elements = Vec::with_capacity(18);
+        elements.push(Felt::from(self.clk));
+        elements.push(Felt::from(self.is_final as u32));
+        elements.extend_from_slice(self.system.as_elements());
+        elements.extend_from_slice(self.stack.as_elements());
+        elements.extend_from_slice(self.memory.as_elements());
+        elements.extend_from_slice(self.continuations.as_elements());
+        Rpo256::hash_elements(&elements)
+    }
+}
+
+impl Serializable for SegmentBoundary {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u32(self.clk);
+        target.write_bool(self.is_final);
+        self.system.write_into(target);
+        self.stack.write_into(target);
+        self.memory.write_into(target);
+        self.continuations.write_into(target);
+    }
+}
+
+impl Deserializable for SegmentBoundary {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        Ok(Self {
+            clk: source.read_u32()?,
+            is_final: source.read_bool()?,
+            system: Digest::read_from(source)?,
+            stack: Digest::read_from(source)?,
+            memory: Digest::read_from(source)?,
+            continuations: Digest::read_from(source)?,
+        })
+    }
+}
+
+// EXECUTION SEGMENT
+// ================================================================================================
+
+/// A segment of the execution of a program.
+///
+/// A segment consists of a snapshot of the VM state at the start of the segment, and of the
+/// commitments to the VM state at the start and at the end of the segment. The commitments to the
+/// starting state are derived from the snapshot (and are not serialized).
+#[derive(Debug, Clone)]
+pub struct ExecutionSegment {
+    pub(super) snapshot: ProcessSnapshot,
+    pub(super) start: SegmentBoundary,
+    pub(super) end: SegmentBoundary,
+}
+
+impl ExecutionSegment {
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the snapshot of the VM state at the start of this segment.
+    pub fn snapshot(&self) -> &ProcessSnapshot {
+        &self.snapshot
+    }
+
+    /// Returns the commitments to the VM state at the start of this segment.
+    pub fn start(&self) -> SegmentBoundary {
+        self.start
+    }
+
+    /// Returns the commitments to the VM state at the end of this segment.
+    pub fn end(&self) -> SegmentBoundary {
+        self.end
+    }
+
+    /// Returns the number of clock cycles executed in this segment.
+    pub fn num_cycles(&self) -> u32 {
+        self.end.clk - self.snapshot.clk
+    }
+}
+
+impl Serializable for ExecutionSegment {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.snapshot.write_into(target);
+        self.end.write_into(target);
+    }
+}
+
+impl Deserializable for ExecutionSegment {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let snapshot = ProcessSnapshot::read_from(source)?;
+        let end = SegmentBoundary::read_from(source)?;
+        if end.clk < snapshot.clk {
+            return Err(DeserializationError::InvalidValue(
+                "execution segment ends before it starts".to_string(),
+            ));
+        }
+        let start = SegmentBoundary::new(&snapshot);
+        Ok(Self {
+            snapshot,
+            start,
+            end,
+        })
+    }
+}
+
+// SEGMENTED EXECUTION
+// ================================================================================================
+
+/// The execution of a program split into a sequence of [ExecutionSegment]s.
+///
+/// Segments can be checked independently of each other via
+/// [SegmentedExecution::verify_segment()], after which [SegmentedExecution::verify_links()]
+/// checks that the segments form a single execution of the program from its initial state to its
+/// stack outputs. [SegmentedExecution::verify()] performs both checks.
+///
+/// Every segment holds a full snapshot of the VM state at its start, and thus, a segmented
+/// execution kept in memory takes space proportional to the number of segments times the size of
+/// the VM memory. To process segments one at a time instead, use [ExecutionSegments].
+#[derive(Debug, Clone)]
+pub struct SegmentedExecution {
+    pub(super) segments: Vec<ExecutionSegment>,
+    pub(super) stack_outputs: StackOutputs,
+}
+
+impl SegmentedExecution {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Executes the provided program, splitting the execution into segments of at most
+    /// `segment_len` clock cycles each.
+    pub(crate) fn execute(
+        program: &Program,
+        stack_inputs: StackInputs,
+        advice_inputs: AdviceInputs,
+        options: ExecutionOptions,
+        segment_len: u32,
+    ) -> Result<Self, ExecutionError> {
+        let mut iter =
+            ExecutionSegments::new(program, stack_inputs, advice_inputs, options, segment_len)?;
+        let segments = iter.by_ref().collect::<Result<Vec<_>, _>>()?;
+        let stack_outputs = iter.stack_outputs.expect("program execution has not completed");
+        Ok(Self {
+            segments,
+            stack_outputs,
+        })
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the segments of this execution.
+    pub fn segments(&self) -> &[ExecutionSegment] {
+        &self.segments
+    }
+
+    /// Returns the outputs of the program.
+    pub fn stack_outputs(&self) -> &StackOutputs {
+        &self.stack_outputs
+    }
+
+    /// Returns the total number of clock cycles executed by the program.
+    pub fn num_cycles(&self) -> u32 {
+        self.segments.last().map_or(0, |segment| segment.end.clk)
+    }
+
+    // VERIFICATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Checks that all segments of this execution are valid and that they form a single
+    /// execution of the provided program against the provided inputs.
+    ///
+    /// # Errors
+    /// Returns an error if any of the checks performed by [SegmentedExecution::verify_segment()]
+    /// or [SegmentedExecution::verify_links()] fails.
+    pub fn verify(
+        &self,
+        program: &Program,
+        stack_inputs: StackInputs,
+        options: ExecutionOptions,
+    ) -> Result<(), ExecutionError> {
+        self.verify_links(program, stack_inputs, options)?;
+        for index in 0..self.segments.len() {
+            self.verify_segment(index, program, options)?;
+        }
+        Ok(())
+    }
+
+    /// Checks that the segment at the specified index is valid.
+    ///
+    /// The segment is executed from its starting snapshot, and the state of the VM at the end of
+    /// the segment is checked against the end boundary of the segment. If the segment is the last
+    /// one, the outputs of the program are checked as well.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds.
+    pub fn verify_segment(
+        &self,
+        index: usize,
+        program: &Program,
+        options: ExecutionOptions,
+    ) -> Result<(), ExecutionError> {
+        let segment = &self.segments[index];
+        let mut process = FastProcess::<DefaultHost<MemAdviceProvider>>::from_snapshot(
+            segment.snapshot.clone(),
+            program,
+            options,
+        )
+        .map_err(|err| ExecutionError::InvalidSegment(index, err.to_string()))?;
+        let result = process
+            .execute_until(program, segment.end.clk)
+            .map_err(|err| ExecutionError::InvalidSegment(index, err.to_string()))?;
+
+        if SegmentBoundary::new(&process.snapshot(program)) != segment.end {
+            return Err(ExecutionError::InvalidSegment(
+                index,
+                "the state at the end of the segment does not match its boundary".to_string(),
+            ));
+        }
+        if segment.end.is_final && result.as_ref() != Some(&self.stack_outputs) {
+            return Err(ExecutionError::InvalidSegment(
+                index,
+                "the outputs of the program do not match the final state".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that the segments of this execution are linked into a single execution of the
+    /// provided program against the provided inputs.
+    ///
+    /// Specifically, the first segment must start at the initial state of the program, each
+    /// segment must start at the boundary at which the previous segment ends, and only the last
+    /// segment may end with the completion of the program.
+    pub fn verify_links(
+        &self,
+        program: &Program,
+        stack_inputs: StackInputs,
+        options: ExecutionOptions,
+    ) -> Result<(), ExecutionError> {
+        let mut process = FastProcess::new(
+            program.kernel().clone(),
+            stack_inputs,
+            DefaultHost::default(),
+            options,
+        );
+        process.execute_until(program, 0)?;
+        let mut boundary = SegmentBoundary::new(&process.snapshot(program));
+
+        for (index, segment) in self.segments.iter().enumerate() {
+            if segment.start() != boundary {
+                return Err(ExecutionError::InvalidSegment(
+                    index,
+                    "the segment does not start where the previous one ends".to_string(),
+                ));
+            }
+            let is_last = index == self.segments.len() - 1;
+            if segment.end.is_final != is_last {
+                return Err(ExecutionError::InvalidSegment(
+                    index,
+                    "only the last segment may complete the program".to_string(),
+                ));
+            }
+            boundary = segment.end;
+        }
+
+        if self.segments.is_empty() {
+            return Err(ExecutionError::InvalidSegment(0, "no segments were provided".to_string()));
+        }
+        Ok(())
+    }
+}
+
+impl Serializable for SegmentedExecution {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_usize(self.segments.len());
+        target.write_many(&self.segments);
+        self.stack_outputs.write_into(target);
+    }
+}
+
+impl Deserializable for SegmentedExecution {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let num_segments = source.read_usize()?;
+        let segments = source.read_many(num_segments)?;
+        let stack_outputs = StackOutputs::read_from(source)?;
+        Ok(Self {
+            segments,
+            stack_outputs,
+        })
+    }
+}
+
+// EXECUTION SEGMENTS ITERATOR
+// ================================================================================================
+
+/// An iterator which executes a program one segment at a time, and yields the [ExecutionSegment]s
+/// of the execution as they are completed.
+///
+/// Unlike [SegmentedExecution], this iterator keeps only the snapshot of the segment being
+/// executed in memory, and thus, allows processing (e.g., saving) every segment before the next
+/// one is executed. After the last segment is yielded, the outputs of the program are available
+/// via [ExecutionSegments::stack_outputs()]. The iterator yields no more segments after an error.
+pub struct ExecutionSegments<'a> {
+    program: &'a Program,
+    process: FastProcess<DefaultHost<MemAdviceProvider>>,
+    start: Option<(ProcessSnapshot, SegmentBoundary)>,
+    segment_len: u32,
+    stack_outputs: Option<StackOutputs>,
+}
+
+impl<'a> ExecutionSegments<'a> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Starts the execution of the provided program, which is split into segments of at most
+    /// `segment_len` clock cycles each.
+    pub(crate) fn new(
+        program: &'a Program,
+        stack_inputs: StackInputs,
+        advice_inputs: AdviceInputs,
+        options: ExecutionOptions,
+        segment_len: u32,
+    ) -> Result<Self, ExecutionError> {
+        if segment_len == 0 {
+            return Err(ExecutionError::InvalidSegmentLength);
+        }
+
+        let host = DefaultHost::new(MemAdviceProvider::from(advice_inputs));
+        let mut process = FastProcess::new(program.kernel().clone(), stack_inputs, host, options);
+
+        // start the execution without executing any cycles to capture the initial state
+        process.execute_until(program, 0)?;
+        let snapshot = process.snapshot(program);
+        let start = SegmentBoundary::new(&snapshot);
+
+        Ok(Self {
+            program,
+            process,
+            start: Some((snapshot, start)),
+            segment_len,
+            stack_outputs: None,
+        })
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the outputs of the program if the program has completed, or None otherwise.
+    pub fn stack_outputs(&self) -> Option<&StackOutputs> {
+        self.stack_outputs.as_ref()
+    }
+}
+
+impl Iterator for ExecutionSegments<'_> {
+    type Item = Result<ExecutionSegment, ExecutionError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (snapshot, start) = self.start.take()?;
+        let end_clk = snapshot.clk.saturating_add(self.segment_len);
+        let result = match self.process.execute_until(self.program, end_clk) {
+            Ok(result) => result,
+            Err(err) => return Some(Err(err)),
+        };
+
+        let end_snapshot = self.process.snapshot(self.program);
+        let end = SegmentBoundary::new(&end_snapshot);
+        match result {
+            Some(stack_outputs) => self.stack_outputs = Some(stack_outputs),
+            None => self.start = Some((end_snapshot, end)),
+        }
+        Some(Ok(ExecutionSegment {
+            snapshot,
+            start,
+            end,
+        }))
+    }
+}
@@ -0,0 +1,400 @@
+//! Snapshots of the state of a [FastProcess].
+//!
+//! A snapshot is serialized into a binary blob with the following format:
+//!
+//! - 4 magic bytes (`MVSS`) followed by a single format version byte.
+//! - Hash of the program being executed.
+//! - System registers: clock cycle, context ID, free memory pointer, syscall flag, and hash of the
+//!   function which initiated the current context.
+//! - Operand stack, including the items and the addresses of the overflow table.
+//! - Memory of all execution contexts.
+//! - Continuations of the process, from the bottom of the continuation stack to its top, followed
+//!   by the hashes of the code blocks referenced by the continuations.
+//! - State of the advice provider, serialized as [AdviceInputs].
+//!
+//! Snapshots are supported only by the fast processor; the tracing [crate::Process] (and thus,
+//! the debugger) cannot be paused and resumed, as its state also includes the execution trace
+//! being built.
+
+use super::{
+    CallerContext, ContextId, Continuation, Digest, ExecutionError, ExecutionOptions, FastProcess,
+    FastStack, Felt, MastNode, MastNodeId, Program, SpanCursor, Word,
+};
+use crate::{AdviceInputs, DefaultHost, SnapshotAdviceProvider};
+use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
+use core::cell::RefCell;
+use vm_core::utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Magic bytes identifying a serialized process snapshot.
+const MAGIC: &[u8; 4] = b"MVSS";
+
+/// The current version of the process snapshot serialization format.
+const VERSION: u8 = 2;
+
+// continuation tags
+const START_NODE: u8 = 0;
+const START_DYN: u8 = 1;
+const RESUME_SPAN: u8 = 2;
+const FINISH_LOOP: u8 = 3;
+const FINISH_CALL: u8 = 4;
+const END: u8 = 5;
+
+// PROCESS SNAPSHOT
+// ================================================================================================
+
+/// A snapshot of the state of a [FastProcess] which has been paused in the middle of a program.
+///
+/// The snapshot contains everything needed to resume the execution of the program: the system
+/// registers, the operand stack (including the overflow table), the memory of all execution
+/// contexts, the continuations of the process (i.e., the code blocks being executed and the
+/// position of the execution within them), and the state of the advice provider.
+///
+/// A snapshot can be resumed only with the program it was taken from; code blocks of the program
+/// are referenced by their IDs in the MAST forest of the program, and the hashes of the referenced
+/// code blocks are checked against the program when the snapshot is resumed.
+///
+/// Snapshots can be taken only from processes whose hosts are [DefaultHost]s with advice providers
+/// implementing [SnapshotAdviceProvider] (e.g., [crate::MemAdviceProvider]).
+#[derive(Debug, Clone)]
+pub struct ProcessSnapshot {
+    pub(super) program_hash: Digest,
+    pub(super) clk: u32,
+    pub(super) ctx: ContextId,
+    pub(super) fmp: Felt,
+    pub(super) in_syscall: bool,
+    pub(super) fn_hash: Word,
+    pub(super) stack: FastStack,
+    pub(super) memory: BTreeMap<ContextId, BTreeMap<u32, Word>>,
+    pub(super) continuations: Vec<Continuation>,
+    node_hashes: Vec<Digest>,
+    advice: AdviceInputs,
+}
+
+impl ProcessSnapshot {
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the hash of the program this snapshot was taken from.
+    pub fn program_hash(&self) -> Digest {
+        self.program_hash
+    }
+
+    /// Returns the clock cycle at which this snapshot was taken.
+    pub fn clk(&self) -> u32 {
+        self.clk
+    }
+
+    /// Returns true if the program had completed by the time this snapshot was taken.
+    pub fn is_finished(&self) -> bool {
+        self.continuations.is_empty()
+    }
+
+    /// Returns the state of the advice provider at the time this snapshot was taken.
+    pub fn advice_inputs(&self) -> &AdviceInputs {
+        &self.advice
+    }
+}
+
+// SNAPSHOTS OF FAST PROCESS
+// ================================================================================================
+
+impl<A: SnapshotAdviceProvider> FastProcess<DefaultHost<A>> {
+    /// Returns a snapshot of the current state of this process, which is executing the provided
+    /// program.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// - The execution of a program has not been started in this process.
+    /// - The provided program is not the program being executed by this process.
+    pub fn snapshot(&self, program: &Program) -> ProcessSnapshot {
+        let program_hash = self.program_hash.expect("program execution has not been started");
+        assert_eq!(program_hash, program.hash(), "a different program is being executed");
+
+        let node_hashes = self
+            .continuations
+            .iter()
+            .filter_map(referenced_node)
+            .map(|node_id| program.get_node(node_id).hash())
+            .collect();
+
+        ProcessSnapshot {
+            program_hash,
+            clk: self.clk,
+            ctx: self.ctx,
+            fmp: self.fmp,
+            in_syscall: self.in_syscall,
+            fn_hash: self.fn_hash,
+            stack: self.stack.clone(),
+            memory: self.memory.clone(),
+            continuations: self.continuations.clone(),
+            node_hashes,
+            advice: self.host.borrow().advice_provider().to_inputs(),
+        }
+    }
+
+    /// Returns a new process which resumes the execution of the provided program from the
+    /// specified snapshot.
+    ///
+    /// The execution can then be continued via [FastProcess::execute()] or
+    /// [FastProcess::execute_until()] with the same program, and results in the same outputs as
+    /// the execution of the program without pausing it.
+    ///
+    /// # Errors
+    /// Returns an error if the snapshot was taken from a different program, or if the snapshot
+    /// references code blocks which are not present in the program or whose hashes differ from the
+    /// hashes recorded in the snapshot.
+    pub fn from_snapshot(
+        snapshot: ProcessSnapshot,
+        program: &Program,
+        execution_options: ExecutionOptions,
+    ) -> Result<Self, ExecutionError> {
+        if snapshot.program_hash != program.hash() {
+            return Err(ExecutionError::InvalidSnapshot(format!(
+                "snapshot was taken from program {:?}, but the provided program is {:?}",
+                snapshot.program_hash,
+                program.hash()
+            )));
+        }
+        for continuation in snapshot.continuations.iter() {
+            validate_continuation(continuation, program)
+                .map_err(ExecutionError::InvalidSnapshot)?;
+        }
+        let node_ids = snapshot.continuations.iter().filter_map(referenced_node);
+        for (node_id, &expected_hash) in node_ids.zip(snapshot.node_hashes.iter()) {
+            let node_hash = program.get_node(node_id).hash();
+            if node_hash != expected_hash {
+                return Err(ExecutionError::InvalidSnapshot(format!(
+                    "node {node_id} of the program has hash {node_hash:?}, but the snapshot \
+                    expects hash {expected_hash:?}"
+                )));
+            }
+        }
+
+        let host = DefaultHost::new(A::from(snapshot.advice));
+        Ok(Self {
+            clk: snapshot.clk,
+            ctx: snapshot.ctx,
+            fmp: snapshot.fmp,
+            in_syscall: snapshot.in_syscall,
+            fn_hash: snapshot.fn_hash,
+            stack: snapshot.stack,
+            memory: snapshot.memory,
+            kernel: program.kernel().clone(),
+            host: RefCell::new(host),
+            max_cycles: execution_options.max_cycles(),
+            enable_tracing: execution_options.enable_tracing(),
+            error_messages: program.error_messages().clone(),
+            program_hash: Some(snapshot.program_hash),
+            continuations: snapshot.continuations,
+        })
+    }
+}
+
+/// Makes sure that the code blocks referenced by the specified continuation are present in the
+/// program, and that positions within SPAN blocks are valid.
+fn validate_continuation(continuation: &Continuation, program: &Program) -> Result<(), String> {
+    let get_node = |node_id: MastNodeId| {
+        if program.forest().contains(node_id) {
+            Ok(program.get_node(node_id))
+        } else {
+            Err(format!("node {node_id} is not present in the program"))
+        }
+    };
+
+    match continuation {
+        Continuation::StartNode(node_id) | Continuation::FinishLoop(node_id) => {
+            get_node(*node_id).map(|_| ())
+        }
+        Continuation::ResumeSpan(node_id, cursor) => {
+            let block = match get_node(*node_id)? {
+                MastNode::Span(block) => block,
+                _ => return Err(format!("node {node_id} is not a SPAN block")),
+            };
+            let batch = block.op_batches().get(cursor.batch_idx).ok_or_else(|| {
+                format!("op batch {} is not present in node {node_id}", cursor.batch_idx)
+            })?;
+            let is_valid = if cursor.op_idx < batch.ops().len() {
+                batch
+                    .op_counts()
+                    .get(cursor.group_idx)
+                    .is_some_and(|&op_count| cursor.group_op_idx < op_count)
+            } else {
+                cursor.op_idx == batch.ops().len()
+            };
+            if is_valid {
+                Ok(())
+            } else {
+                Err(format!("invalid position within node {node_id}: {cursor:?}"))
+            }
+        }
+        Continuation::StartDyn | Continuation::FinishCall(_) | Continuation::End => Ok(()),
+    }
+}
+
+/// Returns the ID of the code block referenced by the specified continuation, if any.
+fn referenced_node(continuation: &Continuation) -> Option<MastNodeId> {
+    match continuation {
+        Continuation::StartNode(node_id)
+        | Continuation::ResumeSpan(node_id, _)
+        | Continuation::FinishLoop(node_id) => Some(*node_id),
+        Continuation::StartDyn | Continuation::FinishCall(_) | Continuation::End => None,
+    }
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+impl Serializable for ProcessSnapshot {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_bytes(MAGIC);
+        target.write_u8(VERSION);
+
+        self.program_hash.write_into(target);
+        target.write_u32(self.clk);
+        target.write_u32(self.ctx.into());
+        self.fmp.write_into(target);
+        target.write_bool(self.in_syscall);
+        self.fn_hash.write_into(target);
+
+        self.stack.write_into(target);
+
+        target.write_usize(self.memory.len());
+        for (ctx, segment) in self.memory.iter() {
+            target.write_u32((*ctx).into());
+            segment.write_into(target);
+        }
+
+        target.write_usize(self.continuations.len());
+        target.write_many(&self.continuations);
+        target.write_many(&self.node_hashes);
+
+        self.advice.write_into(target);
+    }
+}
+
+impl Deserializable for ProcessSnapshot {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let magic: [u8; 4] = source.read_array()?;
+        if &magic != MAGIC {
+            return Err(DeserializationError::InvalidValue(format!(
+                "invalid magic bytes; expected {MAGIC:?}, but was {magic:?}"
+            )));
+        }
+
+        let version = source.read_u8()?;
+        if version != VERSION {
+            return Err(DeserializationError::InvalidValue(format!(
+                "unsupported snapshot format version; expected {VERSION}, but was {version}"
+            )));
+        }
+
+        let program_hash = Digest::read_from(source)?;
+        let clk = source.read_u32()?;
+        let ctx = source.read_u32()?.into();
+        let fmp = Felt::read_from(source)?;
+        let in_syscall = source.read_bool()?;
+        let fn_hash = Word::read_from(source)?;
+
+        let stack = FastStack::read_from(source)?;
+
+        let mut memory = BTreeMap::new();
+        let num_contexts = source.read_usize()?;
+        for _ in 0..num_contexts {
+            let ctx = source.read_u32()?.into();
+            memory.insert(ctx, BTreeMap::read_from(source)?);
+        }
+
+        let num_continuations = source.read_usize()?;
+        let continuations = source.read_many::<Continuation>(num_continuations)?;
+        let num_node_hashes = continuations.iter().filter_map(referenced_node).count();
+        let node_hashes = source.read_many::<Digest>(num_node_hashes)?;
+
+        let advice = AdviceInputs::read_from(source)?;
+
+        Ok(Self {
+            program_hash,
+            clk,
+            ctx,
+            fmp,
+            in_syscall,
+            fn_hash,
+            stack,
+            memory,
+            continuations,
+            node_hashes,
+            advice,
+        })
+    }
+}
+
+impl Serializable for Continuation {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        match self {
+            Continuation::StartNode(node_id) => {
+                target.write_u8(START_NODE);
+                node_id.write_into(target);
+            }
+            Continuation::StartDyn => target.write_u8(START_DYN),
+            Continuation::ResumeSpan(node_id, cursor) => {
+                target.write_u8(RESUME_SPAN);
+                node_id.write_into(target);
+                target.write_usize(cursor.batch_idx);
+                target.write_usize(cursor.op_idx);
+                target.write_usize(cursor.group_idx);
+                target.write_usize(cursor.next_group_idx);
+                target.write_usize(cursor.group_op_idx);
+                target.write_usize(cursor.num_noops);
+            }
+            Continuation::FinishLoop(body) => {
+                target.write_u8(FINISH_LOOP);
+                body.write_into(target);
+            }
+            Continuation::FinishCall(caller) => {
+                target.write_u8(FINISH_CALL);
+                target.write_u32(caller.ctx.into());
+                caller.fmp.write_into(target);
+                caller.fn_hash.write_into(target);
+                target.write_usize(caller.stack_floor);
+            }
+            Continuation::End => target.write_u8(END),
+        }
+    }
+}
+
+impl Deserializable for Continuation {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            START_NODE => Ok(Continuation::StartNode(MastNodeId::read_from(source)?)),
+            START_DYN => Ok(Continuation::StartDyn),
+            RESUME_SPAN => {
+                let node_id = MastNodeId::read_from(source)?;
+                let cursor = SpanCursor {
+                    batch_idx: source.read_usize()?,
+                    op_idx: source.read_usize()?,
+                    group_idx: source.read_usize()?,
+                    next_group_idx: source.read_usize()?,
+                    group_op_idx: source.read_usize()?,
+                    num_noops: source.read_usize()?,
+                };
+                Ok(Continuation::ResumeSpan(node_id, cursor))
+            }
+            FINISH_LOOP => Ok(Continuation::FinishLoop(MastNodeId::read_from(source)?)),
+            FINISH_CALL => {
+                let caller = CallerContext {
+                    ctx: source.read_u32()?.into(),
+                    fmp: Felt::read_from(source)?,
+                    fn_hash: Word::read_from(source)?,
+                    stack_floor: source.read_usize()?,
+                };
+                Ok(Continuation::FinishCall(caller))
+            }
+            END => Ok(Continuation::End),
+            tag => {
+                Err(DeserializationError::InvalidValue(format!("invalid continuation tag {tag}")))
+            }
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use super::{Digest, Felt, StackInputs, StackOutputs, Word, ZERO};
+use alloc::{string::ToString, vec::Vec};
+use vm_core::{
+    crypto::hash::Rpo256,
+    stack::STACK_TOP_SIZE,
+    utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable},
+    StarkField, WORD_SIZE,
+};
+
+// FAST STACK
+// ================================================================================================
+
+/// Operand stack of the [FastProcess](super::FastProcess).
+///
+/// All items of the stack, including the items in the overflow table, are kept in a single vector
+/// with the top of the stack at the end of the vector. Only the addresses of the overflow table
+/// rows (i.e., the clock cycles at which the items were moved into the overflow table) are
+/// tracked in addition to the values, since they are a part of the [StackOutputs].
+///
+/// The interface of this stack mirrors the interface of the [Stack](crate::stack::Stack) used by
+/// the tracing process: operations read the current state via `get()`, shift the stack via
+/// `shift_left()`/`shift_right()`, and then write their results via `set()`. Unlike the tracing
+/// stack, all updates are applied in place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FastStack {
+    items: Vec<Felt>,
+    overflow_addrs: Vec<Felt>,
+    ctx_floor: usize,
+}
+
+impl FastStack {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a [FastStack] initialized with the specified program inputs.
+    pub fn new(inputs: &StackInputs) -> Self {
+        let init_values = inputs.values();
+        let depth = core::cmp::max(STACK_TOP_SIZE, init_values.len());
+
+        let mut items = vec![ZERO; depth - init_values.len()];
+        items.extend(init_values.iter().rev());
+
+        // initial values in the overflow table use "negative" (mod p) clock cycles as addresses,
+        // the same way as the overflow table of the tracing stack does
+        let num_overflow = depth - STACK_TOP_SIZE;
+        let overflow_addrs = (0..num_overflow)
+            .map(|i| Felt::new(Felt::MODULUS - (num_overflow - i) as u64))
+            .collect();
+
+        Self {
+            items,
+            overflow_addrs,
+            ctx_floor: 0,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns depth of the stack in the current execution context.
+    pub fn depth(&self) -> usize {
+        self.items.len() - self.ctx_floor
+    }
+
+    /// Returns a copy of the item currently at the top of the stack.
+    pub fn peek(&self) -> Felt {
+        self.get(0)
+    }
+
+    /// Returns the value located at the specified position on the stack.
+    pub fn get(&self, pos: usize) -> Felt {
+        debug_assert!(pos < STACK_TOP_SIZE, "stack underflow");
+        self.items[self.items.len() - 1 - pos]
+    }
+
+    /// Returns a word located at the specified word index on the stack.
+    ///
+    /// The words are created in reverse order. For example, for word 0 the top element of the
+    /// stack will be at the last position in the word.
+    pub fn get_word(&self, word_idx: usize) -> Word {
+        let offset = word_idx * WORD_SIZE;
+        [
+            self.get(offset + 3),
+            self.get(offset + 2),
+            self.get(offset + 1),
+            self.get(offset),
+        ]
+    }
+
+    /// Returns all items on the stack (including the items in the overflow table) in stack order.
+    pub fn get_state(&self) -> Vec<Felt> {
+        self.items.iter().rev().copied().collect()
+    }
+
+    /// Returns [StackOutputs] consisting of all values on the stack and all addresses in the
+    /// overflow table that are required to rebuild the rows in the overflow table.
+    pub fn build_stack_outputs(&self) -> StackOutputs {
+        let overflow_addrs = if self.overflow_addrs.is_empty() {
+            Vec::new()
+        } else {
+            // the deepest row of the overflow table never has a previous row
+            let mut addrs = Vec::with_capacity(self.overflow_addrs.len() + 1);
+            addrs.push(ZERO);
+            addrs.extend_from_slice(&self.overflow_addrs);
+            addrs
+        };
+        StackOutputs::new(self.get_state(), overflow_addrs)
+            .expect("processor stack handling logic is valid")
+    }
+
+    /// Returns a commitment to the full state of the stack.
+    ///
+    /// The commitment is computed as a hash of the bottom of the stack in the current execution
+    /// context, all items on the stack (including the items in the overflow table) starting from
+    /// the deepest one, and the addresses of the overflow table rows.
+    pub fn commitment(&self) -> Digest {
+        let mut elements = Vec::with_capacity(1 + self.items.len() + self.overflow_addrs.len());
+        elements.push(Felt::new(self.ctx_floor as u64));
+        elements.extend_from_slice(&self.items);
+        elements.extend_from_slice(&self.overflow_addrs);
+        Rpo256::hash_elements(&elements)
+    }
+
+    // STATE MUTATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Sets the value at the specified position on the stack.
+    pub fn set(&mut self, pos: usize, value: Felt) {
+        debug_assert!(pos < STACK_TOP_SIZE, "stack underflow");
+        let idx = self.items.len() - 1 - pos;
+        self.items[idx] = value;
+    }
+
+    /// Removes the item at position `start_pos - 1`, shifting all items starting at the specified
+    /// position one slot to the left.
+    ///
+    /// If the stack depth is greater than 16, an item is moved from the overflow table to the
+    /// top 16 items of the stack. If the stack depth is 16, the 16th element of the stack is set
+    /// to ZERO.
+    pub fn shift_left(&mut self, start_pos: usize) {
+        debug_assert!(start_pos > 0, "start position must be greater than 0");
+        debug_assert!(start_pos <= STACK_TOP_SIZE, "start position cannot exceed stack top size");
+
+        let idx = self.items.len() - start_pos;
+        self.items.remove(idx);
+
+        if self.items.len() < self.ctx_floor + STACK_TOP_SIZE {
+            // shift in a ZERO to prevent depth shrinking below the minimum stack depth
+            self.items.insert(self.ctx_floor, ZERO);
+        } else {
+            self.overflow_addrs.pop();
+        }
+    }
+
+    /// Inserts a ZERO at the specified position, shifting all items starting at this position one
+    /// slot to the right. The inserted value is expected to be overwritten via `set()`.
+    ///
+    /// The item pushed beyond the top 16 items of the stack is moved into the overflow table; the
+    /// provided clock cycle is used as the address of the new overflow table row.
+    pub fn shift_right(&mut self, start_pos: usize, clk: u32) {
+        debug_assert!(start_pos < STACK_TOP_SIZE, "start position cannot exceed stack top size");
+
+        let idx = self.items.len() - start_pos;
+        self.items.insert(idx, ZERO);
+        self.overflow_addrs.push(Felt::from(clk));
+    }
+
+    // CONTEXT MANAGEMENT
+    // --------------------------------------------------------------------------------------------
+
+    /// Starts a new execution context for this stack and returns the floor of the previous
+    /// context.
+    ///
+    /// This has the effect of hiding the contents of the overflow table such that it appears as
+    /// if the overflow table in the new context is empty.
+    pub fn start_context(&mut self) -> usize {
+        let parent_floor = self.ctx_floor;
+        self.ctx_floor = self.items.len() - STACK_TOP_SIZE;
+        parent_floor
+    }
+
+    /// Restores the context with the specified floor.
+    ///
+    /// This has the effect bringing back items previously hidden from the overflow table.
+    pub fn restore_context(&mut self, parent_floor: usize) {
+        debug_assert_eq!(self.depth(), STACK_TOP_SIZE, "overflow table not empty");
+        self.ctx_floor = parent_floor;
+    }
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+impl Serializable for FastStack {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.items.write_into(target);
+        self.overflow_addrs.write_into(target);
+        target.write_usize(self.ctx_floor);
+    }
+}
+
+impl Deserializable for FastStack {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let items = Vec::<Felt>::read_from(source)?;
+        let overflow_addrs = Vec::<Felt>::read_from(source)?;
+        let ctx_floor = source.read_usize()?;
+
+        // every item beyond the top 16 items of the stack must have an overflow table address, and
+        // the current context must contain at least 16 items
+        if items.len() < STACK_TOP_SIZE
+            || ctx_floor > items.len() - STACK_TOP_SIZE
+            || overflow_addrs.len() != items.len() - STACK_TOP_SIZE
+        {
+            return Err(DeserializationError::InvalidValue(
+                "inconsistent operand stack state".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            items,
+            overflow_addrs,
+            ctx_floor,
+        })
+    }
+}
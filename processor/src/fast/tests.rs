@@ -0,0 +1,514 @@
+use super::{
+    super::{
+        execute, execute_fast, execute_segmented, execute_segments, AdviceInputs, DefaultHost,
+        ExecutionError, ExecutionOptions, MemAdviceProvider, StackInputs, StackOutputs,
+    },
+    Continuation, FastProcess, ProcessSnapshot, SegmentedExecution,
+};
+use crate::{crypto::MerkleStore, ProcessState};
+use alloc::vec::Vec;
+use miden_assembly::Assembler;
+use vm_core::{
+    crypto::merkle::MerkleTree,
+    utils::{Deserializable, Serializable},
+    Felt, Program, Word, ONE, ZERO,
+};
+
+// DIFFERENTIAL TESTS
+// ================================================================================================
+
+#[test]
+fn field_and_stack_ops() {
+    let source = "
+        begin
+            add mul neg inv add.1
+            dup.15 movup.8 movdn.5 swapw.3 swapdw push.1 cswap push.0 cswapw
+            eq eq.0 not push.1 and push.0 or
+            push.2 push.3 exp.u5
+            ext2mul
+        end";
+    assert_same_execution(source, &(1..=20).collect::<Vec<_>>(), AdviceInputs::default());
+
+    // deep stack with values moving in and out of the overflow table
+    let source = "
+        begin
+            repeat.20 dup.15 end
+            repeat.30 drop end
+            repeat.5 push.7 end
+            sdepth clk
+        end";
+    assert_same_execution(source, &(1..=30).collect::<Vec<_>>(), AdviceInputs::default());
+}
+
+#[test]
+fn u32_ops() {
+    let source = "
+        begin
+            push.18446744069414584320 u32split
+            push.5.7 u32overflowing_add
+            push.5.7.9 u32overflowing_add3
+            push.5.7 u32overflowing_sub
+            push.5.7 u32overflowing_mul
+            push.5.7.9 u32overflowing_madd
+            push.50.7 u32divmod
+            push.12.10 u32and
+            push.12.10 u32xor
+            push.1.2 u32assert2
+        end";
+    assert_same_execution(source, &[], AdviceInputs::default());
+}
+
+#[test]
+fn memory_ops_and_locals() {
+    let source = "
+        proc.foo.3
+            loc_store.0 loc_storew.1 loc_load.0 loc_loadw.1 locaddr.2
+        end
+
+        begin
+            push.10 mem_store.100 push.1.2.3.4 mem_storew.101 dropw
+            mem_load.100 padw mem_loadw.101
+            exec.foo
+            push.100 padw padw padw mem_stream
+        end";
+    assert_same_execution(source, &[1, 2, 3, 4, 5], AdviceInputs::default());
+}
+
+#[test]
+fn calls_and_contexts() {
+    let source = "
+        proc.foo
+            push.1 mem_store.0 mem_load.0
+            repeat.20 drop end
+            push.3.4 add swap drop
+        end
+
+        proc.bar
+            dropw push.5 add
+        end
+
+        begin
+            push.2 mem_store.0
+            push.18 call.foo
+            mem_load.0
+            padw procref.bar dyncall
+            procref.bar dynexec
+        end";
+    assert_same_execution(source, &(1..18).collect::<Vec<_>>(), AdviceInputs::default());
+}
+
+#[test]
+fn syscalls() {
+    let kernel = "
+        export.foo
+            caller push.1 mem_store.0 mem_load.0 add
+        end";
+    let source = "
+        proc.bar
+            syscall.foo
+        end
+
+        begin
+            syscall.foo call.bar
+        end";
+    let assembler = Assembler::default().with_kernel(kernel).unwrap();
+    let program = assembler.compile(source).unwrap();
+    assert_same_program_execution(&program, &[1, 2], AdviceInputs::default());
+}
+
+#[test]
+fn advice_and_crypto_ops() {
+    let leaves: Vec<Word> = (0..8).map(|i| [Felt::new(i), ZERO, ZERO, ONE]).collect();
+    let tree = MerkleTree::new(leaves.clone()).unwrap();
+    let root: Word = tree.root().into();
+    let advice_inputs = AdviceInputs::default()
+        .with_stack_values(0..16)
+        .unwrap()
+        .with_merkle_store(MerkleStore::from(&tree));
+
+    // the stack is expected to be [V, d, i, R, ...] with the leaf at index 5 on the top
+    let mut stack_inputs = Vec::new();
+    stack_inputs.extend(root.iter().map(|e| e.as_int()));
+    stack_inputs.extend([5, 3]);
+    stack_inputs.extend(leaves[5].iter().map(|e| e.as_int()));
+
+    let source = "
+        begin
+            mtree_verify
+            dropw push.9.8.7.6 movdn.9 movdn.9 movdn.9 movdn.9
+            mtree_set
+            adv_push.2 adv_loadw
+            push.200 padw padw padw adv_pipe
+            hperm
+        end";
+    assert_same_execution(source, &stack_inputs, advice_inputs);
+}
+
+#[test]
+fn recursive_verifier_ops() {
+    let source = "
+        begin
+            push.1.2.3.4 mem_storew.2 dropw push.5.6.7.8 mem_storew.3 dropw
+            rcomb_base
+        end";
+    assert_same_execution(source, &(1..=16).collect::<Vec<_>>(), AdviceInputs::default());
+
+    // the stack is expected to be [v7, ..., v0, f_pos, d_seg, poe, pe1, pe0, a1, a0, cptr, ...]
+    let source = "begin fri_ext2fold4 end";
+    let stack_inputs = [100, 50, 3, 4, 11, 12, 7, 0, 2, 11, 12, 13, 14, 15, 16, 17, 18];
+    assert_same_execution(source, &stack_inputs, AdviceInputs::default());
+}
+
+#[test]
+fn errors() {
+    // failed assertion with an error code
+    let source = "begin push.1 assert push.0 assert.err=123 end";
+    assert_same_error(source, &[], ExecutionOptions::default());
+
+    // division by zero reports the clock cycle of the failing operation
+    let source = "begin repeat.5 push.1 end push.0 div end";
+    assert_same_error(source, &[], ExecutionOptions::default());
+    let source = "begin push.5 push.0 u32div end";
+    assert_same_error(source, &[], ExecutionOptions::default());
+
+    // invalid values
+    let source = "begin push.4294967296 push.1 u32and end";
+    assert_same_error(source, &[], ExecutionOptions::default());
+    let source = "begin push.4294967296 push.1 u32assert2.err=7 end";
+    assert_same_error(source, &[], ExecutionOptions::default());
+    let source = "begin fri_ext2fold4 end";
+    assert_same_error(
+        source,
+        &[0, 0, 0, 0, 0, 0, 0, 5, 0, 1, 2, 3, 4, 5, 6, 7, 8],
+        ExecutionOptions::default(),
+    );
+    assert_same_error(
+        source,
+        &[0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8],
+        ExecutionOptions::default(),
+    );
+    let source = "begin push.2 if.true add end end";
+    assert_same_error(source, &[], ExecutionOptions::default());
+    let source = "begin push.4294967296 mem_load end";
+    assert_same_error(source, &[], ExecutionOptions::default());
+
+    // empty advice stack
+    let source = "begin adv_push.1 end";
+    assert_same_error(source, &[], ExecutionOptions::default());
+
+    // invalid stack depth on return from a call
+    let source = "proc.foo push.1 end begin call.foo end";
+    assert_same_error(source, &[], ExecutionOptions::default());
+
+    // cycle limit exceeded
+    let source = "begin push.1 while.true push.1 end end";
+    let options = ExecutionOptions::new(Some(100), 64, false).unwrap();
+    assert_same_error(source, &[], options);
+}
+
+// SNAPSHOT TESTS
+// ================================================================================================
+
+#[test]
+fn resume_from_snapshots() {
+    let program = build_snapshot_test_program();
+    let advice_inputs = AdviceInputs::default().with_stack_values([1, 2, 3, 4]).unwrap();
+    let stack_inputs = StackInputs::try_from_ints([1, 2, 3, 4]).unwrap();
+
+    let mut process = new_process(&program, stack_inputs.clone(), advice_inputs.clone());
+    let expected = process.execute(&program).unwrap();
+    let num_cycles = process.clk();
+
+    // pause the execution at every clock cycle, and resume it from a deserialized snapshot
+    for clk in 0..num_cycles {
+        let mut process = new_process(&program, stack_inputs.clone(), advice_inputs.clone());
+        assert_eq!(None, process.execute_until(&program, clk).unwrap());
+        assert_eq!(clk, process.clk());
+
+        let snapshot = process.snapshot(&program);
+        assert_eq!(clk, snapshot.clk());
+        assert!(!snapshot.is_finished());
+        let snapshot = ProcessSnapshot::read_from_bytes(&snapshot.to_bytes()).unwrap();
+
+        let mut process =
+            MemFastProcess::from_snapshot(snapshot, &program, ExecutionOptions::default()).unwrap();
+        assert_eq!(expected, process.execute(&program).unwrap());
+        assert_eq!(num_cycles, process.clk());
+    }
+
+    // pausing the execution repeatedly does not affect the outputs
+    let mut process = new_process(&program, stack_inputs.clone(), advice_inputs.clone());
+    let mut clk = 0;
+    let outputs = loop {
+        clk += 7;
+        if let Some(outputs) = process.execute_until(&program, clk).unwrap() {
+            break outputs;
+        }
+    };
+    assert_eq!(expected, outputs);
+
+    // execution which completes before the pause returns the outputs of the program
+    let mut process = new_process(&program, stack_inputs, advice_inputs);
+    assert_eq!(Some(expected), process.execute_until(&program, num_cycles + 1).unwrap());
+    assert!(process.snapshot(&program).is_finished());
+}
+
+#[test]
+fn invalid_snapshots() {
+    let program = Assembler::default().compile("begin push.1 push.2 add end").unwrap();
+    let other_program = Assembler::default().compile("begin push.1 push.3 add end").unwrap();
+
+    let mut process = new_process(&program, StackInputs::default(), AdviceInputs::default());
+    assert_eq!(None, process.execute_until(&program, 2).unwrap());
+    let snapshot = process.snapshot(&program);
+
+    // snapshots can be resumed only with the program they were taken from
+    let result = MemFastProcess::from_snapshot(
+        snapshot.clone(),
+        &other_program,
+        ExecutionOptions::default(),
+    );
+    assert!(matches!(result, Err(ExecutionError::InvalidSnapshot(_))));
+
+    // snapshots with invalid magic bytes or version are rejected
+    let mut bytes = snapshot.to_bytes();
+    bytes[0] = b'X';
+    assert!(ProcessSnapshot::read_from_bytes(&bytes).is_err());
+    let mut bytes = snapshot.to_bytes();
+    bytes[4] += 1;
+    assert!(ProcessSnapshot::read_from_bytes(&bytes).is_err());
+
+    // snapshots whose continuations reference code blocks with unexpected hashes are rejected
+    let program = Assembler::default()
+        .compile("begin push.1 if.true push.2 else push.3 end push.4 end")
+        .unwrap();
+    let mut process = new_process(&program, StackInputs::default(), AdviceInputs::default());
+    assert_eq!(None, process.execute_until(&program, 1).unwrap());
+    let mut snapshot = process.snapshot(&program);
+    let continuation = snapshot
+        .continuations
+        .iter_mut()
+        .find(|continuation| matches!(continuation, Continuation::StartNode(_)))
+        .unwrap();
+    *continuation = Continuation::StartNode(program.entrypoint());
+    let result = MemFastProcess::from_snapshot(snapshot, &program, ExecutionOptions::default());
+    assert!(matches!(result, Err(ExecutionError::InvalidSnapshot(_))));
+}
+
+// SEGMENTED EXECUTION TESTS
+// ================================================================================================
+
+#[test]
+fn segmented_execution() {
+    let program = build_snapshot_test_program();
+    let advice_inputs = AdviceInputs::default().with_stack_values([1, 2, 3, 4]).unwrap();
+    let stack_inputs = StackInputs::try_from_ints([1, 2, 3, 4]).unwrap();
+    let options = ExecutionOptions::default();
+
+    let mut process = new_process(&program, stack_inputs.clone(), advice_inputs.clone());
+    let expected = process.execute(&program).unwrap();
+    let num_cycles = process.clk();
+
+    // segments must be at least one cycle long
+    let result =
+        execute_segmented(&program, stack_inputs.clone(), advice_inputs.clone(), options, 0);
+    assert!(matches!(result, Err(ExecutionError::InvalidSegmentLength)));
+    let result =
+        execute_segments(&program, stack_inputs.clone(), advice_inputs.clone(), options, 0);
+    assert!(matches!(result, Err(ExecutionError::InvalidSegmentLength)));
+
+    for segment_len in [1, 5, 16, 64, num_cycles, num_cycles + 1] {
+        let execution = execute_segmented(
+            &program,
+            stack_inputs.clone(),
+            advice_inputs.clone(),
+            options,
+            segment_len,
+        )
+        .unwrap();
+        assert_eq!(&expected, execution.stack_outputs());
+        assert_eq!(num_cycles, execution.num_cycles());
+
+        // all segments but the last one have the specified length, and each segment starts at
+        // the boundary at which the previous one ends
+        let segments = execution.segments();
+        assert_eq!(num_cycles.div_ceil(segment_len) as usize, segments.len());
+        for (i, segment) in segments.iter().enumerate() {
+            assert_eq!(i as u32 * segment_len, segment.start().clk());
+            if i < segments.len() - 1 {
+                assert_eq!(segment_len, segment.num_cycles());
+                assert_eq!(segment.end(), segments[i + 1].start());
+                assert!(!segment.end().is_final());
+            } else {
+                assert!(segment.end().is_final());
+            }
+        }
+
+        // executing the segments one at a time yields the same segments
+        let mut iter = execute_segments(
+            &program,
+            stack_inputs.clone(),
+            advice_inputs.clone(),
+            options,
+            segment_len,
+        )
+        .unwrap();
+        for segment in segments {
+            assert_eq!(None, iter.stack_outputs());
+            let streamed = iter.next().unwrap().unwrap();
+            assert_eq!(segment.start(), streamed.start());
+            assert_eq!(segment.end(), streamed.end());
+        }
+        assert!(iter.next().is_none());
+        assert_eq!(Some(&expected), iter.stack_outputs());
+
+        execution.verify(&program, stack_inputs.clone(), options).unwrap();
+
+        // segments survive a serialization round trip
+        let bytes = execution.to_bytes();
+        let execution = SegmentedExecution::read_from_bytes(&bytes).unwrap();
+        assert_eq!(&expected, execution.stack_outputs());
+        execution.verify_links(&program, stack_inputs.clone(), options).unwrap();
+    }
+}
+
+#[test]
+fn invalid_segmented_executions() {
+    let program = build_snapshot_test_program();
+    let advice_inputs = AdviceInputs::default().with_stack_values([1, 2, 3, 4]).unwrap();
+    let stack_inputs = StackInputs::try_from_ints([1, 2, 3, 4]).unwrap();
+    let options = ExecutionOptions::default();
+
+    let execution =
+        execute_segmented(&program, stack_inputs.clone(), advice_inputs.clone(), options, 32)
+            .unwrap();
+    let segments = execution.segments().to_vec();
+    assert!(segments.len() > 3);
+
+    // segments which are not linked with each other are rejected
+    let mut swapped = segments.clone();
+    swapped.swap(1, 2);
+    let invalid = SegmentedExecution {
+        segments: swapped,
+        stack_outputs: execution.stack_outputs().clone(),
+    };
+    let result = invalid.verify_links(&program, stack_inputs.clone(), options);
+    assert!(matches!(result, Err(ExecutionError::InvalidSegment(1, _))));
+
+    // an execution which does not start at the initial state of the program is rejected
+    let other_inputs = StackInputs::try_from_ints([5, 6, 7, 8]).unwrap();
+    let result = execution.verify_links(&program, other_inputs, options);
+    assert!(matches!(result, Err(ExecutionError::InvalidSegment(0, _))));
+
+    // a segment which does not end at its boundary is rejected
+    let other_advice = AdviceInputs::default().with_stack_values([5, 6, 7, 8]).unwrap();
+    let other =
+        execute_segmented(&program, stack_inputs.clone(), other_advice, options, 32).unwrap();
+    let last = segments.len() - 1;
+    let mut modified = segments.clone();
+    modified[last].end = other.segments()[last].end();
+    assert_ne!(segments[last].end(), modified[last].end());
+    let invalid = SegmentedExecution {
+        segments: modified,
+        stack_outputs: execution.stack_outputs().clone(),
+    };
+    let result = invalid.verify_segment(last, &program, options);
+    assert!(matches!(result, Err(ExecutionError::InvalidSegment(index, _)) if index == last));
+
+    // an execution with incorrect outputs is rejected at the last segment
+    let invalid = SegmentedExecution {
+        segments,
+        stack_outputs: StackOutputs::new(vec![ZERO; 16], Vec::new()).unwrap(),
+    };
+    invalid.verify_links(&program, stack_inputs, options).unwrap();
+    let result = invalid.verify_segment(last, &program, options);
+    assert!(matches!(result, Err(ExecutionError::InvalidSegment(index, _)) if index == last));
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+type MemFastProcess = FastProcess<DefaultHost<MemAdviceProvider>>;
+
+/// Returns a program which exercises all kinds of code blocks, as well as calls into the kernel,
+/// memory accesses and non-deterministic inputs.
+fn build_snapshot_test_program() -> Program {
+    let source = "
+        proc.foo.2
+            loc_storew.0 dropw push.10 mem_store.1
+            adv_push.2 add
+            push.1 while.true push.1 sub dup neq.0 end
+        end
+
+        proc.bar
+            syscall.baz procref.foo dynexec dropw dropw dropw dropw
+        end
+
+        begin
+            repeat.20 push.7 end
+            push.0.100.0.7 adv.push_u64div adv_push.4 dropw dropw
+            push.1 if.true exec.foo else push.2 end
+            call.bar
+            push.3 push.0 if.true drop else mul end
+            dropw dropw dropw
+        end";
+    let kernel = "
+        export.baz
+            push.5 mem_store.0 mem_load.0 add
+        end";
+    Assembler::default().with_kernel(kernel).unwrap().compile(source).unwrap()
+}
+
+fn new_process(
+    program: &Program,
+    stack_inputs: StackInputs,
+    advice_inputs: AdviceInputs,
+) -> MemFastProcess {
+    let host = DefaultHost::new(MemAdviceProvider::from(advice_inputs));
+    FastProcess::new(program.kernel().clone(), stack_inputs, host, ExecutionOptions::default())
+}
+
+/// Compiles the provided source, executes it using both the tracing and the fast executors, and
+/// asserts that both executions succeed with the same outputs.
+fn assert_same_execution(source: &str, stack_inputs: &[u64], advice_inputs: AdviceInputs) {
+    let program = Assembler::default().compile(source).unwrap();
+    assert_same_program_execution(&program, stack_inputs, advice_inputs);
+}
+
+fn assert_same_program_execution(
+    program: &Program,
+    stack_inputs: &[u64],
+    advice_inputs: AdviceInputs,
+) {
+    let (expected, result) =
+        execute_both(program, stack_inputs, advice_inputs, ExecutionOptions::default());
+    assert!(expected.is_ok(), "execution failed: {expected:?}");
+    assert_eq!(expected, result);
+}
+
+/// Compiles the provided source, executes it using both the tracing and the fast executors, and
+/// asserts that both executions fail with the same error.
+fn assert_same_error(source: &str, stack_inputs: &[u64], options: ExecutionOptions) {
+    let program = Assembler::default().compile(source).unwrap();
+    let (expected, result) = execute_both(&program, stack_inputs, AdviceInputs::default(), options);
+    assert!(expected.is_err(), "execution did not fail");
+    assert_eq!(expected, result);
+}
+
+fn execute_both(
+    program: &Program,
+    stack_inputs: &[u64],
+    advice_inputs: AdviceInputs,
+    options: ExecutionOptions,
+) -> (Result<StackOutputs, ExecutionError>, Result<StackOutputs, ExecutionError>) {
+    let stack_inputs = StackInputs::try_from_ints(stack_inputs.iter().copied()).unwrap();
+
+    let host = DefaultHost::new(MemAdviceProvider::from(advice_inputs.clone()));
+    let expected = execute(program, stack_inputs.clone(), host, options)
+        .map(|trace| trace.stack_outputs().clone());
+
+    let host = DefaultHost::new(MemAdviceProvider::from(advice_inputs));
+    let result = execute_fast(program, stack_inputs, host, options);
+
+    (expected, result)
+}
@@ -1,6 +1,9 @@
 use super::{AdviceMap, Felt, InnerNodeInfo, InputError, MerkleStore};
 use alloc::vec::Vec;
-use vm_core::crypto::hash::RpoDigest;
+use vm_core::{
+    crypto::hash::RpoDigest,
+    utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable},
+};
 
 // ADVICE INPUTS
 // ================================================================================================
@@ -130,6 +133,23 @@ impl AdviceInputs {
     }
 }
 
+impl Serializable for AdviceInputs {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.stack.write_into(target);
+        self.map.write_into(target);
+        self.store.write_into(target);
+    }
+}
+
+impl Deserializable for AdviceInputs {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let stack = Vec::<Felt>::read_from(source)?;
+        let map = AdviceMap::read_from(source)?;
+        let store = MerkleStore::read_from(source)?;
+        Ok(Self { stack, map, store })
+    }
+}
+
 // INTERNALS
 // ================================================================================================
 
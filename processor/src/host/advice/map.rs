@@ -2,7 +2,10 @@ use super::Felt;
 use alloc::collections::btree_map::IntoIter;
 use alloc::collections::BTreeMap;
 use alloc::vec::Vec;
-use vm_core::crypto::hash::RpoDigest;
+use vm_core::{
+    crypto::hash::RpoDigest,
+    utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable},
+};
 
 // ADVICE MAP
 // ================================================================================================
@@ -57,3 +60,15 @@ impl Extend<(RpoDigest, Vec<Felt>)> for AdviceMap {
         self.0.extend(iter)
     }
 }
+
+impl Serializable for AdviceMap {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.0.write_into(target);
+    }
+}
+
+impl Deserializable for AdviceMap {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        Ok(Self(BTreeMap::read_from(source)?))
+    }
+}
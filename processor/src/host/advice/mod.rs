@@ -805,3 +805,18 @@ where
         T::get_store_subset(self, roots)
     }
 }
+
+// SNAPSHOT ADVICE PROVIDER
+// ================================================================================================
+
+/// Defines an advice provider whose state can be captured into [AdviceInputs] and restored from
+/// them.
+///
+/// Processes can be saved into snapshots (see [crate::ProcessSnapshot]) only if their hosts use
+/// such advice providers. [MemAdviceProvider] implements this trait; [RecAdviceProvider] does not,
+/// as the records of the data it provided cannot be restored from [AdviceInputs].
+pub trait SnapshotAdviceProvider: AdviceProvider + From<AdviceInputs> {
+    /// Returns [AdviceInputs] describing the current state of the advice provider, such that a
+    /// provider instantiated from these inputs is identical to this provider.
+    fn to_inputs(&self) -> AdviceInputs;
+}
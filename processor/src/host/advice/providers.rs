@@ -2,7 +2,7 @@ use crate::ProcessState;
 
 use super::{
     injectors, AdviceInputs, AdviceProvider, AdviceSource, ExecutionError, Felt, MerklePath,
-    MerkleStore, NodeIndex, RpoDigest, StoreNode, Word,
+    MerkleStore, NodeIndex, RpoDigest, SnapshotAdviceProvider, StoreNode, Word,
 };
 use alloc::collections::BTreeMap;
 use alloc::vec::Vec;
@@ -327,6 +327,16 @@ impl AdviceProvider for MemAdviceProvider {
 
 }
 
+impl SnapshotAdviceProvider for MemAdviceProvider {
+    fn to_inputs(&self) -> AdviceInputs {
+        let BaseAdviceProvider { stack, map, store } = &self.provider;
+        AdviceInputs::default()
+            .with_stack(stack.iter().rev().copied())
+            .with_map(map.clone())
+            .with_merkle_store(store.clone())
+    }
+}
+
 impl MemAdviceProvider {
     // FINALIZATION
     // --------------------------------------------------------------------------------------------
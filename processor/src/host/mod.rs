@@ -1,5 +1,6 @@
 use super::{ExecutionError, Felt, ProcessState};
 use crate::MemAdviceProvider;
+use alloc::{string::String, vec::Vec};
 use vm_core::{crypto::merkle::MerklePath, AdviceInjector, DebugOptions, Word};
 
 pub(super) mod advice;
@@ -99,14 +100,29 @@ pub trait Host {
     }
 
     /// Handles the failure of the assertion instruction.
+    ///
+    /// The returned error includes the message for the error code if the executed program has one.
     fn on_assert_failed<S: ProcessState>(&mut self, process: &S, err_code: u32) -> ExecutionError {
         ExecutionError::FailedAssertion {
             clk: process.clk(),
             err_code,
-            err_msg: None,
+            err_msg: process.get_error_message(err_code).map(String::from),
         }
     }
 
+    /// Inserts the specified values into the advice map under the specified key.
+    ///
+    /// This is used to provide the data segments of a program to the program before it is
+    /// executed. The default implementation returns an error; thus, hosts which do not override it
+    /// cannot execute programs with data segments.
+    fn insert_into_adv_map(
+        &mut self,
+        _key: Word,
+        _values: Vec<Felt>,
+    ) -> Result<(), ExecutionError> {
+        Err(ExecutionError::DataSegmentsNotSupported)
+    }
+
     /// Pops an element from the advice stack and returns it.
     ///
     /// # Errors
@@ -209,6 +225,10 @@ where
     fn on_assert_failed<S: ProcessState>(&mut self, process: &S, err_code: u32) -> ExecutionError {
         H::on_assert_failed(self, process, err_code)
     }
+
+    fn insert_into_adv_map(&mut self, key: Word, values: Vec<Felt>) -> Result<(), ExecutionError> {
+        H::insert_into_adv_map(self, key, values)
+    }
 }
 
 // HOST RESPONSE
@@ -281,7 +301,6 @@ impl<A: AdviceProvider> DefaultHost<A> {
         Self { adv_provider }
     }
 
-    #[cfg(any(test, feature = "internals"))]
     pub fn advice_provider(&self) -> &A {
         &self.adv_provider
     }
@@ -312,4 +331,8 @@ impl<A: AdviceProvider> Host for DefaultHost<A> {
     ) -> Result<HostResponse, ExecutionError> {
         self.adv_provider.set_advice(process, &injector)
     }
+
+    fn insert_into_adv_map(&mut self, key: Word, values: Vec<Felt>) -> Result<(), ExecutionError> {
+        self.adv_provider.insert_into_map(key, values)
+    }
 }
@@ -6,7 +6,7 @@ extern crate std;
 #[macro_use]
 extern crate alloc;
 
-use alloc::vec::Vec;
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
 use core::cell::RefCell;
 
 use miden_air::trace::{
@@ -16,14 +16,16 @@ use miden_air::trace::{
 pub use miden_air::{ExecutionOptions, ExecutionOptionsError};
 pub use vm_core::{
     chiplets::hasher::Digest, crypto::merkle::SMT_DEPTH, errors::InputError,
-    utils::DeserializationError, AdviceInjector, AssemblyOp, Felt, Kernel, Operation, Program,
-    ProgramInfo, QuadExtension, StackInputs, StackOutputs, Word, EMPTY_WORD, ONE, ZERO,
+    utils::DeserializationError, AdviceInjector, AssemblyOp, CodeLocation, Felt, Kernel,
+    MastSerdeOptions, Operation, Program, ProgramInfo, QuadExtension, StackInputs, StackOutputs,
+    Word, EMPTY_WORD, ONE, ZERO,
 };
 use vm_core::{
     code_blocks::{
         Call, CodeBlock, Dyn, Join, Loop, OpBatch, Span, Split, OP_BATCH_SIZE, OP_GROUP_SIZE,
     },
-    CodeBlockTable, Decorator, DecoratorIterator, FieldElement, StackTopState,
+    Decorator, DecoratorIterator, FieldElement, JoinNode, LoopNode, MastNode, MastNodeId,
+    SpanLocations, SplitNode, StackTopState,
 };
 
 pub use winter_prover::matrix::ColMatrix;
@@ -47,7 +49,7 @@ mod host;
 pub use host::{
     advice::{
         AdviceExtractor, AdviceInputs, AdviceMap, AdviceProvider, AdviceSource, MemAdviceProvider,
-        RecAdviceProvider,
+        RecAdviceProvider, SnapshotAdviceProvider,
     },
     DefaultHost, Host, HostResponse,
 };
@@ -67,6 +69,15 @@ pub mod utils;
 mod debug;
 pub use debug::{AsmOpInfo, VmState, VmStateIterator};
 
+mod coverage;
+pub use coverage::{ExecutionCoverage, LineCoverage};
+
+mod fast;
+pub use fast::{
+    ExecutionSegment, ExecutionSegments, FastProcess, ProcessSnapshot, SegmentBoundary,
+    SegmentedExecution,
+};
+
 // RE-EXPORTS
 // ================================================================================================
 
@@ -135,6 +146,68 @@ where
     Ok(trace)
 }
 
+/// Returns the outputs resulting from executing the provided program against the provided inputs.
+///
+/// Unlike [execute()], this does not build an execution trace: the program is executed by a
+/// lightweight interpreter which keeps track of the current state of the VM only. The program is
+/// executed with the same semantics, including interactions with the host and the accounting of
+/// clock cycles, and thus, the outputs and errors are the same as those of [execute()].
+#[tracing::instrument("execute_program_fast", skip_all)]
+pub fn execute_fast<H>(
+    program: &Program,
+    stack_inputs: StackInputs,
+    host: H,
+    options: ExecutionOptions,
+) -> Result<StackOutputs, ExecutionError>
+where
+    H: Host,
+{
+    let mut process = FastProcess::new(program.kernel().clone(), stack_inputs, host, options);
+    process.execute(program)
+}
+
+/// Executes the provided program against the provided inputs, splitting the execution into
+/// segments of at most `segment_len` clock cycles each.
+///
+/// The program is executed in the same way as in [execute_fast()]. At the end of every segment,
+/// the state of the VM is captured in a [ProcessSnapshot], from which the next segment starts,
+/// and is committed to via a [SegmentBoundary]. This allows checking the segments independently
+/// of each other, while the boundaries link the segments into a single execution of the program
+/// (see [SegmentedExecution]).
+///
+/// # Errors
+/// Returns an error if `segment_len` is zero, or if the execution of the program fails.
+#[tracing::instrument("execute_program_segmented", skip_all)]
+pub fn execute_segmented(
+    program: &Program,
+    stack_inputs: StackInputs,
+    advice_inputs: AdviceInputs,
+    options: ExecutionOptions,
+    segment_len: u32,
+) -> Result<SegmentedExecution, ExecutionError> {
+    SegmentedExecution::execute(program, stack_inputs, advice_inputs, options, segment_len)
+}
+
+/// Returns an iterator which executes the provided program against the provided inputs one
+/// segment at a time, where every segment is at most `segment_len` clock cycles long.
+///
+/// Segments are the same as the ones produced by [execute_segmented()], but every segment is
+/// yielded as soon as it is executed, and is not retained by the iterator (see
+/// [ExecutionSegments]).
+///
+/// # Errors
+/// Returns an error if `segment_len` is zero, or if the execution of the program could not be
+/// started.
+pub fn execute_segments(
+    program: &Program,
+    stack_inputs: StackInputs,
+    advice_inputs: AdviceInputs,
+    options: ExecutionOptions,
+    segment_len: u32,
+) -> Result<ExecutionSegments<'_>, ExecutionError> {
+    ExecutionSegments::new(program, stack_inputs, advice_inputs, options, segment_len)
+}
+
 /// Returns an iterator which allows callers to step through the execution and inspect VM state at
 /// each execution step.
 pub fn execute_iter<H>(program: &Program, stack_inputs: StackInputs, host: H) -> VmStateIterator
@@ -169,6 +242,8 @@ where
     host: RefCell<H>,
     max_cycles: u32,
     enable_tracing: bool,
+    error_messages: BTreeMap<u32, String>,
+    coverage: Option<ExecutionCoverage>,
 }
 
 impl<H> Process<H>
@@ -204,15 +279,21 @@ where
         execution_options: ExecutionOptions,
     ) -> Self {
         let in_debug_mode = execution_options.enable_debugging();
+        let chiplets = Chiplets::new(kernel);
+        let mut decoder = Decoder::new(in_debug_mode);
+        decoder.append_chiplets_lengths(ChipletsLengths::new(&chiplets));
+
         Self {
             system: System::new(execution_options.expected_cycles() as usize),
-            decoder: Decoder::new(in_debug_mode),
+            decoder,
             stack: Stack::new(&stack, execution_options.expected_cycles() as usize, in_debug_mode),
             range: RangeChecker::new(),
-            chiplets: Chiplets::new(kernel),
+            chiplets,
             host: RefCell::new(host),
             max_cycles: execution_options.max_cycles(),
             enable_tracing: execution_options.enable_tracing(),
+            error_messages: BTreeMap::new(),
+            coverage: execution_options.enable_coverage().then(ExecutionCoverage::new),
         }
     }
 
@@ -220,9 +301,18 @@ where
     // --------------------------------------------------------------------------------------------
 
     /// Executes the provided [Program] in this process.
+    ///
+    /// The error messages of the program are made available to the host via
+    /// [ProcessState::get_error_message()], and the data segments of the program are inserted into
+    /// the advice map of the host, from which the program loads them into memory.
     pub fn execute(&mut self, program: &Program) -> Result<StackOutputs, ExecutionError> {
         assert_eq!(self.system.clk(), 0, "a program has already been executed in this process");
-        self.execute_code_block(program.root(), program.cb_table())?;
+        self.error_messages = program.error_messages().clone();
+        for segment in program.data_segments() {
+            let key = segment.commitment().into();
+            self.host.borrow_mut().insert_into_adv_map(key, segment.padded_values())?;
+        }
+        self.execute_mast_node(program.entrypoint(), program)?;
 
         Ok(self.stack.build_stack_outputs())
     }
@@ -230,94 +320,116 @@ where
     // CODE BLOCK EXECUTORS
     // --------------------------------------------------------------------------------------------
 
-    /// Executes the specified [CodeBlock].
+    /// Executes the [MastNode] with the specified ID in the MAST forest of the provided program.
     ///
     /// # Errors
-    /// Returns an [ExecutionError] if executing the specified block fails for any reason.
-    fn execute_code_block(
+    /// Returns an [ExecutionError] if executing the specified node fails for any reason.
+    fn execute_mast_node(
         &mut self,
-        block: &CodeBlock,
-        cb_table: &CodeBlockTable,
+        node_id: MastNodeId,
+        program: &Program,
     ) -> Result<(), ExecutionError> {
-        match block {
-            CodeBlock::Join(block) => self.execute_join_block(block, cb_table),
-            CodeBlock::Split(block) => self.execute_split_block(block, cb_table),
-            CodeBlock::Loop(block) => self.execute_loop_block(block, cb_table),
-            CodeBlock::Call(block) => self.execute_call_block(block, cb_table),
-            CodeBlock::Dyn(block) => self.execute_dyn_block(block, cb_table),
-            CodeBlock::Span(block) => self.execute_span_block(block),
-            CodeBlock::Proxy(_) => Err(ExecutionError::UnexecutableCodeBlock(block.clone())),
+        let node = program.get_node(node_id);
+        if let Some(coverage) = self.coverage.as_mut() {
+            coverage.record_block(node.hash());
+        }
+
+        match node {
+            MastNode::Join(node) => self.execute_join_node(node, program),
+            MastNode::Split(node) => self.execute_split_node(node, program),
+            MastNode::Loop(node) => self.execute_loop_node(node, program),
+            MastNode::Call(block) => self.execute_call_block(block, program),
+            MastNode::Dyn(block) => self.execute_dyn_block(block, program),
+            MastNode::Span(block) => self.execute_span_block(block, program),
+            MastNode::Proxy(block) => {
+                Err(ExecutionError::UnexecutableCodeBlock(CodeBlock::Proxy(block.clone())))
+            }
+        }
+    }
+
+    /// Records the evaluation of the condition of the SPLIT or LOOP block with the specified hash,
+    /// if coverage is collected.
+    #[inline(always)]
+    fn record_branch(&mut self, block_hash: Digest, taken: bool) {
+        if let Some(coverage) = self.coverage.as_mut() {
+            coverage.record_branch(block_hash, taken);
         }
     }
 
-    /// Executes the specified [Join] block.
+    /// Executes the specified JOIN node.
     #[inline(always)]
-    fn execute_join_block(
+    fn execute_join_node(
         &mut self,
-        block: &Join,
-        cb_table: &CodeBlockTable,
+        node: &JoinNode,
+        program: &Program,
     ) -> Result<(), ExecutionError> {
-        self.start_join_block(block)?;
+        self.start_join_block(node, program)?;
 
         // execute first and then second child of the join block
-        self.execute_code_block(block.first(), cb_table)?;
-        self.execute_code_block(block.second(), cb_table)?;
+        self.execute_mast_node(node.first(), program)?;
+        self.execute_mast_node(node.second(), program)?;
 
-        self.end_join_block(block)
+        self.end_join_block(node)
     }
 
-    /// Executes the specified [Split] block.
+    /// Executes the specified SPLIT node.
     #[inline(always)]
-    fn execute_split_block(
+    fn execute_split_node(
         &mut self,
-        block: &Split,
-        cb_table: &CodeBlockTable,
+        node: &SplitNode,
+        program: &Program,
     ) -> Result<(), ExecutionError> {
         // start the SPLIT block; this also pops the stack and returns the popped element
-        let condition = self.start_split_block(block)?;
+        let condition = self.start_split_block(node, program)?;
 
         // execute either the true or the false branch of the split block based on the condition
         if condition == ONE {
-            self.execute_code_block(block.on_true(), cb_table)?;
+            self.record_branch(node.hash(), true);
+            self.execute_mast_node(node.on_true(), program)?;
         } else if condition == ZERO {
-            self.execute_code_block(block.on_false(), cb_table)?;
+            self.record_branch(node.hash(), false);
+            self.execute_mast_node(node.on_false(), program)?;
         } else {
             return Err(ExecutionError::NotBinaryValue(condition));
         }
 
-        self.end_split_block(block)
+        self.end_split_block(node)
     }
 
-    /// Executes the specified [Loop] block.
+    /// Executes the specified LOOP node.
     #[inline(always)]
-    fn execute_loop_block(
+    fn execute_loop_node(
         &mut self,
-        block: &Loop,
-        cb_table: &CodeBlockTable,
+        node: &LoopNode,
+        program: &Program,
     ) -> Result<(), ExecutionError> {
         // start the LOOP block; this also pops the stack and returns the popped element
-        let condition = self.start_loop_block(block)?;
+        let condition = self.start_loop_block(node, program)?;
 
         // if the top of the stack is ONE, execute the loop body; otherwise skip the loop body
         if condition == ONE {
             // execute the loop body at least once
-            self.execute_code_block(block.body(), cb_table)?;
+            self.record_branch(node.hash(), true);
+            self.execute_mast_node(node.body(), program)?;
 
             // keep executing the loop body until the condition on the top of the stack is no
             // longer ONE; each iteration of the loop is preceded by executing REPEAT operation
             // which drops the condition from the stack
             while self.stack.peek() == ONE {
+                self.record_branch(node.hash(), true);
                 self.decoder.repeat();
                 self.execute_op(Operation::Drop)?;
-                self.execute_code_block(block.body(), cb_table)?;
+                self.execute_mast_node(node.body(), program)?;
             }
 
             // end the LOOP block and drop the condition from the stack
-            self.end_loop_block(block, true)
+            self.record_branch(node.hash(), false);
+            self.end_loop_block(node, true)
         } else if condition == ZERO {
             // end the LOOP block, but don't drop the condition from the stack because it was
             // already dropped when we started the LOOP block
-            self.end_loop_block(block, false)
+            self.record_branch(node.hash(), false);
+            self.end_loop_block(node, false)
         } else {
             Err(ExecutionError::NotBinaryValue(condition))
         }
@@ -328,7 +440,7 @@ where
     fn execute_call_block(
         &mut self,
         block: &Call,
-        cb_table: &CodeBlockTable,
+        program: &Program,
     ) -> Result<(), ExecutionError> {
         // if this is a syscall, make sure the call target exists in the kernel
         if block.is_syscall() {
@@ -339,13 +451,14 @@ where
 
         // if this is a dyncall, execute the dynamic code block
         if block.fn_hash() == Dyn::dyn_hash() {
-            self.execute_dyn_block(&Dyn::new(), cb_table)?;
+            self.execute_dyn_block(&Dyn::new(), program)?;
         } else {
             // get function body from the code block table and execute it
-            let fn_body = cb_table
+            let fn_body = program
+                .cb_table()
                 .get(block.fn_hash())
                 .ok_or_else(|| ExecutionError::CodeBlockNotFound(block.fn_hash()))?;
-            self.execute_code_block(fn_body, cb_table)?;
+            self.execute_mast_node(fn_body, program)?;
         }
 
         self.end_call_block(block)
@@ -353,35 +466,46 @@ where
 
     /// Executes the specified [Dyn] block.
     #[inline(always)]
-    fn execute_dyn_block(
-        &mut self,
-        block: &Dyn,
-        cb_table: &CodeBlockTable,
-    ) -> Result<(), ExecutionError> {
+    fn execute_dyn_block(&mut self, block: &Dyn, program: &Program) -> Result<(), ExecutionError> {
         // get target hash from the stack
         let dyn_hash = self.stack.get_word(0);
         self.start_dyn_block(block, dyn_hash)?;
 
         // get dynamic code from the code block table and execute it
         let dyn_digest = dyn_hash.into();
-        let dyn_code = cb_table
+        let dyn_code = program
+            .cb_table()
             .get(dyn_digest)
             .ok_or_else(|| ExecutionError::DynamicCodeBlockNotFound(dyn_digest))?;
-        self.execute_code_block(dyn_code, cb_table)?;
+        self.execute_mast_node(dyn_code, program)?;
 
         self.end_dyn_block(block)
     }
 
     /// Executes the specified [Span] block.
     #[inline(always)]
-    fn execute_span_block(&mut self, block: &Span) -> Result<(), ExecutionError> {
+    ///
+    /// If the source map of the program contains locations of the block operations, errors
+    /// raised while executing the block are annotated with the location of the failing operation.
+    fn execute_span_block(
+        &mut self,
+        block: &Span,
+        program: &Program,
+    ) -> Result<(), ExecutionError> {
         self.start_span_block(block)?;
 
         let mut op_offset = 0;
         let mut decorators = block.decorator_iter();
+        let locations = program.source_map().get_span(block.hash());
 
         // execute the first operation batch
-        self.execute_op_batch(&block.op_batches()[0], &mut decorators, op_offset)?;
+        self.execute_op_batch(
+            block,
+            &block.op_batches()[0],
+            &mut decorators,
+            op_offset,
+            locations,
+        )?;
         op_offset += block.op_batches()[0].ops().len();
 
         // if the span contains more operation batches, execute them. each additional batch is
@@ -390,10 +514,13 @@ where
         for op_batch in block.op_batches().iter().skip(1) {
             self.respan(op_batch);
             self.execute_op(Operation::Noop)?;
-            self.execute_op_batch(op_batch, &mut decorators, op_offset)?;
+            self.execute_op_batch(block, op_batch, &mut decorators, op_offset, locations)?;
             op_offset += op_batch.ops().len();
         }
 
+        if locations.is_some() && self.decoder.in_debug_mode() {
+            self.decoder.append_source_location(self.system.clk(), None);
+        }
         self.end_span_block(block)?;
 
         // execute any decorators which have not been executed during span ops execution; this
@@ -413,12 +540,19 @@ where
     ///   executed after it.
     /// - If the number of groups in a batch is not a power of 2, NOOPs are executed (one per
     ///   group) to bring it up to the next power of two (e.g., 3 -> 4, 5 -> 8).
+    ///
+    /// If source locations of the operations are provided, errors raised by the operations (and
+    /// their decorators) are annotated with the locations, and in debug mode, the locations are
+    /// recorded by the decoder. If coverage is collected, executions of the operations are recorded
+    /// under the hash of the SPAN block the batch belongs to.
     #[inline(always)]
     fn execute_op_batch(
         &mut self,
+        block: &Span,
         batch: &OpBatch,
         decorators: &mut DecoratorIterator,
         op_offset: usize,
+        locations: Option<&SpanLocations>,
     ) -> Result<(), ExecutionError> {
         let op_counts = batch.op_counts();
         let mut op_idx = 0;
@@ -432,13 +566,23 @@ where
 
         // execute operations in the batch one by one
         for (i, &op) in batch.ops().iter().enumerate() {
+            let location = locations.and_then(|locations| locations.get(i + op_offset));
+            if locations.is_some() && self.decoder.in_debug_mode() {
+                self.decoder.append_source_location(self.system.clk(), location);
+            }
+
             while let Some(decorator) = decorators.next_filtered(i + op_offset) {
-                self.execute_decorator(decorator)?;
+                self.execute_decorator(decorator)
+                    .map_err(|err| err.with_source_location(location))?;
+            }
+
+            if let Some(coverage) = self.coverage.as_mut() {
+                coverage.record_op(block.hash(), i + op_offset);
             }
 
             // decode and execute the operation
             self.decoder.execute_user_op(op, op_idx);
-            self.execute_op(op)?;
+            self.execute_op(op).map_err(|err| err.with_source_location(location))?;
 
             // if the operation carries an immediate value, the value is stored at the next group
             // pointer; so, we advance the pointer to the following group
@@ -582,6 +726,13 @@ pub trait ProcessState {
     /// The state is returned as a vector of (address, value) tuples, and includes addresses which
     /// have been accessed at least once.
     fn get_mem_state(&self, ctx: ContextId) -> Vec<(u64, Word)>;
+
+    /// Returns the error message for the specified error code, if the executed program has one.
+    ///
+    /// By default, no error messages are available.
+    fn get_error_message(&self, _err_code: u32) -> Option<&str> {
+        None
+    }
 }
 
 impl<H: Host> ProcessState for Process<H> {
@@ -616,6 +767,10 @@ impl<H: Host> ProcessState for Process<H> {
     fn get_mem_state(&self, ctx: ContextId) -> Vec<(u64, Word)> {
         self.chiplets.get_mem_state_at(ctx, self.system.clk())
     }
+
+    fn get_error_message(&self, err_code: u32) -> Option<&str> {
+        self.error_messages.get(&err_code).map(String::as_str)
+    }
 }
 
 // INTERNALS
@@ -634,4 +789,6 @@ where
     pub host: RefCell<H>,
     pub max_cycles: u32,
     pub enable_tracing: bool,
+    pub error_messages: BTreeMap<u32, String>,
+    pub coverage: Option<ExecutionCoverage>,
 }
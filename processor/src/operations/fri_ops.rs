@@ -7,7 +7,7 @@ use vm_core::{ExtensionOf, FieldElement, StarkField, ONE, ZERO};
 const TWO: Felt = Felt::new(2);
 const TWO_INV: Felt = Felt::new(9223372034707292161);
 
-const DOMAIN_OFFSET: Felt = Felt::GENERATOR;
+pub(crate) const DOMAIN_OFFSET: Felt = Felt::GENERATOR;
 
 // Pre-computed powers of 1/tau, where tau is the generator of multiplicative subgroup of size 4
 // (i.e., tau is the 4th root of unity). Correctness of these constants is checked in the test at
@@ -190,7 +190,7 @@ where
 // ================================================================================================
 
 /// Determines tau factor (needed to compute x value) for the specified domain segment.
-fn get_tau_factor(domain_segment: usize) -> Felt {
+pub(crate) fn get_tau_factor(domain_segment: usize) -> Felt {
     match domain_segment {
         0 => ONE,
         1 => TAU_INV,
@@ -201,7 +201,7 @@ fn get_tau_factor(domain_segment: usize) -> Felt {
 }
 
 /// Determines a set of binary flags needed to describe the specified domain segment.
-fn get_domain_segment_flags(domain_segment: usize) -> [Felt; 4] {
+pub(crate) fn get_domain_segment_flags(domain_segment: usize) -> [Felt; 4] {
     match domain_segment {
         0 => [ONE, ZERO, ZERO, ZERO],
         1 => [ZERO, ONE, ZERO, ZERO],
@@ -212,7 +212,7 @@ fn get_domain_segment_flags(domain_segment: usize) -> [Felt; 4] {
 }
 
 /// Computes 2 evaluation points needed for [fold4] function.
-fn compute_evaluation_points(alpha: QuadFelt, x_inv: Felt) -> (QuadFelt, QuadFelt) {
+pub(crate) fn compute_evaluation_points(alpha: QuadFelt, x_inv: Felt) -> (QuadFelt, QuadFelt) {
     let ev = alpha.mul_base(x_inv);
     let es = ev.square();
     (ev, es)
@@ -222,7 +222,11 @@ fn compute_evaluation_points(alpha: QuadFelt, x_inv: Felt) -> (QuadFelt, QuadFel
 /// verifier challenge alpha as follows:
 /// - ev = alpha / x
 /// - es = (alpha / x)^2
-fn fold4(values: [QuadFelt; 4], ev: QuadFelt, es: QuadFelt) -> (QuadFelt, QuadFelt, QuadFelt) {
+pub(crate) fn fold4(
+    values: [QuadFelt; 4],
+    ev: QuadFelt,
+    es: QuadFelt,
+) -> (QuadFelt, QuadFelt, QuadFelt) {
     let tmp0 = fold2(values[0], values[2], ev);
     let tmp1 = fold2(values[1], values[3], ev.mul_base(TAU_INV));
     let folded_value = fold2(tmp0, tmp1, es);
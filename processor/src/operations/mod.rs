@@ -1,4 +1,4 @@
-use super::{ExecutionError, Felt, FieldElement, Host, Operation, Process};
+use super::{ChipletsLengths, ExecutionError, Felt, FieldElement, Host, Operation, Process};
 use vm_core::stack::STACK_TOP_SIZE;
 
 mod comb_ops;
@@ -12,6 +12,11 @@ mod sys_ops;
 mod u32_ops;
 mod utils;
 
+pub(crate) use fri_ops::{
+    compute_evaluation_points, fold4, get_domain_segment_flags, get_tau_factor, DOMAIN_OFFSET,
+};
+pub(crate) use utils::assert_binary;
+
 #[cfg(test)]
 use super::Kernel;
 
@@ -162,6 +167,9 @@ where
         self.system.advance_clock(self.max_cycles)?;
         self.stack.advance_clock();
         self.chiplets.advance_clock();
+        if self.decoder.in_debug_mode() {
+            self.decoder.append_chiplets_lengths(ChipletsLengths::new(&self.chiplets));
+        }
         Ok(())
     }
 
@@ -2,8 +2,8 @@ use super::{
     chiplets::AuxTraceBuilder as ChipletsAuxTraceBuilder, crypto::RpoRandomCoin,
     decoder::AuxTraceBuilder as DecoderAuxTraceBuilder,
     range::AuxTraceBuilder as RangeCheckerAuxTraceBuilder,
-    stack::AuxTraceBuilder as StackAuxTraceBuilder, ColMatrix, Digest, Felt, FieldElement, Host,
-    Process, StackTopState,
+    stack::AuxTraceBuilder as StackAuxTraceBuilder, ColMatrix, Digest, ExecutionCoverage, Felt,
+    FieldElement, Host, Process, StackTopState,
 };
 use alloc::vec::Vec;
 use miden_air::trace::{
@@ -54,6 +54,7 @@ pub struct ExecutionTrace {
     program_info: ProgramInfo,
     stack_outputs: StackOutputs,
     trace_len_summary: TraceLenSummary,
+    coverage: Option<ExecutionCoverage>,
 }
 
 impl ExecutionTrace {
@@ -66,7 +67,7 @@ impl ExecutionTrace {
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
     /// Builds an execution trace for the provided process.
-    pub(super) fn new<H>(process: Process<H>, stack_outputs: StackOutputs) -> Self
+    pub(super) fn new<H>(mut process: Process<H>, stack_outputs: StackOutputs) -> Self
     where
         H: Host,
     {
@@ -80,6 +81,7 @@ impl ExecutionTrace {
         // create a new program info instance with the underlying kernel
         let kernel = process.kernel().clone();
         let program_info = ProgramInfo::new(program_hash.into(), kernel);
+        let coverage = process.coverage.take();
         let (main_trace, aux_trace_hints, trace_len_summary) = finalize_trace(process, rng);
 
         Self {
@@ -90,6 +92,7 @@ impl ExecutionTrace {
             program_info,
             stack_outputs,
             trace_len_summary,
+            coverage,
         }
     }
 
@@ -149,6 +152,12 @@ impl ExecutionTrace {
         &self.trace_len_summary
     }
 
+    /// Returns the code coverage collected during the execution, or None if collection of
+    /// coverage was not enabled in the execution options.
+    pub fn coverage(&self) -> Option<&ExecutionCoverage> {
+        self.coverage.as_ref()
+    }
+
     // HELPER METHODS
     // --------------------------------------------------------------------------------------------
 
@@ -5,9 +5,7 @@ use super::{
 use crate::{AdviceInputs, DefaultHost, ExecutionOptions, MemAdviceProvider, StackInputs};
 use alloc::vec::Vec;
 use test_utils::rand::rand_array;
-use vm_core::{
-    code_blocks::CodeBlock, CodeBlockTable, Kernel, Operation, StackOutputs, Word, ONE, ZERO,
-};
+use vm_core::{code_blocks::CodeBlock, Kernel, Operation, Program, StackOutputs, Word, ONE, ZERO};
 
 mod chiplets;
 mod decoder;
@@ -24,7 +22,7 @@ pub fn build_trace_from_block(program: &CodeBlock, stack_inputs: &[u64]) -> Exec
     let host = DefaultHost::default();
     let mut process =
         Process::new(Kernel::default(), stack_inputs, host, ExecutionOptions::default());
-    process.execute_code_block(program, &CodeBlockTable::default()).unwrap();
+    process.execute(&Program::new(program.clone())).unwrap();
     ExecutionTrace::new(process, StackOutputs::default())
 }
 
@@ -47,7 +45,7 @@ pub fn build_trace_from_ops_with_inputs(
     let host = DefaultHost::new(advice_provider);
     let mut process =
         Process::new(Kernel::default(), stack_inputs, host, ExecutionOptions::default());
-    let program = CodeBlock::new_span(operations);
-    process.execute_code_block(&program, &CodeBlockTable::default()).unwrap();
+    let program = Program::new(CodeBlock::new_span(operations));
+    process.execute(&program).unwrap();
     ExecutionTrace::new(process, StackOutputs::default())
 }
@@ -0,0 +1,118 @@
+use assembly::{LibraryPath, MaslLibrary};
+use processor::LineCoverage;
+use std::{
+    collections::hash_map::DefaultHasher,
+    env,
+    fs::{self, OpenOptions},
+    hash::{Hash, Hasher},
+    io::Write,
+    path::{Path, PathBuf},
+    string::String,
+    sync::Mutex,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Name of the environment variable which enables collection of code coverage by tests.
+///
+/// When the variable is set, the coverage of all modules executed by every test is appended to
+/// the file at the path specified by the variable in the LCOV tracefile format. Records of the
+/// same source file from different tests are merged by LCOV tools.
+///
+/// Modules of libraries are mapped to their source files in the workspace (see
+/// [library_source_file]), while the sources of test programs and kernels, which are not stored
+/// in files, are written to the `<coverage file>.sources` directory.
+pub const COVERAGE_ENV_VAR: &str = "MIDEN_COVERAGE";
+
+/// Directories of the sources of libraries (relative to the root of the workspace), keyed by the
+/// namespaces of the libraries.
+const LIBRARY_SOURCE_DIRS: &[(&str, &str)] = &[("std", "stdlib/asm")];
+
+/// Serializes writes of coverage records by tests running in parallel.
+static COVERAGE_FILE_LOCK: Mutex<()> = Mutex::new(());
+
+// COVERAGE HELPERS
+// ================================================================================================
+
+/// Returns the path of the file to which the coverage of tests is appended, or None if
+/// collection of coverage is not enabled.
+pub fn coverage_file() -> Option<PathBuf> {
+    env::var_os(COVERAGE_ENV_VAR).map(PathBuf::from)
+}
+
+/// Returns the path of the source file of the library module with the specified path (e.g.,
+/// `stdlib/asm/math/u64.masm` for `std::math::u64`), or None if the module does not belong to a
+/// library with known sources or its source file does not exist.
+///
+/// Both `<module>.masm` and `<module>/mod.masm` files are recognized.
+pub fn library_source_file(module_path: &str) -> Option<String> {
+    let mut parts = module_path.split(LibraryPath::PATH_DELIM);
+    let namespace = parts.next()?;
+    let (_, dir) = LIBRARY_SOURCE_DIRS.iter().find(|(name, _)| *name == namespace)?;
+
+    let mut path = Path::new(env!("CARGO_MANIFEST_DIR")).parent()?.join(dir);
+    path.extend(parts);
+    [path.with_extension(MaslLibrary::MODULE_EXTENSION), path.join(MaslLibrary::MOD)]
+        .into_iter()
+        .map(|path| path.with_extension(MaslLibrary::MODULE_EXTENSION))
+        .find(|path| path.is_file())
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+/// Appends the coverage of all executed modules to the file specified via [COVERAGE_ENV_VAR].
+///
+/// `sources` contains the sources of the modules which are not stored in files (i.e., of the
+/// test program and kernel), keyed by the paths of the modules. Such sources are written to files
+/// named after their hashes, so that records of the same source executed by different tests are
+/// merged.
+///
+/// # Panics
+/// Panics if the coverage or the sources could not be written to files.
+pub(crate) fn append_coverage(coverage: &LineCoverage, sources: &[(&str, &str)]) {
+    let Some(path) = coverage_file() else {
+        return;
+    };
+
+    let _lock = COVERAGE_FILE_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+    let lcov = coverage.to_lcov(|module_path| {
+        match sources.iter().find(|(path, _)| *path == module_path) {
+            Some((_, source)) => Some(write_source_file(&path, module_path, source)),
+            None => library_source_file(module_path),
+        }
+    });
+    if lcov.is_empty() {
+        return;
+    }
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(lcov.as_bytes()))
+        .unwrap_or_else(|err| panic!("failed to write coverage to {}: {err}", path.display()));
+}
+
+/// Writes the source of the module with the specified path to the sources directory of the
+/// coverage file (unless it was already written), and returns the path of the written file.
+///
+/// # Panics
+/// Panics if the source could not be written to the file.
+fn write_source_file(coverage_file: &Path, module_path: &str, source: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+
+    let mut dir = coverage_file.to_path_buf().into_os_string();
+    dir.push(".sources");
+    let name = module_path.trim_start_matches('#');
+    let path = Path::new(&dir)
+        .join(format!("{name}-{:016x}", hasher.finish()))
+        .with_extension(MaslLibrary::MODULE_EXTENSION);
+
+    if !path.is_file() {
+        fs::create_dir_all(&dir)
+            .and_then(|_| fs::write(&path, source))
+            .unwrap_or_else(|err| panic!("failed to write source to {}: {err}", path.display()));
+    }
+    path.to_string_lossy().into_owned()
+}
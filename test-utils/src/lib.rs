@@ -19,8 +19,9 @@ use vm_core::chiplets::hasher::apply_permutation;
 
 pub use assembly::{Library, MaslLibrary};
 pub use processor::{
-    AdviceInputs, AdviceProvider, ContextId, DefaultHost, ExecutionError, ExecutionOptions,
-    ExecutionTrace, Process, ProcessState, StackInputs, VmStateIterator,
+    AdviceInputs, AdviceProvider, ContextId, DefaultHost, ExecutionCoverage, ExecutionError,
+    ExecutionOptions, ExecutionTrace, LineCoverage, Process, ProcessState, StackInputs,
+    VmStateIterator,
 };
 pub use prover::{prove, MemAdviceProvider, ProvingOptions};
 pub use test_case::test_case;
@@ -44,6 +45,9 @@ pub mod serde {
     };
 }
 
+#[cfg(feature = "std")]
+pub mod coverage;
+
 pub mod crypto;
 
 #[cfg(not(target_family = "wasm"))]
@@ -51,7 +55,7 @@ pub mod rand;
 
 mod test_builders;
 
-use assembly::AssemblyError;
+use assembly::{AssemblyError, LibraryPath};
 #[cfg(not(target_family = "wasm"))]
 pub use proptest;
 
@@ -92,6 +96,9 @@ pub enum TestError {
 /// AssemblyError which contains the specified substring.
 /// - Execution error test: check that running a program compiled from the given source causes
 ///   an ExecutionError which contains the specified substring.
+///
+/// If the `MIDEN_COVERAGE` environment variable is set, the coverage of the code executed by tests
+/// is collected (see [coverage::COVERAGE_ENV_VAR]).
 pub struct Test {
     pub source: String,
     pub kernel: Option<String>,
@@ -125,8 +132,9 @@ impl Test {
     pub fn expect_error(&self, expected_error: TestError) {
         match expected_error {
             TestError::AssemblyError(assembly_error) => {
+                // source locations of assembly errors are not a part of the expected errors
                 let actual_error = self.compile().err().unwrap();
-                assert_eq!(assembly_error, actual_error);
+                assert_eq!(&assembly_error, actual_error.inner());
             }
             TestError::ExecutionError(execution_error) => {
                 let actual_error = self.execute().err().unwrap();
@@ -203,8 +211,24 @@ impl Test {
 
     /// Compiles a test's source and returns the resulting Program or Assembly error.
     pub fn compile(&self) -> Result<Program, AssemblyError> {
+        self.compile_program(false)
+    }
+
+    /// Returns the options for executing the test, which enable collection of coverage if it was
+    /// requested via the `MIDEN_COVERAGE` environment variable.
+    fn execution_options(&self) -> ExecutionOptions {
+        #[cfg(feature = "std")]
+        if coverage::coverage_file().is_some() {
+            return ExecutionOptions::default().with_coverage();
+        }
+        ExecutionOptions::default()
+    }
+
+    /// Compiles the test's source, optionally attaching a source map to the resulting program.
+    fn compile_program(&self, emit_source_map: bool) -> Result<Program, AssemblyError> {
         let assembler = assembly::Assembler::default()
             .with_debug_mode(self.in_debug_mode)
+            .with_source_map(emit_source_map)
             .with_libraries(self.libraries.iter())
             .expect("failed to load stdlib");
 
@@ -217,10 +241,48 @@ impl Test {
 
     /// Compiles the test's source to a Program and executes it with the tests inputs. Returns a
     /// resulting execution trace or error.
+    ///
+    /// If collection of coverage is enabled, the coverage of the execution is appended to the
+    /// coverage file.
     pub fn execute(&self) -> Result<ExecutionTrace, ExecutionError> {
         let program = self.compile().expect("Failed to compile test source.");
         let host = DefaultHost::new(MemAdviceProvider::from(self.advice_inputs.clone()));
-        processor::execute(&program, self.stack_inputs.clone(), host, ExecutionOptions::default())
+        let result =
+            processor::execute(&program, self.stack_inputs.clone(), host, self.execution_options());
+
+        // the program is compiled without a source map so that execution errors are the same
+        // regardless of whether coverage is collected; since source maps do not affect the MAST,
+        // the coverage is mapped to the source code via a source map of a separate compilation
+        #[cfg(feature = "std")]
+        if let Some(coverage) = result.as_ref().ok().and_then(|trace| trace.coverage()) {
+            let program = self.compile_program(true).expect("Failed to compile test source.");
+            let mut sources = vec![(LibraryPath::EXEC_PATH, self.source.as_str())];
+            if let Some(kernel) = self.kernel.as_ref() {
+                sources.push((LibraryPath::KERNEL_PATH, kernel.as_str()));
+            }
+            coverage::append_coverage(&coverage.line_coverage(&program), &sources);
+        }
+
+        // the fast executor must produce exactly the same outputs (or errors) as the tracing one
+        let host = DefaultHost::new(MemAdviceProvider::from(self.advice_inputs.clone()));
+        let fast_result = processor::execute_fast(
+            &program,
+            self.stack_inputs.clone(),
+            host,
+            ExecutionOptions::default(),
+        );
+        match (&result, &fast_result) {
+            (Ok(trace), Ok(outputs)) => assert_eq!(trace.stack_outputs(), outputs),
+            (Err(err), Err(fast_err)) => assert_eq!(err, fast_err),
+            (Ok(trace), Err(err)) => {
+                panic!("fast execution failed with {err:?}, expected {:?}", trace.stack_outputs())
+            }
+            (Err(err), Ok(outputs)) => {
+                panic!("fast execution returned {outputs:?}, expected error {err:?}")
+            }
+        }
+
+        result
     }
 
     /// Compiles the test's source to a Program and executes it with the tests inputs. Returns the
@@ -269,6 +331,17 @@ impl Test {
         processor::execute_iter(&program, self.stack_inputs.clone(), host)
     }
 
+    /// Compiles the test's source with source maps, executes it with the tests inputs, and returns
+    /// the line coverage of the executed code or an execution error.
+    pub fn line_coverage(&self) -> Result<LineCoverage, ExecutionError> {
+        let program = self.compile_program(true).expect("Failed to compile test source.");
+        let host = DefaultHost::new(MemAdviceProvider::from(self.advice_inputs.clone()));
+        let options = ExecutionOptions::default().with_coverage();
+        let trace = processor::execute(&program, self.stack_inputs.clone(), host, options)?;
+        let coverage = trace.coverage().expect("coverage was not collected");
+        Ok(coverage.line_coverage(&program))
+    }
+
     /// Returns the last state of the stack after executing a test.
     pub fn get_last_stack_state(&self) -> [Felt; STACK_TOP_SIZE] {
         let trace = self.execute().unwrap();